@@ -7,6 +7,7 @@ use bevy_yarnspinner::prelude::*;
 use crate::world::systems::*;
 use crate::world::components::*;
 use crate::world::state::*;
+use crate::world::resources::game_state::GameState as SaveGameState;
 // Consolidated: no separate WorldPlugin needed
 
 pub struct GamePlugin;
@@ -46,7 +47,8 @@ impl Plugin for GamePlugin {
         app.init_resource::<WorldState>()
             .init_resource::<GameState>()
             .init_resource::<DreadLevel>()
-            .init_resource::<AssetHandles>();
+            .init_resource::<AssetHandles>()
+            .init_resource::<SaveGameState>();
 
         // Game systems - only include what actually exists
         app.add_systems(Startup, (
@@ -60,9 +62,295 @@ impl Plugin for GamePlugin {
             dread_progression_system,
             asset_loading_system,
             ui_update_system,
-            check_forced_rest,
         ).run_if(in_state(GameStateEnum::Playing)));
 
+        // Quicksave and quickload. Loading runs before any feature restores
+        // from the save, so none of them mirrors stale state over it first.
+        app.init_resource::<SaveFile>()
+            .add_event::<SaveGameEvent>()
+            .add_event::<LoadGameEvent>()
+            .add_systems(PreUpdate, (
+                save_game_system,
+                load_game_system,
+            ).chain().run_if(in_state(GameStateEnum::Playing)))
+            .add_systems(Update, save_input_system.run_if(in_state(GameStateEnum::Playing)));
+
+        // Dungeon interiors built from HBF dungeon areas
+        app.init_resource::<DungeonLayouts>()
+            .init_resource::<DungeonProgressLedger>()
+            .init_resource::<ActiveDungeon>()
+            .add_event::<EnterDungeonEvent>()
+            .add_event::<ExitDungeonEvent>()
+            .add_event::<DungeonMoveEvent>()
+            .add_event::<DungeonFightEvent>()
+            .add_event::<MonsterDefeatedEvent>()
+            .add_event::<TreasureLootedEvent>()
+            .add_event::<DungeonAreaClearedEvent>()
+            .add_plugins(DataFilePlugin::<DungeonLayouts>::default())
+            .add_systems(Update, (
+                dungeon_entrance_interaction_system,
+                build_dungeon_interior_system,
+                dungeon_input_system,
+                dungeon_room_navigation_system,
+                dungeon_trap_trigger_system,
+                dungeon_combat_system,
+                dungeon_monster_defeat_system,
+                dungeon_area_clear_system,
+                dungeon_treasure_loot_system,
+                dungeon_fog_of_war_system,
+                dungeon_exit_system,
+                restore_from_save::<DungeonProgressLedger>,
+                sync_to_save::<DungeonProgressLedger>,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Inventory, equipment and item effects
+        app.init_resource::<ItemDatabase>()
+            .add_event::<EquipItemEvent>()
+            .add_event::<UnequipItemEvent>()
+            .add_event::<UseItemEvent>()
+            .add_event::<UseToolEvent>()
+            .add_event::<ToolBrokenEvent>()
+            .add_event::<DiscardItemEvent>()
+            .add_event::<ItemEffectExpiredEvent>()
+            .add_plugins(DataFilePlugin::<ItemDatabase>::default())
+            .add_systems(Update, (
+                attach_player_item_components,
+                restore_items_from_save,
+                item_input_system,
+                equip_item_system,
+                unequip_item_system,
+                use_consumable_system,
+                tick_item_effects_system,
+                tool_durability_system,
+                discard_item_system,
+                award_treasure_items_system.after(dungeon_treasure_loot_system),
+                update_combat_stats_system,
+                sync_items_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Mounts: riding, stamina, fear and stables
+        app.add_event::<ToggleMountEvent>()
+            .add_event::<BuyMountEvent>()
+            .add_event::<MountPanicEvent>()
+            .add_event::<MountLostEvent>()
+            .add_systems(Update, (
+                attach_settlement_stables,
+                restore_mount_from_save,
+                mount_input_system,
+                toggle_mount_system,
+                buy_mount_system,
+                mount_travel_system,
+                mount_recovery_system,
+                mount_loss_system,
+                sync_mount_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Game clock, weather fronts and day/night
+        app.init_resource::<DayNightCycle>()
+            .init_resource::<WeatherSystem>()
+            .init_resource::<WeatherFronts>()
+            .init_resource::<crate::world::systems::procedural_audio::ProceduralAudioSystem>()
+            .add_event::<AdvanceTimeEvent>()
+            .add_event::<TimeAdvancedEvent>()
+            .add_systems(Startup, setup_world_lighting)
+            .add_systems(Update, (
+                restore_clock_from_save,
+                travel_time_system,
+                update_day_night_cycle,
+                weather_front_system,
+                night_dread_system,
+                update_world_lighting,
+                update_weather_audio,
+                sync_clock_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Survival: fatigue, hunger, camping and inns
+        app.init_resource::<SurvivalConfig>()
+            .add_event::<MakeCampEvent>()
+            .add_event::<InnRestEvent>()
+            .add_event::<RestEvent>()
+            .add_event::<AmbushEvent>()
+            .add_event::<RestRequiredEvent>()
+            .add_systems(Update, (
+                attach_player_survival_stats,
+                restore_survival_from_save,
+                rest_input_system,
+                travel_fatigue_system,
+                hunger_thirst_system,
+                exhaustion_penalty_system,
+                check_forced_rest,
+                setup_camp_system,
+                handle_inn_rest,
+                resolve_rest_system,
+                sync_survival_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Settlements: services and corruption
+        app.init_resource::<SettlementDatabase>()
+            .add_event::<UseServiceEvent>()
+            .add_event::<ServiceStatusChangedEvent>()
+            .add_event::<HostileServiceEvent>()
+            .add_plugins(DataFilePlugin::<SettlementDatabase>::default())
+            .add_systems(Update, (
+                attach_settlement_data,
+                restore_settlements_from_save,
+                service_input_system,
+                settlement_service_system.before(handle_inn_rest),
+                settlement_corruption_system.after(update_day_night_cycle),
+                sync_settlements_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Economy: regional supply, scarcity pricing and trade
+        app.add_event::<BuyItemEvent>()
+            .add_event::<SellItemEvent>()
+            .add_event::<TradeCompletedEvent>()
+            .add_event::<SupplyShockEvent>()
+            .add_systems(Update, (
+                attach_settlement_markets.after(attach_settlement_data),
+                restore_markets_from_save,
+                trade_input_system,
+                buy_item_system,
+                sell_item_system,
+                supply_shock_system,
+                market_recovery_system.after(update_day_night_cycle),
+                sync_markets_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Factions: influence, reputation and turns
+        app.init_resource::<FactionDatabase>()
+            .init_resource::<FactionSimulation>()
+            .add_event::<ReputationChangeEvent>()
+            .add_event::<FactionTurnEvent>()
+            .add_event::<FactionEncounterEvent>()
+            .add_plugins(DataFilePlugin::<FactionDatabase>::default())
+            .add_systems(Update, (
+                restore_factions_from_save,
+                start_faction_simulation,
+                seed_faction_influence.after(attach_settlement_data),
+                assign_npc_factions,
+                reputation_change_system,
+                faction_turn_system.after(update_day_night_cycle),
+                update_presence_influence,
+                update_npc_attitudes,
+                faction_encounter_system,
+                sync_factions_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Quests: log, objectives and branching outcomes
+        app.init_resource::<QuestDatabase>()
+            .init_resource::<QuestLog>()
+            .add_event::<StartQuestEvent>()
+            .add_event::<TalkToNpcEvent>()
+            .add_event::<SettleDilemmaEvent>()
+            .add_event::<QuestProgressEvent>()
+            .add_plugins(DataFilePlugin::<QuestDatabase>::default())
+            .add_systems(Update, (
+                restore_from_save::<QuestLog>,
+                start_quest_system,
+                dilemma_choice_input_system,
+                settle_dilemma_system,
+                quest_progress_system,
+                apply_quest_outcomes.before(reputation_change_system),
+                sync_to_save::<QuestLog>,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // NPCs: schedules, memory and interactions
+        app.init_resource::<NpcDatabase>()
+            .init_resource::<QuestGivers>()
+            .add_event::<InteractWithNpcEvent>()
+            .add_event::<NpcInteractionEvent>()
+            .add_event::<NpcMemoryEvent>()
+            .add_plugins(DataFilePlugin::<NpcDatabase>::default())
+            .add_systems(Update, (
+                hydrate_npcs,
+                restore_npcs_from_save,
+                restore_from_save::<QuestGivers>,
+                npc_schedule_system,
+                npc_input_system,
+                npc_interaction_system,
+                npc_memory_system,
+                npc_corruption_system,
+                sync_npcs_to_save,
+                sync_to_save::<QuestGivers>,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Alignment: player choices and their consequences
+        app.init_resource::<ChoiceLedger>()
+            .add_event::<AlignmentChangedEvent>()
+            .add_systems(Update, (
+                restore_from_save::<ChoiceLedger>,
+                choice_consequence_system.before(reputation_change_system),
+                choice_ledger_debug_system,
+                sync_to_save::<ChoiceLedger>,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Abilities: unlocks, costs, cooldowns and tainted power
+        app.init_resource::<AbilityDatabase>()
+            .add_event::<UseAbilityEvent>()
+            .add_event::<AbilityUsedEvent>()
+            .add_event::<AbilityLearnedEvent>()
+            .add_plugins(DataFilePlugin::<AbilityDatabase>::default())
+            .add_systems(Update, (
+                attach_ability_book,
+                restore_abilities_from_save,
+                learn_abilities_system,
+                tick_ability_cooldowns_system,
+                ability_input_system,
+                use_ability_system.before(update_combat_stats_system),
+                sync_abilities_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Character creation: draft, preview and the finished character
+        app.init_resource::<CharacterCreator>()
+            .add_event::<CreatorActionEvent>()
+            .add_event::<CharacterCreatedEvent>()
+            .add_systems(OnEnter(GameStateEnum::CharacterCreation), enter_character_creation)
+            .add_systems(OnExit(GameStateEnum::CharacterCreation), exit_character_creation)
+            .add_systems(Update, (
+                handle_character_creator_input,
+                character_creator_system,
+                update_character_preview,
+            ).chain().run_if(in_state(GameStateEnum::CharacterCreation)))
+            .add_systems(Update, apply_character_sheet_system
+                .before(update_combat_stats_system)
+                .run_if(in_state(GameStateEnum::Playing)));
+
+        // Leveling: experience, levels and world progression
+        app.add_event::<AwardExperienceEvent>()
+            .add_event::<LevelUpEvent>()
+            .add_event::<SpendAttributePointEvent>()
+            .add_systems(Update, (
+                attach_experience,
+                restore_experience_from_save,
+                experience_award_system.before(learn_abilities_system),
+                attribute_input_system,
+                spend_attribute_point_system,
+                refresh_player_maximums_system.after(apply_character_sheet_system),
+                world_progression_system,
+                sync_experience_to_save,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Atlas: discovery, line of sight, rumours and map export
+        app.init_resource::<Atlas>()
+            .add_event::<RumourEvent>()
+            .add_event::<AtlasRevealedEvent>()
+            .add_systems(Update, (
+                restore_from_save::<Atlas>,
+                atlas_sight_system,
+                npc_rumour_system.after(npc_interaction_system),
+                atlas_rumour_system.after(quest_progress_system).after(use_ability_system),
+                export_atlas_system,
+                sync_to_save::<Atlas>,
+            ).chain().run_if(in_state(GameStateEnum::Playing)));
+
+        // Unit, building, leader and terrain models from generated metadata
+        app.init_asset::<ModelMetadataAsset>()
+            .init_asset_loader::<ModelMetadataLoader>()
+            .init_resource::<ModelMetadataHandles>()
+            .init_resource::<AssetManifest>()
+            .add_plugins(DataFilePlugin::<AssetManifest>::default())
+            .add_systems(Update, (load_model_metadata, register_model_metadata).chain());
+
         // Game states
         app.init_state::<GameStateEnum>();
     }
@@ -1 +0,0 @@
-// Abilities module placeholder retained for future integration
@@ -1 +0,0 @@
-// Alignment module placeholder for future world rules integration
\ No newline at end of file
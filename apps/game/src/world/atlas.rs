@@ -1 +0,0 @@
-// Atlas utilities placeholder; consider merging with existing tilemap setup in systems
\ No newline at end of file
@@ -1,16 +1,7 @@
-//! World-related types moved from apps/game/src/world/components
+//! Game world: ECS components (unified types from dl_types plus
+//! game-specific ones), resources, state and the systems that drive them
 
-pub mod character;
-pub mod companions;
-pub mod dread;
-pub mod hex;
-pub mod player;
-pub mod tiles;
-
-// Re-export all world types
-pub use character::*;
-pub use companions::*;
-pub use dread::*;
-pub use hex::*;
-pub use player::*;
-pub use tiles::*;
+pub mod components;
+pub mod resources;
+pub mod state;
+pub mod systems;
@@ -1,3 +1,5 @@
+pub mod game_state;
+
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1,7 +1,11 @@
 use bevy::prelude::*;
+use dl_types::world::{ActiveEffect, Item, ItemType, Mount};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::world::systems::rest_fatigue::Difficulty;
+use crate::world::systems::time_weather::WeatherFront;
+
 #[derive(Resource, Default)]
 pub struct GameState {
     pub save_data: SaveData,
@@ -11,6 +15,8 @@ pub struct GameState {
     pub world_events: Vec<WorldEvent>,
     pub game_time: f32, // Time in seconds since game start
     pub player_choices: Vec<PlayerChoice>,
+    /// How many saves have been applied; restore systems compare against it
+    pub loads: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -23,9 +29,44 @@ pub struct SaveData {
     pub unlocked_areas: Vec<String>,
     pub completed_encounters: Vec<String>,
     pub story_flags: HashMap<String, bool>,
+    #[serde(default)]
+    pub dungeon_progress: HashMap<String, dl_types::world::DungeonProgress>, // Keyed by dungeon UUID
+    #[serde(default)]
+    pub world_clock: Option<WorldClockSaveState>,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub settlement_corruption: HashMap<String, f32>, // Keyed by settlement UUID
+    #[serde(default)]
+    pub markets: HashMap<String, dl_types::world::Market>, // Keyed by settlement UUID
+    #[serde(default)]
+    pub factions: Option<dl_types::world::FactionSimulation>,
+    #[serde(default)]
+    pub quest_log: dl_types::world::QuestLog,
+    #[serde(default)]
+    pub npc_memories: HashMap<String, dl_types::world::NpcMemory>, // Keyed by NPC UUID
+    #[serde(default)]
+    pub npc_corruption: HashMap<String, dl_types::world::NpcCorruption>, // Keyed by NPC UUID
+    #[serde(default)]
+    pub quest_givers: HashMap<String, String>, // NPC UUID keyed by quest id
+    #[serde(default)]
+    pub choice_ledger: dl_types::world::ChoiceLedger,
+    #[serde(default)]
+    pub character: Option<dl_types::world::CharacterSheet>,
+    #[serde(default)]
+    pub atlas: dl_types::world::Atlas,
     pub timestamp: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldClockSaveState {
+    pub day: u32,
+    pub hour: f32,
+    pub movement_points_remaining: u32,
+    pub hours_until_next_front: f32,
+    pub weather_fronts: Vec<WeatherFront>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerStats {
     pub health: f32,
@@ -34,7 +75,25 @@ pub struct PlayerStats {
     pub max_sanity: f32,
     pub position: (i32, i32), // Hex coordinates
     pub mount: Option<String>,
+    #[serde(default)]
+    pub mount_state: Option<Mount>,
+    #[serde(default)]
+    pub mounted: bool,
     pub inventory: Vec<ItemSaveState>,
+    #[serde(default)]
+    pub equipment: Vec<ItemSaveState>, // Slot is derived from the item on load
+    #[serde(default)]
+    pub active_effects: Vec<ActiveEffect>,
+    #[serde(default)]
+    pub fatigue: f32,
+    #[serde(default)]
+    pub hunger: f32,
+    #[serde(default)]
+    pub thirst: f32,
+    #[serde(default)]
+    pub abilities: dl_types::world::AbilityBook,
+    #[serde(default)]
+    pub experience: dl_types::world::Experience,
 }
 
 impl Default for PlayerStats {
@@ -46,7 +105,16 @@ impl Default for PlayerStats {
             max_sanity: 100.0,
             position: (0, 0),
             mount: None,
+            mount_state: None,
+            mounted: false,
             inventory: Vec::new(),
+            equipment: Vec::new(),
+            active_effects: Vec::new(),
+            fatigue: 0.0,
+            hunger: 0.0,
+            thirst: 0.0,
+            abilities: dl_types::world::AbilityBook::default(),
+            experience: dl_types::world::Experience::default(),
         }
     }
 }
@@ -70,6 +138,119 @@ pub struct ItemSaveState {
     pub data: HashMap<String, String>, // Flexible storage for item-specific data
 }
 
+impl ItemSaveState {
+    pub fn from_item(item: &Item) -> Self {
+        let mut data = HashMap::new();
+        data.insert("weight".to_string(), item.weight.to_string());
+        data.insert("value".to_string(), item.value.to_string());
+        data.insert("description".to_string(), item.description.clone());
+
+        let item_type = match &item.item_type {
+            ItemType::Weapon { damage, weapon_type, enchantments } => {
+                data.insert("damage".to_string(), damage.to_string());
+                data.insert("weapon_type".to_string(), weapon_type.clone());
+                data.insert("enchantments".to_string(), enchantments.join("|"));
+                "weapon"
+            }
+            ItemType::Armor { protection, armor_type, enchantments } => {
+                data.insert("protection".to_string(), protection.to_string());
+                data.insert("armor_type".to_string(), armor_type.clone());
+                data.insert("enchantments".to_string(), enchantments.join("|"));
+                "armor"
+            }
+            ItemType::Consumable { effect, duration, potency } => {
+                data.insert("effect".to_string(), effect.clone());
+                data.insert("duration".to_string(), duration.to_string());
+                data.insert("potency".to_string(), potency.to_string());
+                "consumable"
+            }
+            ItemType::Tool { tool_type, durability, max_durability } => {
+                data.insert("tool_type".to_string(), tool_type.clone());
+                data.insert("durability".to_string(), durability.to_string());
+                data.insert("max_durability".to_string(), max_durability.to_string());
+                "tool"
+            }
+            ItemType::QuestItem { quest_id, unique } => {
+                data.insert("quest_id".to_string(), quest_id.clone());
+                data.insert("unique".to_string(), unique.to_string());
+                "quest_item"
+            }
+            ItemType::Currency { currency_type } => {
+                data.insert("currency_type".to_string(), currency_type.clone());
+                "currency"
+            }
+            ItemType::Material { material_type, rarity } => {
+                data.insert("material_type".to_string(), material_type.clone());
+                data.insert("rarity".to_string(), rarity.clone());
+                "material"
+            }
+        };
+
+        Self {
+            name: item.name.clone(),
+            item_type: item_type.to_string(),
+            quantity: item.quantity,
+            data,
+        }
+    }
+
+    /// Rebuild the item; `None` if the saved type tag is unknown
+    pub fn to_item(&self) -> Option<Item> {
+        let text = |key: &str| self.data.get(key).cloned().unwrap_or_default();
+        let number = |key: &str| self.data.get(key).and_then(|v| v.parse::<u32>().ok()).unwrap_or(0);
+        let list = |key: &str| {
+            self.data
+                .get(key)
+                .map(|v| v.split('|').filter(|s| !s.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+
+        let item_type = match self.item_type.as_str() {
+            "weapon" => ItemType::Weapon {
+                damage: number("damage"),
+                weapon_type: text("weapon_type"),
+                enchantments: list("enchantments"),
+            },
+            "armor" => ItemType::Armor {
+                protection: number("protection"),
+                armor_type: text("armor_type"),
+                enchantments: list("enchantments"),
+            },
+            "consumable" => ItemType::Consumable {
+                effect: text("effect"),
+                duration: self.data.get("duration").and_then(|v| v.parse().ok()).unwrap_or(0.0),
+                potency: number("potency"),
+            },
+            "tool" => ItemType::Tool {
+                tool_type: text("tool_type"),
+                durability: number("durability"),
+                max_durability: number("max_durability"),
+            },
+            "quest_item" => ItemType::QuestItem {
+                quest_id: text("quest_id"),
+                unique: self.data.get("unique").is_some_and(|v| v == "true"),
+            },
+            "currency" => ItemType::Currency {
+                currency_type: text("currency_type"),
+            },
+            "material" => ItemType::Material {
+                material_type: text("material_type"),
+                rarity: text("rarity"),
+            },
+            _ => return None,
+        };
+
+        Some(Item {
+            name: self.name.clone(),
+            item_type,
+            quantity: self.quantity,
+            weight: self.data.get("weight").and_then(|v| v.parse().ok()).unwrap_or(0.0),
+            value: number("value"),
+            description: text("description"),
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WorldEvent {
     pub event_type: WorldEventType,
@@ -113,6 +294,14 @@ pub struct PlayerChoice {
     pub consequences_applied: bool,
     pub dread_impact: f32,
     pub companion_reactions: HashMap<String, String>,
+    #[serde(default)]
+    pub faction_effects: HashMap<String, i32>, // Reputation change keyed by faction UUID
+    #[serde(default)]
+    pub alignment: dl_types::world::Alignment,
+    #[serde(default)]
+    pub effects: Vec<dl_types::world::ConsequenceEffect>,
+    #[serde(default)]
+    pub delayed: Vec<dl_types::world::DelayedConsequence>,
 }
 
 impl GameState {
@@ -125,6 +314,7 @@ impl GameState {
             world_events: Vec::new(),
             game_time: 0.0,
             player_choices: Vec::new(),
+            loads: 0,
         }
     }
     
@@ -164,6 +354,7 @@ impl GameState {
     
     pub fn apply_save_data(&mut self, save_data: SaveData) {
         self.save_data = save_data;
+        self.loads += 1;
     }
 }
 
@@ -1,6 +1,8 @@
 use bevy::prelude::*;
+use bevy_rand::prelude::*;
+use bevy_rand::WyRng;
 use std::collections::{HashMap, HashSet};
-use dl_types::world::HexCoord;
+use dl_types::world::{HexCoord, ModelSocket};
 
 #[derive(Resource, Default, Debug, Clone, PartialEq)]
 pub enum GameState {
@@ -75,6 +77,21 @@ impl WorldState {
     }
 }
 
+/// One system's random stream, seeded from the world seed so the same world
+/// rolls the same way. Kept in a `Local`; reseeds if the world seed changes.
+#[derive(Default)]
+pub struct WorldRng(Option<(u64, WyRng)>);
+
+impl WorldRng {
+    /// `stream` keeps systems sharing a world seed from rolling in lockstep
+    pub fn get(&mut self, world_seed: u64, stream: u64) -> &mut WyRng {
+        if !matches!(self.0, Some((seed, _)) if seed == world_seed) {
+            self.0 = Some((world_seed, WyRng::seed_from_u64(world_seed.wrapping_add(stream))));
+        }
+        &mut self.0.as_mut().expect("seeded above").1
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProgressionPhase {
     EarlyGame,  // Basic world exploration, simple companions
@@ -94,6 +111,13 @@ pub struct AssetHandles {
     pub character_models: HashMap<String, Handle<Scene>>,
     pub dialogue_files: HashMap<String, Handle<bevy::asset::LoadedUntypedAsset>>,
     pub audio_files: HashMap<String, Handle<bevy::audio::AudioSource>>,
+    /// Unit, building, leader and terrain scenes by model metadata id
+    pub models: HashMap<String, Handle<Scene>>,
+    pub model_sockets: HashMap<String, Vec<ModelSocket>>,
+    /// Sounds by model id, then by event name
+    pub model_sounds: HashMap<String, HashMap<String, Handle<bevy::audio::AudioSource>>>,
+    /// Models the asset manifest lists as not made yet; drawn with the fallback mesh
+    pub missing_models: HashSet<String>,
     pub fallback_mesh: Option<Handle<Mesh>>,
     pub fallback_material: Option<Handle<StandardMaterial>>,
 }
@@ -111,6 +135,22 @@ impl AssetHandles {
         self.character_models.get(character_type)
     }
     
+    pub fn get_model(&self, model_id: &str) -> Option<&Handle<Scene>> {
+        self.models.get(model_id)
+    }
+    
+    pub fn uses_fallback(&self, model_id: &str) -> bool {
+        self.missing_models.contains(model_id)
+    }
+    
+    pub fn get_model_socket(&self, model_id: &str, socket: &str) -> Option<&ModelSocket> {
+        self.model_sockets.get(model_id)?.iter().find(|s| s.name == socket)
+    }
+    
+    pub fn get_model_sound(&self, model_id: &str, event: &str) -> Option<&Handle<bevy::audio::AudioSource>> {
+        self.model_sounds.get(model_id)?.get(event)
+    }
+    
     pub fn is_tilemap_loaded(&self) -> bool {
         self.tilemap_texture.is_some()
     }
@@ -186,46 +226,13 @@ pub struct MovementPreview {
     pub target_hex: Option<HexCoord>,
 }
 
-#[derive(Resource, Default)]
-pub struct DayNightCycle {
-    pub time_of_day: f32,
-    pub cycle_speed: f32,
-}
-
-#[derive(Resource, Default)]
-pub struct WeatherSystem {
-    pub current_weather: WeatherType,
-    pub intensity: f32,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum WeatherType {
-    Clear,
-    Fog,
-    Rain,
-    Storm,
-    Corruption,
-}
-
-impl Default for WeatherType {
-    fn default() -> Self {
-        Self::Clear
-    }
-}
+// Day/night and weather live in systems::time_weather
 
+/// The character being made on the creation screen
 #[derive(Resource, Default)]
 pub struct CharacterCreator {
-    pub name: String,
-    pub background: String,
-    pub starting_stats: CharacterStats,
-}
-
-#[derive(Debug, Clone, Default)]
-pub struct CharacterStats {
-    pub health: f32,
-    pub sanity: f32,
-    pub experience: u32,
-    pub level: u32,
+    pub draft: dl_types::world::CharacterDraft,
+    pub last_error: Option<String>,
 }
 
 #[derive(Resource, Default)]
@@ -0,0 +1,277 @@
+use bevy::prelude::*;
+
+use crate::world::components::{
+    AbilityBook, AbilityContext, AbilityDatabase, AbilityEffect, AbilityKind, AbilityResources, AbilityTarget,
+    ActiveEffects, Alignment, ChoiceLedger, HexCoord, Player,
+};
+use crate::world::resources::game_state::GameState;
+use crate::world::state::{DreadLevel, WorldState};
+use crate::world::systems::alignment::AlignmentChangedEvent;
+use crate::world::systems::data_files::DataFile;
+use crate::world::systems::input::{cursor_hex, number_key_pressed, NumberKeyModifier};
+use crate::world::systems::rest_fatigue::PlayerStats;
+use crate::world::systems::save::SaveSlot;
+use crate::world::systems::time_weather::TimeAdvancedEvent;
+
+/// Fatigue ceiling for a caster without `PlayerStats`
+const DEFAULT_MAX_FATIGUE: f32 = 100.0;
+
+/// Hand-written alongside the other game config
+impl DataFile for AbilityDatabase {
+    type Contents = Self;
+    const PATH: &'static str = "config/abilities.ron";
+
+    fn from_contents(contents: Self) -> Self {
+        contents
+    }
+}
+
+/// Use an active ability, aimed at a hex or at the caster's own
+#[derive(Event)]
+pub struct UseAbilityEvent {
+    pub ability_id: String,
+    pub target: Option<HexCoord>,
+}
+
+/// An ability went off. Damage and revealed hexes are resolved by whoever
+/// owns the targets; costs and effects on the caster are already applied.
+#[derive(Event)]
+pub struct AbilityUsedEvent {
+    pub ability_id: String,
+    pub hexes: Vec<HexCoord>,
+    pub effects: Vec<AbilityEffect>,
+}
+
+#[derive(Event)]
+pub struct AbilityLearnedEvent {
+    pub ability_id: String,
+}
+
+/// Give a freshly spawned player an empty ability book
+pub fn attach_ability_book(mut commands: Commands, players: Query<Entity, (With<Player>, Without<AbilityBook>)>) {
+    for entity in players.iter() {
+        commands.entity(entity).insert(AbilityBook::default());
+    }
+}
+
+/// Learn every ability the player's level has unlocked
+pub fn learn_abilities_system(
+    database: Res<AbilityDatabase>,
+    mut player_query: Query<(&PlayerStats, &mut AbilityBook)>,
+    mut learned_events: EventWriter<AbilityLearnedEvent>,
+) {
+    for (stats, mut book) in player_query.iter_mut() {
+        // Check first so the book is only marked changed when something is learned
+        let unlocked = database.unlocked_by(stats.level);
+        if unlocked.iter().all(|ability| book.knows(&ability.id)) {
+            continue;
+        }
+        for ability_id in book.learn_up_to(&database, stats.level) {
+            if let Some(ability) = database.get(&ability_id) {
+                info!("Learned {}", ability.name);
+            }
+            learned_events.send(AbilityLearnedEvent { ability_id });
+        }
+    }
+}
+
+/// Hold C and press a number to use that known active ability, in the order
+/// they were learned. Abilities aimed at a hex go where the mouse points.
+pub fn ability_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    database: Res<AbilityDatabase>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    player_query: Query<&AbilityBook, With<Player>>,
+    mut use_events: EventWriter<UseAbilityEvent>,
+) {
+    let Some(index) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyC)) else {
+        return;
+    };
+    let Ok(book) = player_query.get_single() else {
+        return;
+    };
+    let Some(ability) = book
+        .known
+        .iter()
+        .filter_map(|id| database.get(id))
+        .filter(|ability| ability.kind == AbilityKind::Active)
+        .nth(index)
+    else {
+        info!("You know no ability {}", index + 1);
+        return;
+    };
+    let target = match ability.target {
+        AbilityTarget::Caster => None,
+        AbilityTarget::Hex { .. } | AbilityTarget::Area { .. } => cursor_hex(&windows, &camera_query),
+    };
+    use_events.send(UseAbilityEvent {
+        ability_id: ability.id.clone(),
+        target,
+    });
+}
+
+/// Pay for and apply active abilities. Tainted abilities corrupt the caster:
+/// their corruption pulls the player's alignment toward the void.
+#[allow(clippy::too_many_arguments)]
+pub fn use_ability_system(
+    mut use_events: EventReader<UseAbilityEvent>,
+    database: Res<AbilityDatabase>,
+    game_state: Res<GameState>,
+    mut world_state: ResMut<WorldState>,
+    mut dread: ResMut<DreadLevel>,
+    mut ledger: ResMut<ChoiceLedger>,
+    mut player_query: Query<(&mut Player, &mut AbilityBook, &mut ActiveEffects, Option<&mut PlayerStats>)>,
+    mut used_events: EventWriter<AbilityUsedEvent>,
+    mut alignment_events: EventWriter<AlignmentChangedEvent>,
+) {
+    let Ok((mut player, mut book, mut active_effects, mut stats)) = player_query.get_single_mut() else {
+        use_events.clear();
+        return;
+    };
+    let origin = world_state.player_hex.unwrap_or_else(HexCoord::origin);
+    let context = if game_state.active_encounters.is_empty() {
+        AbilityContext::Overworld
+    } else {
+        AbilityContext::Combat
+    };
+
+    for event in use_events.read() {
+        let Some(ability) = database.get(&event.ability_id) else {
+            warn!("Unknown ability {}", event.ability_id);
+            continue;
+        };
+        let resources = AbilityResources {
+            health: player.health,
+            sanity: player.sanity,
+            fatigue: stats.as_ref().map_or(0.0, |stats| stats.fatigue),
+            max_fatigue: stats.as_ref().map_or(DEFAULT_MAX_FATIGUE, |stats| stats.max_fatigue),
+        };
+        let target = event.target.unwrap_or(origin);
+        let cast = match book.use_ability(ability, &resources, context, origin, target, dread.current) {
+            Ok(cast) => cast,
+            Err(e) => {
+                info!("Cannot use {}: {}", ability.name, e);
+                continue;
+            }
+        };
+
+        player.health -= cast.cost.health;
+        player.sanity = (player.sanity - cast.cost.sanity).max(0.0);
+        if let Some(stats) = stats.as_deref_mut() {
+            stats.add_fatigue(cast.cost.fatigue);
+        }
+
+        // Restoratives only reach the caster when their hex is among the targets
+        let hits_caster = cast.hexes.contains(&origin);
+        for effect in &cast.effects {
+            match effect {
+                AbilityEffect::Heal { amount } if hits_caster => {
+                    player.health = (player.health + amount).min(player.max_health);
+                }
+                AbilityEffect::RestoreSanity { amount } if hits_caster => {
+                    player.sanity = (player.sanity + amount).min(player.max_sanity);
+                }
+                AbilityEffect::Vigor { amount } if hits_caster => {
+                    if let Some(stats) = stats.as_deref_mut() {
+                        stats.add_fatigue(-amount);
+                    }
+                }
+                AbilityEffect::Buff { effect, potency, hours } => {
+                    active_effects.apply(&ability.name, effect.clone(), *potency, *hours);
+                }
+                AbilityEffect::Dread { amount } => {
+                    if *amount > 0.0 {
+                        dread.add_dread(*amount);
+                    } else {
+                        dread.remove_dread(-amount);
+                    }
+                }
+                AbilityEffect::Cleanse { amount } => {
+                    for hex in &cast.hexes {
+                        world_state.add_corruption(*hex, -amount);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if cast.corruption > 0.0 {
+            let before = (ledger.alignment.path(), ledger.alignment.stage());
+            ledger.alignment.shift(&Alignment {
+                light: -cast.corruption,
+                ..Default::default()
+            });
+            let (path, stage) = (ledger.alignment.path(), ledger.alignment.stage());
+            if (path, stage) != before {
+                warn!("The power of {} takes its price ({:?})", ability.name, stage);
+                alignment_events.send(AlignmentChangedEvent { path, stage });
+            }
+        }
+
+        info!("Used {}", ability.name);
+        used_events.send(AbilityUsedEvent {
+            ability_id: cast.ability_id,
+            hexes: cast.hexes,
+            effects: cast.effects,
+        });
+    }
+}
+
+/// Count cooldowns down as game hours pass
+pub fn tick_ability_cooldowns_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    mut player_query: Query<&mut AbilityBook>,
+) {
+    let hours: f32 = time_events.read().map(|event| event.hours).sum();
+    if hours <= 0.0 {
+        return;
+    }
+    for mut book in player_query.iter_mut() {
+        if !book.cooldowns.is_empty() {
+            book.tick(hours);
+        }
+    }
+}
+
+/// Mirror the ability book into save data whenever it changes
+pub fn sync_abilities_to_save(
+    player_query: Query<&AbilityBook, (With<Player>, Changed<AbilityBook>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    let Ok(book) = player_query.get_single() else {
+        return;
+    };
+    game_state.save_data.player_stats.abilities = book.clone();
+}
+
+/// Restore the ability book once a save has been loaded
+pub fn restore_abilities_from_save(mut slot: SaveSlot, mut player_query: Query<&mut AbilityBook, With<Player>>) {
+    let Ok(mut book) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(save) = slot.take() else {
+        return;
+    };
+    *book = save.player_stats.abilities.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::components::MAX_LEVEL;
+
+    #[test]
+    fn test_shipped_ability_database_parses() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join(AbilityDatabase::PATH);
+        let content = std::fs::read_to_string(path).unwrap();
+        let database: AbilityDatabase = ron::from_str(&content).unwrap();
+
+        assert!(!database.abilities.is_empty());
+        for (id, ability) in &database.abilities {
+            assert_eq!(id, &ability.id);
+            assert!((1..=MAX_LEVEL).contains(&ability.unlock_level), "{} unlocks at {}", id, ability.unlock_level);
+        }
+        assert!(database.abilities.values().any(|ability| ability.taint.is_some()));
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+
+use crate::world::components::{
+    Becoming, ChoiceLedger, Companion, ConsequenceEffect, MoralChoice, MoralPath, companion_reaction_trust,
+};
+use crate::world::resources::game_state::{GameState, PlayerChoice, SaveData, WorldEvent, WorldEventType};
+use crate::world::state::{DreadLevel, WorldState};
+use crate::world::systems::factions::ReputationChangeEvent;
+use crate::world::systems::save::SavedResource;
+
+/// Prints the choice ledger for designers
+const LEDGER_DEBUG_KEY: KeyCode = KeyCode::F9;
+
+/// The player's path or stage of becoming changed
+#[derive(Event)]
+pub struct AlignmentChangedEvent {
+    pub path: MoralPath,
+    pub stage: Becoming,
+}
+
+/// A recorded player choice as the alignment system sees it. Companion
+/// reactions become trust changes; everything is sorted so replays match.
+fn moral_choice(choice: &PlayerChoice) -> MoralChoice {
+    let mut effects = Vec::new();
+    if choice.dread_impact != 0.0 {
+        effects.push(ConsequenceEffect::Dread {
+            amount: choice.dread_impact,
+        });
+    }
+
+    let mut reactions: Vec<_> = choice.companion_reactions.iter().collect();
+    reactions.sort();
+    for (companion, reaction) in reactions {
+        let amount = companion_reaction_trust(reaction);
+        if amount != 0.0 {
+            effects.push(ConsequenceEffect::CompanionTrust {
+                companion: Some(companion.clone()),
+                amount,
+            });
+        }
+    }
+
+    let mut factions: Vec<_> = choice.faction_effects.iter().collect();
+    factions.sort();
+    for (faction, amount) in factions {
+        effects.push(ConsequenceEffect::Reputation {
+            faction: faction.clone(),
+            amount: *amount,
+        });
+    }
+
+    effects.extend(choice.effects.iter().cloned());
+    MoralChoice {
+        id: choice.choice_id.clone(),
+        text: choice.choice_text.clone(),
+        alignment: choice.alignment,
+        effects,
+        delayed: choice.delayed.clone(),
+    }
+}
+
+/// Apply newly recorded choices: move alignment, apply their consequences
+/// now and schedule the rest. Scheduled consequences fire once the player
+/// reaches their band.
+#[allow(clippy::too_many_arguments)]
+pub fn choice_consequence_system(
+    mut game_state: ResMut<GameState>,
+    mut ledger: ResMut<ChoiceLedger>,
+    world_state: Res<WorldState>,
+    mut dread: ResMut<DreadLevel>,
+    mut companions: Query<&mut Companion>,
+    mut reputation_events: EventWriter<ReputationChangeEvent>,
+    mut alignment_events: EventWriter<AlignmentChangedEvent>,
+) {
+    let band = world_state.world_progression;
+    let has_new_choices = game_state.player_choices.iter().any(|choice| !choice.consequences_applied);
+    let has_due = ledger.pending.iter().any(|scheduled| scheduled.due_band <= band);
+    if !has_new_choices && !has_due {
+        return;
+    }
+
+    let before = (ledger.alignment.path(), ledger.alignment.stage());
+    let mut effects: Vec<(String, ConsequenceEffect)> = Vec::new();
+    for choice in game_state.player_choices.iter_mut().filter(|choice| !choice.consequences_applied) {
+        let source = format!("choice {}", choice.choice_id);
+        effects.extend(ledger.record(&moral_choice(choice), band).into_iter().map(|effect| (source.clone(), effect)));
+        choice.consequences_applied = true;
+    }
+    for scheduled in ledger.take_due(band) {
+        info!("{} comes to pass", scheduled.description);
+        let source = format!("choice {}", scheduled.choice_id);
+        effects.extend(scheduled.effects.into_iter().map(|effect| (source.clone(), effect)));
+    }
+
+    for (source, effect) in effects {
+        let description = effect.describe();
+        let event_type = match effect {
+            ConsequenceEffect::Dread { amount } => {
+                if amount > 0.0 {
+                    dread.add_dread(amount);
+                } else {
+                    dread.remove_dread(-amount);
+                }
+                continue;
+            }
+            ConsequenceEffect::CompanionTrust { companion, amount } => {
+                for mut member in companions.iter_mut() {
+                    if companion.as_ref().is_none_or(|name| *name == member.name) {
+                        member.trust = (member.trust + amount).clamp(0.0, 100.0);
+                    }
+                }
+                continue;
+            }
+            ConsequenceEffect::Reputation { faction, amount } => {
+                reputation_events.send(ReputationChangeEvent {
+                    faction,
+                    amount,
+                    reason: source,
+                });
+                continue;
+            }
+            ConsequenceEffect::StoryFlag { flag, value } => {
+                game_state.set_story_flag(flag, value);
+                continue;
+            }
+            ConsequenceEffect::CompanionCrisis { companion, crisis } => WorldEventType::CompanionCrisis {
+                companion_name: companion,
+                crisis_type: crisis,
+            },
+            ConsequenceEffect::CorruptionSpread { radius, intensity } => {
+                let Some(center) = world_state.player_hex else {
+                    continue;
+                };
+                WorldEventType::CorruptionSpread {
+                    center,
+                    radius,
+                    intensity,
+                }
+            }
+            ConsequenceEffect::VoidIncursion { stability } => {
+                let Some(tear_location) = world_state.player_hex else {
+                    continue;
+                };
+                WorldEventType::VoidIncursion {
+                    tear_location,
+                    stability,
+                }
+            }
+        };
+        let trigger_time = game_state.game_time;
+        game_state.add_world_event(WorldEvent {
+            event_type,
+            trigger_time,
+            location: world_state.player_hex,
+            is_completed: false,
+            consequences: vec![description],
+        });
+    }
+
+    let (path, stage) = (ledger.alignment.path(), ledger.alignment.stage());
+    if (path, stage) != before {
+        if stage > before.1 {
+            warn!("You are becoming what you fought against ({:?})", stage);
+        } else {
+            info!("Your path: {:?} ({:?})", path, stage);
+        }
+        alignment_events.send(AlignmentChangedEvent { path, stage });
+    }
+}
+
+/// Print the choice ledger for designers
+pub fn choice_ledger_debug_system(keyboard: Res<ButtonInput<KeyCode>>, ledger: Res<ChoiceLedger>) {
+    if keyboard.just_pressed(LEDGER_DEBUG_KEY) {
+        info!("Choice ledger\n{}", ledger.report());
+    }
+}
+
+impl SavedResource for ChoiceLedger {
+    fn is_saved(&self, save: &SaveData) -> bool {
+        save.choice_ledger == *self
+    }
+
+    fn save(&self, save: &mut SaveData) {
+        save.choice_ledger = self.clone();
+    }
+
+    fn restore(&mut self, save: &SaveData) {
+        *self = save.choice_ledger.clone();
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use crate::world::components::{AssetManifest, AssetStatus, ASSET_MANIFEST_FILE};
 use crate::world::state::AssetHandles;
 use bevy::asset::LoadState;
 use std::fs;
@@ -143,6 +144,18 @@ fn generate_missing_assets_report() {
         }
     }
 
+    // Models the asset manifest expects from the pipeline
+    let manifest = fs::read_to_string(root.join(ASSET_MANIFEST_FILE))
+        .ok()
+        .and_then(|content| ron::from_str::<AssetManifest>(&content).ok());
+    for entry in manifest.iter().flat_map(|manifest| manifest.entries.values()) {
+        if !root.join(&entry.path).exists() {
+            missing.push(format!("GLB: {} ({})", entry.path, entry.id));
+        } else if entry.status == AssetStatus::Stale {
+            missing.push(format!("STALE GLB: {} ({})", entry.path, entry.id));
+        }
+    }
+
     // UI and dialogue assets
     let cob_paths = [
         "ui/splash_screen.cob",
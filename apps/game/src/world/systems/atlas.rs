@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::world::components::{
+    AbilityEffect, Atlas, AtlasMarker, BiomeType, Discovery, HexCoord, MarkerKind, NpcInteraction, QuestDatabase,
+    QuestLog, QuestObjective, QuestUpdate, Tile,
+};
+use crate::world::resources::game_state::SaveData;
+use crate::world::state::WorldState;
+use crate::world::systems::abilities::AbilityUsedEvent;
+use crate::world::systems::hex_world::{DungeonEntranceMarker, EntityCorrelations};
+use crate::world::systems::npcs::NpcInteractionEvent;
+use crate::world::systems::quests::QuestProgressEvent;
+use crate::world::systems::save::SavedResource;
+use crate::utils::hex::world_to_hex;
+
+/// Writes the atlas out as SVG for design review
+const ATLAS_EXPORT_KEY: KeyCode = KeyCode::F10;
+const ATLAS_EXPORT_PATH: &str = "atlas.svg";
+/// Quest destinations are rumoured this loosely
+const QUEST_RUMOUR_RADIUS: u32 = 1;
+/// Dungeons heard of in conversation are rumoured this loosely
+const GOSSIP_RUMOUR_RADIUS: u32 = 2;
+
+/// Word of a distant place, e.g. from dialogue or a found map
+#[derive(Event)]
+pub struct RumourEvent {
+    pub hex: HexCoord,
+    pub radius: u32,
+    pub marker: Option<AtlasMarker>,
+}
+
+/// Hexes the player saw or heard of for the first time
+#[derive(Event)]
+pub struct AtlasRevealedEvent {
+    pub hexes: Vec<HexCoord>,
+    pub discovery: Discovery,
+}
+
+/// Settlements, dungeons and landmarks the world data places on a hex
+fn correlation_markers(correlations: &EntityCorrelations, hex: HexCoord) -> Vec<AtlasMarker> {
+    let entities = correlations.get_entities_at_hex((hex.q, hex.r));
+    let marker = |kind| move |id: &String| AtlasMarker { kind, id: id.clone() };
+    entities
+        .settlements
+        .iter()
+        .map(marker(MarkerKind::Settlement))
+        .chain(entities.dungeons.iter().map(marker(MarkerKind::Dungeon)))
+        .chain(entities.special_features.iter().map(marker(MarkerKind::Landmark)))
+        .collect()
+}
+
+/// Each time the player reaches a new hex, mark it visited and look around
+/// from it, annotating whatever comes into view
+pub fn atlas_sight_system(
+    world_state: Res<WorldState>,
+    tiles: Query<&Tile>,
+    correlations: Option<Res<EntityCorrelations>>,
+    mut atlas: ResMut<Atlas>,
+    mut revealed_events: EventWriter<AtlasRevealedEvent>,
+    mut last_hex: Local<Option<HexCoord>>,
+) {
+    let Some(player_hex) = world_state.player_hex else {
+        return;
+    };
+    if *last_hex == Some(player_hex) {
+        return;
+    }
+    *last_hex = Some(player_hex);
+
+    let terrain: HashMap<HexCoord, BiomeType> = tiles.iter().map(|tile| (tile.coords, tile.biome_type.clone())).collect();
+    let revealed = atlas.reveal_from(player_hex, &terrain);
+    if revealed.is_empty() {
+        return;
+    }
+    if let Some(correlations) = correlations {
+        for hex in &revealed {
+            for marker in correlation_markers(&correlations, *hex) {
+                atlas.annotate(*hex, marker);
+            }
+        }
+    }
+    revealed_events.send(AtlasRevealedEvent {
+        hexes: revealed,
+        discovery: Discovery::Seen,
+    });
+}
+
+/// NPCs who talk with the player tell of the nearest dungeon the map does
+/// not show yet
+pub fn npc_rumour_system(
+    mut interaction_events: EventReader<NpcInteractionEvent>,
+    atlas: Res<Atlas>,
+    npcs: Query<&GlobalTransform>,
+    entrances: Query<(&DungeonEntranceMarker, &GlobalTransform)>,
+    mut rumour_events: EventWriter<RumourEvent>,
+) {
+    for event in interaction_events.read() {
+        if !matches!(event.interaction, NpcInteraction::Dialogue { .. }) {
+            continue;
+        }
+        let Ok(npc_transform) = npcs.get(event.npc) else {
+            continue;
+        };
+        let npc_hex = world_to_hex(npc_transform.translation());
+        let nearest = entrances
+            .iter()
+            .map(|(entrance, transform)| (entrance, world_to_hex(transform.translation())))
+            .filter(|(_, hex)| atlas.discovery(*hex) == Discovery::Unseen)
+            .min_by_key(|(_, hex)| npc_hex.distance_to(hex));
+        if let Some((entrance, hex)) = nearest {
+            rumour_events.send(RumourEvent {
+                hex,
+                radius: GOSSIP_RUMOUR_RADIUS,
+                marker: Some(AtlasMarker {
+                    kind: MarkerKind::Dungeon,
+                    id: entrance.dungeon_uuid.clone(),
+                }),
+            });
+        }
+    }
+}
+
+/// Rumours, quest destinations and revealing abilities put hexes on the map
+/// beyond the player's sight
+#[allow(clippy::too_many_arguments)]
+pub fn atlas_rumour_system(
+    mut rumour_events: EventReader<RumourEvent>,
+    mut quest_events: EventReader<QuestProgressEvent>,
+    mut ability_events: EventReader<AbilityUsedEvent>,
+    quests: Res<QuestDatabase>,
+    quest_log: Res<QuestLog>,
+    tiles: Query<&Tile>,
+    mut atlas: ResMut<Atlas>,
+    mut revealed_events: EventWriter<AtlasRevealedEvent>,
+) {
+    let mut rumoured = Vec::new();
+    for event in rumour_events.read() {
+        rumoured.extend(atlas.rumour(event.hex, event.radius));
+        if let Some(marker) = &event.marker {
+            atlas.annotate(event.hex, marker.clone());
+        }
+    }
+
+    // A quest's next destination is rumoured as soon as the step is known
+    for event in quest_events.read() {
+        let quest_id = match &event.update {
+            QuestUpdate::Started { quest_id } | QuestUpdate::StepCompleted { quest_id, .. } => quest_id,
+            _ => continue,
+        };
+        if let Some(QuestObjective::ReachHex { hex }) =
+            quest_log.current_step(&quests, quest_id).map(|step| &step.objective)
+        {
+            rumoured.extend(atlas.rumour(*hex, QUEST_RUMOUR_RADIUS));
+        }
+    }
+
+    let mut seen = Vec::new();
+    for event in ability_events.read() {
+        if !event.effects.iter().any(|effect| matches!(effect, AbilityEffect::Reveal)) {
+            continue;
+        }
+        for hex in &event.hexes {
+            let biome = tiles.iter().find(|tile| tile.coords == *hex).map(|tile| &tile.biome_type);
+            if atlas.discovery(*hex) < Discovery::Seen {
+                seen.push(*hex);
+            }
+            atlas.discover(*hex, Discovery::Seen, biome);
+        }
+    }
+
+    if !rumoured.is_empty() {
+        revealed_events.send(AtlasRevealedEvent {
+            hexes: rumoured,
+            discovery: Discovery::Rumoured,
+        });
+    }
+    if !seen.is_empty() {
+        revealed_events.send(AtlasRevealedEvent {
+            hexes: seen,
+            discovery: Discovery::Seen,
+        });
+    }
+}
+
+/// Write the atlas to an SVG file for designers
+pub fn export_atlas_system(keyboard: Res<ButtonInput<KeyCode>>, atlas: Res<Atlas>) {
+    if !keyboard.just_pressed(ATLAS_EXPORT_KEY) {
+        return;
+    }
+    match fs::write(ATLAS_EXPORT_PATH, atlas.to_svg()) {
+        Ok(()) => info!(
+            "Atlas written to {} ({} visited, {} seen, {} rumoured)",
+            ATLAS_EXPORT_PATH,
+            atlas.count(Discovery::Visited),
+            atlas.count(Discovery::Seen),
+            atlas.count(Discovery::Rumoured)
+        ),
+        Err(e) => warn!("Failed to write atlas to {}: {}", ATLAS_EXPORT_PATH, e),
+    }
+}
+
+impl SavedResource for Atlas {
+    fn is_saved(&self, save: &SaveData) -> bool {
+        save.atlas == *self
+    }
+
+    fn save(&self, save: &mut SaveData) {
+        save.atlas = self.clone();
+    }
+
+    fn restore(&mut self, save: &SaveData) {
+        *self = save.atlas.clone();
+    }
+}
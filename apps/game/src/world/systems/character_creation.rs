@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+
+use crate::game::GameStateEnum;
+use crate::world::components::{
+    Attribute, Background, CharacterAppearance, CharacterSheet, Companion, Gender, Player, starting_companion,
+};
+use crate::world::resources::game_state::GameState;
+use crate::world::state::CharacterCreator;
+use crate::world::systems::save::SaveSlot;
+
+/// Where the preview model stands in front of the creation camera
+const PREVIEW_POSITION: Vec3 = Vec3::new(0.0, 0.0, -3.0);
+/// The starting companion joins a step behind the player
+const COMPANION_OFFSET: Vec3 = Vec3::new(-1.0, 0.0, -1.0);
+
+/// One choice on the creation screen
+#[derive(Debug, Clone)]
+pub enum CreatorAction {
+    SetName(String),
+    SetGender(Gender),
+    SetBackground(Background),
+    SetAppearance(CharacterAppearance),
+    Raise(Attribute),
+    Lower(Attribute),
+    Roll { seed: u64 },
+    PointBuy,
+    ChooseCompanion(String),
+    Confirm,
+    Cancel,
+}
+
+#[derive(Event)]
+pub struct CreatorActionEvent {
+    pub action: CreatorAction,
+}
+
+#[derive(Event)]
+pub struct CharacterCreatedEvent {
+    pub sheet: CharacterSheet,
+}
+
+/// The player model shown while creating a character
+#[derive(Component)]
+pub struct CharacterPreview {
+    pub model_path: String,
+}
+
+fn preview_scene(asset_server: &AssetServer, model_path: &str) -> SceneRoot {
+    SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(model_path.to_string())))
+}
+
+/// Start a fresh draft and put its model on screen
+pub fn enter_character_creation(
+    mut commands: Commands,
+    mut creator: ResMut<CharacterCreator>,
+    asset_server: Res<AssetServer>,
+) {
+    *creator = CharacterCreator::default();
+    let draft = &creator.draft;
+    let model_path = draft.appearance.get_player_model_path(&draft.gender);
+    let mut transform = Transform::from_translation(PREVIEW_POSITION);
+    draft.appearance.apply_customization(&mut transform);
+
+    commands.spawn((
+        preview_scene(&asset_server, &model_path),
+        transform,
+        Visibility::default(),
+        CharacterPreview { model_path },
+        Name::new("CharacterPreview"),
+    ));
+}
+
+pub fn exit_character_creation(mut commands: Commands, previews: Query<Entity, With<CharacterPreview>>) {
+    for entity in previews.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Apply creation-screen choices to the draft. A confirmed draft that
+/// validates is saved and the game begins; otherwise the reason is kept for
+/// the screen to show.
+pub fn character_creator_system(
+    mut action_events: EventReader<CreatorActionEvent>,
+    mut creator: ResMut<CharacterCreator>,
+    mut game_state: ResMut<GameState>,
+    mut next_state: ResMut<NextState<GameStateEnum>>,
+    mut created_events: EventWriter<CharacterCreatedEvent>,
+) {
+    for event in action_events.read() {
+        let draft = &mut creator.draft;
+        let result = match event.action.clone() {
+            CreatorAction::SetName(name) => {
+                draft.name = name;
+                Ok(())
+            }
+            CreatorAction::SetGender(gender) => {
+                draft.gender = gender;
+                Ok(())
+            }
+            CreatorAction::SetBackground(background) => {
+                draft.set_background(background);
+                Ok(())
+            }
+            CreatorAction::SetAppearance(appearance) => {
+                draft.appearance = appearance;
+                Ok(())
+            }
+            CreatorAction::Raise(attribute) => draft.raise(attribute),
+            CreatorAction::Lower(attribute) => draft.lower(attribute),
+            CreatorAction::Roll { seed } => {
+                draft.roll(seed);
+                Ok(())
+            }
+            CreatorAction::PointBuy => {
+                draft.use_point_buy();
+                Ok(())
+            }
+            CreatorAction::ChooseCompanion(name) => {
+                draft.companion = Some(name);
+                Ok(())
+            }
+            CreatorAction::Confirm => draft.build().map(|sheet| {
+                info!("{} the {:?} sets out with {}", sheet.data.name, sheet.background, sheet.companion);
+                game_state.save_data.character = Some(sheet.clone());
+                created_events.send(CharacterCreatedEvent { sheet });
+                next_state.set(GameStateEnum::Playing);
+            }),
+            CreatorAction::Cancel => {
+                next_state.set(GameStateEnum::MainMenu);
+                Ok(())
+            }
+        };
+
+        creator.last_error = match result {
+            Ok(()) => None,
+            Err(e) => {
+                info!("Character creation: {}", e);
+                Some(e.to_string())
+            }
+        };
+    }
+}
+
+/// Keep the preview model in step with the draft's gender and build
+pub fn update_character_preview(
+    creator: Res<CharacterCreator>,
+    asset_server: Res<AssetServer>,
+    mut previews: Query<(&mut CharacterPreview, &mut SceneRoot, &mut Transform)>,
+) {
+    if !creator.is_changed() {
+        return;
+    }
+    let draft = &creator.draft;
+    let model_path = draft.appearance.get_player_model_path(&draft.gender);
+    for (mut preview, mut scene, mut transform) in previews.iter_mut() {
+        if preview.model_path != model_path {
+            *scene = preview_scene(&asset_server, &model_path);
+            preview.model_path = model_path.clone();
+        }
+        draft.appearance.apply_customization(&mut transform);
+    }
+}
+
+/// Give the player their created (or loaded) character: the sheet itself and
+/// the companion they chose. Health and sanity follow from the sheet through
+/// the leveling systems.
+pub fn apply_character_sheet_system(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    mut slot: SaveSlot,
+    player_query: Query<(Entity, &Transform, Option<&CharacterSheet>), With<Player>>,
+    companions: Query<&Companion>,
+) {
+    let Some(sheet) = &game_state.save_data.character else {
+        return;
+    };
+    let Ok((entity, transform, current)) = player_query.get_single() else {
+        return;
+    };
+    let loaded = slot.take().is_some();
+    if current.is_some() && !loaded {
+        return;
+    }
+
+    commands
+        .entity(entity)
+        .insert((sheet.clone(), Name::new(sheet.data.name.clone())));
+
+    if companions.iter().any(|companion| companion.name == sheet.companion) {
+        return;
+    }
+    let Some(starting) = starting_companion(&sheet.companion) else {
+        warn!("Unknown starting companion {}", sheet.companion);
+        return;
+    };
+    commands.spawn((
+        Companion::new(starting.name.to_string(), starting.type_name().to_string()),
+        starting.companion_type.clone(),
+        Transform::from_translation(transform.translation + COMPANION_OFFSET),
+        Visibility::default(),
+        Name::new(format!("Companion_{}", starting.name)),
+    ));
+}
@@ -0,0 +1,121 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::utility::GenericTypePathCell;
+use bevy::reflect::TypePath;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// A resource read whole from one RON or JSON file under the assets root,
+/// written by the generators. Loading goes through the asset server, so the
+/// same path works from any working directory and on the web.
+pub trait DataFile: Resource + Sized {
+    /// What the file deserializes to
+    type Contents: DeserializeOwned + Send + Sync + 'static;
+
+    /// Relative to the assets root; `.json` files are read as JSON, the rest as RON
+    const PATH: &'static str;
+
+    fn from_contents(contents: Self::Contents) -> Self;
+}
+
+/// A data file's contents while it waits to become its resource
+#[derive(Asset)]
+pub struct DataAsset<T: Send + Sync + 'static>(pub T);
+
+impl<T: Send + Sync + 'static> TypePath for DataAsset<T> {
+    fn type_path() -> &'static str {
+        static CELL: GenericTypePathCell = GenericTypePathCell::new();
+        CELL.get_or_insert::<Self, _>(|| format!("game::DataAsset<{}>", std::any::type_name::<T>()))
+    }
+
+    fn short_type_path() -> &'static str {
+        static CELL: GenericTypePathCell = GenericTypePathCell::new();
+        CELL.get_or_insert::<Self, _>(|| format!("DataAsset<{}>", std::any::type_name::<T>()))
+    }
+}
+
+/// Reads any data file; the asset server picks the loader by the asset type
+/// asked for, so every `DataAsset` can share the extensions
+pub struct DataAssetLoader<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for DataAssetLoader<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: DeserializeOwned + Send + Sync + 'static> AssetLoader for DataAssetLoader<T> {
+    type Asset = DataAsset<T>;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let contents = if load_context.path().extension().is_some_and(|extension| extension == "json") {
+            serde_json::from_slice(&bytes)?
+        } else {
+            ron::de::from_bytes(&bytes)?
+        };
+        Ok(DataAsset(contents))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron", "json"]
+    }
+}
+
+/// Keeps a data file loaded until its resource has been built
+#[derive(Resource)]
+pub struct DataFileHandle<R: DataFile>(pub Handle<DataAsset<R::Contents>>);
+
+/// Loads `R` from its data file at startup, and again whenever the file
+/// changes. Until then `R` keeps whatever it was initialised with.
+pub struct DataFilePlugin<R>(PhantomData<fn() -> R>);
+
+impl<R> Default for DataFilePlugin<R> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<R: DataFile> Plugin for DataFilePlugin<R> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<DataAsset<R::Contents>>()
+            .init_asset_loader::<DataAssetLoader<R::Contents>>()
+            .add_systems(Startup, load_data_file::<R>)
+            .add_systems(PreUpdate, insert_data_file::<R>);
+    }
+}
+
+pub fn load_data_file<R: DataFile>(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(DataFileHandle::<R>(asset_server.load(R::PATH)));
+}
+
+/// Replace `R` with the file's contents once they have loaded. Failed loads
+/// are logged by the asset server and leave `R` as it was.
+pub fn insert_data_file<R: DataFile>(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<DataAsset<R::Contents>>>,
+    mut assets: ResMut<Assets<DataAsset<R::Contents>>>,
+    handle: Option<Res<DataFileHandle<R>>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in asset_events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) {
+            continue;
+        }
+        if let Some(DataAsset(contents)) = assets.remove(&handle.0) {
+            info!("Loaded {}", R::PATH);
+            commands.insert_resource(R::from_contents(contents));
+        }
+    }
+}
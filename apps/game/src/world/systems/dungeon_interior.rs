@@ -0,0 +1,746 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::world::components::{CombatStats, DungeonLayout, DungeonProgress, DungeonRoom, Player, RoomFog};
+use crate::world::resources::game_state::SaveData;
+use crate::world::state::{DreadLevel, WorldState};
+use crate::world::systems::data_files::DataFile;
+use crate::world::systems::hex_world::DungeonEntranceMarker;
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::save::SavedResource;
+use crate::utils::hex::{hex_to_world, world_to_hex};
+
+/// Dungeon interiors are built away from the overworld tilemap so both can exist at once
+const DUNGEON_INTERIOR_OFFSET: Vec3 = Vec3::new(10_000.0, 0.0, 10_000.0);
+
+/// Progression bands per step of threat for foes in rooms without a difficulty
+pub const BANDS_PER_THREAT: u32 = 20;
+
+/// Damage of one blow from a monster against an unarmoured player
+const FOE_BLOW_DAMAGE: f32 = 5.0;
+
+/// All dungeon layouts generated from HBF dungeon areas, keyed by dungeon UUID
+#[derive(Resource, Default)]
+pub struct DungeonLayouts {
+    pub layouts: HashMap<String, DungeonLayout>,
+}
+
+/// Written by `ron-generator dungeons`
+impl DataFile for DungeonLayouts {
+    type Contents = Vec<DungeonLayout>;
+    const PATH: &'static str = "world/dungeons.ron";
+
+    fn from_contents(contents: Vec<DungeonLayout>) -> Self {
+        Self {
+            layouts: contents.into_iter().map(|layout| (layout.dungeon_uuid.clone(), layout)).collect(),
+        }
+    }
+}
+
+impl DungeonLayouts {
+    pub fn get(&self, dungeon_uuid: &str) -> Option<&DungeonLayout> {
+        self.layouts.get(dungeon_uuid)
+    }
+}
+
+/// Progress for every dungeon the player has entered, mirrored into save data
+#[derive(Resource, Default)]
+pub struct DungeonProgressLedger {
+    pub progress: HashMap<String, DungeonProgress>,
+}
+
+impl DungeonProgressLedger {
+    pub fn entry(&mut self, dungeon_uuid: &str) -> &mut DungeonProgress {
+        self.progress.entry(dungeon_uuid.to_string()).or_default()
+    }
+}
+
+/// The dungeon the player is currently inside, if any
+#[derive(Resource, Default)]
+pub struct ActiveDungeon {
+    pub dungeon_uuid: Option<String>,
+    pub current_area: i32,
+    pub root: Option<Entity>,
+    pub return_translation: Vec3,
+}
+
+impl ActiveDungeon {
+    pub fn is_inside(&self) -> bool {
+        self.dungeon_uuid.is_some()
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct DungeonInteriorRoot {
+    pub dungeon_uuid: String,
+}
+
+#[derive(Component, Debug)]
+pub struct DungeonRoomMarker {
+    pub dungeon_uuid: String,
+    pub area_number: i32,
+}
+
+#[derive(Component, Debug)]
+pub struct DungeonMonster {
+    pub area_number: i32,
+    pub uuid: String,
+    pub name: String,
+    pub count: u32,
+    pub threat: u32,
+}
+
+#[derive(Component, Debug)]
+pub struct DungeonTrap {
+    pub area_number: i32,
+    pub uuid: String,
+    pub name: String,
+}
+
+#[derive(Component, Debug)]
+pub struct DungeonTreasure {
+    pub area_number: i32,
+    pub uuid: String,
+    pub name: String,
+}
+
+#[derive(Event)]
+pub struct EnterDungeonEvent {
+    pub dungeon_uuid: String,
+}
+
+#[derive(Event)]
+pub struct ExitDungeonEvent;
+
+#[derive(Event)]
+pub struct DungeonMoveEvent {
+    pub to_area: i32,
+}
+
+/// The player attacks the monsters in the current room
+#[derive(Event)]
+pub struct DungeonFightEvent;
+
+/// Sent by combat when a dungeon monster group is beaten
+#[derive(Event)]
+pub struct MonsterDefeatedEvent {
+    pub monster_uuid: String,
+    /// Foes in the group and the threat of each, for experience
+    pub count: u32,
+    pub threat: u32,
+}
+
+#[derive(Event)]
+pub struct TreasureLootedEvent {
+    pub treasure_uuid: String,
+}
+
+#[derive(Event)]
+pub struct DungeonAreaClearedEvent {
+    pub dungeon_uuid: String,
+    pub area_number: i32,
+}
+
+/// Pressing Space on a hex with an accessible dungeon entrance enters that dungeon
+pub fn dungeon_entrance_interaction_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    world_state: Res<WorldState>,
+    active_dungeon: Res<ActiveDungeon>,
+    entrances: Query<(&DungeonEntranceMarker, &GlobalTransform)>,
+    mut enter_events: EventWriter<EnterDungeonEvent>,
+) {
+    if active_dungeon.is_inside() || !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let Some(player_hex) = world_state.player_hex else {
+        return;
+    };
+
+    for (entrance, transform) in entrances.iter() {
+        if entrance.is_accessible && world_to_hex(transform.translation()) == player_hex {
+            enter_events.send(EnterDungeonEvent {
+                dungeon_uuid: entrance.dungeon_uuid.clone(),
+            });
+            break;
+        }
+    }
+}
+
+/// Build the interior for an entered dungeon: one entity per room, with monsters,
+/// traps and treasure that the player has not already dealt with
+pub fn build_dungeon_interior_system(
+    mut commands: Commands,
+    mut enter_events: EventReader<EnterDungeonEvent>,
+    layouts: Res<DungeonLayouts>,
+    world_state: Res<WorldState>,
+    mut ledger: ResMut<DungeonProgressLedger>,
+    mut active_dungeon: ResMut<ActiveDungeon>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) {
+    let Some(event) = enter_events.read().last() else {
+        return;
+    };
+    let Some(layout) = layouts.get(&event.dungeon_uuid) else {
+        warn!("No interior layout generated for dungeon {}", event.dungeon_uuid);
+        return;
+    };
+
+    if let Some(previous_root) = active_dungeon.root.take() {
+        commands.entity(previous_root).despawn_recursive();
+    }
+
+    let progress = ledger.entry(&layout.dungeon_uuid);
+    progress.visit(layout, layout.entrance_area);
+
+    let root = commands.spawn((
+        DungeonInteriorRoot { dungeon_uuid: layout.dungeon_uuid.clone() },
+        Transform::from_translation(DUNGEON_INTERIOR_OFFSET),
+        Visibility::default(),
+        Name::new(format!("DungeonInterior_{}", layout.name)),
+    )).id();
+
+    for room in &layout.rooms {
+        let fog = progress.fog_for(room.area_number);
+        let room_entity = commands.spawn((
+            DungeonRoomMarker {
+                dungeon_uuid: layout.dungeon_uuid.clone(),
+                area_number: room.area_number,
+            },
+            fog,
+            Transform::from_translation(hex_to_world(room.position)),
+            fog_visibility(fog),
+            Name::new(format!("DungeonRoom_{}", room.area_number)),
+        )).id();
+        commands.entity(root).add_child(room_entity);
+
+        if !progress.is_cleared(room.area_number) {
+            let threat = room_threat(room, world_state.world_progression);
+            for monster in &room.monsters {
+                let monster_entity = commands.spawn((
+                    DungeonMonster {
+                        area_number: room.area_number,
+                        uuid: monster.uuid.clone(),
+                        name: monster.name.clone(),
+                        count: monster.count,
+                        threat,
+                    },
+                    Transform::from_translation(Vec3::new(0.0, 1.0, 0.0)),
+                    Visibility::Inherited,
+                    Name::new(format!("DungeonMonster_{}", monster.name)),
+                )).id();
+                commands.entity(room_entity).add_child(monster_entity);
+            }
+        }
+
+        for trap in room.traps.iter().filter(|trap| !progress.is_sprung(&trap.uuid)) {
+            let trap_entity = commands.spawn((
+                DungeonTrap {
+                    area_number: room.area_number,
+                    uuid: trap.uuid.clone(),
+                    name: trap.name.clone(),
+                },
+                Transform::default(),
+                Visibility::Inherited,
+                Name::new(format!("DungeonTrap_{}", trap.name)),
+            )).id();
+            commands.entity(room_entity).add_child(trap_entity);
+        }
+
+        if let Some(treasure) = room.treasure.as_ref().filter(|t| !progress.is_looted(&t.uuid)) {
+            let treasure_entity = commands.spawn((
+                DungeonTreasure {
+                    area_number: room.area_number,
+                    uuid: treasure.uuid.clone(),
+                    name: treasure.name.clone(),
+                },
+                Transform::from_translation(Vec3::new(0.5, 0.5, 0.5)),
+                Visibility::Inherited,
+                Name::new(format!("DungeonTreasure_{}", treasure.name)),
+            )).id();
+            commands.entity(room_entity).add_child(treasure_entity);
+        }
+    }
+
+    if let Ok(mut player_transform) = player_query.get_single_mut() {
+        let entrance_offset = layout.entrance().map_or(Vec3::ZERO, |room| hex_to_world(room.position));
+        active_dungeon.return_translation = player_transform.translation;
+        player_transform.translation = DUNGEON_INTERIOR_OFFSET + entrance_offset + Vec3::Y;
+    }
+
+    active_dungeon.dungeon_uuid = Some(layout.dungeon_uuid.clone());
+    active_dungeon.current_area = layout.entrance_area;
+    active_dungeon.root = Some(root);
+
+    info!("Entered {} ({} rooms)", layout.name, layout.rooms.len());
+}
+
+/// Keys inside a dungeon: a number key walks through that exit of the
+/// current room, F fights whatever is in it, L loots it once nothing guards
+/// it, and Escape at the entrance leaves
+#[allow(clippy::too_many_arguments)]
+pub fn dungeon_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    layouts: Res<DungeonLayouts>,
+    active_dungeon: Res<ActiveDungeon>,
+    monsters: Query<&DungeonMonster>,
+    treasures: Query<&DungeonTreasure>,
+    mut move_events: EventWriter<DungeonMoveEvent>,
+    mut fight_events: EventWriter<DungeonFightEvent>,
+    mut loot_events: EventWriter<TreasureLootedEvent>,
+    mut exit_events: EventWriter<ExitDungeonEvent>,
+) {
+    let Some(layout) = active_dungeon.dungeon_uuid.as_ref().and_then(|uuid| layouts.get(uuid)) else {
+        return;
+    };
+    let area = active_dungeon.current_area;
+
+    if let Some(index) = number_key_pressed(&keyboard, NumberKeyModifier::None) {
+        if let Some(&to_area) = layout.neighbors(area).get(index) {
+            move_events.send(DungeonMoveEvent { to_area });
+        } else {
+            info!("There is no exit {} from this room", index + 1);
+        }
+    }
+    if keyboard.just_pressed(KeyCode::KeyF) {
+        fight_events.send(DungeonFightEvent);
+    }
+    if keyboard.just_pressed(KeyCode::KeyL) {
+        if let Some(guard) = monsters.iter().find(|monster| monster.area_number == area) {
+            info!("The {} stand between you and the treasure", guard.name);
+        } else {
+            for treasure in treasures.iter().filter(|treasure| treasure.area_number == area) {
+                loot_events.send(TreasureLootedEvent {
+                    treasure_uuid: treasure.uuid.clone(),
+                });
+            }
+        }
+    }
+    if keyboard.just_pressed(KeyCode::Escape) && area == layout.entrance_area {
+        exit_events.send(ExitDungeonEvent);
+    }
+}
+
+/// Move between connected rooms, revealing neighbours of each room entered
+pub fn dungeon_room_navigation_system(
+    mut move_events: EventReader<DungeonMoveEvent>,
+    layouts: Res<DungeonLayouts>,
+    mut ledger: ResMut<DungeonProgressLedger>,
+    mut active_dungeon: ResMut<ActiveDungeon>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) {
+    let Some(dungeon_uuid) = active_dungeon.dungeon_uuid.clone() else {
+        move_events.clear();
+        return;
+    };
+    let Some(layout) = layouts.get(&dungeon_uuid) else {
+        return;
+    };
+
+    for event in move_events.read() {
+        if !layout.are_connected(active_dungeon.current_area, event.to_area) {
+            continue;
+        }
+        let Some(room) = layout.room(event.to_area) else {
+            continue;
+        };
+
+        ledger.entry(&dungeon_uuid).visit(layout, event.to_area);
+        active_dungeon.current_area = event.to_area;
+
+        if let Ok(mut player_transform) = player_query.get_single_mut() {
+            player_transform.translation = DUNGEON_INTERIOR_OFFSET + hex_to_world(room.position) + Vec3::Y;
+        }
+    }
+}
+
+/// Keep room fog and visibility in step with the progress ledger
+pub fn dungeon_fog_of_war_system(
+    active_dungeon: Res<ActiveDungeon>,
+    ledger: Res<DungeonProgressLedger>,
+    mut rooms: Query<(&DungeonRoomMarker, &mut RoomFog, &mut Visibility)>,
+) {
+    if !ledger.is_changed() {
+        return;
+    }
+    let Some(progress) = active_dungeon
+        .dungeon_uuid
+        .as_ref()
+        .and_then(|uuid| ledger.progress.get(uuid))
+    else {
+        return;
+    };
+
+    for (room, mut fog, mut visibility) in rooms.iter_mut() {
+        let new_fog = progress.fog_for(room.area_number);
+        if *fog != new_fog {
+            *fog = new_fog;
+            *visibility = fog_visibility(new_fog);
+        }
+    }
+}
+
+/// Traps in a room spring the first time the player enters it
+pub fn dungeon_trap_trigger_system(
+    mut commands: Commands,
+    active_dungeon: Res<ActiveDungeon>,
+    mut ledger: ResMut<DungeonProgressLedger>,
+    mut dread_level: ResMut<DreadLevel>,
+    traps: Query<(Entity, &DungeonTrap)>,
+) {
+    if !active_dungeon.is_changed() {
+        return;
+    }
+    let Some(dungeon_uuid) = active_dungeon.dungeon_uuid.clone() else {
+        return;
+    };
+
+    for (entity, trap) in traps.iter() {
+        if trap.area_number != active_dungeon.current_area {
+            continue;
+        }
+        info!("Trap sprung: {}", trap.name);
+        dread_level.add_dread(2.0);
+        ledger.entry(&dungeon_uuid).sprung_traps.insert(trap.uuid.clone());
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Fight every monster group in the current room in turn. Each group wounds
+/// the player according to its size and threat before it falls; if the
+/// player drops, the rest of the room stands.
+pub fn dungeon_combat_system(
+    mut fight_events: EventReader<DungeonFightEvent>,
+    active_dungeon: Res<ActiveDungeon>,
+    monsters: Query<&DungeonMonster>,
+    mut player_query: Query<(&mut Player, &CombatStats)>,
+    mut defeat_events: EventWriter<MonsterDefeatedEvent>,
+) {
+    if fight_events.read().count() == 0 || !active_dungeon.is_inside() {
+        return;
+    }
+    let Ok((mut player, combat)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let mut foes = monsters
+        .iter()
+        .filter(|monster| monster.area_number == active_dungeon.current_area)
+        .peekable();
+    if foes.peek().is_none() {
+        info!("There is nothing here to fight");
+        return;
+    }
+    for monster in foes {
+        if player.health <= 0.0 {
+            break;
+        }
+        let damage = fight_damage(combat, monster.count, monster.threat);
+        player.health = (player.health - damage).max(0.0);
+        if player.health <= 0.0 {
+            warn!("The {} overwhelm you", monster.name);
+            break;
+        }
+        info!("You fight off the {} and take {:.0} damage", monster.name, damage);
+        defeat_events.send(MonsterDefeatedEvent {
+            monster_uuid: monster.uuid.clone(),
+            count: monster.count,
+            threat: monster.threat,
+        });
+    }
+}
+
+/// Defeated monsters leave the room so the area can be cleared
+pub fn dungeon_monster_defeat_system(
+    mut commands: Commands,
+    mut defeat_events: EventReader<MonsterDefeatedEvent>,
+    active_dungeon: Res<ActiveDungeon>,
+    monsters: Query<(Entity, &DungeonMonster)>,
+) {
+    if !active_dungeon.is_inside() {
+        defeat_events.clear();
+        return;
+    }
+
+    for event in defeat_events.read() {
+        for (entity, monster) in monsters.iter() {
+            if monster.uuid == event.monster_uuid && monster.area_number == active_dungeon.current_area {
+                info!("Defeated {} {}", monster.count, monster.name);
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// An area is cleared once no monsters remain in it
+pub fn dungeon_area_clear_system(
+    active_dungeon: Res<ActiveDungeon>,
+    layouts: Res<DungeonLayouts>,
+    mut ledger: ResMut<DungeonProgressLedger>,
+    monsters: Query<&DungeonMonster>,
+    mut cleared_events: EventWriter<DungeonAreaClearedEvent>,
+) {
+    let Some(dungeon_uuid) = active_dungeon.dungeon_uuid.clone() else {
+        return;
+    };
+    let Some(layout) = layouts.get(&dungeon_uuid) else {
+        return;
+    };
+
+    let area_number = active_dungeon.current_area;
+    if ledger.progress.get(&dungeon_uuid).is_some_and(|p| p.is_cleared(area_number)) {
+        return;
+    }
+    if layout.room(area_number).is_none() || monsters.iter().any(|m| m.area_number == area_number) {
+        return;
+    }
+
+    ledger.entry(&dungeon_uuid).cleared_areas.insert(area_number);
+    cleared_events.send(DungeonAreaClearedEvent {
+        dungeon_uuid,
+        area_number,
+    });
+}
+
+pub fn dungeon_treasure_loot_system(
+    mut commands: Commands,
+    mut loot_events: EventReader<TreasureLootedEvent>,
+    active_dungeon: Res<ActiveDungeon>,
+    mut ledger: ResMut<DungeonProgressLedger>,
+    treasures: Query<(Entity, &DungeonTreasure)>,
+) {
+    let Some(dungeon_uuid) = active_dungeon.dungeon_uuid.clone() else {
+        loot_events.clear();
+        return;
+    };
+
+    for event in loot_events.read() {
+        for (entity, treasure) in treasures.iter() {
+            if treasure.uuid == event.treasure_uuid {
+                ledger.entry(&dungeon_uuid).looted_treasure.insert(treasure.uuid.clone());
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// Leave the dungeon, back to where the player entered it
+pub fn dungeon_exit_system(
+    mut commands: Commands,
+    mut exit_events: EventReader<ExitDungeonEvent>,
+    mut active_dungeon: ResMut<ActiveDungeon>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) {
+    if exit_events.read().count() == 0 || !active_dungeon.is_inside() {
+        return;
+    }
+
+    if let Some(root) = active_dungeon.root.take() {
+        commands.entity(root).despawn_recursive();
+    }
+    if let Ok(mut player_transform) = player_query.get_single_mut() {
+        player_transform.translation = active_dungeon.return_translation;
+    }
+    active_dungeon.dungeon_uuid = None;
+}
+
+impl SavedResource for DungeonProgressLedger {
+    fn is_saved(&self, save: &SaveData) -> bool {
+        save.dungeon_progress == self.progress
+    }
+
+    fn save(&self, save: &mut SaveData) {
+        save.dungeon_progress = self.progress.clone();
+    }
+
+    fn restore(&mut self, save: &SaveData) {
+        self.progress = save.dungeon_progress.clone();
+    }
+}
+
+/// Threat of a room's foes: its own difficulty where the data gives one,
+/// otherwise how far the journey has come
+fn room_threat(room: &DungeonRoom, world_progression: u32) -> u32 {
+    room.difficulty_level
+        .map_or(threat_for_band(world_progression), |level| level.max(1) as u32)
+}
+
+/// Threat of foes met at this progression band when nothing says otherwise
+pub fn threat_for_band(world_progression: u32) -> u32 {
+    1 + world_progression / BANDS_PER_THREAT
+}
+
+/// Damage taken beating a group of `foes` monsters of the given threat.
+/// Every foe lands a blow per step of threat; defence above the base 10
+/// softens each blow and attack ends the fight sooner.
+fn fight_damage(combat: &CombatStats, foes: u32, threat: u32) -> f32 {
+    let blows = (foes.max(1) * threat.max(1)) as f32;
+    let per_blow = (FOE_BLOW_DAMAGE - (combat.defense - 10) as f32).max(1.0);
+    let haste = 1.0 + combat.attack.max(0) as f32 * 0.1;
+    blows * per_blow / haste
+}
+
+fn fog_visibility(fog: RoomFog) -> Visibility {
+    match fog {
+        RoomFog::Hidden => Visibility::Hidden,
+        RoomFog::Revealed | RoomFog::Visited => Visibility::Inherited,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::components::{HexCoord, RoomOccupant};
+
+    const DUNGEON: &str = "crypt";
+
+    fn occupant(uuid: &str, name: &str, count: u32) -> RoomOccupant {
+        RoomOccupant {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            count,
+        }
+    }
+
+    fn room(area_number: i32, connected_areas: Vec<i32>) -> DungeonRoom {
+        DungeonRoom {
+            area_uuid: format!("area{}", area_number),
+            area_number,
+            description: String::new(),
+            connected_areas,
+            monsters: Vec::new(),
+            traps: Vec::new(),
+            treasure: None,
+            special_features: Vec::new(),
+            difficulty_level: Some(1),
+            position: HexCoord::new(0, 0),
+        }
+    }
+
+    /// An entrance hall with a guarded, trapped vault beyond it
+    fn layout() -> DungeonLayout {
+        let vault = DungeonRoom {
+            monsters: vec![occupant("ghouls", "Ghouls", 2)],
+            traps: vec![occupant("blade", "Swinging Blade", 1)],
+            treasure: Some(occupant("hoard", "Hoard", 1)),
+            ..room(2, vec![1])
+        };
+        DungeonLayout::new(DUNGEON.to_string(), "Crypt".to_string(), vec![room(1, vec![2]), vault])
+    }
+
+    fn app() -> App {
+        let layout = layout();
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<WorldState>()
+            .init_resource::<DreadLevel>()
+            .init_resource::<DungeonProgressLedger>()
+            .init_resource::<ActiveDungeon>()
+            .insert_resource(DungeonLayouts {
+                layouts: HashMap::from([(layout.dungeon_uuid.clone(), layout)]),
+            })
+            .add_event::<EnterDungeonEvent>()
+            .add_event::<ExitDungeonEvent>()
+            .add_event::<DungeonMoveEvent>()
+            .add_event::<DungeonFightEvent>()
+            .add_event::<MonsterDefeatedEvent>()
+            .add_event::<TreasureLootedEvent>()
+            .add_event::<DungeonAreaClearedEvent>()
+            .add_systems(Update, (
+                build_dungeon_interior_system,
+                dungeon_input_system,
+                dungeon_room_navigation_system,
+                dungeon_trap_trigger_system,
+                dungeon_combat_system,
+                dungeon_monster_defeat_system,
+                dungeon_area_clear_system,
+                dungeon_treasure_loot_system,
+                dungeon_exit_system,
+            ).chain());
+        app.world_mut().spawn((
+            Player {
+                health: 100.0,
+                max_health: 100.0,
+                sanity: 100.0,
+                max_sanity: 100.0,
+                inventory: Vec::new(),
+                mount: None,
+            },
+            CombatStats {
+                defense: 10,
+                ..CombatStats::default()
+            },
+            Transform::default(),
+        ));
+        app
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        let mut keyboard = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard.release_all();
+        keyboard.clear();
+        keyboard.press(key);
+        app.update();
+    }
+
+    fn progress(app: &App) -> DungeonProgress {
+        app.world().resource::<DungeonProgressLedger>().progress[DUNGEON].clone()
+    }
+
+    #[test]
+    fn fight_damage_follows_foes_and_gear() {
+        let unarmed = CombatStats {
+            defense: 10,
+            ..CombatStats::default()
+        };
+        let armoured = CombatStats {
+            attack: 5,
+            defense: 13,
+            ..CombatStats::default()
+        };
+        assert_eq!(fight_damage(&unarmed, 2, 1), 10.0);
+        assert_eq!(fight_damage(&unarmed, 2, 3), 30.0);
+        assert!(fight_damage(&armoured, 2, 3) < fight_damage(&unarmed, 2, 3));
+        // Heavy armour still takes a scratch from every blow
+        let plated = CombatStats {
+            defense: 40,
+            ..CombatStats::default()
+        };
+        assert_eq!(fight_damage(&plated, 4, 1), 4.0);
+    }
+
+    #[test]
+    fn player_walks_fights_loots_and_leaves() {
+        let mut app = app();
+        app.world_mut().send_event(EnterDungeonEvent {
+            dungeon_uuid: DUNGEON.to_string(),
+        });
+        app.update();
+        assert_eq!(app.world().resource::<ActiveDungeon>().current_area, 1);
+
+        press(&mut app, KeyCode::Digit1);
+        assert_eq!(app.world().resource::<ActiveDungeon>().current_area, 2);
+        assert!(progress(&app).is_sprung("blade"));
+
+        // The ghouls guard the hoard until they are beaten
+        press(&mut app, KeyCode::KeyL);
+        assert!(!progress(&app).is_looted("hoard"));
+
+        press(&mut app, KeyCode::KeyF);
+        app.update();
+        let mut players = app.world_mut().query::<&Player>();
+        assert_eq!(players.single(app.world()).unwrap().health, 90.0);
+        assert!(progress(&app).is_cleared(2));
+
+        press(&mut app, KeyCode::KeyL);
+        app.update();
+        assert!(progress(&app).is_looted("hoard"));
+
+        // Escape only leaves from the entrance
+        press(&mut app, KeyCode::Escape);
+        assert!(app.world().resource::<ActiveDungeon>().is_inside());
+        press(&mut app, KeyCode::Digit1);
+        press(&mut app, KeyCode::Escape);
+        assert!(!app.world().resource::<ActiveDungeon>().is_inside());
+    }
+}
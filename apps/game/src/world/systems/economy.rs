@@ -0,0 +1,383 @@
+use bevy::prelude::*;
+
+use crate::world::components::{
+    barter_modifier, Companion, CompanionType, CorruptionNode, GoodsCategory, HexCoord, Inventory, Item,
+    ItemDatabase, ItemType, Market, Player, PriceModifiers, ServiceType, Settlement,
+};
+use crate::world::resources::game_state::{GameState, WorldEventType};
+use crate::world::state::WorldState;
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::save::SaveSlot;
+use crate::world::systems::time_weather::TimeAdvancedEvent;
+use crate::utils::hex::world_to_hex;
+
+/// Companions only haggle for a party they trust at least this much
+const MIN_NEGOTIATOR_TRUST: f32 = 50.0;
+
+/// Goods for sale at a settlement with Commerce
+#[derive(Component, Debug, Clone)]
+pub struct Shop {
+    pub stock: Vec<Item>,
+}
+
+#[derive(Event)]
+pub struct BuyItemEvent {
+    pub settlement: Entity,
+    pub item_name: String,
+    pub quantity: u32,
+}
+
+#[derive(Event)]
+pub struct SellItemEvent {
+    pub settlement: Entity,
+    pub item_name: String,
+    pub quantity: u32,
+}
+
+/// A buy or sell went through at a settlement's shop
+#[derive(Event)]
+pub struct TradeCompletedEvent {
+    pub settlement: Entity,
+}
+
+/// A world event has cut a settlement's supply
+#[derive(Event)]
+pub struct SupplyShockEvent {
+    pub settlement: Entity,
+    pub intensity: f32,
+}
+
+/// Best haggler among companions who trust the player enough to bother
+pub fn party_negotiation_skill(companions: &Query<(&Companion, &CompanionType)>) -> u32 {
+    companions
+        .iter()
+        .filter(|(companion, _)| companion.trust >= MIN_NEGOTIATOR_TRUST)
+        .map(|(_, companion_type)| companion_type.negotiation_skill())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Hexes from `hex` to the nearest spreading corruption
+pub fn front_distance(hex: HexCoord, nodes: &Query<(&CorruptionNode, &GlobalTransform)>) -> Option<u32> {
+    nodes
+        .iter()
+        .filter(|(node, _)| node.is_spreading)
+        .map(|(_, transform)| hex.distance_to(&world_to_hex(transform.translation())))
+        .min()
+}
+
+fn price_modifiers(
+    settlement: &Settlement,
+    market: &Market,
+    hex: HexCoord,
+    companions: &Query<(&Companion, &CompanionType)>,
+    nodes: &Query<(&CorruptionNode, &GlobalTransform)>,
+) -> PriceModifiers {
+    PriceModifiers {
+        corruption_band: settlement.corruption_band(),
+        front_distance: front_distance(hex, nodes),
+        barter: barter_modifier(party_negotiation_skill(companions), market.reputation),
+    }
+}
+
+/// Give each settlement a market (restored from the save when it has one)
+/// and, if it trades, a shop stocked from that market
+pub fn attach_settlement_markets(
+    mut commands: Commands,
+    world_state: Res<WorldState>,
+    items: Res<ItemDatabase>,
+    game_state: Res<GameState>,
+    settlements: Query<(Entity, &Settlement), Added<Settlement>>,
+) {
+    for (entity, settlement) in settlements.iter() {
+        let market = game_state
+            .save_data
+            .markets
+            .get(&settlement.uuid)
+            .cloned()
+            .unwrap_or_else(|| Market::generate(settlement, world_state.seed));
+
+        if settlement.has_service(ServiceType::Commerce) {
+            commands.entity(entity).insert(Shop {
+                stock: market.stock(settlement, &items, world_state.seed),
+            });
+        }
+        commands.entity(entity).insert(market);
+    }
+}
+
+fn player_can_trade(world_state: &WorldState, settlement: &Settlement, hex: HexCoord) -> bool {
+    if world_state.player_hex != Some(hex) {
+        info!("You must be at {} to trade", settlement.name);
+        return false;
+    }
+    if !settlement.is_open(ServiceType::Commerce) {
+        info!("The market of {} is shuttered", settlement.name);
+        return false;
+    }
+    true
+}
+
+/// Hold B and press a number to buy one of that shop item, or hold V and
+/// press a number to sell one of that inventory item, at the shop where the
+/// player stands
+pub fn trade_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    world_state: Res<WorldState>,
+    shops: Query<(Entity, &Shop, &GlobalTransform)>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut buy_events: EventWriter<BuyItemEvent>,
+    mut sell_events: EventWriter<SellItemEvent>,
+) {
+    let buy = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyB));
+    let sell = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyV));
+    if buy.is_none() && sell.is_none() {
+        return;
+    }
+    let Some((settlement, shop, _)) = shops
+        .iter()
+        .find(|(_, _, transform)| world_state.player_hex == Some(world_to_hex(transform.translation())))
+    else {
+        info!("There is no shop here");
+        return;
+    };
+
+    if let Some(index) = buy {
+        match shop.stock.get(index) {
+            Some(item) => {
+                buy_events.send(BuyItemEvent {
+                    settlement,
+                    item_name: item.name.clone(),
+                    quantity: 1,
+                });
+            }
+            None => info!("The shop has no item {}", index + 1),
+        }
+    }
+    if let Some(index) = sell {
+        let Ok(inventory) = player_query.get_single() else {
+            return;
+        };
+        match inventory.items.get(index) {
+            Some(item) => {
+                sell_events.send(SellItemEvent {
+                    settlement,
+                    item_name: item.name.clone(),
+                    quantity: 1,
+                });
+            }
+            None => info!("You carry no item {}", index + 1),
+        }
+    }
+}
+
+/// Buy from a shop on the player's hex at the market's current price
+pub fn buy_item_system(
+    mut buy_events: EventReader<BuyItemEvent>,
+    world_state: Res<WorldState>,
+    mut shops: Query<(&Settlement, &mut Market, &mut Shop, &GlobalTransform)>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    companions: Query<(&Companion, &CompanionType)>,
+    nodes: Query<(&CorruptionNode, &GlobalTransform)>,
+    mut trade_events: EventWriter<TradeCompletedEvent>,
+) {
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        buy_events.clear();
+        return;
+    };
+
+    for event in buy_events.read() {
+        let Ok((settlement, mut market, mut shop, transform)) = shops.get_mut(event.settlement) else {
+            info!("There is no shop here");
+            continue;
+        };
+        let hex = world_to_hex(transform.translation());
+        if !player_can_trade(&world_state, settlement, hex) {
+            continue;
+        }
+        let Some(index) = shop
+            .stock
+            .iter()
+            .position(|item| item.name == event.item_name && item.quantity >= event.quantity)
+        else {
+            info!("{} has no {} x{} for sale", settlement.name, event.item_name, event.quantity);
+            continue;
+        };
+
+        let bought = Item {
+            quantity: event.quantity,
+            ..shop.stock[index].clone()
+        };
+        let (Some(category), Some(unit_price)) = (
+            GoodsCategory::for_item(&bought),
+            market.buy_price(
+                &bought,
+                settlement.scale,
+                &price_modifiers(settlement, &market, hex, &companions, &nodes),
+            ),
+        ) else {
+            continue;
+        };
+        if !inventory.can_add_item(&bought) {
+            info!("You cannot carry {}", bought.name);
+            continue;
+        }
+        let price = unit_price * event.quantity;
+        if !inventory.spend_currency(price) {
+            info!("{} costs {} cp", bought.name, price);
+            continue;
+        }
+
+        shop.stock[index].quantity -= event.quantity;
+        if shop.stock[index].quantity == 0 {
+            shop.stock.remove(index);
+        }
+        market.record_trade(category, -(event.quantity as i32), price);
+        info!("Bought {} x{} for {} cp", bought.name, bought.quantity, price);
+        inventory.add_item(bought);
+        trade_events.send(TradeCompletedEvent {
+            settlement: event.settlement,
+        });
+    }
+}
+
+/// Sell to a shop on the player's hex. Quest items and coin cannot be sold.
+pub fn sell_item_system(
+    mut sell_events: EventReader<SellItemEvent>,
+    world_state: Res<WorldState>,
+    mut shops: Query<(&Settlement, &mut Market, &mut Shop, &GlobalTransform)>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    companions: Query<(&Companion, &CompanionType)>,
+    nodes: Query<(&CorruptionNode, &GlobalTransform)>,
+    mut trade_events: EventWriter<TradeCompletedEvent>,
+) {
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        sell_events.clear();
+        return;
+    };
+
+    for event in sell_events.read() {
+        let Ok((settlement, mut market, mut shop, transform)) = shops.get_mut(event.settlement) else {
+            info!("There is no shop here");
+            continue;
+        };
+        let hex = world_to_hex(transform.translation());
+        if !player_can_trade(&world_state, settlement, hex) {
+            continue;
+        }
+        let is_coin = inventory
+            .items
+            .iter()
+            .any(|item| item.name == event.item_name && matches!(item.item_type, ItemType::Currency { .. }));
+        if is_coin {
+            continue;
+        }
+        let Some(sold) = inventory.discard_item(&event.item_name, event.quantity) else {
+            info!("You cannot sell {}", event.item_name);
+            continue;
+        };
+        let modifiers = price_modifiers(settlement, &market, hex, &companions, &nodes);
+        let (Some(category), Some(unit_price)) = (GoodsCategory::for_item(&sold), market.sell_price(&sold, settlement.scale, &modifiers))
+        else {
+            inventory.add_item(sold);
+            continue;
+        };
+
+        let payment = unit_price * sold.quantity;
+        if payment > 0 {
+            inventory.add_item(Item::coins("cp", payment));
+        }
+        market.record_trade(category, sold.quantity as i32, payment);
+        info!("Sold {} x{} for {} cp", sold.name, sold.quantity, payment);
+        match shop.stock.iter_mut().find(|item| item.can_stack_with(&sold)) {
+            Some(stack) => stack.quantity += sold.quantity,
+            None => shop.stock.push(sold),
+        }
+        trade_events.send(TradeCompletedEvent {
+            settlement: event.settlement,
+        });
+    }
+}
+
+/// Corruption spreading through a region cuts supply in every market it
+/// reaches, hardest at its centre
+pub fn supply_shock_system(
+    game_state: Res<GameState>,
+    mut markets: Query<(Entity, &mut Market, &GlobalTransform)>,
+    mut shock_events: EventWriter<SupplyShockEvent>,
+    mut events_seen: Local<usize>,
+) {
+    let world_events = &game_state.world_events;
+    if world_events.len() < *events_seen {
+        // World events were reset (new game or load)
+        *events_seen = 0;
+    }
+
+    for event in &world_events[*events_seen..] {
+        let WorldEventType::CorruptionSpread { center, radius, intensity } = &event.event_type else {
+            continue;
+        };
+        for (entity, mut market, transform) in markets.iter_mut() {
+            let distance = world_to_hex(transform.translation()).distance_to(center);
+            if distance > *radius {
+                continue;
+            }
+            let falloff = 1.0 - distance as f32 / (*radius + 1) as f32;
+            let shock = intensity.clamp(0.0, 1.0) * falloff;
+            market.apply_shock(shock);
+            shock_events.send(SupplyShockEvent {
+                settlement: entity,
+                intensity: shock,
+            });
+        }
+    }
+    *events_seen = world_events.len();
+}
+
+/// Supply drifts back toward normal as game hours pass; shops restock each
+/// morning from what their market can supply
+pub fn market_recovery_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    world_state: Res<WorldState>,
+    items: Res<ItemDatabase>,
+    mut markets: Query<(&Settlement, &mut Market, Option<&mut Shop>)>,
+) {
+    let (hours, days) = time_events
+        .read()
+        .fold((0.0, 0), |(hours, days), event| (hours + event.hours, days + event.days_passed));
+    if hours <= 0.0 {
+        return;
+    }
+
+    for (settlement, mut market, shop) in markets.iter_mut() {
+        market.recover(hours);
+        if let Some(mut shop) = shop.filter(|_| days > 0) {
+            shop.stock = market.stock(settlement, &items, world_state.seed);
+        }
+    }
+}
+
+/// Mirror market supply and reputation into save data whenever they change
+pub fn sync_markets_to_save(
+    markets: Query<&Market, Changed<Market>>,
+    mut game_state: ResMut<GameState>,
+) {
+    for market in markets.iter() {
+        game_state
+            .save_data
+            .markets
+            .insert(market.settlement_uuid.clone(), market.clone());
+    }
+}
+
+/// Restore markets once a save has been loaded
+pub fn restore_markets_from_save(mut slot: SaveSlot, mut markets: Query<&mut Market>) {
+    let Some(save) = slot.take() else {
+        return;
+    };
+    for mut market in markets.iter_mut() {
+        if let Some(saved) = save.markets.get(&market.settlement_uuid) {
+            *market = saved.clone();
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::world::components::{
+    FactionDatabase, FactionSimulation, FactionTurnOutcome, HexCoord, NpcAttitude, Settlement,
+};
+use crate::world::resources::game_state::GameState;
+use crate::world::state::{DreadLevel, WorldRng, WorldState};
+use crate::world::systems::data_files::DataFile;
+use crate::world::systems::hex_world::{FactionPresenceMarker, NPCMarker};
+use crate::world::systems::save::SaveSlot;
+use crate::world::systems::time_weather::{encounter_rate, DayNightCycle, TimeAdvancedEvent, WeatherSystem};
+use crate::utils::hex::world_to_hex;
+
+/// Game days between faction turns
+const DAYS_PER_FACTION_TURN: u32 = 7;
+/// Random stream for patrol encounters, see `WorldRng`
+const ENCOUNTER_RNG_STREAM: u64 = 4;
+/// Influence radiated by a settlement seat, plus a little per member there
+const SEAT_INFLUENCE: f32 = 0.6;
+const SEAT_INFLUENCE_PER_MEMBER: f32 = 0.1;
+/// Influence radiated by a faction presence spawned on a hex
+const PRESENCE_INFLUENCE: f32 = 0.5;
+
+/// Written by `ron-generator factions`
+impl DataFile for FactionDatabase {
+    type Contents = Self;
+    const PATH: &'static str = "world/factions.ron";
+
+    fn from_contents(contents: Self) -> Self {
+        contents
+    }
+}
+
+/// An NPC who belongs to a faction
+#[derive(Component, Debug, Clone)]
+pub struct FactionMember {
+    pub faction: String,
+}
+
+/// Change the player's standing with a faction, e.g. as a quest reward
+#[derive(Event)]
+pub struct ReputationChangeEvent {
+    pub faction: String,
+    pub amount: i32,
+    pub reason: String,
+}
+
+#[derive(Event)]
+pub struct FactionTurnEvent {
+    pub turn: u32,
+    pub outcome: FactionTurnOutcome,
+}
+
+/// The player ran into a faction's patrol on entering a hex
+#[derive(Event)]
+pub struct FactionEncounterEvent {
+    pub faction: String,
+    pub hex: HexCoord,
+    pub hostile: bool,
+}
+
+/// Start the simulation from the database once it has loaded. A save
+/// restored before then already carries its own simulation.
+pub fn start_faction_simulation(database: Res<FactionDatabase>, mut simulation: ResMut<FactionSimulation>) {
+    if database.is_changed() && simulation.factions.is_empty() {
+        *simulation = FactionSimulation::from_database(&database);
+    }
+}
+
+/// Settlements where a faction keeps members become its seats of power, and
+/// faction presences spawned on hexes radiate influence of their own
+pub fn seed_faction_influence(
+    database: Res<FactionDatabase>,
+    mut simulation: ResMut<FactionSimulation>,
+    settlements: Query<(&Settlement, &GlobalTransform), Added<Settlement>>,
+    presences: Query<(&FactionPresenceMarker, &GlobalTransform), Changed<GlobalTransform>>,
+) {
+    let mut changed = false;
+
+    for (settlement, transform) in settlements.iter() {
+        let hex = world_to_hex(transform.translation());
+        for faction in database.factions.values() {
+            let Some(members) = faction.seats.get(&settlement.uuid) else {
+                continue;
+            };
+            let strength = (SEAT_INFLUENCE + *members as f32 * SEAT_INFLUENCE_PER_MEMBER).min(1.0);
+            changed |= simulation.add_source(&faction.uuid, hex, strength);
+        }
+    }
+
+    for (presence, transform) in presences.iter() {
+        let hex = world_to_hex(transform.translation());
+        changed |= simulation.add_source(&presence.uuid, hex, PRESENCE_INFLUENCE);
+    }
+
+    if changed {
+        simulation.rebuild_influence();
+    }
+}
+
+/// Keep each presence marker's influence in step with the simulation
+pub fn update_presence_influence(
+    simulation: Res<FactionSimulation>,
+    mut presences: Query<(&mut FactionPresenceMarker, &GlobalTransform)>,
+) {
+    if !simulation.is_changed() {
+        return;
+    }
+    for (mut presence, transform) in presences.iter_mut() {
+        let hex = world_to_hex(transform.translation());
+        presence.influence_level = simulation.influence_at(hex, &presence.uuid);
+    }
+}
+
+/// Run a faction turn every `DAYS_PER_FACTION_TURN` game days
+pub fn faction_turn_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    mut simulation: ResMut<FactionSimulation>,
+    mut turn_events: EventWriter<FactionTurnEvent>,
+    mut days_since_turn: Local<u32>,
+) {
+    let days: u32 = time_events.read().map(|event| event.days_passed).sum();
+    if days == 0 {
+        return;
+    }
+    *days_since_turn += days;
+
+    while *days_since_turn >= DAYS_PER_FACTION_TURN {
+        *days_since_turn -= DAYS_PER_FACTION_TURN;
+        for outcome in simulation.run_turn() {
+            match &outcome {
+                FactionTurnOutcome::Expanded { faction, hex } => {
+                    info!("{} pushes its borders to {:?}", faction_name(&simulation, faction), hex);
+                }
+                FactionTurnOutcome::RelationChanged { a, b, relation } => {
+                    info!(
+                        "{} and {} are now {:?}",
+                        faction_name(&simulation, a),
+                        faction_name(&simulation, b),
+                        relation
+                    );
+                }
+                FactionTurnOutcome::Skirmish { a, b } => {
+                    debug!("{} and {} skirmish", faction_name(&simulation, a), faction_name(&simulation, b));
+                }
+            }
+            turn_events.send(FactionTurnEvent {
+                turn: simulation.turn,
+                outcome,
+            });
+        }
+    }
+}
+
+fn faction_name<'a>(simulation: &'a FactionSimulation, uuid: &'a str) -> &'a str {
+    simulation.factions.get(uuid).map_or(uuid, |faction| faction.name.as_str())
+}
+
+pub fn reputation_change_system(
+    mut reputation_events: EventReader<ReputationChangeEvent>,
+    mut simulation: ResMut<FactionSimulation>,
+) {
+    for event in reputation_events.read() {
+        if !simulation.factions.contains_key(&event.faction) {
+            debug!("Reputation change for unknown faction {}", event.faction);
+            continue;
+        }
+        simulation.adjust_reputation(&event.faction, event.amount);
+        info!(
+            "{} {} your standing ({})",
+            faction_name(&simulation, &event.faction),
+            if event.amount >= 0 { "improves" } else { "lowers" },
+            event.reason
+        );
+    }
+}
+
+/// Tag spawned NPCs with the faction HBF lists them under
+pub fn assign_npc_factions(
+    mut commands: Commands,
+    database: Res<FactionDatabase>,
+    simulation: Res<FactionSimulation>,
+    npcs: Query<(Entity, &NPCMarker), Added<NPCMarker>>,
+) {
+    for (entity, npc) in npcs.iter() {
+        let Some(faction) = database.faction_of_npc(&npc.uuid) else {
+            continue;
+        };
+        commands.entity(entity).insert((
+            FactionMember {
+                faction: faction.uuid.clone(),
+            },
+            simulation.npc_attitude(&faction.uuid),
+        ));
+    }
+}
+
+/// Faction members greet the player according to their faction's reputation
+pub fn update_npc_attitudes(
+    simulation: Res<FactionSimulation>,
+    mut members: Query<(&FactionMember, &mut NpcAttitude)>,
+) {
+    if !simulation.is_changed() {
+        return;
+    }
+    for (member, mut attitude) in members.iter_mut() {
+        let current = simulation.npc_attitude(&member.faction);
+        if *attitude != current {
+            *attitude = current;
+        }
+    }
+}
+
+/// Roll for a faction patrol whenever the player enters a new hex. Factions
+/// are met in proportion to their influence there; the overall chance
+/// follows dread, time of day and weather.
+#[allow(clippy::too_many_arguments)]
+pub fn faction_encounter_system(
+    world_state: Res<WorldState>,
+    simulation: Res<FactionSimulation>,
+    dread: Res<DreadLevel>,
+    day_night: Res<DayNightCycle>,
+    weather: Res<WeatherSystem>,
+    mut rng: Local<WorldRng>,
+    mut encounter_events: EventWriter<FactionEncounterEvent>,
+    mut last_hex: Local<Option<HexCoord>>,
+) {
+    let Some(player_hex) = world_state.player_hex else {
+        return;
+    };
+    if *last_hex == Some(player_hex) {
+        return;
+    }
+    *last_hex = Some(player_hex);
+
+    let table = simulation.encounter_table(player_hex);
+    let total_weight: f32 = table.iter().map(|entry| entry.weight).sum();
+    if total_weight <= 0.0 {
+        return;
+    }
+    let chance = (encounter_rate(&dread.phase, &day_night, &weather) * total_weight.min(1.0)).min(1.0);
+    let rng = rng.get(world_state.seed, ENCOUNTER_RNG_STREAM);
+    if rng.random::<f32>() >= chance {
+        return;
+    }
+
+    let mut roll = rng.random::<f32>() * total_weight;
+    let Some(entry) = table.iter().find(|entry| {
+        roll -= entry.weight;
+        roll <= 0.0
+    }) else {
+        return;
+    };
+    if entry.hostile {
+        warn!("A {} patrol bars your way", faction_name(&simulation, &entry.faction));
+    } else {
+        info!("You meet a {} patrol", faction_name(&simulation, &entry.faction));
+    }
+    encounter_events.send(FactionEncounterEvent {
+        faction: entry.faction.clone(),
+        hex: player_hex,
+        hostile: entry.hostile,
+    });
+}
+
+/// Mirror faction state into save data whenever it changes
+pub fn sync_factions_to_save(simulation: Res<FactionSimulation>, mut game_state: ResMut<GameState>) {
+    if simulation.is_changed() {
+        game_state.save_data.factions = Some(simulation.clone());
+    }
+}
+
+/// Restore faction state once a save has been loaded
+pub fn restore_factions_from_save(mut slot: SaveSlot, mut simulation: ResMut<FactionSimulation>) {
+    let Some(save) = slot.take() else {
+        return;
+    };
+    if let Some(saved) = &save.factions {
+        *simulation = saved.clone();
+        simulation.rebuild_influence();
+    }
+}
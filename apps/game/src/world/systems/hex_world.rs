@@ -155,7 +155,8 @@ fn spawn_correlated_entities_at_hex(
             Transform::from_translation(hex_world_pos + Vec3::new(1.0, 1.0, 0.0)),
             FactionPresenceMarker {
                 uuid: faction_uuid.clone(),
-                influence_level: calculate_faction_influence(faction_uuid, hex_coord),
+                // Filled in from the faction simulation's influence field
+                influence_level: 0.0,
             },
             Name::new(format!("FactionPresence_{}", faction_uuid)),
         )).id();
@@ -86,6 +86,12 @@ fn handle_touch_input(
     None
 }
 
+/// The hex under the mouse cursor, if it is over the window
+pub fn cursor_hex(windows: &Query<&Window>, camera_query: &Query<(&Camera, &GlobalTransform)>) -> Option<HexCoord> {
+    let cursor_pos = windows.get_single().ok()?.cursor_position()?;
+    screen_to_hex_coord(cursor_pos, camera_query)
+}
+
 /// Convert screen coordinates to hex coordinates
 fn screen_to_hex_coord(
     screen_pos: Vec2,
@@ -256,3 +262,61 @@ pub enum HexDirection {
     SouthWest,
     NorthWest,
 }
+
+/// Modifier held with a number key; each picks from a different list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberKeyModifier {
+    None,
+    Shift,
+    Control,
+    /// One of `HOLD_KEYS` held down
+    Hold(KeyCode),
+}
+
+/// Letters held with a number key to pick from their own list
+const HOLD_KEYS: [KeyCode; 10] = [
+    KeyCode::KeyP, // Attribute to raise
+    KeyCode::KeyI, // Inventory item to use or equip
+    KeyCode::KeyO, // Equipment slot to take off
+    KeyCode::KeyX, // Inventory item to drop
+    KeyCode::KeyH, // Mount to buy from a stable
+    KeyCode::KeyU, // Settlement service to use
+    KeyCode::KeyB, // Shop stock to buy
+    KeyCode::KeyV, // Inventory item to sell
+    KeyCode::KeyT, // NPC to talk to
+    KeyCode::KeyC, // Known ability to use
+];
+
+const NUMBER_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Zero-based index of the number key 1-9 pressed this frame, if it was
+/// pressed with exactly the given modifier
+pub fn number_key_pressed(keyboard: &ButtonInput<KeyCode>, modifier: NumberKeyModifier) -> Option<usize> {
+    let mut held = Vec::new();
+    if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        held.push(NumberKeyModifier::Shift);
+    }
+    if keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        held.push(NumberKeyModifier::Control);
+    }
+    held.extend(HOLD_KEYS.iter().filter(|key| keyboard.pressed(**key)).map(|key| NumberKeyModifier::Hold(*key)));
+    let held = match held.as_slice() {
+        [] => NumberKeyModifier::None,
+        [held] => *held,
+        _ => return None,
+    };
+    if held != modifier {
+        return None;
+    }
+    NUMBER_KEYS.iter().position(|key| keyboard.just_pressed(*key))
+}
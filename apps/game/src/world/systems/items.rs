@@ -0,0 +1,439 @@
+use bevy::prelude::*;
+
+use crate::world::components::{
+    AbilityBook, AbilityDatabase, ActiveEffect, ActiveEffects, CharacterSheet, CombatStats, Equipment, EquipmentSlot,
+    Inventory, ItemDatabase, ItemEffect, ItemType, Player,
+};
+use crate::world::resources::game_state::{GameState, ItemSaveState};
+use crate::world::systems::data_files::DataFile;
+use crate::world::systems::dungeon_interior::{ActiveDungeon, DungeonLayouts, TreasureLootedEvent};
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::rest_fatigue::PlayerStats;
+use crate::world::systems::save::SaveSlot;
+use crate::world::systems::time_weather::TimeAdvancedEvent;
+
+const PLAYER_INVENTORY_SLOTS: u32 = 30;
+const PLAYER_MAX_WEIGHT: f32 = 120.0;
+/// Buff consumables with no duration in the data still last this long
+const MIN_BUFF_HOURS: f32 = 1.0;
+/// Slots in the order the O key and a number take them off
+const SLOT_KEYS: [EquipmentSlot; 7] = [
+    EquipmentSlot::Head,
+    EquipmentSlot::Body,
+    EquipmentSlot::Hands,
+    EquipmentSlot::Feet,
+    EquipmentSlot::MainHand,
+    EquipmentSlot::OffHand,
+    EquipmentSlot::Trinket,
+];
+
+/// Written by `ron-generator items`
+impl DataFile for ItemDatabase {
+    type Contents = Self;
+    const PATH: &'static str = "world/items.ron";
+
+    fn from_contents(contents: Self) -> Self {
+        contents
+    }
+}
+
+#[derive(Event)]
+pub struct EquipItemEvent {
+    pub item_name: String,
+}
+
+#[derive(Event)]
+pub struct UnequipItemEvent {
+    pub slot: EquipmentSlot,
+}
+
+#[derive(Event)]
+pub struct UseItemEvent {
+    pub item_name: String,
+}
+
+#[derive(Event)]
+pub struct UseToolEvent {
+    pub tool_type: String,
+    pub wear: u32,
+}
+
+#[derive(Event)]
+pub struct ToolBrokenEvent {
+    pub tool_type: String,
+}
+
+#[derive(Event)]
+pub struct DiscardItemEvent {
+    pub item_name: String,
+    pub quantity: u32,
+}
+
+#[derive(Event)]
+pub struct ItemEffectExpiredEvent {
+    pub effect: ActiveEffect,
+}
+
+/// Give a freshly spawned player the item components
+pub fn attach_player_item_components(
+    mut commands: Commands,
+    players: Query<Entity, (With<Player>, Without<Inventory>)>,
+) {
+    for entity in players.iter() {
+        commands.entity(entity).insert((
+            Inventory::new(PLAYER_INVENTORY_SLOTS, PLAYER_MAX_WEIGHT),
+            Equipment::default(),
+            ActiveEffects::default(),
+            CombatStats::default(),
+        ));
+    }
+}
+
+/// Hold I and press a number to use that inventory item: gear is equipped
+/// and consumables are consumed. Hold X and a number to drop one of it, and
+/// O and 1-7 to take off what is worn on the head, body, hands, feet, main
+/// hand, off hand or as a trinket.
+pub fn item_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut equip_events: EventWriter<EquipItemEvent>,
+    mut unequip_events: EventWriter<UnequipItemEvent>,
+    mut use_events: EventWriter<UseItemEvent>,
+    mut discard_events: EventWriter<DiscardItemEvent>,
+) {
+    if let Some(slot) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyO))
+        .and_then(|index| SLOT_KEYS.get(index))
+    {
+        unequip_events.send(UnequipItemEvent { slot: *slot });
+        return;
+    }
+
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    if let Some(item) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyI))
+        .and_then(|index| inventory.items.get(index))
+    {
+        let item_name = item.name.clone();
+        if EquipmentSlot::for_item(item).is_some() {
+            equip_events.send(EquipItemEvent { item_name });
+        } else if matches!(item.item_type, ItemType::Consumable { .. }) {
+            use_events.send(UseItemEvent { item_name });
+        } else {
+            info!("{} cannot be used", item.name);
+        }
+    }
+    if let Some(item) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyX))
+        .and_then(|index| inventory.items.get(index))
+    {
+        discard_events.send(DiscardItemEvent {
+            item_name: item.name.clone(),
+            quantity: 1,
+        });
+    }
+}
+
+/// Move an item from the inventory into its equipment slot, swapping out
+/// whatever was there
+pub fn equip_item_system(
+    mut equip_events: EventReader<EquipItemEvent>,
+    mut player_query: Query<(&mut Inventory, &mut Equipment), With<Player>>,
+) {
+    let Ok((mut inventory, mut equipment)) = player_query.get_single_mut() else {
+        equip_events.clear();
+        return;
+    };
+
+    for event in equip_events.read() {
+        let Some(item) = inventory.remove_item(&event.item_name, 1) else {
+            warn!("Cannot equip {}: not in inventory", event.item_name);
+            continue;
+        };
+
+        match equipment.equip(item) {
+            Ok(Some(previous)) => {
+                if !inventory.add_item(previous.clone()) {
+                    // No room for the swapped-out item; undo the swap
+                    if let Ok(Some(item)) = equipment.equip(previous) {
+                        inventory.add_item(item);
+                    }
+                    warn!("Cannot equip {}: no room for the item it replaces", event.item_name);
+                }
+            }
+            Ok(None) => {}
+            Err(item) => {
+                warn!("{} cannot be equipped", item.name);
+                inventory.add_item(item);
+            }
+        }
+    }
+}
+
+pub fn unequip_item_system(
+    mut unequip_events: EventReader<UnequipItemEvent>,
+    mut player_query: Query<(&mut Inventory, &mut Equipment), With<Player>>,
+) {
+    let Ok((mut inventory, mut equipment)) = player_query.get_single_mut() else {
+        unequip_events.clear();
+        return;
+    };
+
+    for event in unequip_events.read() {
+        let Some(item) = equipment.get(event.slot) else {
+            continue;
+        };
+        if !inventory.can_add_item(item) {
+            warn!("Cannot unequip {}: inventory is full", item.name);
+            continue;
+        }
+        if let Some(item) = equipment.unequip(event.slot) {
+            inventory.add_item(item);
+        }
+    }
+}
+
+/// Consume one of a consumable. Effects with no duration apply immediately;
+/// the rest become timed `ActiveEffects`.
+pub fn use_consumable_system(
+    mut use_events: EventReader<UseItemEvent>,
+    mut player_query: Query<(&mut Player, &mut Inventory, &mut ActiveEffects, Option<&mut PlayerStats>)>,
+) {
+    let Ok((mut player, mut inventory, mut effects, mut stats)) = player_query.get_single_mut() else {
+        use_events.clear();
+        return;
+    };
+
+    for event in use_events.read() {
+        let is_consumable = inventory
+            .items
+            .iter()
+            .any(|item| item.name == event.item_name && matches!(item.item_type, ItemType::Consumable { .. }));
+        if !is_consumable {
+            warn!("{} is not a usable consumable", event.item_name);
+            continue;
+        }
+
+        let Some(item) = inventory.remove_item(&event.item_name, 1) else {
+            continue;
+        };
+        let ItemType::Consumable { effect, duration, potency } = item.item_type else {
+            continue;
+        };
+
+        let effect = ItemEffect::from_name(&effect);
+        match effect {
+            ItemEffect::Heal
+            | ItemEffect::RestoreSanity
+            | ItemEffect::Vigor
+            | ItemEffect::Nourish
+            | ItemEffect::Hydrate
+                if duration <= 0.0 =>
+            {
+                apply_effect_amount(&effect, potency as f32, &mut player, stats.as_deref_mut());
+            }
+            ItemEffect::Other(ref name) => {
+                info!("{} has no known effect ({})", item.name, name);
+            }
+            _ => {
+                effects.apply(&item.name, effect, potency, duration.max(MIN_BUFF_HOURS));
+            }
+        }
+        info!("Used {}", item.name);
+    }
+}
+
+/// Advance timed effects as game hours pass and apply heal/sanity/vigor
+/// over time
+pub fn tick_item_effects_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    mut player_query: Query<(&mut Player, &mut ActiveEffects, Option<&mut PlayerStats>)>,
+    mut expired_events: EventWriter<ItemEffectExpiredEvent>,
+) {
+    let hours: f32 = time_events.read().map(|event| event.hours).sum();
+    let Ok((mut player, mut effects, mut stats)) = player_query.get_single_mut() else {
+        return;
+    };
+    if hours <= 0.0 || effects.effects.is_empty() {
+        return;
+    }
+
+    for active in effects.effects.clone() {
+        let amount = active.potency as f32 * hours.min(active.remaining_hours.max(0.0));
+        apply_effect_amount(&active.effect, amount, &mut player, stats.as_deref_mut());
+    }
+
+    for effect in effects.tick(hours) {
+        expired_events.send(ItemEffectExpiredEvent { effect });
+    }
+}
+
+/// Instant or per-tick portion of a restorative effect. Buffs are read from
+/// `ActiveEffects` by `CombatStats` instead.
+fn apply_effect_amount(effect: &ItemEffect, amount: f32, player: &mut Player, stats: Option<&mut PlayerStats>) {
+    match effect {
+        ItemEffect::Heal => {
+            player.health = (player.health + amount).min(player.max_health);
+        }
+        ItemEffect::RestoreSanity => {
+            player.sanity = (player.sanity + amount).min(player.max_sanity);
+        }
+        ItemEffect::Vigor => {
+            if let Some(stats) = stats {
+                stats.add_fatigue(-amount);
+            }
+        }
+        ItemEffect::Nourish => {
+            if let Some(stats) = stats {
+                stats.hunger = (stats.hunger - amount).max(0.0);
+            }
+        }
+        ItemEffect::Hydrate => {
+            if let Some(stats) = stats {
+                stats.thirst = (stats.thirst - amount).max(0.0);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn tool_durability_system(
+    mut tool_events: EventReader<UseToolEvent>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    mut broken_events: EventWriter<ToolBrokenEvent>,
+) {
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        tool_events.clear();
+        return;
+    };
+
+    for event in tool_events.read() {
+        match inventory.wear_tool(&event.tool_type, event.wear) {
+            Some(0) => {
+                info!("Your {} broke", event.tool_type);
+                broken_events.send(ToolBrokenEvent {
+                    tool_type: event.tool_type.clone(),
+                });
+            }
+            Some(_) => {}
+            None => warn!("No usable {} in inventory", event.tool_type),
+        }
+    }
+}
+
+/// Drop items. Quest items stay locked until their quest releases them.
+pub fn discard_item_system(
+    mut discard_events: EventReader<DiscardItemEvent>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+) {
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        discard_events.clear();
+        return;
+    };
+
+    for event in discard_events.read() {
+        if inventory.discard_item(&event.item_name, event.quantity).is_none() {
+            info!("{} cannot be discarded", event.item_name);
+        }
+    }
+}
+
+pub fn update_combat_stats_system(
+    abilities: Res<AbilityDatabase>,
+    mut player_query: Query<
+        (&Equipment, &ActiveEffects, Option<&AbilityBook>, Option<&CharacterSheet>, &mut CombatStats),
+        Or<(Changed<Equipment>, Changed<ActiveEffects>, Changed<AbilityBook>, Changed<CharacterSheet>)>,
+    >,
+) {
+    for (equipment, effects, book, sheet, mut combat_stats) in player_query.iter_mut() {
+        // Base attributes stay at defaults until character creation supplies them
+        let base_stats = sheet.map(|sheet| sheet.data.stats.clone()).unwrap_or_default();
+        let mut calculated = CombatStats::calculate(&base_stats, equipment, effects);
+        if let Some(book) = book {
+            let passive = book.passive_bonus(&abilities);
+            calculated.attack += passive.attack;
+            calculated.defense += passive.defense;
+            calculated.dread_resistance += passive.dread_resistance;
+        }
+        *combat_stats = calculated;
+    }
+}
+
+/// Add the catalogued contents of a looted dungeon treasure to the inventory.
+/// Item templates are keyed by the HBF page they were read from, which for a
+/// hoard is the dungeon area holding it, not the hoard's own reroll ID.
+pub fn award_treasure_items_system(
+    mut loot_events: EventReader<TreasureLootedEvent>,
+    database: Res<ItemDatabase>,
+    layouts: Res<DungeonLayouts>,
+    active_dungeon: Res<ActiveDungeon>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+) {
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        loot_events.clear();
+        return;
+    };
+    let Some(layout) = active_dungeon.dungeon_uuid.as_ref().and_then(|uuid| layouts.get(uuid)) else {
+        loot_events.clear();
+        return;
+    };
+
+    for event in loot_events.read() {
+        let Some(room) = layout
+            .rooms
+            .iter()
+            .find(|room| room.treasure.as_ref().is_some_and(|t| t.uuid == event.treasure_uuid))
+        else {
+            continue;
+        };
+        for template in database.from_source(&room.area_uuid) {
+            if !inventory.add_item(template.item.clone()) {
+                warn!("No room for {}", template.item.name);
+            }
+        }
+    }
+}
+
+/// Mirror inventory, equipment and effects into save data whenever they change
+pub fn sync_items_to_save(
+    player_query: Query<
+        (&Inventory, &Equipment, &ActiveEffects),
+        Or<(Changed<Inventory>, Changed<Equipment>, Changed<ActiveEffects>)>,
+    >,
+    mut game_state: ResMut<GameState>,
+) {
+    let Ok((inventory, equipment, effects)) = player_query.get_single() else {
+        return;
+    };
+
+    let player_stats = &mut game_state.save_data.player_stats;
+    player_stats.inventory = inventory.items.iter().map(ItemSaveState::from_item).collect();
+    player_stats.equipment = equipment.slots.values().map(ItemSaveState::from_item).collect();
+    player_stats.active_effects = effects.effects.clone();
+}
+
+/// Restore items once a save has been loaded
+pub fn restore_items_from_save(
+    mut slot: SaveSlot,
+    mut player_query: Query<(&mut Inventory, &mut Equipment, &mut ActiveEffects), With<Player>>,
+) {
+    let Ok((mut inventory, mut equipment, mut effects)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(save) = slot.take() else {
+        return;
+    };
+    let saved = &save.player_stats;
+    *inventory = Inventory::new(inventory.capacity, inventory.max_weight);
+    for item in saved.inventory.iter().filter_map(ItemSaveState::to_item) {
+        inventory.add_item(item);
+    }
+
+    *equipment = Equipment::default();
+    for item in saved.equipment.iter().filter_map(ItemSaveState::to_item) {
+        if let Err(item) = equipment.equip(item) {
+            warn!("Saved equipment {} cannot be equipped", item.name);
+        }
+    }
+
+    effects.effects = saved.active_effects.clone();
+}
@@ -0,0 +1,249 @@
+use bevy::prelude::*;
+
+use crate::world::components::{
+    Attribute, CharacterSheet, Experience, ExperienceSource, Player, QuestDatabase, QuestUpdate,
+};
+use crate::world::resources::game_state::GameState;
+use crate::world::state::WorldState;
+use crate::world::systems::dungeon_interior::MonsterDefeatedEvent;
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::quests::QuestProgressEvent;
+use crate::world::systems::rest_fatigue::PlayerStats;
+use crate::world::systems::save::SaveSlot;
+
+type ChangedCharacter = Or<(Changed<Experience>, Changed<CharacterSheet>)>;
+
+/// XP from anything without its own event, e.g. fighting off an ambush
+#[derive(Event)]
+pub struct AwardExperienceEvent {
+    pub source: ExperienceSource,
+}
+
+#[derive(Event)]
+pub struct LevelUpEvent {
+    pub level: u32,
+    pub attribute_point: bool,
+}
+
+#[derive(Event)]
+pub struct SpendAttributePointEvent {
+    pub attribute: Attribute,
+}
+
+/// Give a freshly spawned player a level-1 experience track
+pub fn attach_experience(mut commands: Commands, players: Query<Entity, (With<Player>, Without<Experience>)>) {
+    for entity in players.iter() {
+        commands.entity(entity).insert(Experience::default());
+    }
+}
+
+/// Award XP for defeated monsters, quests and pushing the frontier, and keep
+/// the player's level everywhere it is read in step. Each monster in a
+/// defeated group is worth its threat.
+#[allow(clippy::too_many_arguments)]
+pub fn experience_award_system(
+    mut award_events: EventReader<AwardExperienceEvent>,
+    mut quest_events: EventReader<QuestProgressEvent>,
+    mut defeat_events: EventReader<MonsterDefeatedEvent>,
+    quests: Res<QuestDatabase>,
+    world_state: Res<WorldState>,
+    mut player_query: Query<(&mut Experience, Option<&mut PlayerStats>)>,
+    mut level_up_events: EventWriter<LevelUpEvent>,
+) {
+    let mut sources: Vec<ExperienceSource> = award_events.read().map(|event| event.source.clone()).collect();
+    for event in quest_events.read() {
+        match &event.update {
+            QuestUpdate::StepCompleted { .. } => sources.push(ExperienceSource::QuestStep),
+            QuestUpdate::Completed { quest_id, .. } => {
+                let steps = quests.get(quest_id).map_or(0, |quest| quest.steps.len() as u32);
+                sources.push(ExperienceSource::Quest { steps });
+            }
+            _ => {}
+        }
+    }
+    for event in defeat_events.read() {
+        let source = ExperienceSource::Combat { threat: event.threat };
+        sources.extend(std::iter::repeat_n(source, event.count.max(1) as usize));
+    }
+
+    let Ok((mut experience, stats)) = player_query.get_single_mut() else {
+        return;
+    };
+    let distance = world_state.player_hex.map_or(0, |hex| hex.distance_from_origin());
+    if distance > experience.furthest_distance {
+        sources.extend(experience.reach(distance).map(|rings| ExperienceSource::Frontier { rings }));
+    }
+
+    for source in sources {
+        for level_up in experience.award(&source) {
+            info!("You reached level {}", level_up.level);
+            if level_up.attribute_point {
+                info!("You may raise an attribute");
+            }
+            level_up_events.send(LevelUpEvent {
+                level: level_up.level,
+                attribute_point: level_up.attribute_point,
+            });
+        }
+    }
+
+    if let Some(mut stats) = stats.filter(|stats| stats.level != experience.level) {
+        stats.level = experience.level;
+    }
+}
+
+/// Health and sanity maximums follow the character and their level. This is
+/// the only system that sets them: a new or loaded sheet reaches the player
+/// through `apply_character_sheet_system` and is picked up here. Raising a
+/// maximum raises the current value with it, so a level-up heals the gain.
+pub fn refresh_player_maximums_system(
+    mut player_query: Query<(&mut Player, &Experience, Option<&CharacterSheet>), ChangedCharacter>,
+) {
+    for (mut player, experience, sheet) in player_query.iter_mut() {
+        let max_health = experience.max_health(sheet);
+        let max_sanity = experience.max_sanity(sheet);
+        if player.max_health == max_health && player.max_sanity == max_sanity {
+            continue;
+        }
+        let health_gain = (max_health - player.max_health).max(0.0);
+        let sanity_gain = (max_sanity - player.max_sanity).max(0.0);
+        player.max_health = max_health;
+        player.max_sanity = max_sanity;
+        player.health = (player.health + health_gain).min(max_health);
+        player.sanity = (player.sanity + sanity_gain).min(max_sanity);
+    }
+}
+
+/// Hold P and press 1-6 to raise strength, dexterity, constitution,
+/// intelligence, wisdom or charisma
+pub fn attribute_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut spend_events: EventWriter<SpendAttributePointEvent>,
+) {
+    let Some(index) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyP)) else {
+        return;
+    };
+    if let Some(&attribute) = Attribute::ALL.get(index) {
+        spend_events.send(SpendAttributePointEvent { attribute });
+    }
+}
+
+/// Spend an earned attribute point on the character sheet
+pub fn spend_attribute_point_system(
+    mut spend_events: EventReader<SpendAttributePointEvent>,
+    mut player_query: Query<(&mut Experience, &mut CharacterSheet)>,
+    mut game_state: ResMut<GameState>,
+) {
+    let Ok((mut experience, mut sheet)) = player_query.get_single_mut() else {
+        spend_events.clear();
+        return;
+    };
+
+    for event in spend_events.read() {
+        if experience.attribute_points == 0 {
+            info!("No attribute points to spend");
+            continue;
+        }
+        experience.attribute_points -= 1;
+        *sheet.data.stats.get_mut(event.attribute) += 1;
+        info!("{:?} raised to {}", event.attribute, sheet.data.stats.get(event.attribute));
+        game_state.save_data.character = Some(sheet.clone());
+    }
+}
+
+/// The world's progression band advances with the player's level and
+/// distance from the start; it never moves back
+pub fn world_progression_system(
+    player_query: Query<&Experience, Changed<Experience>>,
+    mut world_state: ResMut<WorldState>,
+) {
+    let Ok(experience) = player_query.get_single() else {
+        return;
+    };
+    let band = experience.band();
+    if band > world_state.world_progression {
+        world_state.world_progression = band;
+        info!("The journey reaches band {}", band);
+    }
+}
+
+/// Mirror experience and the journey's progression into save data whenever
+/// they change
+pub fn sync_experience_to_save(
+    player_query: Query<&Experience, (With<Player>, Changed<Experience>)>,
+    world_state: Res<WorldState>,
+    mut game_state: ResMut<GameState>,
+) {
+    if game_state.save_data.progression != world_state.world_progression {
+        game_state.save_data.progression = world_state.world_progression;
+    }
+    let Ok(experience) = player_query.get_single() else {
+        return;
+    };
+    game_state.save_data.player_stats.experience = experience.clone();
+}
+
+/// Restore experience once a save has been loaded
+pub fn restore_experience_from_save(
+    mut slot: SaveSlot,
+    mut player_query: Query<&mut Experience, With<Player>>,
+    mut world_state: ResMut<WorldState>,
+) {
+    let Ok(mut experience) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(save) = slot.take() else {
+        return;
+    };
+    *experience = save.player_stats.experience.clone();
+    world_state.world_progression = save.progression;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<QuestDatabase>()
+            .insert_resource(WorldState {
+                world_progression: 60,
+                ..default()
+            })
+            .add_event::<AwardExperienceEvent>()
+            .add_event::<QuestProgressEvent>()
+            .add_event::<MonsterDefeatedEvent>()
+            .add_event::<LevelUpEvent>()
+            .add_systems(Update, experience_award_system);
+        app.world_mut().spawn(Experience::default());
+        app
+    }
+
+    fn total(app: &mut App) -> u64 {
+        let mut experience = app.world_mut().query::<&Experience>();
+        experience.single(app.world()).unwrap().total
+    }
+
+    #[test]
+    fn test_each_defeated_monster_is_worth_its_threat() {
+        let mut app = app();
+        app.world_mut().send_event(MonsterDefeatedEvent {
+            monster_uuid: "ghouls".to_string(),
+            count: 3,
+            threat: 2,
+        });
+        app.update();
+        assert_eq!(total(&mut app), 3 * ExperienceSource::Combat { threat: 2 }.amount());
+
+        // The world's band alone awards nothing
+        app.update();
+        assert_eq!(total(&mut app), 3 * ExperienceSource::Combat { threat: 2 }.amount());
+
+        app.world_mut().send_event(AwardExperienceEvent {
+            source: ExperienceSource::Other { amount: 5 },
+        });
+        app.update();
+        assert_eq!(total(&mut app), 3 * ExperienceSource::Combat { threat: 2 }.amount() + 5);
+    }
+}
@@ -10,6 +10,23 @@ pub mod procedural_audio;
 pub mod regional_progression;
 pub mod pathfinding;
 pub mod rest_fatigue;
+pub mod dungeon_interior;
+pub mod items;
+pub mod mounts;
+pub mod time_weather;
+pub mod settlements;
+pub mod economy;
+pub mod factions;
+pub mod quests;
+pub mod npcs;
+pub mod alignment;
+pub mod abilities;
+pub mod character_creation;
+pub mod leveling;
+pub mod atlas;
+pub mod model_assets;
+pub mod save;
+pub mod data_files;
 
 pub use hex_world::*;
 pub use player::*;
@@ -23,3 +40,20 @@ pub use procedural_audio::*;
 pub use regional_progression::*;
 pub use pathfinding::*;
 pub use rest_fatigue::*;
+pub use dungeon_interior::*;
+pub use items::*;
+pub use mounts::*;
+pub use time_weather::*;
+pub use settlements::*;
+pub use economy::*;
+pub use factions::*;
+pub use quests::*;
+pub use npcs::*;
+pub use alignment::*;
+pub use abilities::*;
+pub use character_creation::*;
+pub use leveling::*;
+pub use atlas::*;
+pub use model_assets::*;
+pub use save::*;
+pub use data_files::*;
@@ -0,0 +1,118 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+
+use crate::world::components::{
+    AssetManifest, AssetStatus, ModelMetadata, ASSET_MANIFEST_FILE, MODEL_METADATA_EXTENSION, MODEL_METADATA_VERSION,
+};
+use crate::world::state::AssetHandles;
+use crate::world::systems::data_files::DataFile;
+
+/// One `.meta.ron` file written by ron-generator
+#[derive(Asset, TypePath, Debug)]
+pub struct ModelMetadataAsset(pub ModelMetadata);
+
+#[derive(Default)]
+pub struct ModelMetadataLoader;
+
+impl AssetLoader for ModelMetadataLoader {
+    type Asset = ModelMetadataAsset;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let metadata: ModelMetadata = ron::de::from_bytes(&bytes)?;
+        if !metadata.is_supported_version() {
+            anyhow::bail!(
+                "{} has schema version {}, this build reads up to {}",
+                metadata.id,
+                metadata.version,
+                MODEL_METADATA_VERSION
+            );
+        }
+        Ok(ModelMetadataAsset(metadata))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[MODEL_METADATA_EXTENSION]
+    }
+}
+
+/// Metadata handles, kept so the files stay loaded for the life of the app
+#[derive(Resource, Default)]
+pub struct ModelMetadataHandles {
+    pub handles: Vec<Handle<ModelMetadataAsset>>,
+}
+
+/// Written by `ron-generator` next to the model metadata. It names every
+/// model's metadata file; models it lists as missing go straight to the
+/// fallback mesh.
+impl DataFile for AssetManifest {
+    type Contents = Self;
+    const PATH: &'static str = ASSET_MANIFEST_FILE;
+
+    fn from_contents(contents: Self) -> Self {
+        contents
+    }
+}
+
+/// Load the metadata file of every model the manifest lists, once it has
+/// loaded. The manifest names each file, so nothing has to list a folder,
+/// which the web asset reader cannot do.
+pub fn load_model_metadata(
+    asset_server: Res<AssetServer>,
+    manifest: Res<AssetManifest>,
+    mut metadata: ResMut<ModelMetadataHandles>,
+) {
+    if !manifest.is_changed() {
+        return;
+    }
+    metadata.handles = manifest
+        .entries
+        .values()
+        .filter(|entry| !entry.metadata_path.is_empty())
+        .map(|entry| asset_server.load(entry.metadata_path.clone()))
+        .collect();
+}
+
+/// Resolve each loaded metadata file into scene, socket and sound handles
+pub fn register_model_metadata(
+    mut metadata_events: EventReader<AssetEvent<ModelMetadataAsset>>,
+    metadata_assets: Res<Assets<ModelMetadataAsset>>,
+    asset_server: Res<AssetServer>,
+    manifest: Res<AssetManifest>,
+    mut asset_handles: ResMut<AssetHandles>,
+) {
+    for event in metadata_events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let Some(ModelMetadataAsset(metadata)) = metadata_assets.get(*id) else {
+            continue;
+        };
+
+        if manifest.get(&metadata.id).is_some_and(|entry| entry.status == AssetStatus::Missing) {
+            asset_handles.models.remove(&metadata.id);
+            asset_handles.missing_models.insert(metadata.id.clone());
+        } else {
+            let scene = asset_server.load(GltfAssetLabel::Scene(0).from_asset(metadata.model_path.clone()));
+            asset_handles.models.insert(metadata.id.clone(), scene);
+            asset_handles.missing_models.remove(&metadata.id);
+        }
+        asset_handles.model_sockets.insert(metadata.id.clone(), metadata.sockets.clone());
+        let sounds = metadata
+            .sounds
+            .iter()
+            .map(|sound| (sound.event.clone(), asset_server.load(sound.sound_path.clone())))
+            .collect();
+        asset_handles.model_sounds.insert(metadata.id.clone(), sounds);
+        debug!("Registered model {} ({})", metadata.id, metadata.display_name);
+    }
+}
@@ -0,0 +1,400 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::world::components::{BiomeType, HexCoord, Inventory, Mount, MountType, Mounted, Player, Tile, fnv1a};
+use crate::world::resources::game_state::GameState;
+use crate::world::state::{WorldRng, WorldState};
+use crate::world::systems::hex_world::SettlementMarker;
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::save::SaveSlot;
+use crate::world::systems::time_weather::TimeAdvancedEvent;
+use crate::utils::hex::world_to_hex;
+
+/// Random stream for panic rolls, see `WorldRng`
+const MOUNT_RNG_STREAM: u64 = 1;
+/// Damage taken by a rider thrown from a panicking mount
+const THROWN_RIDER_DAMAGE: f32 = 8.0;
+
+/// Mounts for sale at a settlement
+#[derive(Component, Debug, Clone)]
+pub struct Stable {
+    pub offers: Vec<MountOffer>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MountOffer {
+    pub name: String,
+    pub mount_type: MountType,
+    pub price: u32, // Copper pieces
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MountLossCause {
+    Died,
+    Bolted,
+}
+
+#[derive(Event)]
+pub struct ToggleMountEvent;
+
+#[derive(Event)]
+pub struct BuyMountEvent {
+    pub settlement: Entity,
+    pub offer_index: usize,
+}
+
+#[derive(Event)]
+pub struct MountPanicEvent {
+    pub mount: Entity,
+    pub rider_thrown: bool,
+}
+
+#[derive(Event)]
+pub struct MountLostEvent {
+    pub mount_name: String,
+    pub cause: MountLossCause,
+}
+
+/// Biome of the tile at a hex, if that hex is loaded
+pub fn biome_at(tiles: &Query<&Tile>, hex: HexCoord) -> Option<BiomeType> {
+    tiles
+        .iter()
+        .find(|tile| tile.coords == hex)
+        .map(|tile| tile.biome_type.clone())
+}
+
+/// Settlement types that keep a stable, and what they stock. Selection is
+/// keyed off the settlement UUID so the same settlement always has the same
+/// stable across sessions.
+pub fn stable_stock_for(settlement: &SettlementMarker) -> Option<Vec<MountOffer>> {
+    let seed = fnv1a(settlement.uuid.as_bytes()) as u32;
+
+    let horse = |breed: &str, temperament: &str| MountType::Horse {
+        breed: breed.to_string(),
+        temperament: temperament.to_string(),
+    };
+    let ox = MountType::Ox {
+        strength: 12 + seed % 6,
+        endurance: 10 + seed % 8,
+    };
+
+    let stock = match settlement.settlement_type.as_str() {
+        "village" if seed % 3 != 0 => vec![horse("cob", "placid"), ox],
+        "stronghold" => vec![horse("destrier", "bold"), horse("mountain pony", "steady")],
+        "oasis" => vec![MountType::ExoticMount {
+            species: "sand strider".to_string(),
+            special_abilities: vec!["desert_endurance".to_string()],
+        }],
+        "port" | "settlement" if seed % 2 == 0 => vec![horse("courser", "nervous")],
+        "cursed_refuge" | "void_outpost" => vec![MountType::CorruptedMount {
+            original_type: Box::new(horse("nightmare", "silent")),
+            corruption_level: 0.3 + (seed % 5) as f32 * 0.1,
+        }],
+        _ => return None,
+    };
+
+    Some(
+        stock
+            .into_iter()
+            .map(|mount_type| MountOffer {
+                name: mount_display_name(&mount_type),
+                price: mount_type.get_base_price(),
+                mount_type,
+            })
+            .collect(),
+    )
+}
+
+fn mount_display_name(mount_type: &MountType) -> String {
+    match mount_type {
+        MountType::Horse { breed, .. } => format!("{} horse", breed),
+        MountType::Ox { .. } => "draft ox".to_string(),
+        MountType::ExoticMount { species, .. } => species.clone(),
+        MountType::CorruptedMount { original_type, .. } => format!("hollow-eyed {}", mount_display_name(original_type)),
+    }
+}
+
+pub fn attach_settlement_stables(
+    mut commands: Commands,
+    settlements: Query<(Entity, &SettlementMarker), Added<SettlementMarker>>,
+) {
+    for (entity, settlement) in settlements.iter() {
+        if let Some(offers) = stable_stock_for(settlement) {
+            commands.entity(entity).insert(Stable { offers });
+        }
+    }
+}
+
+/// M toggles riding the player's mount. Hold H and press a number to buy
+/// that mount from the stable where the player stands.
+pub fn mount_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    world_state: Res<WorldState>,
+    stables: Query<(Entity, &GlobalTransform), With<Stable>>,
+    mut toggle_events: EventWriter<ToggleMountEvent>,
+    mut buy_events: EventWriter<BuyMountEvent>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyM) {
+        toggle_events.send(ToggleMountEvent);
+    }
+
+    let Some(offer_index) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyH)) else {
+        return;
+    };
+    let stable = stables
+        .iter()
+        .find(|(_, transform)| world_state.player_hex == Some(world_to_hex(transform.translation())));
+    match stable {
+        Some((settlement, _)) => {
+            buy_events.send(BuyMountEvent { settlement, offer_index });
+        }
+        None => info!("There is no stable here"),
+    }
+}
+
+pub fn toggle_mount_system(
+    mut commands: Commands,
+    mut toggle_events: EventReader<ToggleMountEvent>,
+    player_query: Query<(Entity, &Player, Has<Mounted>)>,
+    mounts: Query<&Mount>,
+) {
+    let Ok((player_entity, player, is_mounted)) = player_query.get_single() else {
+        toggle_events.clear();
+        return;
+    };
+
+    for _ in toggle_events.read() {
+        if is_mounted {
+            commands.entity(player_entity).remove::<Mounted>();
+            info!("Dismounted");
+            continue;
+        }
+
+        let Some(mount) = player.mount.and_then(|entity| mounts.get(entity).ok()) else {
+            info!("You have no mount");
+            continue;
+        };
+        if mount.panic_chance() > 0.0 {
+            info!("{} is too frightened to be ridden", mount.name);
+            continue;
+        }
+        commands.entity(player_entity).insert(Mounted);
+        info!("Mounted {}", mount.name);
+    }
+}
+
+/// Buy a mount from a stable on the player's hex, paying in carried coin.
+/// A previously owned mount is sold back to the stable for half its price.
+pub fn buy_mount_system(
+    mut commands: Commands,
+    mut buy_events: EventReader<BuyMountEvent>,
+    world_state: Res<WorldState>,
+    stables: Query<(&Stable, &GlobalTransform)>,
+    mut player_query: Query<(Entity, &mut Player, &mut Inventory)>,
+    mounts: Query<&Mount>,
+) {
+    let Ok((player_entity, mut player, mut inventory)) = player_query.get_single_mut() else {
+        buy_events.clear();
+        return;
+    };
+
+    for event in buy_events.read() {
+        let Ok((stable, transform)) = stables.get(event.settlement) else {
+            warn!("That settlement has no stable");
+            continue;
+        };
+        if world_state.player_hex != Some(world_to_hex(transform.translation())) {
+            info!("You must be at the settlement to buy a mount");
+            continue;
+        }
+        let Some(offer) = stable.offers.get(event.offer_index) else {
+            continue;
+        };
+
+        let trade_in = player
+            .mount
+            .and_then(|entity| mounts.get(entity).ok())
+            .map_or(0, |mount| mount.mount_type.get_base_price() / 2);
+        let price = offer.price.saturating_sub(trade_in);
+        if !inventory.spend_currency(price) {
+            info!("You cannot afford the {} ({} cp)", offer.name, price);
+            continue;
+        }
+
+        if let Some(old_mount) = player.mount.take() {
+            commands.entity(old_mount).despawn_recursive();
+        }
+        let mount_entity = commands
+            .spawn((
+                Mount::new(offer.name.clone(), offer.mount_type.clone()),
+                Name::new(format!("Mount_{}", offer.name)),
+            ))
+            .id();
+        player.mount = Some(mount_entity);
+        commands.entity(player_entity).remove::<Mounted>();
+        info!("Bought a {} for {} cp", offer.name, price);
+    }
+}
+
+/// Per-hex mount simulation while riding: stamina drain, terrain damage,
+/// fear from corrupted and void ground, and panic rolls
+pub fn mount_travel_system(
+    mut commands: Commands,
+    world_state: Res<WorldState>,
+    tiles: Query<&Tile>,
+    mut player_query: Query<(Entity, &mut Player), With<Mounted>>,
+    mut mounts: Query<&mut Mount>,
+    mut rng: Local<WorldRng>,
+    mut panic_events: EventWriter<MountPanicEvent>,
+    mut last_hex: Local<Option<HexCoord>>,
+) {
+    let Some(player_hex) = world_state.player_hex else {
+        return;
+    };
+    if *last_hex == Some(player_hex) {
+        return;
+    }
+    *last_hex = Some(player_hex);
+
+    let Ok((player_entity, mut player)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(mount_entity) = player.mount else {
+        commands.entity(player_entity).remove::<Mounted>();
+        return;
+    };
+    let Ok(mut mount) = mounts.get_mut(mount_entity) else {
+        return;
+    };
+    let Some(biome) = biome_at(&tiles, player_hex) else {
+        return;
+    };
+
+    let stamina_cost = mount.stamina_cost(&biome);
+    mount.spend_stamina(stamina_cost);
+    mount.health -= biome.get_damage_per_turn();
+    let fear = mount.fear_change(&biome);
+    mount.add_fear(fear);
+
+    if mount.is_exhausted() {
+        info!("{} is exhausted and must be led", mount.name);
+    }
+
+    if rng.get(world_state.seed, MOUNT_RNG_STREAM).random::<f32>() < mount.panic_chance() {
+        // A panicking mount throws its rider; it may bolt entirely
+        player.health -= THROWN_RIDER_DAMAGE;
+        commands.entity(player_entity).remove::<Mounted>();
+        panic_events.send(MountPanicEvent {
+            mount: mount_entity,
+            rider_thrown: true,
+        });
+        warn!("{} panics and throws you!", mount.name);
+    }
+}
+
+/// Mounts that are not being ridden recover stamina and calm down as game
+/// hours pass
+pub fn mount_recovery_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    player_query: Query<&Player, Without<Mounted>>,
+    mut mounts: Query<&mut Mount>,
+) {
+    let hours: f32 = time_events.read().map(|event| event.hours).sum();
+    if hours <= 0.0 {
+        return;
+    }
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+    let Some(mut mount) = player.mount.and_then(|entity| mounts.get_mut(entity).ok()) else {
+        return;
+    };
+    mount.recover(hours);
+}
+
+/// Remove mounts that died or bolted
+pub fn mount_loss_system(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Player)>,
+    mounts: Query<&Mount>,
+    mut lost_events: EventWriter<MountLostEvent>,
+) {
+    let Ok((player_entity, mut player)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(mount_entity) = player.mount else {
+        return;
+    };
+    let Ok(mount) = mounts.get(mount_entity) else {
+        player.mount = None;
+        return;
+    };
+
+    let cause = if mount.is_dead() {
+        MountLossCause::Died
+    } else if mount.will_bolt() {
+        MountLossCause::Bolted
+    } else {
+        return;
+    };
+
+    warn!("{} is lost ({:?})", mount.name, cause);
+    lost_events.send(MountLostEvent {
+        mount_name: mount.name.clone(),
+        cause,
+    });
+    commands.entity(mount_entity).despawn_recursive();
+    commands.entity(player_entity).remove::<Mounted>();
+    player.mount = None;
+}
+
+/// Mirror the owned mount into save data whenever it changes
+pub fn sync_mount_to_save(
+    player_query: Query<(Ref<Player>, Has<Mounted>)>,
+    mounts: Query<Ref<Mount>>,
+    mut game_state: ResMut<GameState>,
+) {
+    let Ok((player, is_mounted)) = player_query.get_single() else {
+        return;
+    };
+    let mount = player.mount.and_then(|entity| mounts.get(entity).ok());
+    let player_stats = &mut game_state.save_data.player_stats;
+    let changed = player.is_changed()
+        || mount.as_ref().is_some_and(|mount| mount.is_changed())
+        || is_mounted != player_stats.mounted;
+    if !changed {
+        return;
+    }
+
+    player_stats.mount = mount.as_ref().map(|mount| mount.name.clone());
+    player_stats.mount_state = mount.map(|mount| mount.clone());
+    player_stats.mounted = is_mounted;
+}
+
+/// Respawn the saved mount once a save has been loaded
+pub fn restore_mount_from_save(
+    mut commands: Commands,
+    mut slot: SaveSlot,
+    mut player_query: Query<(Entity, &mut Player)>,
+) {
+    let Ok((player_entity, mut player)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(save) = slot.take() else {
+        return;
+    };
+
+    if let Some(old_mount) = player.mount.take() {
+        commands.entity(old_mount).despawn_recursive();
+    }
+    commands.entity(player_entity).remove::<Mounted>();
+
+    let saved = &save.player_stats;
+    if let Some(mount) = saved.mount_state.clone() {
+        let name = mount.name.clone();
+        player.mount = Some(commands.spawn((mount, Name::new(format!("Mount_{}", name)))).id());
+        if saved.mounted {
+            commands.entity(player_entity).insert(Mounted);
+        }
+    }
+}
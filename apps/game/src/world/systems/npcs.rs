@@ -0,0 +1,378 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::world::components::{
+    FactionAlignment, FactionDatabase, MemoryKind, NpcAttitude, NpcCorruption, NpcDatabase, NpcInteraction,
+    NpcMemory, NpcSchedule, QuestDatabase, QuestLog, QuestUpdate, Settlement, fnv1a, route_interaction,
+};
+use crate::world::resources::game_state::{GameState, SaveData};
+use crate::world::state::{DreadLevel, WorldState};
+use crate::world::systems::data_files::DataFile;
+use crate::world::systems::economy::TradeCompletedEvent;
+use crate::world::systems::hex_world::NPCMarker;
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::quests::{QuestProgressEvent, StartQuestEvent, TalkToNpcEvent};
+use crate::world::systems::save::{SaveSlot, SavedResource};
+use crate::world::systems::time_weather::{DayNightCycle, TimeAdvancedEvent};
+use crate::utils::hex::world_to_hex;
+use dl_types::world::NPC;
+
+/// Settlement corruption from which a breaking NPC joins the cult rather than flee
+const CULT_CORRUPTION: f32 = 50.0;
+
+/// What routing an approach needs to know about an NPC
+type InteractionData<'a> = (
+    &'a NPC,
+    &'a NPCMarker,
+    &'a NpcSchedule,
+    &'a NpcMemory,
+    &'a NpcCorruption,
+    Option<&'a NpcAttitude>,
+);
+type ChangedNpcState = Or<(Changed<NpcMemory>, Changed<NpcCorruption>)>;
+
+/// Written by `ron-generator npcs`
+impl DataFile for NpcDatabase {
+    type Contents = Self;
+    const PATH: &'static str = "world/npcs.ron";
+
+    fn from_contents(contents: Self) -> Self {
+        contents
+    }
+}
+
+/// The player approaches an NPC
+#[derive(Event)]
+pub struct InteractWithNpcEvent {
+    pub npc: Entity,
+}
+
+/// Where an approach to an NPC led
+#[derive(Event)]
+pub struct NpcInteractionEvent {
+    pub npc: Entity,
+    pub interaction: NpcInteraction,
+}
+
+/// Something the player did that an NPC will remember, e.g. from combat
+#[derive(Event)]
+pub struct NpcMemoryEvent {
+    pub npc_uuid: String,
+    pub kind: MemoryKind,
+}
+
+/// Which NPC offered each quest, so they remember how it ended
+#[derive(Resource, Default)]
+pub struct QuestGivers {
+    pub givers: HashMap<String, String>,
+}
+
+/// Give each spawned NPC marker its HBF character, a daily schedule, and
+/// whatever it remembers and has become in this save
+pub fn hydrate_npcs(
+    mut commands: Commands,
+    database: Res<NpcDatabase>,
+    game_state: Res<GameState>,
+    day_night: Res<DayNightCycle>,
+    mut markers: Query<(Entity, &mut NPCMarker), Added<NPCMarker>>,
+) {
+    for (entity, mut marker) in markers.iter_mut() {
+        let Some(record) = database.get(&marker.uuid) else {
+            debug!("No NPC data for {}", marker.uuid);
+            continue;
+        };
+        let mut schedule = NpcSchedule::for_type(&record.npc_type);
+        schedule.update(day_night.current_hour);
+        let memory = game_state.save_data.npc_memories.get(&record.uuid).cloned().unwrap_or_default();
+        let corruption = game_state.save_data.npc_corruption.get(&record.uuid).copied().unwrap_or_default();
+
+        marker.npc_type = format!("{:?}", record.npc_type).to_lowercase();
+        marker.is_active = corruption != NpcCorruption::Fled;
+        commands.entity(entity).insert((
+            record.to_component(),
+            schedule,
+            memory,
+            corruption,
+            Name::new(record.name.clone()),
+        ));
+    }
+}
+
+/// Move NPCs through their day as time passes
+pub fn npc_schedule_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    day_night: Res<DayNightCycle>,
+    mut npcs: Query<(&NPC, &mut NpcSchedule)>,
+) {
+    if time_events.read().count() == 0 {
+        return;
+    }
+    for (npc, mut schedule) in npcs.iter_mut() {
+        if schedule.activity_at(day_night.current_hour) != schedule.current {
+            schedule.update(day_night.current_hour);
+            debug!("{} is now {:?}", npc.name, schedule.current);
+        }
+    }
+}
+
+/// Hold T and press a number to approach that NPC on the player's hex,
+/// counting them in name order
+pub fn npc_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    world_state: Res<WorldState>,
+    npcs: Query<(Entity, &NPC, &GlobalTransform)>,
+    mut interact_events: EventWriter<InteractWithNpcEvent>,
+) {
+    let Some(index) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyT)) else {
+        return;
+    };
+    let mut here: Vec<(Entity, &NPC)> = npcs
+        .iter()
+        .filter(|(_, _, transform)| world_state.player_hex == Some(world_to_hex(transform.translation())))
+        .map(|(entity, npc, _)| (entity, npc))
+        .collect();
+    if here.is_empty() {
+        info!("There is no one here");
+        return;
+    }
+    here.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    match here.get(index) {
+        Some((npc, _)) => {
+            interact_events.send(InteractWithNpcEvent { npc: *npc });
+        }
+        None => info!("There are only {} people here", here.len()),
+    }
+}
+
+/// Route the player's approach to an NPC to dialogue, their shop or a quest
+#[allow(clippy::too_many_arguments)]
+pub fn npc_interaction_system(
+    mut interact_events: EventReader<InteractWithNpcEvent>,
+    quests: Res<QuestDatabase>,
+    quest_log: Res<QuestLog>,
+    mut quest_givers: ResMut<QuestGivers>,
+    mut game_state: ResMut<GameState>,
+    npcs: Query<InteractionData>,
+    mut talk_events: EventWriter<TalkToNpcEvent>,
+    mut start_events: EventWriter<StartQuestEvent>,
+    mut interaction_events: EventWriter<NpcInteractionEvent>,
+) {
+    for event in interact_events.read() {
+        let Ok((npc, marker, schedule, memory, corruption, attitude)) = npcs.get(event.npc) else {
+            continue;
+        };
+        let quest = npc
+            .quest_giver
+            .then(|| offerable_quest(&quests, &quest_log, &marker.uuid))
+            .flatten();
+        let interaction = route_interaction(
+            npc,
+            *corruption,
+            schedule.current,
+            memory.disposition(),
+            attitude.copied(),
+            quest.as_deref(),
+        );
+
+        match &interaction {
+            NpcInteraction::Dialogue { dialogue_tree } => {
+                game_state.current_dialogue = Some(dialogue_tree.clone());
+                talk_events.send(TalkToNpcEvent {
+                    npc_uuid: marker.uuid.clone(),
+                });
+            }
+            NpcInteraction::Shop => {
+                info!("{} shows you their wares", npc.name);
+                talk_events.send(TalkToNpcEvent {
+                    npc_uuid: marker.uuid.clone(),
+                });
+            }
+            NpcInteraction::Quest { quest_id } => {
+                quest_givers.givers.insert(quest_id.clone(), marker.uuid.clone());
+                talk_events.send(TalkToNpcEvent {
+                    npc_uuid: marker.uuid.clone(),
+                });
+                start_events.send(StartQuestEvent {
+                    quest_id: quest_id.clone(),
+                });
+            }
+            NpcInteraction::Refuse { reason } => info!("{}", reason),
+            NpcInteraction::Hostile => warn!("{} turns on you", npc.name),
+            NpcInteraction::Absent => info!("{} is nowhere to be found", npc.name),
+        }
+        interaction_events.send(NpcInteractionEvent {
+            npc: event.npc,
+            interaction,
+        });
+    }
+}
+
+/// A quest the NPC could hand out: one that must be offered and that the
+/// player has not taken yet. Each NPC settles on their own pick.
+fn offerable_quest(quests: &QuestDatabase, quest_log: &QuestLog, npc_uuid: &str) -> Option<String> {
+    let mut offerable: Vec<&str> = quests
+        .quests
+        .values()
+        .filter(|quest| quest.start_trigger.is_none())
+        .filter(|quest| !quest_log.is_active(&quest.id) && !quest_log.is_finished(&quest.id))
+        .map(|quest| quest.id.as_str())
+        .collect();
+    if offerable.is_empty() {
+        return None;
+    }
+    offerable.sort_unstable();
+    let pick = (fnv1a(npc_uuid.as_bytes()) % offerable.len() as u64) as usize;
+    Some(offerable[pick].to_string())
+}
+
+/// NPCs remember trades, the quests they gave and whatever else other
+/// systems report
+pub fn npc_memory_system(
+    mut memory_events: EventReader<NpcMemoryEvent>,
+    mut quest_events: EventReader<QuestProgressEvent>,
+    mut trade_events: EventReader<TradeCompletedEvent>,
+    quest_givers: Res<QuestGivers>,
+    day_night: Res<DayNightCycle>,
+    settlements: Query<&GlobalTransform, With<Settlement>>,
+    mut npcs: Query<(&NPC, &NPCMarker, &NpcSchedule, &mut NpcMemory, &GlobalTransform)>,
+) {
+    let mut remembered: Vec<(String, MemoryKind)> = memory_events
+        .read()
+        .map(|event| (event.npc_uuid.clone(), event.kind))
+        .collect();
+
+    for event in quest_events.read() {
+        let (quest_id, kind) = match &event.update {
+            QuestUpdate::Completed { quest_id, .. } => (quest_id, MemoryKind::QuestCompleted),
+            QuestUpdate::Failed { quest_id, .. } => (quest_id, MemoryKind::QuestFailed),
+            _ => continue,
+        };
+        if let Some(giver) = quest_givers.givers.get(quest_id) {
+            remembered.push((giver.clone(), kind));
+        }
+    }
+
+    // Shopkeepers at work on the settlement's hex remember the trade
+    let trade_hexes: Vec<_> = trade_events
+        .read()
+        .filter_map(|event| settlements.get(event.settlement).ok())
+        .map(|transform| world_to_hex(transform.translation()))
+        .collect();
+
+    for (npc, marker, schedule, mut memory, transform) in npcs.iter_mut() {
+        let hex = world_to_hex(transform.translation());
+        let trades = if npc.shop_inventory.is_some() && schedule.current.is_available() {
+            trade_hexes.iter().filter(|trade_hex| **trade_hex == hex).count()
+        } else {
+            0
+        };
+        for _ in 0..trades {
+            memory.remember(MemoryKind::Traded, day_night.day);
+        }
+        for (_, kind) in remembered.iter().filter(|(uuid, _)| *uuid == marker.uuid) {
+            memory.remember(*kind, day_night.day);
+        }
+    }
+}
+
+/// As time passes under dread, NPCs waver and break: into the cult where
+/// corruption already holds their settlement or faction, otherwise they flee
+pub fn npc_corruption_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    dread: Res<DreadLevel>,
+    database: Res<NpcDatabase>,
+    factions: Res<FactionDatabase>,
+    settlements: Query<&Settlement>,
+    mut npcs: Query<(&NPC, &mut NPCMarker, &mut NpcCorruption)>,
+) {
+    if time_events.read().count() == 0 {
+        return;
+    }
+    let settlement_corruption: HashMap<&str, f32> = settlements
+        .iter()
+        .map(|settlement| (settlement.uuid.as_str(), settlement.corruption))
+        .collect();
+
+    for (npc, mut marker, mut corruption) in npcs.iter_mut() {
+        let Some(record) = database.get(&marker.uuid) else {
+            continue;
+        };
+        let local_corruption = record
+            .settlement_uuid
+            .as_deref()
+            .and_then(|uuid| settlement_corruption.get(uuid))
+            .copied()
+            .unwrap_or(0.0);
+        let corrupt_faction = record
+            .faction
+            .as_deref()
+            .and_then(|uuid| factions.get(uuid))
+            .is_some_and(|faction| faction.alignment == FactionAlignment::Corrupt);
+
+        let pressure = NpcCorruption::pressure(&dread.phase, local_corruption);
+        let next = corruption.next(pressure, record.resolve(), corrupt_faction || local_corruption >= CULT_CORRUPTION);
+        if next == *corruption {
+            continue;
+        }
+        match next {
+            NpcCorruption::Wavering => debug!("{} grows uneasy", npc.name),
+            NpcCorruption::Loyal => debug!("{} steadies", npc.name),
+            NpcCorruption::Cultist => warn!("{} has joined the cult", npc.name),
+            NpcCorruption::Fled => {
+                info!("{} has fled", npc.name);
+                marker.is_active = false;
+            }
+        }
+        *corruption = next;
+    }
+}
+
+/// Mirror NPC memory and corruption into save data whenever they change.
+/// NPCs the player never touched are left out.
+pub fn sync_npcs_to_save(
+    npcs: Query<(&NPCMarker, &NpcMemory, &NpcCorruption), ChangedNpcState>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (marker, memory, corruption) in npcs.iter() {
+        let save = &mut game_state.save_data;
+        if memory.memories.is_empty() && memory.settled == 0 {
+            save.npc_memories.remove(&marker.uuid);
+        } else {
+            save.npc_memories.insert(marker.uuid.clone(), memory.clone());
+        }
+        if *corruption == NpcCorruption::Loyal {
+            save.npc_corruption.remove(&marker.uuid);
+        } else {
+            save.npc_corruption.insert(marker.uuid.clone(), *corruption);
+        }
+    }
+}
+
+/// Restore NPC memory and corruption once a save has been loaded
+pub fn restore_npcs_from_save(
+    mut slot: SaveSlot,
+    mut npcs: Query<(&mut NPCMarker, &mut NpcMemory, &mut NpcCorruption)>,
+) {
+    let Some(save) = slot.take() else {
+        return;
+    };
+    for (mut marker, mut memory, mut corruption) in npcs.iter_mut() {
+        *memory = save.npc_memories.get(&marker.uuid).cloned().unwrap_or_default();
+        *corruption = save.npc_corruption.get(&marker.uuid).copied().unwrap_or_default();
+        marker.is_active = *corruption != NpcCorruption::Fled;
+    }
+}
+
+impl SavedResource for QuestGivers {
+    fn is_saved(&self, save: &SaveData) -> bool {
+        save.quest_givers == self.givers
+    }
+
+    fn save(&self, save: &mut SaveData) {
+        save.quest_givers = self.givers.clone();
+    }
+
+    fn restore(&mut self, save: &SaveData) {
+        self.givers = save.quest_givers.clone();
+    }
+}
@@ -2,6 +2,19 @@ use bevy::prelude::*;
 use std::collections::{HashMap, VecDeque, BinaryHeap};
 use std::cmp::Ordering;
 use crate::world::components::tiles::{HexCoord, BiomeType};
+use crate::world::components::player::Mount;
+
+/// Share of travel fatigue the rider still takes while a fresh mount carries them
+const MOUNTED_RIDER_FATIGUE: f32 = 0.3;
+
+/// Fatigue multiplier for the rider: a rested mount carries most of the
+/// burden, an exhausted one has to be led
+pub fn rider_fatigue_multiplier(mount: Option<&Mount>) -> f32 {
+    match mount {
+        Some(mount) if !mount.is_exhausted() => MOUNTED_RIDER_FATIGUE,
+        _ => 1.0,
+    }
+}
 
 #[derive(Component, Debug)]
 pub struct PlayerPosition {
@@ -100,6 +113,7 @@ pub fn calculate_hex_distance(a: HexCoord, b: HexCoord) -> f32 {
     (dx.abs() + dy.abs() + dz.abs()) / 2.0
 }
 
+/// `weather_intensity` is the local `WeatherSystem::movement_penalty()`
 pub fn get_movement_cost(biome: &BiomeType, weather_intensity: f32) -> f32 {
     let base_cost = match biome {
         BiomeType::Grassland => 1.0,
@@ -134,6 +148,13 @@ pub fn get_movement_cost(biome: &BiomeType, weather_intensity: f32) -> f32 {
     base_cost * (1.0 + weather_intensity * 0.5)
 }
 
+/// Movement cost of entering a hex, scaled by the mount's speed on that
+/// terrain when the player is riding
+pub fn get_travel_cost(biome: &BiomeType, weather_intensity: f32, mount: Option<&Mount>) -> f32 {
+    let cost = get_movement_cost(biome, weather_intensity);
+    mount.map_or(cost, |mount| cost / mount.travel_multiplier(biome))
+}
+
 pub fn get_tile_accessibility(biome: &BiomeType, player_level: u32) -> TileAccessibility {
     match biome {
         BiomeType::Grassland | BiomeType::Forest => TileAccessibility::Passable,
@@ -1,19 +1,22 @@
 use bevy::prelude::*;
-use crate::world::components::{Player, Mount};
+use crate::world::components::{Player, Mount, Mounted, Tile};
 use crate::world::state::WorldState;
+use crate::world::systems::mounts::biome_at;
+use crate::world::systems::pathfinding::{TileAccessibility, get_tile_accessibility};
+use crate::world::systems::rest_fatigue::PlayerStats;
 use crate::utils::hex::*;
 
 pub fn player_movement_system(
     keyboard: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
-    mut player_query: Query<(&mut Transform, &Player, Option<&Mount>)>,
+    mut player_query: Query<(&mut Transform, &Player, Option<&PlayerStats>, Has<Mounted>)>,
+    mounts: Query<&Mount>,
+    tiles: Query<&Tile>,
     mut world_state: ResMut<WorldState>,
 ) {
-    if let Ok((mut transform, player, mount)) = player_query.get_single_mut() {
+    if let Ok((mut transform, player, stats, is_mounted)) = player_query.get_single_mut() {
         let mut movement = Vec3::ZERO;
         let base_speed = 5.0;
-        let mount_multiplier = mount.map_or(1.0, |m| m.speed_multiplier);
-        let movement_speed = base_speed * mount_multiplier;
         
         // Handle hex-based movement input
         if keyboard.just_pressed(KeyCode::KeyW) || keyboard.just_pressed(KeyCode::ArrowUp) {
@@ -36,24 +39,39 @@ pub fn player_movement_system(
         }
         
         if movement.length() > 0.0 {
+            // Riding speed depends on how well the mount handles the current terrain
+            let mount = player.mount.filter(|_| is_mounted).and_then(|entity| mounts.get(entity).ok());
+            let mount_multiplier = match (mount, biome_at(&tiles, world_to_hex(transform.translation))) {
+                (Some(mount), Some(biome)) => mount.travel_multiplier(&biome),
+                (Some(mount), None) => mount.speed_multiplier,
+                (None, _) => 1.0,
+            };
+            let movement_speed = base_speed * mount_multiplier;
+            
             // Normalize hex movement and apply to transform
             let target_hex = world_to_hex(transform.translation + movement.normalize());
-            let target_world = hex_to_world(target_hex);
-            
-            // Smooth movement to target position
-            let direction = (target_world - transform.translation).normalize();
-            transform.translation += direction * movement_speed * time.delta_secs();
             
-            // Snap to hex center when close enough
-            if transform.translation.distance(target_world) < 0.1 {
-                transform.translation = target_world;
-                world_state.player_hex = Some(target_hex);
+            // Harsher terrain stays closed until the player is experienced enough
+            let player_level = stats.map_or(1, |stats| stats.level);
+            let blocked = biome_at(&tiles, target_hex)
+                .is_some_and(|biome| get_tile_accessibility(&biome, player_level) == TileAccessibility::Impassable);
+            if !blocked {
+                let target_world = hex_to_world(target_hex);
+                
+                // Smooth movement to target position
+                let direction = (target_world - transform.translation).normalize();
+                transform.translation += direction * movement_speed * time.delta_secs();
+                
+                // Snap to hex center when close enough
+                if transform.translation.distance(target_world) < 0.1 {
+                    transform.translation = target_world;
+                    world_state.player_hex = Some(target_hex);
+                }
             }
         }
         
-        // Handle mount interactions
+        // Mounting is handled by the mount systems (M); Space interacts with features
         if keyboard.just_pressed(KeyCode::Space) {
-            // Try to mount/dismount or interact with features
             handle_player_interaction(&mut world_state, transform.translation);
         }
     }
@@ -62,11 +80,10 @@ pub fn player_movement_system(
 fn handle_player_interaction(world_state: &mut ResMut<WorldState>, player_position: Vec3) {
     let player_hex = world_to_hex(player_position);
     
-    // Check for nearby features or mounts
+    // Check for nearby features
     info!("Player interacting at hex: {:?}", player_hex);
     
     // TODO: Implement feature interaction system
-    // TODO: Implement mount system
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -3,6 +3,7 @@ use bevy::audio::*;
 use std::collections::HashMap;
 use serde_json::Value;
 use crate::world::state::{DreadLevel, GameState};
+use crate::world::systems::time_weather::{DayNightCycle, WeatherSystem, WeatherType};
 
 // Note: Audio assets are pre-generated during build time using FREESOUND_API_KEY
 // Runtime audio system only plays pre-bundled audio files - no API calls needed
@@ -253,6 +254,78 @@ pub fn play_contextual_audio_stinger(
     ));
 }
 
+/// Looping weather bed layered under the dread theme
+#[derive(Component)]
+pub struct WeatherAmbience;
+
+fn get_weather_audio_file(weather: &WeatherType, is_night: bool) -> &'static str {
+    match weather {
+        WeatherType::Clear if is_night => "audio/weather/night_wind.ogg",
+        WeatherType::Clear => "audio/weather/light_breeze.ogg",
+        WeatherType::Rain => "audio/weather/rain.ogg",
+        WeatherType::Storm => "audio/weather/storm.ogg",
+        WeatherType::Snow => "audio/weather/snow_wind.ogg",
+        WeatherType::Fog => "audio/weather/fog_drips.ogg",
+        WeatherType::VoidStorm => "audio/weather/void_storm.ogg",
+    }
+}
+
+// Swap the weather bed when the weather or time of day changes and follow
+// the storm's intensity in between. The old bed plays on until the new one
+// has loaded.
+#[allow(clippy::too_many_arguments)]
+pub fn update_weather_audio(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    audio_system: Res<ProceduralAudioSystem>,
+    weather: Res<WeatherSystem>,
+    day_night: Res<DayNightCycle>,
+    mut ambience_query: Query<(Entity, Option<&mut AudioSink>), With<WeatherAmbience>>,
+    mut playing: Local<Option<(WeatherType, bool)>>,
+    mut loading: Local<Option<Handle<AudioSource>>>,
+) {
+    let is_night = day_night.is_night();
+    let volume = audio_system.volume_multiplier * (0.2 + weather.intensity * 0.6);
+
+    if *playing == Some((weather.current_weather, is_night)) {
+        for (_, sink) in ambience_query.iter_mut() {
+            if let Some(mut sink) = sink {
+                sink.set_volume(Volume::Linear(volume));
+            }
+        }
+        return;
+    }
+
+    let handle: Handle<AudioSource> =
+        asset_server.load(get_weather_audio_file(&weather.current_weather, is_night));
+    let load_state = asset_server.load_state(&handle);
+    if !load_state.is_loaded() && !load_state.is_failed() {
+        // Hold the handle so the load carries on between frames
+        *loading = Some(handle);
+        return;
+    }
+    *loading = None;
+    *playing = Some((weather.current_weather, is_night));
+
+    for (entity, _) in ambience_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    // Weather beds are optional; play nothing rather than a missing asset
+    if load_state.is_failed() {
+        debug!("No weather audio for {:?}", weather.current_weather);
+        return;
+    }
+    commands.spawn((
+        AudioPlayer(handle),
+        PlaybackSettings {
+            volume: Volume::Linear(volume),
+            mode: PlaybackMode::Loop,
+            ..default()
+        },
+        WeatherAmbience,
+    ));
+}
+
 #[derive(Debug, Clone)]
 pub enum AudioStingerType {
     BossEncounter,
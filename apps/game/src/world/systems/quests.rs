@@ -0,0 +1,404 @@
+use bevy::prelude::*;
+
+use crate::world::components::{
+    FactionSimulation, HexCoord, Inventory, ItemType, Player, QuestDatabase, QuestDefinition, QuestLog,
+    QuestObjective, QuestUpdate, QuestWorld, dilemma_alignment, dilemma_effects, dilemma_flags,
+};
+use crate::world::resources::game_state::{GameState, PlayerChoice, SaveData};
+use crate::world::state::{DreadLevel, WorldState};
+use crate::world::systems::data_files::DataFile;
+use crate::world::systems::dungeon_interior::{DungeonLayouts, DungeonProgressLedger};
+use crate::world::systems::factions::ReputationChangeEvent;
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::save::SavedResource;
+use crate::world::systems::time_weather::DayNightCycle;
+
+/// Quest pool written alongside the other organized data pools. Entries
+/// that are not valid definitions are skipped with a warning.
+impl DataFile for QuestDatabase {
+    type Contents = Vec<serde_json::Value>;
+    const PATH: &'static str = "organized_pools/quests.json";
+
+    fn from_contents(contents: Vec<serde_json::Value>) -> Self {
+        let mut database = QuestDatabase::default();
+        for entry in contents {
+            match serde_json::from_value::<QuestDefinition>(entry) {
+                Ok(quest) => database.insert(quest),
+                Err(e) => warn!("Invalid quest in {}: {}", Self::PATH, e),
+            }
+        }
+        database
+    }
+}
+
+/// Offer a quest to the player, e.g. from a quest giver's dialogue
+#[derive(Event)]
+pub struct StartQuestEvent {
+    pub quest_id: String,
+}
+
+/// The player spoke with an NPC
+#[derive(Event)]
+pub struct TalkToNpcEvent {
+    pub npc_uuid: String,
+}
+
+/// The player's answer to a quest's dilemma
+#[derive(Event)]
+pub struct SettleDilemmaEvent {
+    pub quest_id: String,
+    /// Spare rather than sacrifice
+    pub mercy: bool,
+}
+
+#[derive(Event)]
+pub struct QuestProgressEvent {
+    pub update: QuestUpdate,
+}
+
+/// The live world as the quest log sees it this frame
+struct LiveQuestWorld<'a> {
+    player_hex: Option<HexCoord>,
+    game_state: &'a GameState,
+    dread: f32,
+    day: u32,
+    layouts: &'a DungeonLayouts,
+    ledger: &'a DungeonProgressLedger,
+    factions: &'a FactionSimulation,
+    talked_to: &'a [String],
+    inventory: Option<&'a Inventory>,
+}
+
+impl QuestWorld for LiveQuestWorld<'_> {
+    fn player_hex(&self) -> Option<HexCoord> {
+        self.player_hex
+    }
+
+    fn story_flag(&self, flag: &str) -> bool {
+        self.game_state.get_story_flag(flag)
+    }
+
+    fn dread(&self) -> f32 {
+        self.dread
+    }
+
+    fn day(&self) -> u32 {
+        self.day
+    }
+
+    fn dungeon_cleared(&self, dungeon_uuid: &str) -> bool {
+        match (self.layouts.get(dungeon_uuid), self.ledger.progress.get(dungeon_uuid)) {
+            (Some(layout), Some(progress)) => progress.completion(layout) >= 1.0,
+            _ => false,
+        }
+    }
+
+    fn reputation(&self, faction: &str) -> i32 {
+        self.factions.reputation(faction)
+    }
+
+    fn talked_to(&self, npc_uuid: &str) -> bool {
+        self.talked_to.iter().any(|npc| npc == npc_uuid)
+    }
+
+    fn carries_quest_item(&self, quest_id: &str, item_name: &str) -> bool {
+        self.inventory.is_some_and(|inventory| {
+            inventory.items.iter().any(|item| {
+                item.name == item_name
+                    && matches!(&item.item_type, ItemType::QuestItem { quest_id: id, .. } if id == quest_id)
+            })
+        })
+    }
+}
+
+pub fn start_quest_system(
+    mut start_events: EventReader<StartQuestEvent>,
+    database: Res<QuestDatabase>,
+    day_night: Res<DayNightCycle>,
+    mut quest_log: ResMut<QuestLog>,
+    mut quest_events: EventWriter<QuestProgressEvent>,
+) {
+    for event in start_events.read() {
+        if !quest_log.start(&database, &event.quest_id, day_night.day) {
+            debug!("Quest {} cannot be started", event.quest_id);
+            continue;
+        }
+        quest_events.send(QuestProgressEvent {
+            update: QuestUpdate::Started {
+                quest_id: event.quest_id.clone(),
+            },
+        });
+    }
+}
+
+/// Quests waiting on the player to settle their dilemma, in the order they began
+fn pending_dilemmas<'a>(quest_log: &'a QuestLog, database: &'a QuestDatabase) -> impl Iterator<Item = &'a str> {
+    quest_log
+        .active
+        .iter()
+        .map(|active| active.quest_id.as_str())
+        .filter(|quest_id| quest_log.awaits_dilemma(database, quest_id))
+}
+
+/// Shift and a number key shows mercy in that pending dilemma; Control and
+/// a number key chooses sacrifice
+pub fn dilemma_choice_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    database: Res<QuestDatabase>,
+    quest_log: Res<QuestLog>,
+    mut settle_events: EventWriter<SettleDilemmaEvent>,
+) {
+    let choice = number_key_pressed(&keyboard, NumberKeyModifier::Shift)
+        .map(|index| (index, true))
+        .or_else(|| number_key_pressed(&keyboard, NumberKeyModifier::Control).map(|index| (index, false)));
+    let Some((index, mercy)) = choice else {
+        return;
+    };
+    match pending_dilemmas(&quest_log, &database).nth(index) {
+        Some(quest_id) => {
+            settle_events.send(SettleDilemmaEvent {
+                quest_id: quest_id.to_string(),
+                mercy,
+            });
+        }
+        None => info!("No dilemma {} awaits you", index + 1),
+    }
+}
+
+/// Settle a dilemma as the player chose by setting the quest's dilemma flags;
+/// the quest then ends the next time it is evaluated. The choice is recorded
+/// so it moves the player's alignment.
+pub fn settle_dilemma_system(
+    mut settle_events: EventReader<SettleDilemmaEvent>,
+    database: Res<QuestDatabase>,
+    quest_log: Res<QuestLog>,
+    mut game_state: ResMut<GameState>,
+) {
+    for event in settle_events.read() {
+        if !quest_log.awaits_dilemma(&database, &event.quest_id) {
+            debug!("Quest {} has no dilemma waiting", event.quest_id);
+            continue;
+        }
+        let title = database.get(&event.quest_id).map_or(event.quest_id.as_str(), |quest| quest.title.as_str());
+        let choice = if event.mercy { "mercy" } else { "sacrifice" };
+        info!("You choose {} in {}", choice, title);
+        for (flag, value) in dilemma_flags(&event.quest_id, event.mercy) {
+            game_state.set_story_flag(flag, value);
+        }
+        let timestamp = game_state.game_time;
+        game_state.record_player_choice(PlayerChoice {
+            choice_id: format!("{}_dilemma", event.quest_id),
+            choice_text: format!("Chose {} in {}", choice, title),
+            timestamp,
+            consequences_applied: false,
+            dread_impact: 0.0,
+            companion_reactions: Default::default(),
+            faction_effects: Default::default(),
+            alignment: dilemma_alignment(event.mercy),
+            effects: dilemma_effects(event.mercy),
+            delayed: Vec::new(),
+        });
+    }
+}
+
+/// Evaluate triggers and objectives against the world and advance quests
+#[allow(clippy::too_many_arguments)]
+pub fn quest_progress_system(
+    mut talk_events: EventReader<TalkToNpcEvent>,
+    database: Res<QuestDatabase>,
+    mut quest_log: ResMut<QuestLog>,
+    game_state: Res<GameState>,
+    world_state: Res<WorldState>,
+    dread: Res<DreadLevel>,
+    day_night: Res<DayNightCycle>,
+    layouts: Res<DungeonLayouts>,
+    ledger: Res<DungeonProgressLedger>,
+    factions: Res<FactionSimulation>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut quest_events: EventWriter<QuestProgressEvent>,
+) {
+    let talked_to: Vec<String> = talk_events.read().map(|event| event.npc_uuid.clone()).collect();
+    let world = LiveQuestWorld {
+        player_hex: world_state.player_hex,
+        game_state: &game_state,
+        dread: dread.current,
+        day: day_night.day,
+        layouts: &layouts,
+        ledger: &ledger,
+        factions: &factions,
+        talked_to: &talked_to,
+        inventory: player_query.get_single().ok(),
+    };
+
+    // Only touch the log when something moved, so saves sync on real changes
+    let mut log = quest_log.bypass_change_detection().clone();
+    let updates = log.update(&database, &world);
+    if updates.is_empty() {
+        return;
+    }
+    *quest_log = log;
+    for update in updates {
+        quest_events.send(QuestProgressEvent { update });
+    }
+}
+
+/// Apply what quests did: hand over delivered items, and on completion or
+/// failure set story flags, shift dread and faction standing, release the
+/// quest's items and offer any follow-up quest
+#[allow(clippy::too_many_arguments)]
+pub fn apply_quest_outcomes(
+    mut quest_events: EventReader<QuestProgressEvent>,
+    database: Res<QuestDatabase>,
+    day_night: Res<DayNightCycle>,
+    mut quest_log: ResMut<QuestLog>,
+    mut game_state: ResMut<GameState>,
+    mut dread: ResMut<DreadLevel>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    mut reputation_events: EventWriter<ReputationChangeEvent>,
+) {
+    let mut inventory = player_query.get_single_mut().ok();
+
+    for event in quest_events.read() {
+        let (quest_id, outcome) = match &event.update {
+            QuestUpdate::Started { quest_id } => {
+                let title = database.get(quest_id).map_or(quest_id.as_str(), |quest| quest.title.as_str());
+                info!("New quest: {}", title);
+                if quest_log.awaits_dilemma(&database, quest_id) {
+                    info!("Shift and a number shows mercy in a dilemma, Control and a number chooses sacrifice");
+                }
+                continue;
+            }
+            QuestUpdate::StepCompleted { quest_id, step, objective } => {
+                if let (QuestObjective::DeliverItem { item_name, .. }, Some(inventory)) = (objective, inventory.as_mut()) {
+                    inventory.remove_item(item_name, 1);
+                }
+                debug!("Quest {} step {} complete", quest_id, step + 1);
+                continue;
+            }
+            QuestUpdate::Completed { quest_id, outcome } => {
+                info!("Quest complete: {}", outcome.description);
+                (quest_id, outcome)
+            }
+            QuestUpdate::Failed { quest_id, outcome } => {
+                warn!("Quest failed: {}", outcome.description);
+                (quest_id, outcome)
+            }
+        };
+
+        for (flag, value) in &outcome.set_flags {
+            game_state.set_story_flag(flag.clone(), *value);
+        }
+        if outcome.dread_change > 0.0 {
+            dread.add_dread(outcome.dread_change);
+        } else {
+            dread.remove_dread(-outcome.dread_change);
+        }
+        for (faction, amount) in &outcome.reputation {
+            reputation_events.send(ReputationChangeEvent {
+                faction: faction.clone(),
+                amount: *amount,
+                reason: format!("quest {}", quest_id),
+            });
+        }
+        if let Some(inventory) = inventory.as_mut() {
+            inventory.release_quest_items(quest_id);
+        }
+        let Some(next) = &outcome.unlocks else {
+            continue;
+        };
+        if quest_log.start(&database, next, day_night.day) {
+            info!("New quest: {}", database.get(next).map_or(next.as_str(), |quest| quest.title.as_str()));
+        }
+    }
+}
+
+impl SavedResource for QuestLog {
+    fn is_saved(&self, save: &SaveData) -> bool {
+        save.quest_log == *self
+    }
+
+    fn save(&self, save: &mut SaveData) {
+        save.quest_log = self.clone();
+    }
+
+    fn restore(&mut self, save: &SaveData) {
+        *self = save.quest_log.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::components::{ChoiceLedger, QuestOutcome, QuestStep, dilemma_resolved_flag};
+    use crate::world::systems::alignment::{choice_consequence_system, AlignmentChangedEvent};
+
+    const QUEST: &str = "bell";
+
+    fn app() -> App {
+        let mut database = QuestDatabase::default();
+        database.insert(QuestDefinition {
+            id: QUEST.to_string(),
+            title: "The Drowned Bell".to_string(),
+            summary: String::new(),
+            start_trigger: None,
+            steps: vec![QuestStep {
+                description: "Decide the bell-ringer's fate".to_string(),
+                objective: QuestObjective::StoryFlag {
+                    flag: dilemma_resolved_flag(QUEST),
+                },
+                fail_if: None,
+            }],
+            outcomes: vec![QuestOutcome::default()],
+            failure: QuestOutcome::default(),
+            time_limit_days: None,
+        });
+        let mut quest_log = QuestLog::default();
+        assert!(quest_log.start(&database, QUEST, 1));
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(database)
+            .insert_resource(quest_log)
+            .init_resource::<GameState>()
+            .init_resource::<ChoiceLedger>()
+            .init_resource::<WorldState>()
+            .init_resource::<DreadLevel>()
+            .add_event::<SettleDilemmaEvent>()
+            .add_event::<ReputationChangeEvent>()
+            .add_event::<AlignmentChangedEvent>()
+            .add_systems(Update, (settle_dilemma_system, choice_consequence_system).chain());
+        app
+    }
+
+    fn settle(app: &mut App, mercy: bool) {
+        app.world_mut().send_event(SettleDilemmaEvent {
+            quest_id: QUEST.to_string(),
+            mercy,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn test_settled_dilemma_moves_alignment() {
+        let mut app = app();
+        settle(&mut app, true);
+
+        let game_state = app.world().resource::<GameState>();
+        assert!(game_state.get_story_flag(&dilemma_resolved_flag(QUEST)));
+        assert!(game_state.player_choices.iter().all(|choice| choice.consequences_applied));
+        let ledger = app.world().resource::<ChoiceLedger>();
+        assert_eq!(ledger.alignment, dilemma_alignment(true));
+        assert_eq!(ledger.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_sacrifice_darkens_alignment_and_adds_dread() {
+        let mut app = app();
+        settle(&mut app, false);
+
+        let ledger = app.world().resource::<ChoiceLedger>();
+        assert!(ledger.alignment.mercy < 0.0);
+        assert!(ledger.alignment.light < 0.0);
+        assert!(app.world().resource::<DreadLevel>().current > 0.0);
+    }
+}
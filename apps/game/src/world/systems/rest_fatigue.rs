@@ -1,7 +1,39 @@
 use bevy::prelude::*;
-use bevy_rand::prelude::*;
-use crate::world::components::tiles::HexCoord;
-use crate::world::systems::regional_progression::EmotionalState;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::world::components::player::Mount;
+use crate::world::components::{
+    BiomeType, Companion, ExperienceSource, HexCoord, Inventory, ItemEffect, ItemType, Mounted, Player, ServiceType,
+    Settlement, Tile,
+};
+use crate::world::resources::game_state::GameState;
+use crate::world::state::{WorldRng, WorldState};
+use crate::world::systems::dungeon_interior::threat_for_band;
+use crate::world::systems::hex_world::SettlementMarker;
+use crate::world::systems::items::UseToolEvent;
+use crate::world::systems::leveling::AwardExperienceEvent;
+use crate::world::systems::mounts::biome_at;
+use crate::world::systems::pathfinding::{get_movement_cost, rider_fatigue_multiplier};
+use crate::world::systems::regional_progression::{generate_dynamic_region, EmotionalState};
+use crate::world::systems::save::SaveSlot;
+use crate::world::systems::time_weather::{
+    AdvanceTimeEvent, DayNightCycle, TimeAdvanceReason, TimeAdvancedEvent, WeatherSystem, WeatherType,
+};
+use crate::utils::hex::world_to_hex;
+
+/// Hunger and thirst run from 0 (sated) to this
+pub const MAX_NEED: f32 = 100.0;
+/// Provisions are eaten or drunk automatically past this
+const AUTO_CONSUME_THRESHOLD: f32 = 40.0;
+/// Fraction of max fatigue at which exhaustion penalties start
+const EXHAUSTION_RATIO: f32 = 0.8;
+/// A night's sleep, in game hours
+pub const FULL_REST_HOURS: f32 = 8.0;
+/// Share of a rest lost per unit of shortfall in weather, mood, safety,
+/// comfort or shelter; at worst a rest keeps a quarter of its type's rate
+const RECOVERY_SHORTFALL_WEIGHT: f32 = 0.15;
+/// Random stream for camp ambushes, see `WorldRng`
+const CAMP_RNG_STREAM: u64 = 3;
 
 #[derive(Component, Debug)]
 pub struct PlayerStats {
@@ -11,6 +43,8 @@ pub struct PlayerStats {
     pub fatigue: f32,
     pub max_fatigue: f32,
     pub rest_quality: f32, // 0.0 = exhausted, 1.0 = well rested
+    pub hunger: f32, // 0.0 = fed, MAX_NEED = starving
+    pub thirst: f32, // 0.0 = quenched, MAX_NEED = parched
 }
 
 impl Default for PlayerStats {
@@ -22,30 +56,76 @@ impl Default for PlayerStats {
             fatigue: 0.0,
             max_fatigue: 100.0,
             rest_quality: 1.0,
+            hunger: 0.0,
+            thirst: 0.0,
         }
     }
 }
 
-#[derive(Resource, Debug)]
-pub struct DayNightCycle {
-    pub current_hour: f32, // 0.0 = midnight, 12.0 = noon
-    pub day_length_hours: f32, // Real-time minutes for a full day
-    pub movement_points_remaining: u32,
-    pub max_daily_movement: u32,
+impl PlayerStats {
+    pub fn is_exhausted(&self) -> bool {
+        self.fatigue >= self.max_fatigue * EXHAUSTION_RATIO
+    }
+
+    pub fn add_fatigue(&mut self, amount: f32) {
+        self.fatigue = (self.fatigue + amount).clamp(0.0, self.max_fatigue);
+        self.rest_quality = 1.0 - self.fatigue / self.max_fatigue;
+    }
 }
 
-impl Default for DayNightCycle {
-    fn default() -> Self {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Story,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+/// Survival rules; replace the resource with another difficulty's preset to
+/// retune the whole loop
+#[derive(Resource, Debug, Clone)]
+pub struct SurvivalConfig {
+    pub difficulty: Difficulty,
+    pub fatigue_multiplier: f32, // Scales fatigue per hex moved
+    pub hunger_per_hour: f32,
+    pub thirst_per_hour: f32,
+    pub starvation_damage_per_hour: f32, // Applied at MAX_NEED hunger or thirst
+    pub exhaustion_sanity_loss_per_hour: f32,
+    pub exhaustion_companion_stress_per_hour: f32,
+    pub ambush_multiplier: f32,
+    pub inn_price_multiplier: f32,
+}
+
+impl SurvivalConfig {
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        let (fatigue, hunger, thirst, starvation, sanity, stress, ambush, inn) = match difficulty {
+            Difficulty::Story => (0.6, 1.0, 1.5, 1.0, 0.5, 0.5, 0.5, 0.5),
+            Difficulty::Normal => (1.0, 2.0, 3.0, 2.0, 1.0, 1.5, 1.0, 1.0),
+            Difficulty::Hard => (1.3, 2.5, 4.0, 3.0, 2.0, 2.5, 1.5, 1.5),
+            Difficulty::Nightmare => (1.6, 3.0, 5.0, 5.0, 3.0, 4.0, 2.0, 2.0),
+        };
         Self {
-            current_hour: 8.0, // Start at 8 AM
-            day_length_hours: 24.0,
-            movement_points_remaining: 8, // 8 hex walking, 12 hex running
-            max_daily_movement: 8,
+            difficulty,
+            fatigue_multiplier: fatigue,
+            hunger_per_hour: hunger,
+            thirst_per_hour: thirst,
+            starvation_damage_per_hour: starvation,
+            exhaustion_sanity_loss_per_hour: sanity,
+            exhaustion_companion_stress_per_hour: stress,
+            ambush_multiplier: ambush,
+            inn_price_multiplier: inn,
         }
     }
 }
 
-#[derive(Component, Debug)]
+impl Default for SurvivalConfig {
+    fn default() -> Self {
+        Self::for_difficulty(Difficulty::Normal)
+    }
+}
+
+#[derive(Component, Debug, Clone)]
 pub struct RestSite {
     pub rest_type: RestType,
     pub safety_level: f32, // 0.0 = dangerous, 1.0 = completely safe
@@ -61,33 +141,41 @@ pub enum RestType {
     WildRough,     // Sleeping rough in the wilderness
 }
 
-#[derive(Resource, Debug)]
-pub struct WeatherSystem {
-    pub current_weather: WeatherType,
-    pub intensity: f32, // 0.0 = mild, 1.0 = severe
-    pub temperature: f32, // -1.0 = freezing, 0.0 = mild, 1.0 = scorching
-    pub visibility: f32, // 0.0 = no visibility, 1.0 = clear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestRequiredReason {
+    Night,
+    Exhausted,
+    OutOfMovement,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum WeatherType {
-    Clear,
-    Rain,
-    Storm,
-    Snow,
-    Fog,
-    VoidStorm, // Supernatural weather in corrupted areas
+/// Player asks to make camp where they stand
+#[derive(Event)]
+pub struct MakeCampEvent {
+    pub hours: f32,
 }
 
-impl Default for WeatherSystem {
-    fn default() -> Self {
-        Self {
-            current_weather: WeatherType::Clear,
-            intensity: 0.2,
-            temperature: 0.0,
-            visibility: 1.0,
-        }
-    }
+/// Player asks for a room at a settlement's inn
+#[derive(Event)]
+pub struct InnRestEvent {
+    pub settlement: Entity,
+}
+
+/// A rest that has been set up and is ready to resolve
+#[derive(Event)]
+pub struct RestEvent {
+    pub site: RestSite,
+    pub hours: f32,
+}
+
+#[derive(Event)]
+pub struct AmbushEvent {
+    pub hex: HexCoord,
+    pub hours_rested: f32,
+}
+
+#[derive(Event)]
+pub struct RestRequiredEvent {
+    pub reason: RestRequiredReason,
 }
 
 pub fn calculate_fatigue_from_movement(
@@ -95,13 +183,16 @@ pub fn calculate_fatigue_from_movement(
     terrain_difficulty: f32,
     weather_penalty: f32,
     player_stats: &PlayerStats,
+    mount: Option<&Mount>,
 ) -> f32 {
     let base_fatigue = distance * 2.0;
     let terrain_multiplier = 1.0 + terrain_difficulty;
     let weather_multiplier = 1.0 + weather_penalty;
     let fitness_modifier = 1.0 - (player_stats.level as f32 * 0.01); // Higher level = less fatigue
     
-    base_fatigue * terrain_multiplier * weather_multiplier * fitness_modifier.max(0.5)
+    let mount_modifier = rider_fatigue_multiplier(mount);
+    
+    base_fatigue * terrain_multiplier * weather_multiplier * fitness_modifier.max(0.5) * mount_modifier
 }
 
 pub fn calculate_rest_recovery(
@@ -114,7 +205,7 @@ pub fn calculate_rest_recovery(
         RestType::Inn => 1.0,
         RestType::Camp => 0.7,
         RestType::Shelter => 0.5,
-        RestType::WildRough => 0.3,
+        RestType::WildRough => 0.4,
     };
     
     // Weather affects rest quality
@@ -136,16 +227,21 @@ pub fn calculate_rest_recovery(
         EmotionalState::Void => 0.3,
     };
     
-    let safety_modifier = rest_site.safety_level;
-    let comfort_modifier = rest_site.comfort_level;
     let shelter_modifier = (rest_site.shelter_quality + (1.0 - weather.intensity)) / 2.0;
-    
-    let total_modifier = base_recovery_rate 
-        * weather_modifier 
-        * emotional_modifier 
-        * safety_modifier 
-        * comfort_modifier 
-        * shelter_modifier;
+
+    // Shortfalls add up rather than multiply, so a bad night in bad weather
+    // is still a night's sleep
+    let shortfall: f32 = [
+        weather_modifier,
+        emotional_modifier,
+        rest_site.safety_level,
+        rest_site.comfort_level,
+        shelter_modifier,
+    ]
+    .iter()
+    .map(|modifier| 1.0 - modifier.clamp(0.0, 1.0))
+    .sum();
+    let total_modifier = base_recovery_rate * (1.0 - shortfall * RECOVERY_SHORTFALL_WEIGHT);
     
     let health_recovery = hours_rested * 5.0 * total_modifier;
     let fatigue_recovery = hours_rested * 10.0 * total_modifier;
@@ -153,121 +249,421 @@ pub fn calculate_rest_recovery(
     (health_recovery, fatigue_recovery)
 }
 
-pub fn update_day_night_cycle(
-    time: Res<Time>,
-    mut day_night: ResMut<DayNightCycle>,
+/// Nightly cost of a room, in copper, by settlement type. Void outposts keep
+/// no inn.
+pub fn inn_price_for(settlement: &SettlementMarker) -> Option<u32> {
+    match settlement.settlement_type.as_str() {
+        "village" => Some(50),
+        "settlement" => Some(80),
+        "port" => Some(100),
+        "oasis" => Some(120),
+        "stronghold" => Some(150),
+        "cursed_refuge" => Some(300),
+        _ => None,
+    }
+}
+
+/// Nightly cost of a room where the HBF settlement data is known: only while
+/// its lodging is open, priced by size and corruption
+pub fn inn_price_at(marker: &SettlementMarker, settlement: Option<&Settlement>) -> Option<u32> {
+    match settlement {
+        Some(settlement) if settlement.is_open(ServiceType::Lodging) => Some(settlement.service_price(ServiceType::Lodging)),
+        Some(_) => None,
+        None => inn_price_for(marker),
+    }
+}
+
+/// Safety, comfort and shelter of a camp on this terrain with the kit carried
+pub fn camp_site_for(biome: &BiomeType, inventory: &Inventory) -> RestSite {
+    let has_tool = |wanted: &str| {
+        inventory.items.iter().any(|item| {
+            matches!(&item.item_type, ItemType::Tool { tool_type, durability, .. } if tool_type == wanted && *durability > 0)
+        })
+    };
+    let has_tent = has_tool("tent");
+    let has_bedroll = has_tool("bedroll");
+    let has_fire = has_tool("tinderbox");
+
+    let terrain_safety = if biome.is_corrupted() {
+        0.3
+    } else {
+        match biome.terrain_category() {
+            "forest" | "mountain" => 0.6, // Cover to hide in
+            _ => 0.5,
+        }
+    };
+
+    RestSite {
+        rest_type: if has_tent || has_bedroll { RestType::Camp } else { RestType::WildRough },
+        safety_level: (terrain_safety + if has_fire { 0.2 } else { 0.0 }).min(0.9),
+        comfort_level: if has_bedroll { 0.7 } else { 0.3 },
+        shelter_quality: if has_tent { 0.8 } else { 0.2 },
+    }
+}
+
+fn emotional_state_at(hex: HexCoord, seed: u64) -> EmotionalState {
+    generate_dynamic_region(hex.distance_from_origin().max(1), seed).emotional_arc
+}
+
+/// Give a freshly spawned player survival stats
+pub fn attach_player_survival_stats(
+    mut commands: Commands,
+    players: Query<Entity, (With<Player>, Without<PlayerStats>)>,
 ) {
-    // Advance time - each real second = 1 game hour by default
-    day_night.current_hour += time.delta_secs() / 60.0; // 1 real minute = 1 game hour
-    
-    if day_night.current_hour >= 24.0 {
-        day_night.current_hour -= 24.0;
-        // New day - reset movement points
-        day_night.movement_points_remaining = day_night.max_daily_movement;
+    for entity in players.iter() {
+        commands.entity(entity).insert(PlayerStats::default());
     }
 }
 
-pub fn check_forced_rest(
-    day_night: Res<DayNightCycle>,
-    player_query: Query<&PlayerStats>,
-    // TODO: Add event system for forcing rest
+/// R rests: a room at the inn when standing in a settlement, otherwise camp
+pub fn rest_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    world_state: Res<WorldState>,
+    settlements: Query<(Entity, &SettlementMarker, Option<&Settlement>, &GlobalTransform)>,
+    mut camp_events: EventWriter<MakeCampEvent>,
+    mut inn_events: EventWriter<InnRestEvent>,
 ) {
-    if let Ok(player_stats) = player_query.get_single() {
-        let is_night = day_night.current_hour < 6.0 || day_night.current_hour > 22.0;
-        let is_exhausted = player_stats.fatigue > player_stats.max_fatigue * 0.8;
-        let no_movement_left = day_night.movement_points_remaining == 0;
-        
-        if is_night || is_exhausted || no_movement_left {
-            // TODO: Trigger rest requirement event
+    if !keyboard.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    let inn = settlements.iter().find(|(_, marker, settlement, transform)| {
+        world_state.player_hex == Some(world_to_hex(transform.translation())) && inn_price_at(marker, *settlement).is_some()
+    });
+    match inn {
+        Some((settlement, _, _, _)) => {
+            inn_events.send(InnRestEvent { settlement });
+        }
+        None => {
+            camp_events.send(MakeCampEvent { hours: FULL_REST_HOURS });
         }
     }
 }
 
-pub fn generate_weather_for_region(
-    emotional_state: &EmotionalState,
-    corruption_level: f32,
-    rng: &mut ChaCha8Rng,
-) -> WeatherSystem {
-    let weather_types = match emotional_state {
-        EmotionalState::Peace => vec![
-            (WeatherType::Clear, 0.6),
-            (WeatherType::Rain, 0.3),
-            (WeatherType::Fog, 0.1),
-        ],
-        EmotionalState::Unease => vec![
-            (WeatherType::Clear, 0.4),
-            (WeatherType::Rain, 0.4),
-            (WeatherType::Fog, 0.2),
-        ],
-        EmotionalState::Dread => vec![
-            (WeatherType::Rain, 0.4),
-            (WeatherType::Storm, 0.3),
-            (WeatherType::Fog, 0.3),
-        ],
-        EmotionalState::Terror => vec![
-            (WeatherType::Storm, 0.5),
-            (WeatherType::VoidStorm, 0.3),
-            (WeatherType::Fog, 0.2),
-        ],
-        EmotionalState::Void => vec![
-            (WeatherType::VoidStorm, 0.8),
-            (WeatherType::Storm, 0.2),
-        ],
+/// Each hex entered tires the player according to terrain and weather;
+/// pushing on past the day's movement tires them faster
+pub fn travel_fatigue_system(
+    world_state: Res<WorldState>,
+    weather: Res<WeatherSystem>,
+    day_night: Res<DayNightCycle>,
+    config: Res<SurvivalConfig>,
+    tiles: Query<&Tile>,
+    mut player_query: Query<(&Player, &mut PlayerStats, Has<Mounted>)>,
+    mounts: Query<&Mount>,
+    mut last_hex: Local<Option<HexCoord>>,
+) {
+    let Some(player_hex) = world_state.player_hex else {
+        return;
     };
-    
-    let roll: f32 = rng.r#gen();
-    let mut cumulative = 0.0;
-    let mut selected_weather = WeatherType::Clear;
-    
-    for (weather_type, probability) in weather_types {
-        cumulative += probability;
-        if roll < cumulative {
-            selected_weather = weather_type;
+    let previous_hex = last_hex.replace(player_hex);
+    if previous_hex.is_none() || previous_hex == Some(player_hex) {
+        return;
+    }
+    let Ok((player, mut stats, is_mounted)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(biome) = biome_at(&tiles, player_hex) else {
+        return;
+    };
+
+    let mount = player
+        .mount
+        .filter(|_| is_mounted)
+        .and_then(|entity| mounts.get(entity).ok());
+    let terrain_difficulty = (get_movement_cost(&biome, 0.0) - 1.0).max(0.0);
+    let forced_march = if day_night.movement_points_remaining == 0 { 1.5 } else { 1.0 };
+    let fatigue = calculate_fatigue_from_movement(1.0, terrain_difficulty, weather.movement_penalty(), &stats, mount)
+        * config.fatigue_multiplier
+        * forced_march;
+    stats.add_fatigue(fatigue);
+}
+
+/// Hunger and thirst grow with game time. Provisions in the inventory are
+/// used automatically; without them the player starves.
+pub fn hunger_thirst_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    config: Res<SurvivalConfig>,
+    mut player_query: Query<(&mut Player, &mut PlayerStats, &mut Inventory)>,
+) {
+    let hours: f32 = time_events.read().map(|event| event.hours).sum();
+    if hours <= 0.0 {
+        return;
+    }
+    let Ok((mut player, mut stats, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    stats.hunger = (stats.hunger + config.hunger_per_hour * hours).min(MAX_NEED);
+    stats.thirst = (stats.thirst + config.thirst_per_hour * hours).min(MAX_NEED);
+
+    while stats.hunger >= AUTO_CONSUME_THRESHOLD {
+        let Some(food) = inventory.consume_by_effect(&ItemEffect::Nourish) else {
             break;
+        };
+        if let ItemType::Consumable { potency, .. } = food.item_type {
+            stats.hunger = (stats.hunger - potency as f32).max(0.0);
         }
+        info!("You eat some {}", food.name);
     }
-    
-    let base_intensity = 0.2 + corruption_level * 0.6;
-    let intensity_variation: f32 = rng.r#gen::<f32>() * 0.4 - 0.2; // ±0.2
-    let final_intensity = (base_intensity + intensity_variation).clamp(0.0, 1.0);
-    
-    WeatherSystem {
-        current_weather: selected_weather,
-        intensity: final_intensity,
-        temperature: rng.r#gen::<f32>() * 2.0 - 1.0, // -1.0 to 1.0
-        visibility: match selected_weather {
-            WeatherType::Clear => 1.0,
-            WeatherType::Rain => 0.8,
-            WeatherType::Storm => 0.6,
-            WeatherType::Snow => 0.7,
-            WeatherType::Fog => 0.3,
-            WeatherType::VoidStorm => 0.2,
-        },
+    while stats.thirst >= AUTO_CONSUME_THRESHOLD {
+        let Some(water) = inventory.consume_by_effect(&ItemEffect::Hydrate) else {
+            break;
+        };
+        if let ItemType::Consumable { potency, .. } = water.item_type {
+            stats.thirst = (stats.thirst - potency as f32).max(0.0);
+        }
+        info!("You drink from your {}", water.name);
+    }
+
+    let starving = [stats.hunger, stats.thirst].iter().filter(|need| **need >= MAX_NEED).count();
+    if starving > 0 {
+        player.health -= config.starvation_damage_per_hour * hours * starving as f32;
+        warn!("You are weakening from hunger and thirst");
     }
 }
 
+/// Pressing on while exhausted frays the player's mind and the party's nerves
+pub fn exhaustion_penalty_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    config: Res<SurvivalConfig>,
+    mut player_query: Query<(&mut Player, &PlayerStats)>,
+    mut companions: Query<&mut Companion>,
+) {
+    // Sleeping through exhaustion is the cure, not a further penalty
+    let hours: f32 = time_events
+        .read()
+        .filter(|event| event.reason != TimeAdvanceReason::Rest)
+        .map(|event| event.hours)
+        .sum();
+    if hours <= 0.0 {
+        return;
+    }
+    let Ok((mut player, stats)) = player_query.get_single_mut() else {
+        return;
+    };
+    if !stats.is_exhausted() {
+        return;
+    }
+
+    player.sanity = (player.sanity - config.exhaustion_sanity_loss_per_hour * hours).max(0.0);
+    for mut companion in companions.iter_mut() {
+        companion.stress = (companion.stress + config.exhaustion_companion_stress_per_hour * hours).clamp(0.0, 100.0);
+    }
+}
+
+/// Warn when the player ought to rest, and make them collapse where they
+/// stand once fatigue is maxed out
+pub fn check_forced_rest(
+    day_night: Res<DayNightCycle>,
+    player_query: Query<&PlayerStats>,
+    mut required_events: EventWriter<RestRequiredEvent>,
+    mut rest_events: EventWriter<RestEvent>,
+    mut last_reason: Local<Option<RestRequiredReason>>,
+) {
+    let Ok(player_stats) = player_query.get_single() else {
+        return;
+    };
+
+    if player_stats.fatigue >= player_stats.max_fatigue {
+        warn!("You collapse from exhaustion");
+        rest_events.send(RestEvent {
+            site: RestSite {
+                rest_type: RestType::WildRough,
+                safety_level: 0.3,
+                comfort_level: 0.2,
+                shelter_quality: 0.1,
+            },
+            hours: FULL_REST_HOURS,
+        });
+        *last_reason = None;
+        return;
+    }
+
+    let reason = if player_stats.is_exhausted() {
+        Some(RestRequiredReason::Exhausted)
+    } else if day_night.movement_points_remaining == 0 {
+        Some(RestRequiredReason::OutOfMovement)
+    } else if day_night.is_night() {
+        Some(RestRequiredReason::Night)
+    } else {
+        None
+    };
+
+    if reason != *last_reason {
+        if let Some(reason) = reason {
+            required_events.send(RestRequiredEvent { reason });
+        }
+        *last_reason = reason;
+    }
+}
+
+/// Make camp in the wild. Settlements, water, lava and the void are no
+/// places to sleep. Each camp risks an ambush that cuts the rest short;
+/// fighting it off is worth experience.
+#[allow(clippy::too_many_arguments)]
 pub fn setup_camp_system(
-    // TODO: Add input handling for camp setup
-    // TODO: Add inventory system for camping supplies
+    mut camp_events: EventReader<MakeCampEvent>,
+    world_state: Res<WorldState>,
+    weather: Res<WeatherSystem>,
+    day_night: Res<DayNightCycle>,
+    config: Res<SurvivalConfig>,
+    tiles: Query<&Tile>,
+    settlements: Query<&GlobalTransform, With<SettlementMarker>>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut rng: Local<WorldRng>,
+    mut rest_events: EventWriter<RestEvent>,
+    mut ambush_events: EventWriter<AmbushEvent>,
+    mut experience_events: EventWriter<AwardExperienceEvent>,
+    mut tool_events: EventWriter<UseToolEvent>,
 ) {
-    // Allow player to set up camp in valid locations
-    // Require camping supplies for better rest quality
-    // Some locations prohibit camping (civilized areas, dangerous terrain)
+    let Some(player_hex) = world_state.player_hex else {
+        camp_events.clear();
+        return;
+    };
+    let Ok(inventory) = player_query.get_single() else {
+        camp_events.clear();
+        return;
+    };
+
+    for event in camp_events.read() {
+        if settlements
+            .iter()
+            .any(|transform| world_to_hex(transform.translation()) == player_hex)
+        {
+            info!("You cannot camp inside a settlement; find an inn");
+            continue;
+        }
+        let Some(biome) = biome_at(&tiles, player_hex) else {
+            continue;
+        };
+        if biome.is_void() || matches!(biome.terrain_category(), "water" | "lava") {
+            info!("There is nowhere to camp here");
+            continue;
+        }
+
+        let site = camp_site_for(&biome, inventory);
+        // The campfire burns through tinder
+        let has_tinderbox = inventory.items.iter().any(|item| {
+            matches!(&item.item_type, ItemType::Tool { tool_type, durability, .. } if tool_type == "tinderbox" && *durability > 0)
+        });
+        if has_tinderbox {
+            tool_events.send(UseToolEvent {
+                tool_type: "tinderbox".to_string(),
+                wear: 1,
+            });
+        }
+
+        let emotional_state = emotional_state_at(player_hex, world_state.seed);
+        let ambush_chance = (calculate_encounter_chance_while_resting(&site, &emotional_state, &weather, &day_night)
+            * config.ambush_multiplier
+            * event.hours
+            / FULL_REST_HOURS)
+            .clamp(0.0, 0.95);
+
+        let mut hours = event.hours;
+        let rng = rng.get(world_state.seed, CAMP_RNG_STREAM);
+        if rng.random::<f32>() < ambush_chance {
+            hours *= rng.random::<f32>();
+            warn!("Your camp is ambushed!");
+            ambush_events.send(AmbushEvent {
+                hex: player_hex,
+                hours_rested: hours,
+            });
+            experience_events.send(AwardExperienceEvent {
+                source: ExperienceSource::Combat {
+                    threat: threat_for_band(world_state.world_progression),
+                },
+            });
+        }
+        rest_events.send(RestEvent { site, hours });
+    }
 }
 
+/// Pay for a room at the inn on the player's hex: a safe night and a hot meal
 pub fn handle_inn_rest(
-    // TODO: Add interaction system for inns
-    // TODO: Add currency system for inn costs
+    mut inn_events: EventReader<InnRestEvent>,
+    world_state: Res<WorldState>,
+    config: Res<SurvivalConfig>,
+    settlements: Query<(&SettlementMarker, Option<&Settlement>, &GlobalTransform)>,
+    mut player_query: Query<(&mut Inventory, &mut PlayerStats), With<Player>>,
+    mut rest_events: EventWriter<RestEvent>,
 ) {
-    // Handle staying at inns
-    // Cost varies by region and inn quality
-    // Provides best rest quality and safety
+    let Ok((mut inventory, mut stats)) = player_query.get_single_mut() else {
+        inn_events.clear();
+        return;
+    };
+
+    for event in inn_events.read() {
+        let Ok((marker, settlement, transform)) = settlements.get(event.settlement) else {
+            continue;
+        };
+        if world_state.player_hex != Some(world_to_hex(transform.translation())) {
+            info!("You must be at the settlement to take a room");
+            continue;
+        }
+        let Some(base_price) = inn_price_at(marker, settlement) else {
+            info!("There is no open inn here");
+            continue;
+        };
+
+        let price = (base_price as f32 * config.inn_price_multiplier).round() as u32;
+        if !inventory.spend_currency(price) {
+            info!("You cannot afford a room ({} cp)", price);
+            continue;
+        }
+
+        stats.hunger = 0.0;
+        stats.thirst = 0.0;
+        info!("You take a room for {} cp", price);
+        rest_events.send(RestEvent {
+            site: RestSite {
+                rest_type: RestType::Inn,
+                safety_level: 1.0,
+                comfort_level: 0.9,
+                shelter_quality: 1.0,
+            },
+            hours: FULL_REST_HOURS,
+        });
+    }
+}
+
+/// Apply recovery from a rest and let the hours pass
+pub fn resolve_rest_system(
+    mut rest_events: EventReader<RestEvent>,
+    world_state: Res<WorldState>,
+    weather: Res<WeatherSystem>,
+    mut player_query: Query<(&mut Player, &mut PlayerStats)>,
+    mut advance_events: EventWriter<AdvanceTimeEvent>,
+) {
+    let Ok((mut player, mut stats)) = player_query.get_single_mut() else {
+        rest_events.clear();
+        return;
+    };
+    let emotional_state = world_state
+        .player_hex
+        .map_or(EmotionalState::Peace, |hex| emotional_state_at(hex, world_state.seed));
+
+    for event in rest_events.read() {
+        if event.hours <= 0.0 {
+            continue;
+        }
+        let (health_recovery, fatigue_recovery) =
+            calculate_rest_recovery(&event.site, &weather, &emotional_state, event.hours);
+        player.health = (player.health + health_recovery).min(player.max_health);
+        stats.add_fatigue(-fatigue_recovery);
+        info!("Rested {:.1} hours ({:?})", event.hours, event.site.rest_type);
+
+        advance_events.send(AdvanceTimeEvent {
+            hours: event.hours,
+            reason: TimeAdvanceReason::Rest,
+        });
+    }
 }
 
 pub fn calculate_encounter_chance_while_resting(
     rest_site: &RestSite,
     emotional_state: &EmotionalState,
     weather: &WeatherSystem,
+    day_night: &DayNightCycle,
 ) -> f32 {
     let base_chance = match emotional_state {
         EmotionalState::Peace => 0.05,
@@ -278,11 +674,187 @@ pub fn calculate_encounter_chance_while_resting(
     };
     
     let safety_modifier = 1.0 - rest_site.safety_level;
-    let weather_modifier = match weather.current_weather {
-        WeatherType::Storm | WeatherType::VoidStorm => 1.5,
-        WeatherType::Fog => 1.2,
-        _ => 1.0,
-    };
+    let weather_modifier = weather.current_weather.encounter_multiplier();
     
-    base_chance * safety_modifier * weather_modifier
+    base_chance * safety_modifier * weather_modifier * day_night.encounter_multiplier()
+}
+
+/// Mirror survival needs into save data whenever they change
+pub fn sync_survival_to_save(
+    player_query: Query<&PlayerStats, Changed<PlayerStats>>,
+    config: Res<SurvivalConfig>,
+    mut game_state: ResMut<GameState>,
+) {
+    if config.is_changed() {
+        game_state.save_data.difficulty = config.difficulty;
+    }
+    let Ok(stats) = player_query.get_single() else {
+        return;
+    };
+    let player_stats = &mut game_state.save_data.player_stats;
+    player_stats.fatigue = stats.fatigue;
+    player_stats.hunger = stats.hunger;
+    player_stats.thirst = stats.thirst;
+}
+
+/// Restore survival needs and difficulty once a save has been loaded
+pub fn restore_survival_from_save(
+    mut slot: SaveSlot,
+    mut config: ResMut<SurvivalConfig>,
+    mut player_query: Query<&mut PlayerStats>,
+) {
+    let Ok(mut stats) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(saved) = slot.take() else {
+        return;
+    };
+    if config.difficulty != saved.difficulty {
+        *config = SurvivalConfig::for_difficulty(saved.difficulty);
+    }
+    stats.hunger = saved.player_stats.hunger;
+    stats.thirst = saved.player_stats.thirst;
+    stats.add_fatigue(saved.player_stats.fatigue - stats.fatigue);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rough_night() -> RestSite {
+        RestSite {
+            rest_type: RestType::WildRough,
+            safety_level: 0.3,
+            comfort_level: 0.2,
+            shelter_quality: 0.1,
+        }
+    }
+
+    fn app(fatigue: f32) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<WorldState>()
+            .init_resource::<WeatherSystem>()
+            .init_resource::<DayNightCycle>()
+            .add_event::<RestRequiredEvent>()
+            .add_event::<RestEvent>()
+            .add_event::<AdvanceTimeEvent>()
+            .add_systems(Update, (check_forced_rest, resolve_rest_system).chain());
+        app.world_mut().spawn((
+            Player {
+                health: 50.0,
+                max_health: 100.0,
+                sanity: 100.0,
+                max_sanity: 100.0,
+                inventory: Vec::new(),
+                mount: None,
+            },
+            PlayerStats {
+                fatigue,
+                ..default()
+            },
+        ));
+        app
+    }
+
+    fn stats(app: &mut App) -> (f32, f32, bool) {
+        let mut players = app.world_mut().query::<(&Player, &PlayerStats)>();
+        let (player, stats) = players.single(app.world()).unwrap();
+        (player.health, stats.fatigue, stats.is_exhausted())
+    }
+
+    #[test]
+    fn test_harder_difficulties_are_harsher() {
+        assert_eq!(SurvivalConfig::default().difficulty, Difficulty::Normal);
+        let presets: Vec<SurvivalConfig> = [Difficulty::Story, Difficulty::Normal, Difficulty::Hard, Difficulty::Nightmare]
+            .into_iter()
+            .map(SurvivalConfig::for_difficulty)
+            .collect();
+        for pair in presets.windows(2) {
+            let (easier, harder) = (&pair[0], &pair[1]);
+            assert!(harder.fatigue_multiplier > easier.fatigue_multiplier);
+            assert!(harder.hunger_per_hour > easier.hunger_per_hour);
+            assert!(harder.thirst_per_hour > easier.thirst_per_hour);
+            assert!(harder.starvation_damage_per_hour > easier.starvation_damage_per_hour);
+            assert!(harder.ambush_multiplier > easier.ambush_multiplier);
+            assert!(harder.inn_price_multiplier > easier.inn_price_multiplier);
+        }
+    }
+
+    #[test]
+    fn test_worst_rest_keeps_a_quarter_of_its_rate() {
+        let void_storm = WeatherSystem {
+            current_weather: WeatherType::VoidStorm,
+            intensity: 1.0,
+            ..default()
+        };
+        let nothing = RestSite {
+            rest_type: RestType::WildRough,
+            safety_level: 0.0,
+            comfort_level: 0.0,
+            shelter_quality: 0.0,
+        };
+        let (_, worst) = calculate_rest_recovery(&nothing, &void_storm, &EmotionalState::Void, FULL_REST_HOURS);
+        // 0.3 weather and 0.3 mood leave a shortfall of 4.4 of the possible 5
+        let expected = FULL_REST_HOURS * 10.0 * 0.4 * (1.0 - 4.4 * RECOVERY_SHORTFALL_WEIGHT);
+        assert!((worst - expected).abs() < 1e-3);
+        assert!(worst > FULL_REST_HOURS * 10.0 * 0.4 * 0.25);
+    }
+
+    #[test]
+    fn test_inn_outrests_camp_outrests_sleeping_rough() {
+        let weather = WeatherSystem::default();
+        let inn = RestSite {
+            rest_type: RestType::Inn,
+            safety_level: 1.0,
+            comfort_level: 0.9,
+            shelter_quality: 1.0,
+        };
+        let camp = RestSite {
+            rest_type: RestType::Camp,
+            safety_level: 0.7,
+            comfort_level: 0.7,
+            shelter_quality: 0.8,
+        };
+        let recovery = |site: &RestSite| calculate_rest_recovery(site, &weather, &EmotionalState::Peace, FULL_REST_HOURS).1;
+        assert!(recovery(&inn) > recovery(&camp));
+        assert!(recovery(&camp) > recovery(&rough_night()));
+    }
+
+    #[test]
+    fn test_collapse_rests_off_exhaustion() {
+        let mut app = app(100.0);
+        app.update();
+
+        let (health, fatigue, exhausted) = stats(&mut app);
+        assert!(health > 50.0);
+        assert!(fatigue < 100.0);
+        assert!(!exhausted, "a collapse should sleep off exhaustion, fatigue is still {}", fatigue);
+
+        let hours: f32 = app
+            .world_mut()
+            .resource_mut::<Events<AdvanceTimeEvent>>()
+            .drain()
+            .map(|event| event.hours)
+            .sum();
+        assert_eq!(hours, FULL_REST_HOURS);
+    }
+
+    #[test]
+    fn test_no_collapse_short_of_max_fatigue() {
+        let mut app = app(90.0);
+        app.update();
+
+        let (health, fatigue, exhausted) = stats(&mut app);
+        assert_eq!(health, 50.0);
+        assert_eq!(fatigue, 90.0);
+        assert!(exhausted);
+        let required: Vec<RestRequiredReason> = app
+            .world_mut()
+            .resource_mut::<Events<RestRequiredEvent>>()
+            .drain()
+            .map(|event| event.reason)
+            .collect();
+        assert_eq!(required, vec![RestRequiredReason::Exhausted]);
+    }
 }
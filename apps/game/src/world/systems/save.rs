@@ -0,0 +1,202 @@
+//! Quicksave and quickload, and the save slot the feature systems mirror
+//! their state through
+//!
+//! Every feature keeps `GameState::save_data` in step with its own state as
+//! it changes. Saving writes that save data to disk; loading applies a save
+//! from disk, after which each feature restores itself from it once.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::world::resources::game_state::{GameState, SaveData};
+
+/// Quicksave written relative to the working directory, like the world databases
+pub const QUICKSAVE_PATH: &str = "saves/quicksave.ron";
+
+const SAVE_KEY: KeyCode = KeyCode::F5;
+const LOAD_KEY: KeyCode = KeyCode::F8;
+
+/// Where quicksaves go
+#[derive(Resource)]
+pub struct SaveFile {
+    pub path: PathBuf,
+}
+
+impl Default for SaveFile {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from(QUICKSAVE_PATH),
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct SaveGameEvent;
+
+#[derive(Event)]
+pub struct LoadGameEvent;
+
+/// The save data as a restore system sees it: `take` hands it over once
+/// for each save applied since that system last restored
+#[derive(SystemParam)]
+pub struct SaveSlot<'w, 's> {
+    game_state: Res<'w, GameState>,
+    restored: Local<'s, u64>,
+}
+
+impl SaveSlot<'_, '_> {
+    pub fn take(&mut self) -> Option<&SaveData> {
+        if self.game_state.loads == *self.restored {
+            return None;
+        }
+        *self.restored = self.game_state.loads;
+        Some(&self.game_state.save_data)
+    }
+}
+
+/// A resource kept whole in save data
+pub trait SavedResource: Resource {
+    /// Whether `save` already holds the resource as it is
+    fn is_saved(&self, save: &SaveData) -> bool;
+    fn save(&self, save: &mut SaveData);
+    fn restore(&mut self, save: &SaveData);
+}
+
+/// Mirror a saved resource into save data whenever it changes
+pub fn sync_to_save<R: SavedResource>(resource: Res<R>, mut game_state: ResMut<GameState>) {
+    if resource.is_changed() && !resource.is_saved(&game_state.save_data) {
+        resource.save(&mut game_state.save_data);
+    }
+}
+
+/// Restore a saved resource once a save has been loaded
+pub fn restore_from_save<R: SavedResource>(mut slot: SaveSlot, mut resource: ResMut<R>) {
+    if let Some(save) = slot.take() {
+        resource.restore(save);
+    }
+}
+
+pub fn save_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut save_events: EventWriter<SaveGameEvent>,
+    mut load_events: EventWriter<LoadGameEvent>,
+) {
+    if keyboard.just_pressed(SAVE_KEY) {
+        save_events.send(SaveGameEvent);
+    }
+    if keyboard.just_pressed(LOAD_KEY) {
+        load_events.send(LoadGameEvent);
+    }
+}
+
+/// Write the save data to disk, stamped with when it was saved
+pub fn save_game_system(
+    mut save_events: EventReader<SaveGameEvent>,
+    save_file: Res<SaveFile>,
+    mut game_state: ResMut<GameState>,
+) {
+    if save_events.read().count() == 0 {
+        return;
+    }
+    game_state.save_data.timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    let content = match ron::ser::to_string_pretty(&game_state.save_data, PrettyConfig::default()) {
+        Ok(content) => content,
+        Err(e) => {
+            warn!("Failed to serialize save: {}", e);
+            return;
+        }
+    };
+    if let Some(dir) = save_file.path.parent() {
+        if let Err(e) = fs::create_dir_all(dir) {
+            warn!("Failed to create save directory {}: {}", dir.display(), e);
+            return;
+        }
+    }
+    match fs::write(&save_file.path, content) {
+        Ok(()) => info!("Game saved to {}", save_file.path.display()),
+        Err(e) => warn!("Failed to write save {}: {}", save_file.path.display(), e),
+    }
+}
+
+/// Apply the save on disk; each feature restores itself from it next
+pub fn load_game_system(
+    mut load_events: EventReader<LoadGameEvent>,
+    save_file: Res<SaveFile>,
+    mut game_state: ResMut<GameState>,
+) {
+    if load_events.read().count() == 0 {
+        return;
+    }
+    match fs::read_to_string(&save_file.path).map(|content| ron::from_str::<SaveData>(&content)) {
+        Ok(Ok(save_data)) => {
+            game_state.apply_save_data(save_data);
+            info!("Game loaded from {}", save_file.path.display());
+        }
+        Ok(Err(e)) => warn!("Invalid save {}: {}", save_file.path.display(), e),
+        Err(e) => warn!("Failed to read save {}: {}", save_file.path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::systems::dungeon_interior::DungeonProgressLedger;
+
+    fn app(save_path: PathBuf) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<GameState>()
+            .init_resource::<DungeonProgressLedger>()
+            .insert_resource(SaveFile { path: save_path })
+            .add_event::<SaveGameEvent>()
+            .add_event::<LoadGameEvent>()
+            .add_systems(Update, (
+                save_game_system,
+                load_game_system,
+                restore_from_save::<DungeonProgressLedger>,
+                sync_to_save::<DungeonProgressLedger>,
+            ).chain());
+        app
+    }
+
+    fn looted(app: &App) -> bool {
+        app.world()
+            .resource::<DungeonProgressLedger>()
+            .progress
+            .get("crypt")
+            .is_some_and(|progress| progress.is_looted("hoard"))
+    }
+
+    #[test]
+    fn dungeon_progress_survives_save_and_load() {
+        let save_path = std::env::temp_dir().join(format!("dl_quicksave_{}.ron", std::process::id()));
+        let mut app = app(save_path.clone());
+
+        app.world_mut()
+            .resource_mut::<DungeonProgressLedger>()
+            .entry("crypt")
+            .looted_treasure
+            .insert("hoard".to_string());
+        app.update();
+        app.world_mut().send_event(SaveGameEvent);
+        app.update();
+        assert!(app.world().resource::<GameState>().save_data.timestamp > 0);
+
+        *app.world_mut().resource_mut::<DungeonProgressLedger>() = DungeonProgressLedger::default();
+        app.update();
+        assert!(!looted(&app));
+
+        app.world_mut().send_event(LoadGameEvent);
+        app.update();
+        let _ = fs::remove_file(&save_path);
+        assert!(looted(&app));
+        assert_eq!(app.world().resource::<GameState>().loads, 1);
+    }
+}
@@ -0,0 +1,314 @@
+use bevy::prelude::*;
+
+use crate::world::components::{
+    Companion, CorruptionNode, Inventory, ItemType, Player, ServiceStatus, ServiceType, Settlement,
+    SettlementDatabase, TraumaLevel,
+};
+use crate::world::resources::game_state::GameState;
+use crate::world::state::{DreadLevel, WorldState};
+use crate::world::systems::data_files::DataFile;
+use crate::world::systems::hex_world::SettlementMarker;
+use crate::world::systems::input::{number_key_pressed, NumberKeyModifier};
+use crate::world::systems::rest_fatigue::InnRestEvent;
+use crate::world::systems::save::SaveSlot;
+use crate::world::systems::time_weather::TimeAdvancedEvent;
+use crate::utils::hex::world_to_hex;
+
+/// Settlement corruption gained per hour from a fully intense node at the
+/// same hex, before resistance
+const NODE_CORRUPTION_PER_HOUR: f32 = 2.0;
+/// Corruption an open temple burns off per hour when no node is in range
+const TEMPLE_CLEANSING_PER_HOUR: f32 = 0.2;
+const HEALER_STRESS_RELIEF: f32 = 30.0;
+const TEMPLE_DREAD_CLEANSING: f32 = 0.1;
+const TEMPLE_SANITY_RESTORED: f32 = 25.0;
+const HOSTILE_SERVICE_DAMAGE: f32 = 10.0;
+const HOSTILE_SERVICE_DREAD: f32 = 0.05;
+
+/// Written by `ron-generator settlements`
+impl DataFile for SettlementDatabase {
+    type Contents = Self;
+    const PATH: &'static str = "world/settlements.ron";
+
+    fn from_contents(contents: Self) -> Self {
+        contents
+    }
+}
+
+#[derive(Event)]
+pub struct UseServiceEvent {
+    pub settlement: Entity,
+    pub service: ServiceType,
+}
+
+#[derive(Event)]
+pub struct ServiceStatusChangedEvent {
+    pub settlement: Entity,
+    pub service: ServiceType,
+    pub status: ServiceStatus,
+}
+
+/// The player walked into a service that has turned on its patrons
+#[derive(Event)]
+pub struct HostileServiceEvent {
+    pub settlement: Entity,
+    pub service: ServiceType,
+}
+
+/// Give each spawned settlement marker its HBF settlement data, with any
+/// corruption it has already accumulated in this save
+pub fn attach_settlement_data(
+    mut commands: Commands,
+    database: Res<SettlementDatabase>,
+    game_state: Res<GameState>,
+    markers: Query<(Entity, &SettlementMarker), Added<SettlementMarker>>,
+) {
+    for (entity, marker) in markers.iter() {
+        let Some(data) = database.get(&marker.uuid) else {
+            debug!("No settlement data for {}", marker.uuid);
+            continue;
+        };
+        let mut settlement = data.clone();
+        if let Some(corruption) = game_state.save_data.settlement_corruption.get(&settlement.uuid) {
+            settlement.corruption = *corruption;
+        }
+        commands.entity(entity).insert(settlement);
+    }
+}
+
+/// Hold U and press a number to use that service of the settlement where
+/// the player stands, in the order the settlement lists them
+pub fn service_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    world_state: Res<WorldState>,
+    settlements: Query<(Entity, &Settlement, &GlobalTransform)>,
+    mut service_events: EventWriter<UseServiceEvent>,
+) {
+    let Some(index) = number_key_pressed(&keyboard, NumberKeyModifier::Hold(KeyCode::KeyU)) else {
+        return;
+    };
+    let Some((entity, settlement, _)) = settlements
+        .iter()
+        .find(|(_, _, transform)| world_state.player_hex == Some(world_to_hex(transform.translation())))
+    else {
+        info!("There is no settlement here");
+        return;
+    };
+    match settlement.service_types.get(index) {
+        Some(service) => {
+            service_events.send(UseServiceEvent {
+                settlement: entity,
+                service: *service,
+            });
+        }
+        None => info!("{} has no service {}", settlement.name, index + 1),
+    }
+}
+
+/// Heal, cleanse, repair or lodge at a settlement on the player's hex. Closed
+/// services turn the player away; hostile ones attack.
+#[allow(clippy::too_many_arguments)]
+pub fn settlement_service_system(
+    mut service_events: EventReader<UseServiceEvent>,
+    world_state: Res<WorldState>,
+    mut dread: ResMut<DreadLevel>,
+    settlements: Query<(&Settlement, &GlobalTransform)>,
+    mut player_query: Query<(&mut Player, &mut Inventory)>,
+    mut companions: Query<&mut Companion>,
+    mut inn_events: EventWriter<InnRestEvent>,
+    mut hostile_events: EventWriter<HostileServiceEvent>,
+) {
+    let Ok((mut player, mut inventory)) = player_query.get_single_mut() else {
+        service_events.clear();
+        return;
+    };
+
+    for event in service_events.read() {
+        let Ok((settlement, transform)) = settlements.get(event.settlement) else {
+            continue;
+        };
+        if world_state.player_hex != Some(world_to_hex(transform.translation())) {
+            info!("You must be at {} to use its services", settlement.name);
+            continue;
+        }
+
+        match settlement.service_status(event.service) {
+            None => {
+                info!("{} offers no {:?} service", settlement.name, event.service);
+                continue;
+            }
+            Some(ServiceStatus::Closed) => {
+                info!("The {:?} doors of {} are barred", event.service, settlement.name);
+                continue;
+            }
+            Some(ServiceStatus::Hostile) => {
+                warn!("The {:?} folk of {} turn on you!", event.service, settlement.name);
+                player.health = (player.health - HOSTILE_SERVICE_DAMAGE).max(0.0);
+                dread.add_dread(HOSTILE_SERVICE_DREAD);
+                hostile_events.send(HostileServiceEvent {
+                    settlement: event.settlement,
+                    service: event.service,
+                });
+                continue;
+            }
+            Some(ServiceStatus::Open) => {}
+        }
+
+        let price = settlement.service_price(event.service);
+        match event.service {
+            ServiceType::Lodging => {
+                // Inn rest handles its own payment
+                inn_events.send(InnRestEvent {
+                    settlement: event.settlement,
+                });
+            }
+            ServiceType::Medical => {
+                if !inventory.spend_currency(price) {
+                    info!("The healer wants {} cp", price);
+                    continue;
+                }
+                player.health = player.max_health;
+                for mut companion in companions.iter_mut() {
+                    companion.stress = (companion.stress - HEALER_STRESS_RELIEF).max(0.0);
+                    companion.trauma_level = ease_trauma(&companion.trauma_level);
+                }
+                info!("The healers of {} tend your wounds for {} cp", settlement.name, price);
+            }
+            ServiceType::Religious => {
+                if !inventory.spend_currency(price) {
+                    info!("The temple asks an offering of {} cp", price);
+                    continue;
+                }
+                dread.remove_dread(TEMPLE_DREAD_CLEANSING);
+                player.sanity = (player.sanity + TEMPLE_SANITY_RESTORED).min(player.max_sanity);
+                info!("The temple of {} eases your dread for {} cp", settlement.name, price);
+            }
+            ServiceType::Crafting => {
+                let damage: u32 = inventory
+                    .items
+                    .iter()
+                    .map(|item| match item.item_type {
+                        ItemType::Tool { durability, max_durability, .. } => max_durability.saturating_sub(durability),
+                        _ => 0,
+                    })
+                    .sum();
+                if damage == 0 {
+                    info!("Nothing needs repairing");
+                    continue;
+                }
+                let cost = price * damage;
+                if !inventory.spend_currency(cost) {
+                    info!("Repairs would cost {} cp", cost);
+                    continue;
+                }
+                for item in inventory.items.iter_mut() {
+                    if let ItemType::Tool { durability, max_durability, .. } = &mut item.item_type {
+                        *durability = *max_durability;
+                    }
+                }
+                info!("Your tools are repaired for {} cp", cost);
+            }
+            ServiceType::Commerce => info!("Browse the shop to trade in {}", settlement.name),
+            ServiceType::Defense | ServiceType::Government | ServiceType::Learning => {
+                info!("{} has nothing for you at its {:?} halls", settlement.name, event.service);
+            }
+        }
+    }
+}
+
+fn ease_trauma(trauma: &TraumaLevel) -> TraumaLevel {
+    match trauma {
+        TraumaLevel::Critical => TraumaLevel::Severe,
+        TraumaLevel::Severe => TraumaLevel::Moderate,
+        TraumaLevel::Moderate => TraumaLevel::Mild,
+        TraumaLevel::Mild | TraumaLevel::None => TraumaLevel::None,
+    }
+}
+
+/// Corruption seeps into settlements from spreading `CorruptionNode`s within
+/// their radius, slowed by the settlement's resistance. Settlements out of
+/// reach with an open temple slowly recover.
+pub fn settlement_corruption_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    nodes: Query<(&CorruptionNode, &GlobalTransform)>,
+    mut settlements: Query<(Entity, &mut Settlement, &GlobalTransform)>,
+    mut status_events: EventWriter<ServiceStatusChangedEvent>,
+) {
+    let hours: f32 = time_events.read().map(|event| event.hours).sum();
+    if hours <= 0.0 {
+        return;
+    }
+
+    let nodes: Vec<_> = nodes
+        .iter()
+        .filter(|(node, _)| node.is_spreading)
+        .map(|(node, transform)| (node, world_to_hex(transform.translation())))
+        .collect();
+
+    for (entity, mut settlement, transform) in settlements.iter_mut() {
+        let hex = world_to_hex(transform.translation());
+        let pressure: f32 = nodes
+            .iter()
+            .filter_map(|(node, node_hex)| {
+                let distance = hex.distance_to(node_hex) as f32;
+                (distance <= node.affected_radius).then(|| {
+                    let falloff = 1.0 - distance / (node.affected_radius + 1.0);
+                    node.corruption_level * node.spread_rate * falloff
+                })
+            })
+            .sum();
+
+        let change = if pressure > 0.0 {
+            let resistance = 1.0 - settlement.corruption_resistance as f32 / 20.0;
+            pressure * NODE_CORRUPTION_PER_HOUR * resistance * hours
+        } else if settlement.is_open(ServiceType::Religious) && settlement.corruption > 0.0 {
+            -TEMPLE_CLEANSING_PER_HOUR * hours
+        } else {
+            continue;
+        };
+
+        for (service, status) in settlement.add_corruption(change) {
+            info!("{:?} in {} is now {:?}", service, settlement.name, status);
+            status_events.send(ServiceStatusChangedEvent {
+                settlement: entity,
+                service,
+                status,
+            });
+        }
+    }
+}
+
+/// Mirror settlement corruption into save data whenever it changes
+pub fn sync_settlements_to_save(
+    settlements: Query<&Settlement, Changed<Settlement>>,
+    mut game_state: ResMut<GameState>,
+) {
+    for settlement in settlements.iter() {
+        game_state
+            .save_data
+            .settlement_corruption
+            .insert(settlement.uuid.clone(), settlement.corruption);
+    }
+}
+
+/// Restore settlement corruption once a save has been loaded. Settlements
+/// the save never touched go back to how the world data left them.
+pub fn restore_settlements_from_save(
+    mut slot: SaveSlot,
+    database: Res<SettlementDatabase>,
+    mut settlements: Query<&mut Settlement>,
+) {
+    let Some(save) = slot.take() else {
+        return;
+    };
+    for mut settlement in settlements.iter_mut() {
+        let Some(data) = database.get(&settlement.uuid) else {
+            continue;
+        };
+        settlement.corruption = save
+            .settlement_corruption
+            .get(&settlement.uuid)
+            .copied()
+            .unwrap_or(data.corruption);
+    }
+}
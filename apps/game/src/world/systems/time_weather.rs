@@ -0,0 +1,397 @@
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::PI;
+
+use crate::world::components::{HexCoord, Mount, Mounted, Player, Tile};
+use crate::world::resources::game_state::{GameState, WorldClockSaveState};
+use crate::world::state::{DreadLevel, WorldRng, WorldState};
+use crate::world::systems::mounts::biome_at;
+use crate::world::systems::pathfinding::get_travel_cost;
+use crate::world::systems::regional_progression::{generate_dynamic_region, EmotionalState};
+use crate::world::systems::save::SaveSlot;
+
+pub use dl_types::world::weather::*;
+
+/// Game hours to cross one hex of open ground on foot; terrain, weather and
+/// mounts scale this through `get_travel_cost`
+pub const HOURS_PER_HEX: f32 = 1.5;
+/// Dread gained per game hour spent in full darkness
+pub const NIGHT_DREAD_PER_HOUR: f32 = 0.5;
+const MAX_WEATHER_FRONTS: usize = 6;
+/// Random stream for weather fronts, see `WorldRng`
+const WEATHER_RNG_STREAM: u64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeAdvanceReason {
+    Travel,
+    Rest,
+    Wait,
+}
+
+/// Request to move the clock forward
+#[derive(Event)]
+pub struct AdvanceTimeEvent {
+    pub hours: f32,
+    pub reason: TimeAdvanceReason,
+}
+
+/// Sent after the clock has moved; anything that runs on game hours
+/// (effects, recovery, weather) listens to this instead of `Time`
+#[derive(Event)]
+pub struct TimeAdvancedEvent {
+    pub hours: f32,
+    pub reason: TimeAdvanceReason,
+    pub days_passed: u32,
+}
+
+pub fn generate_weather_for_region(
+    emotional_state: &EmotionalState,
+    corruption_level: f32,
+    rng: &mut impl Rng,
+) -> WeatherSystem {
+    let weather_types = match emotional_state {
+        EmotionalState::Peace => vec![
+            (WeatherType::Clear, 0.6),
+            (WeatherType::Rain, 0.3),
+            (WeatherType::Fog, 0.1),
+        ],
+        EmotionalState::Unease => vec![
+            (WeatherType::Clear, 0.4),
+            (WeatherType::Rain, 0.4),
+            (WeatherType::Fog, 0.2),
+        ],
+        EmotionalState::Dread => vec![
+            (WeatherType::Rain, 0.4),
+            (WeatherType::Storm, 0.3),
+            (WeatherType::Fog, 0.3),
+        ],
+        EmotionalState::Terror => vec![
+            (WeatherType::Storm, 0.5),
+            (WeatherType::VoidStorm, 0.3),
+            (WeatherType::Fog, 0.2),
+        ],
+        EmotionalState::Void => vec![
+            (WeatherType::VoidStorm, 0.8),
+            (WeatherType::Storm, 0.2),
+        ],
+    };
+
+    let roll: f32 = rng.random();
+    let mut cumulative = 0.0;
+    let mut selected_weather = WeatherType::Clear;
+
+    for (weather_type, probability) in weather_types {
+        cumulative += probability;
+        if roll < cumulative {
+            selected_weather = weather_type;
+            break;
+        }
+    }
+
+    let base_intensity = 0.2 + corruption_level * 0.6;
+    let intensity_variation: f32 = rng.random::<f32>() * 0.4 - 0.2; // ±0.2
+    let final_intensity = (base_intensity + intensity_variation).clamp(0.0, 1.0);
+    let temperature = rng.random::<f32>() * 2.0 - 1.0; // -1.0 to 1.0
+
+    // Rain falls as snow in freezing weather
+    if selected_weather == WeatherType::Rain && temperature < -0.5 {
+        selected_weather = WeatherType::Snow;
+    }
+
+    WeatherSystem {
+        current_weather: selected_weather,
+        intensity: final_intensity,
+        temperature,
+        visibility: selected_weather.visibility(),
+    }
+}
+
+/// Roll a new front for the player's region, entering from upwind so it
+/// crosses the player's path
+pub fn spawn_weather_front(player_hex: HexCoord, seed: u64, rng: &mut impl Rng) -> WeatherFront {
+    let region = generate_dynamic_region(player_hex.distance_from_origin().max(1), seed);
+    let weather = generate_weather_for_region(&region.emotional_arc, region.corruption_level, rng);
+
+    let heading = rng.random::<f32>() * 2.0 * PI;
+    let speed = 0.2 + rng.random::<f32>() * 0.4;
+    let radius = 3.0 + rng.random::<f32>() * 5.0;
+    let start_distance = radius + 6.0;
+    let (dx, dy) = (heading.cos(), heading.sin());
+
+    WeatherFront {
+        weather: weather.current_weather,
+        center: (
+            player_hex.q as f32 - dx * start_distance,
+            player_hex.r as f32 - dy * start_distance,
+        ),
+        velocity: (dx * speed, dy * speed),
+        radius,
+        intensity: weather.intensity,
+        temperature: weather.temperature,
+        remaining_hours: 24.0 + rng.random::<f32>() * 48.0,
+    }
+}
+
+/// Apply queued time advances to the clock
+pub fn update_day_night_cycle(
+    mut advance_events: EventReader<AdvanceTimeEvent>,
+    mut day_night: ResMut<DayNightCycle>,
+    mut advanced_events: EventWriter<TimeAdvancedEvent>,
+) {
+    for event in advance_events.read() {
+        if event.hours <= 0.0 {
+            continue;
+        }
+        let days_passed = day_night.advance(event.hours);
+        if days_passed > 0 {
+            info!("Day {} dawns", day_night.day);
+        }
+        advanced_events.send(TimeAdvancedEvent {
+            hours: event.hours,
+            reason: event.reason,
+            days_passed,
+        });
+    }
+}
+
+/// Entering a new hex costs game time based on terrain, weather and mount
+pub fn travel_time_system(
+    world_state: Res<WorldState>,
+    weather: Res<WeatherSystem>,
+    tiles: Query<&Tile>,
+    player_query: Query<(&Player, Has<Mounted>)>,
+    mounts: Query<&Mount>,
+    mut day_night: ResMut<DayNightCycle>,
+    mut advance_events: EventWriter<AdvanceTimeEvent>,
+    mut last_hex: Local<Option<HexCoord>>,
+) {
+    let Some(player_hex) = world_state.player_hex else {
+        return;
+    };
+    let previous_hex = last_hex.replace(player_hex);
+    if previous_hex.is_none() || previous_hex == Some(player_hex) {
+        return;
+    }
+
+    let Some(biome) = biome_at(&tiles, player_hex) else {
+        return;
+    };
+    let mount = player_query
+        .get_single()
+        .ok()
+        .filter(|(_, is_mounted)| *is_mounted)
+        .and_then(|(player, _)| player.mount)
+        .and_then(|entity| mounts.get(entity).ok());
+
+    let cost = get_travel_cost(&biome, weather.movement_penalty(), mount);
+    if !cost.is_finite() {
+        return;
+    }
+    day_night.movement_points_remaining = day_night.movement_points_remaining.saturating_sub(1);
+    advance_events.send(AdvanceTimeEvent {
+        hours: cost * HOURS_PER_HEX,
+        reason: TimeAdvanceReason::Travel,
+    });
+}
+
+/// Drift fronts with the clock, roll new ones and resolve the weather at
+/// the player's hex
+pub fn weather_front_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    world_state: Res<WorldState>,
+    mut fronts: ResMut<WeatherFronts>,
+    mut weather: ResMut<WeatherSystem>,
+    mut rng: Local<WorldRng>,
+) {
+    let Some(player_hex) = world_state.player_hex else {
+        time_events.clear();
+        return;
+    };
+    let rng = rng.get(world_state.seed, WEATHER_RNG_STREAM);
+
+    let hours: f32 = time_events.read().map(|event| event.hours).sum();
+    if hours > 0.0 {
+        fronts.advance(hours, player_hex);
+        while fronts.hours_until_next_front <= 0.0 {
+            if fronts.fronts.len() < MAX_WEATHER_FRONTS {
+                let front = spawn_weather_front(player_hex, world_state.seed, rng);
+                info!("A {:?} front gathers on the horizon", front.weather);
+                fronts.fronts.push(front);
+            }
+            fronts.hours_until_next_front += 6.0 + rng.random::<f32>() * 12.0;
+        }
+    }
+
+    let local_weather = fronts.weather_at(player_hex);
+    if local_weather.current_weather != weather.current_weather {
+        info!("The weather turns to {:?}", local_weather.current_weather);
+    }
+    // Only a real change should wake `update_world_lighting` and friends
+    weather.set_if_neq(local_weather);
+}
+
+/// Darkness and void storms wear on the mind
+pub fn night_dread_system(
+    mut time_events: EventReader<TimeAdvancedEvent>,
+    day_night: Res<DayNightCycle>,
+    weather: Res<WeatherSystem>,
+    mut dread_level: ResMut<DreadLevel>,
+) {
+    for event in time_events.read() {
+        let mut dread = 0.0;
+        if day_night.is_night() {
+            dread += NIGHT_DREAD_PER_HOUR * day_night.darkness() * event.hours;
+        }
+        if weather.current_weather == WeatherType::VoidStorm {
+            dread += weather.intensity * event.hours;
+        }
+        if dread > 0.0 {
+            dread_level.add_dread(dread);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Sun;
+
+pub fn setup_world_lighting(mut commands: Commands) {
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 50.0, 0.0).looking_at(Vec3::ZERO, Vec3::Z),
+        Sun,
+        Name::new("Sun"),
+    ));
+}
+
+/// Sun angle and brightness follow the clock; weather dims and tints it
+pub fn update_world_lighting(
+    day_night: Res<DayNightCycle>,
+    weather: Res<WeatherSystem>,
+    mut ambient: ResMut<AmbientLight>,
+    mut sun_query: Query<(&mut DirectionalLight, &mut Transform), With<Sun>>,
+) {
+    if !day_night.is_changed() && !weather.is_changed() {
+        return;
+    }
+
+    let elevation = day_night.sun_elevation();
+    let daylight = elevation.sin().max(0.0);
+    let weather_dimming = 0.4 + 0.6 * weather.visibility;
+
+    if let Ok((mut light, mut transform)) = sun_query.get_single_mut() {
+        light.illuminance = light_consts::lux::AMBIENT_DAYLIGHT * daylight * weather_dimming;
+        light.color = match weather.current_weather {
+            WeatherType::VoidStorm => Color::srgb(0.6, 0.4, 0.8),
+            _ if daylight < 0.3 => Color::srgb(1.0, 0.7, 0.5), // Low sun at dawn and dusk
+            _ => Color::WHITE,
+        };
+        let azimuth = (day_night.current_hour / day_night.day_length_hours) * 2.0 * PI;
+        *transform = Transform::from_rotation(Quat::from_euler(EulerRot::YXZ, azimuth, -elevation.max(0.05), 0.0));
+    }
+
+    // Moonlight keeps the map readable at night
+    ambient.brightness = 80.0 + 400.0 * daylight * weather_dimming;
+    ambient.color = if day_night.is_night() {
+        Color::srgb(0.5, 0.55, 0.8)
+    } else {
+        Color::WHITE
+    };
+}
+
+/// Mirror the clock and weather fronts into save data when they change
+pub fn sync_clock_to_save(
+    day_night: Res<DayNightCycle>,
+    fronts: Res<WeatherFronts>,
+    mut game_state: ResMut<GameState>,
+) {
+    if !day_night.is_changed() && !fronts.is_changed() {
+        return;
+    }
+    game_state.save_data.world_clock = Some(WorldClockSaveState {
+        day: day_night.day,
+        hour: day_night.current_hour,
+        movement_points_remaining: day_night.movement_points_remaining,
+        hours_until_next_front: fronts.hours_until_next_front,
+        weather_fronts: fronts.fronts.clone(),
+    });
+}
+
+/// Restore the clock once a save has been loaded
+pub fn restore_clock_from_save(
+    mut slot: SaveSlot,
+    mut day_night: ResMut<DayNightCycle>,
+    mut fronts: ResMut<WeatherFronts>,
+) {
+    let Some(saved) = slot.take().and_then(|save| save.world_clock.as_ref()) else {
+        return;
+    };
+    day_night.day = saved.day;
+    day_night.current_hour = saved.hour;
+    day_night.movement_points_remaining = saved.movement_points_remaining;
+    fronts.fronts = saved.weather_fronts.clone();
+    fronts.hours_until_next_front = saved.hours_until_next_front;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::components::BiomeType;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(WorldState::new_with_seed(7))
+            .init_resource::<WeatherSystem>()
+            .insert_resource(DayNightCycle {
+                current_hour: 23.0,
+                ..default()
+            })
+            .add_event::<AdvanceTimeEvent>()
+            .add_event::<TimeAdvancedEvent>()
+            .add_systems(Update, (travel_time_system, update_day_night_cycle).chain());
+        for (q, biome_type) in [(0, BiomeType::Grassland), (1, BiomeType::Mountain)] {
+            app.world_mut().spawn(Tile {
+                coords: HexCoord::new(q, 0),
+                biome_type,
+                paths: Vec::new(),
+                features: Vec::new(),
+            });
+        }
+        app
+    }
+
+    fn advanced(app: &mut App) -> Vec<(f32, u32)> {
+        app.world_mut()
+            .resource_mut::<Events<TimeAdvancedEvent>>()
+            .drain()
+            .map(|event| (event.hours, event.days_passed))
+            .collect()
+    }
+
+    fn walk_to(app: &mut App, hex: HexCoord) {
+        app.world_mut().resource_mut::<WorldState>().player_hex = Some(hex);
+        app.update();
+    }
+
+    #[test]
+    fn test_travel_moves_the_clock_into_the_next_day() {
+        let mut app = app();
+        app.update();
+        assert!(advanced(&mut app).is_empty(), "standing still costs no time");
+
+        walk_to(&mut app, HexCoord::new(1, 0));
+        assert_eq!(advanced(&mut app), vec![(2.0 * HOURS_PER_HEX, 1)]);
+        let clock = app.world().resource::<DayNightCycle>();
+        assert_eq!((clock.day, clock.current_hour), (2, 2.0));
+        assert_eq!(clock.movement_points_remaining, clock.max_daily_movement);
+
+        walk_to(&mut app, HexCoord::new(0, 0));
+        assert_eq!(advanced(&mut app), vec![(HOURS_PER_HEX, 0)]);
+        let clock = app.world().resource::<DayNightCycle>();
+        assert_eq!(clock.movement_points_remaining, clock.max_daily_movement - 1);
+    }
+}
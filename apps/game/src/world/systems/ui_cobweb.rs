@@ -3,6 +3,7 @@ use bevy_cobweb_ui::prelude::*;
 use bevy_cobweb::prelude::*;
 use crate::world::state::GameState;
 use crate::game::GameStateEnum;
+use crate::world::systems::character_creation::{CreatorAction, CreatorActionEvent};
 
 /// Minimal UI system that only handles logic - UI structure is in .cob files
 #[derive(Resource, Debug, Default)]
@@ -68,13 +69,13 @@ pub fn handle_ui_transitions(
 /// Simple character creator input handler - UI defined in .cob file
 pub fn handle_character_creator_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut game_state: ResMut<NextState<GameStateEnum>>,
+    mut creator_events: EventWriter<CreatorActionEvent>,
 ) {
     if keyboard_input.just_pressed(KeyCode::Enter) {
-        game_state.set(GameStateEnum::Playing);
+        creator_events.send(CreatorActionEvent { action: CreatorAction::Confirm });
     }
     
     if keyboard_input.just_pressed(KeyCode::Escape) {
-        game_state.set(GameStateEnum::MainMenu);
+        creator_events.send(CreatorActionEvent { action: CreatorAction::Cancel });
     }
 }
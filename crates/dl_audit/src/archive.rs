@@ -9,8 +9,14 @@ use flate2::{Compression, write::GzEncoder};
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
 use tar::Builder;
+use tempfile::TempDir;
 use walkdir::WalkDir;
 
+use crate::reports::{report_name_of, AuditReportMetadata, LoadedReport, ReportKey};
+
+/// Category given to reports from an archive no metadata names
+pub const UNNAMED_ARCHIVE_CATEGORY: &str = "archived";
+
 pub struct ArchiveManager {
     base_dir: PathBuf,
 }
@@ -28,6 +34,15 @@ impl ArchiveManager {
     /// Creates timestamped tar.gz archive if CSV files exist, then removes originals.
     /// Returns path to created archive or None if no files to archive.
     pub fn archive_existing_reports<P: AsRef<Path>>(&self, target_dir: P) -> Result<Option<PathBuf>> {
+        self.archive_existing_reports_at(target_dir, Utc::now())
+    }
+
+    /// Archive all CSV files in a directory under an explicit archive timestamp
+    pub fn archive_existing_reports_at<P: AsRef<Path>>(
+        &self,
+        target_dir: P,
+        archived_at: DateTime<Utc>,
+    ) -> Result<Option<PathBuf>> {
         let target_path = target_dir.as_ref();
         
         if !target_path.exists() {
@@ -48,7 +63,7 @@ impl ArchiveManager {
         }
         
         // Create archive filename with timestamp
-        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let timestamp = archived_at.format("%Y%m%d_%H%M%S");
         let archive_name = format!("audit_archive_{}.tar.gz", timestamp);
         let archive_path = self.base_dir.join("archives").join(&archive_name);
         
@@ -101,25 +116,7 @@ impl ArchiveManager {
             .filter(|e| e.file_type().is_file())
             .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("gz"))
         {
-            let path = entry.path();
-            let metadata = fs::metadata(path)
-                .context("Failed to read file metadata")?;
-                
-            let created = metadata.created()
-                .or_else(|_| metadata.modified())
-                .context("Failed to get file timestamp")?;
-                
-            let created_utc: DateTime<Utc> = created.into();
-            
-            archives.push(ArchiveInfo {
-                path: path.to_path_buf(),
-                filename: path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-                created_at: created_utc,
-                size_bytes: metadata.len(),
-            });
+            archives.push(ArchiveInfo::from_path(entry.path())?);
         }
         
         // Sort by creation time, newest first
@@ -161,6 +158,51 @@ impl ArchiveManager {
             
         Ok(())
     }
+    
+    /// Read the report CSVs inside an archive
+    /// 
+    /// An archive holds one `category/subcategory` directory but does not
+    /// record which. The report generated right after archiving names the
+    /// archive in its metadata, so `metadata` is searched for that; archives
+    /// nothing names are reported under `archived/<archive name>`.
+    pub fn load_archive_reports(
+        &self,
+        archive: &ArchiveInfo,
+        metadata: &[AuditReportMetadata],
+    ) -> Result<Vec<LoadedReport>> {
+        let (category, subcategory) = metadata.iter()
+            .find(|m| {
+                m.archive_created.as_deref()
+                    .and_then(|created| Path::new(created).file_name())
+                    .is_some_and(|name| name.to_string_lossy() == archive.filename)
+            })
+            .map(|m| (m.category.clone(), m.subcategory.clone()))
+            .unwrap_or_else(|| (UNNAMED_ARCHIVE_CATEGORY.to_string(), archive.stem().to_string()));
+        
+        // Removed when dropped, whether or not reading succeeds
+        let extract_dir = TempDir::with_prefix("dl_audit_")
+            .context("Failed to create extraction directory")?;
+        self.extract_archive(archive.path.as_path(), extract_dir.path())?;
+        
+        let mut csv_files: Vec<PathBuf> = WalkDir::new(extract_dir.path())
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("csv"))
+            .map(|e| e.path().to_path_buf())
+            .collect();
+        csv_files.sort();
+        
+        csv_files.iter()
+            .map(|path| {
+                let key = ReportKey {
+                    category: category.clone(),
+                    subcategory: subcategory.clone(),
+                    report_name: report_name_of(path),
+                };
+                LoadedReport::read_csv(key, path, None)
+            })
+            .collect()
+    }
 }
 
 /// Information about an archived audit report
@@ -173,6 +215,29 @@ pub struct ArchiveInfo {
 }
 
 impl ArchiveInfo {
+    /// Describe an archive file on disk
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Failed to read archive {}", path.display()))?;
+            
+        let created = metadata.created()
+            .or_else(|_| metadata.modified())
+            .context("Failed to get file timestamp")?;
+            
+        let created_utc: DateTime<Utc> = created.into();
+        
+        Ok(Self {
+            path: path.to_path_buf(),
+            filename: path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            created_at: created_utc,
+            size_bytes: metadata.len(),
+        })
+    }
+    
     /// Get human-readable size string
     pub fn size_human(&self) -> String {
         let bytes = self.size_bytes as f64;
@@ -185,6 +250,17 @@ impl ArchiveInfo {
         }
     }
     
+    /// Filename without the `.tar.gz` suffix
+    pub fn stem(&self) -> &str {
+        self.filename.strip_suffix(".tar.gz").unwrap_or(&self.filename)
+    }
+    
+    /// When the archived reports were replaced: the filename timestamp,
+    /// falling back to the file's own timestamp
+    pub fn archived_at(&self) -> DateTime<Utc> {
+        self.parse_timestamp_from_filename().unwrap_or(self.created_at)
+    }
+    
     /// Extract timestamp from filename if possible
     pub fn parse_timestamp_from_filename(&self) -> Option<DateTime<Utc>> {
         // Extract timestamp from filename like "audit_archive_20250103_143022.tar.gz"
@@ -209,7 +285,6 @@ impl ArchiveInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
     
     #[test]
     fn test_archive_creation() -> Result<()> {
@@ -0,0 +1,133 @@
+//! Audit Binary - dashboards and archive diffs over generated audit reports
+//! 
+//! Reads the CSV reports and metadata an `AuditSystem` writes, along with
+//! its archives, and renders or diffs them for review.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use dl_audit::dashboard::DEFAULT_HISTORY;
+use dl_audit::diff::DEFAULT_TOLERANCE;
+use dl_audit::{AuditSystem, DiffThresholds};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "audit")]
+#[command(about = "Review Dragon's Labyrinth audit reports")]
+#[command(version = "1.0.0")]
+struct Cli {
+    /// Reports directory the audit system writes to
+    #[arg(short, long, default_value = "./audit_reports")]
+    reports: PathBuf,
+    
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Render every report, its trend across archives and entity coverage as HTML
+    Dashboard {
+        /// HTML file to write
+        #[arg(short, long, default_value = "audit_dashboard.html")]
+        output: PathBuf,
+        
+        /// Newest archives to compare against
+        #[arg(long, default_value_t = DEFAULT_HISTORY)]
+        history: usize,
+    },
+    
+    /// Compare two archives as JSON; exits non-zero when a threshold is violated
+    Diff {
+        /// Earlier archive, as a path or a file name under <reports>/archives
+        before: PathBuf,
+        
+        /// Later archive, as a path or a file name under <reports>/archives
+        after: PathBuf,
+        
+        /// How far a numeric value may move before it counts as changed
+        #[arg(long, default_value_t = DEFAULT_TOLERANCE)]
+        tolerance: f64,
+        
+        /// Most rows any one report may gain
+        #[arg(long)]
+        max_added_rows: Option<usize>,
+        
+        /// Most rows any one report may lose
+        #[arg(long)]
+        max_removed_rows: Option<usize>,
+        
+        /// Most numeric values any one report may change
+        #[arg(long)]
+        max_changed_values: Option<usize>,
+        
+        /// Fail on added, removed or retyped columns and on added or removed reports
+        #[arg(long)]
+        fail_on_schema_change: bool,
+        
+        /// Write the JSON here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let audit_system = AuditSystem::new(&cli.reports);
+    
+    match &cli.command {
+        Commands::Dashboard { output, history } => {
+            println!("📊 Building audit dashboard from {}", cli.reports.display());
+            let dashboard = audit_system.generate_dashboard(output, *history)?;
+            
+            println!("📋 {} reports, {} archives compared", dashboard.trends.len(), dashboard.archives_read);
+            for trend in &dashboard.trends {
+                let change = trend.fill_rate_delta()
+                    .map_or(String::new(), |d| format!(" ({:+.1} pts)", d * 100.0));
+                println!("   • {}: {} rows, {:.1}% filled{}",
+                    trend.key, trend.current.row_count, trend.current.fill_rate * 100.0, change);
+            }
+            if let Some(coverage) = &dashboard.coverage {
+                println!("🔗 {} entities, {} in every UUID report", coverage.entities, coverage.in_every_report);
+            }
+            println!("✅ Dashboard written to {}", output.display());
+        }
+        Commands::Diff {
+            before,
+            after,
+            tolerance,
+            max_added_rows,
+            max_removed_rows,
+            max_changed_values,
+            fail_on_schema_change,
+            output,
+        } => {
+            let thresholds = DiffThresholds::default()
+                .with_tolerance(*tolerance)
+                .with_max_added_rows(*max_added_rows)
+                .with_max_removed_rows(*max_removed_rows)
+                .with_max_changed_values(*max_changed_values)
+                .with_fail_on_schema_change(*fail_on_schema_change);
+            let diff = audit_system.diff_archives(before, after, thresholds)?;
+            
+            // stdout may carry the JSON, so the summary goes to stderr
+            match output {
+                Some(path) => {
+                    diff.write_json(path)?;
+                    eprintln!("📄 Diff written to {}", path.display());
+                }
+                None => println!("{}", diff.to_json()?),
+            }
+            let changed = diff.reports.iter().filter(|r| !r.is_unchanged()).count();
+            eprintln!("🔍 {} → {}: {} of {} reports changed", diff.before, diff.after, changed, diff.reports.len());
+            
+            if !diff.passed() {
+                for violation in &diff.violations {
+                    eprintln!("❌ {}", violation);
+                }
+                std::process::exit(1);
+            }
+        }
+    }
+    
+    Ok(())
+}
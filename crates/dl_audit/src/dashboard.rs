@@ -0,0 +1,534 @@
+//! Cross-report dashboard for audit reports
+//!
+//! Loads every current audit CSV with its metadata, joins reports that carry
+//! an entity UUID column to show which entities each report covers, and
+//! compares each report against the same report in earlier archives. The
+//! result renders as a single static HTML page with tables and SVG charts,
+//! so a pipeline change can be judged by whether fill rates, categorization
+//! metrics and entity coverage moved the right way.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::archive::ArchiveManager;
+use crate::dataframe::AuditStats;
+use crate::reports::{AuditReportMetadata, LoadedReport, ReportConfig, ReportKey};
+
+/// Columns that identify an entity, in order of preference
+pub const ENTITY_UUID_COLUMNS: [&str; 2] = ["entity_uuid", "uuid"];
+
+/// Archives compared against by default
+pub const DEFAULT_HISTORY: usize = 10;
+
+/// One report at one point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSnapshot {
+    pub key: ReportKey,
+    pub timestamp: DateTime<Utc>,
+    pub row_count: usize,
+    pub column_count: usize,
+    /// Share of cells that are not empty
+    pub fill_rate: f64,
+    /// Distinct entity UUIDs, for reports that have a UUID column
+    pub entity_count: Option<usize>,
+    /// Mean of every numeric and boolean column
+    pub metrics: BTreeMap<String, f64>,
+}
+
+impl ReportSnapshot {
+    pub fn from_report(report: &LoadedReport, timestamp: DateTime<Utc>) -> Result<Self> {
+        let df = &report.frame;
+        let stats = AuditStats::from_dataframe(df)?;
+
+        // Empty strings count as missing, the way an empty CSV field reads
+        let cells = stats.row_count * stats.column_count;
+        let nulls: usize = stats.null_counts.values().sum();
+        let empty: usize = df.get_columns()
+            .iter()
+            .filter_map(|c| c.as_materialized_series().str().ok().cloned())
+            .map(|values| values.into_iter().filter(|v| *v == Some("")).count())
+            .sum();
+        let fill_rate = if cells == 0 { 1.0 } else { 1.0 - (nulls + empty) as f64 / cells as f64 };
+
+        let entity_count = match uuid_column(df) {
+            Some(name) => Some(df.column(name)?.n_unique()?),
+            None => None,
+        };
+
+        // Booleans average to the share of rows that are true
+        let metric_columns: Vec<String> = df.get_columns()
+            .iter()
+            .filter(|c| c.dtype().is_primitive_numeric() || c.dtype().is_bool())
+            .map(|c| c.name().to_string())
+            .collect();
+        let mut metrics = BTreeMap::new();
+        if !metric_columns.is_empty() && df.height() > 0 {
+            let means = df.clone()
+                .lazy()
+                .select(metric_columns.iter().map(|name| col(name.as_str()).cast(DataType::Float64).mean()).collect::<Vec<_>>())
+                .collect()
+                .context("Failed to compute report metrics")?;
+            for name in &metric_columns {
+                if let Some(mean) = means.column(name)?.as_materialized_series().f64()?.get(0) {
+                    metrics.insert(name.clone(), mean);
+                }
+            }
+        }
+
+        Ok(Self {
+            key: report.key.clone(),
+            timestamp,
+            row_count: stats.row_count,
+            column_count: stats.column_count,
+            fill_rate,
+            entity_count,
+            metrics,
+        })
+    }
+}
+
+/// The first entity UUID column a report has
+pub(crate) fn uuid_column(df: &DataFrame) -> Option<&'static str> {
+    ENTITY_UUID_COLUMNS.into_iter().find(|name| df.column(name).is_ok())
+}
+
+/// A metric now and in the most recent archive that has it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricDelta {
+    pub name: String,
+    pub current: f64,
+    pub previous: Option<f64>,
+}
+
+impl MetricDelta {
+    pub fn delta(&self) -> Option<f64> {
+        self.previous.map(|previous| self.current - previous)
+    }
+}
+
+/// A report now and in earlier archives
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportTrend {
+    pub key: ReportKey,
+    pub current: ReportSnapshot,
+    /// Archived snapshots, oldest first
+    pub history: Vec<ReportSnapshot>,
+}
+
+impl ReportTrend {
+    /// The most recent archived snapshot
+    pub fn previous(&self) -> Option<&ReportSnapshot> {
+        self.history.last()
+    }
+
+    pub fn row_delta(&self) -> Option<i64> {
+        self.previous().map(|p| self.current.row_count as i64 - p.row_count as i64)
+    }
+
+    pub fn fill_rate_delta(&self) -> Option<f64> {
+        self.previous().map(|p| self.current.fill_rate - p.fill_rate)
+    }
+
+    pub fn metric_deltas(&self) -> Vec<MetricDelta> {
+        self.current.metrics
+            .iter()
+            .map(|(name, current)| MetricDelta {
+                name: name.clone(),
+                current: *current,
+                previous: self.history.iter().rev().find_map(|s| s.metrics.get(name).copied()),
+            })
+            .collect()
+    }
+}
+
+/// Which entities each UUID-keyed report covers
+#[derive(Debug, Clone)]
+pub struct EntityCoverage {
+    /// One row per entity: `entity_uuid`, a boolean column per report and
+    /// `reports`, the number of reports it appears in
+    pub frame: DataFrame,
+    pub reports: Vec<ReportKey>,
+    pub entities: usize,
+    pub in_every_report: usize,
+    pub per_report: Vec<(ReportKey, usize)>,
+}
+
+impl EntityCoverage {
+    /// Outer-join every report with a UUID column on that UUID; None when
+    /// no report has one
+    pub fn from_reports(reports: &[LoadedReport]) -> Result<Option<Self>> {
+        let keyed: Vec<(&LoadedReport, &str)> = reports.iter()
+            .filter_map(|r| uuid_column(&r.frame).map(|c| (r, c)))
+            .collect();
+        if keyed.is_empty() {
+            return Ok(None);
+        }
+
+        let labels: Vec<String> = keyed.iter().map(|(r, _)| r.key.to_string()).collect();
+        let frames = keyed.iter().zip(&labels).map(|((report, uuid), label)| {
+            report.frame.clone()
+                .lazy()
+                .select([col(*uuid).cast(DataType::String).alias("entity_uuid")])
+                .drop_nulls(None)
+                .unique(None, UniqueKeepStrategy::Any)
+                .with_column(lit(true).alias(label.as_str()))
+        });
+        let joined = frames
+            .reduce(|acc, next| {
+                acc.join(
+                    next,
+                    [col("entity_uuid")],
+                    [col("entity_uuid")],
+                    JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+                )
+            })
+            .expect("at least one keyed report");
+
+        let present: Vec<Expr> = labels.iter().map(|l| col(l.as_str()).fill_null(lit(false))).collect();
+        let counted = labels.iter()
+            .map(|l| col(l.as_str()).cast(DataType::UInt32))
+            .reduce(|a, b| a + b)
+            .expect("at least one keyed report");
+        let frame = joined
+            .with_columns(present)
+            .with_column(counted.alias("reports"))
+            .sort(["entity_uuid"], SortMultipleOptions::default())
+            .collect()
+            .context("Failed to join reports on entity UUID")?;
+
+        let in_every_report = frame.column("reports")?
+            .as_materialized_series()
+            .u32()?
+            .into_iter()
+            .filter(|count| *count == Some(labels.len() as u32))
+            .count();
+        let per_report = keyed.iter().zip(&labels)
+            .map(|((report, _), label)| {
+                let present = frame.column(label)?
+                    .as_materialized_series()
+                    .bool()?
+                    .into_iter()
+                    .filter(|p| *p == Some(true))
+                    .count();
+                Ok((report.key.clone(), present))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Self {
+            entities: frame.height(),
+            reports: keyed.iter().map(|(r, _)| r.key.clone()).collect(),
+            in_every_report,
+            per_report,
+            frame,
+        }))
+    }
+
+    /// Entities missing from at least one report, with the reports they are in
+    pub fn partially_covered(&self, limit: usize) -> Result<Vec<(String, Vec<ReportKey>)>> {
+        let uuids = self.frame.column("entity_uuid")?.as_materialized_series().str()?.clone();
+        let counts = self.frame.column("reports")?.as_materialized_series().u32()?.clone();
+        let flags = self.reports.iter()
+            .map(|key| Ok(self.frame.column(&key.to_string())?.as_materialized_series().bool()?.clone()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut partial = Vec::new();
+        for row in 0..self.frame.height() {
+            if partial.len() >= limit {
+                break;
+            }
+            if counts.get(row) == Some(self.reports.len() as u32) {
+                continue;
+            }
+            let found_in = self.reports.iter().zip(&flags)
+                .filter(|(_, flag)| flag.get(row) == Some(true))
+                .map(|(key, _)| key.clone())
+                .collect();
+            partial.push((uuids.get(row).unwrap_or_default().to_string(), found_in));
+        }
+        Ok(partial)
+    }
+}
+
+/// Everything the dashboard shows
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    pub generated_at: DateTime<Utc>,
+    pub trends: Vec<ReportTrend>,
+    pub coverage: Option<EntityCoverage>,
+    pub archives_read: usize,
+}
+
+/// Builds a `Dashboard` from a reports directory and its archives
+pub struct ReportAggregator {
+    config: ReportConfig,
+    archive_manager: ArchiveManager,
+    history: usize,
+}
+
+impl ReportAggregator {
+    pub fn new<P: AsRef<Path>>(reports_dir: P) -> Self {
+        Self {
+            config: ReportConfig::new(&reports_dir),
+            archive_manager: ArchiveManager::new(&reports_dir),
+            history: DEFAULT_HISTORY,
+        }
+    }
+
+    /// Compare against at most this many of the newest archives
+    pub fn with_history(mut self, archives: usize) -> Self {
+        self.history = archives;
+        self
+    }
+
+    pub fn build(&self) -> Result<Dashboard> {
+        let reports = self.config.load_current_reports()?;
+        let metadata = AuditReportMetadata::load_all(&self.config.reports_dir)?;
+
+        let mut trends: BTreeMap<ReportKey, ReportTrend> = BTreeMap::new();
+        for report in &reports {
+            let timestamp = report.metadata.as_ref().map_or_else(Utc::now, |m| m.timestamp);
+            trends.insert(report.key.clone(), ReportTrend {
+                key: report.key.clone(),
+                current: ReportSnapshot::from_report(report, timestamp)?,
+                history: Vec::new(),
+            });
+        }
+
+        // Archives are listed newest first; walk them oldest first so history stays in order
+        let archives = self.archive_manager.list_archives()?;
+        let mut archives: Vec<_> = archives.into_iter().take(self.history).collect();
+        archives.sort_by_key(|a| a.archived_at());
+        for archive in &archives {
+            for report in self.archive_manager.load_archive_reports(archive, &metadata)? {
+                if let Some(trend) = trends.get_mut(&report.key) {
+                    trend.history.push(ReportSnapshot::from_report(&report, archive.archived_at())?);
+                }
+            }
+        }
+
+        Ok(Dashboard {
+            generated_at: Utc::now(),
+            trends: trends.into_values().collect(),
+            coverage: EntityCoverage::from_reports(&reports)?,
+            archives_read: archives.len(),
+        })
+    }
+}
+
+const DASHBOARD_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;color:#222}\
+table{border-collapse:collapse;margin:1rem 0}th,td{border:1px solid #ccc;padding:4px 8px;text-align:right}\
+th:first-child,td:first-child{text-align:left}.up{color:#1a7f37}.down{color:#cf222e}.flat{color:#888}\
+.cards{display:flex;gap:1rem}.card{border:1px solid #ccc;border-radius:6px;padding:0.5rem 1rem}";
+
+/// Rows drawn in the partially covered entities table
+const PARTIAL_ENTITY_LIMIT: usize = 50;
+
+impl Dashboard {
+    /// Render as a standalone HTML page
+    pub fn to_html(&self) -> Result<String> {
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Audit Dashboard</title>")?;
+        writeln!(html, "<style>{}</style></head><body>", DASHBOARD_STYLE)?;
+        writeln!(html, "<h1>Audit Dashboard</h1>")?;
+        writeln!(html, "<p>Generated {} from {} reports and {} archives.</p>",
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC"), self.trends.len(), self.archives_read)?;
+
+        writeln!(html, "<div class=\"cards\">")?;
+        let total_rows: usize = self.trends.iter().map(|t| t.current.row_count).sum();
+        writeln!(html, "<div class=\"card\"><b>{}</b><br>rows</div>", total_rows)?;
+        if let Some(coverage) = &self.coverage {
+            writeln!(html, "<div class=\"card\"><b>{}</b><br>entities</div>", coverage.entities)?;
+            writeln!(html, "<div class=\"card\"><b>{}</b><br>in every UUID report</div>", coverage.in_every_report)?;
+        }
+        writeln!(html, "</div>")?;
+
+        writeln!(html, "<h2>Reports</h2>\n<table><tr><th>Report</th><th>Rows</th><th>Δ</th><th>Fill rate</th><th>Δ</th><th>Entities</th><th>Rows over time</th></tr>")?;
+        for trend in &self.trends {
+            writeln!(html, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&trend.key.to_string()),
+                trend.current.row_count,
+                delta_cell(trend.row_delta().map(|d| d as f64), 0),
+                trend.current.fill_rate * 100.0,
+                delta_cell(trend.fill_rate_delta().map(|d| d * 100.0), 1),
+                trend.current.entity_count.map_or("-".to_string(), |c| c.to_string()),
+                sparkline(&trend.history.iter().chain([&trend.current]).map(|s| s.row_count as f64).collect::<Vec<_>>()),
+            )?;
+        }
+        writeln!(html, "</table>")?;
+
+        writeln!(html, "<h2>Fill rate</h2>\n{}", self.fill_rate_chart())?;
+
+        writeln!(html, "<h2>Metrics</h2>\n<table><tr><th>Report</th><th>Metric</th><th>Current</th><th>Previous</th><th>Δ</th></tr>")?;
+        for trend in &self.trends {
+            for metric in trend.metric_deltas() {
+                writeln!(html, "<tr><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td><td>{}</td></tr>",
+                    escape(&trend.key.to_string()),
+                    escape(&metric.name),
+                    metric.current,
+                    metric.previous.map_or("-".to_string(), |p| format!("{:.2}", p)),
+                    delta_cell(metric.delta(), 2),
+                )?;
+            }
+        }
+        writeln!(html, "</table>")?;
+
+        if let Some(coverage) = &self.coverage {
+            writeln!(html, "<h2>Entity coverage</h2>\n<table><tr><th>Report</th><th>Entities</th><th>Share</th></tr>")?;
+            for (key, count) in &coverage.per_report {
+                writeln!(html, "<tr><td>{}</td><td>{}</td><td>{:.1}%</td></tr>",
+                    escape(&key.to_string()), count, *count as f64 / coverage.entities.max(1) as f64 * 100.0)?;
+            }
+            writeln!(html, "</table>")?;
+
+            let partial = coverage.partially_covered(PARTIAL_ENTITY_LIMIT)?;
+            if !partial.is_empty() {
+                writeln!(html, "<h3>Entities missing from some reports</h3>\n<table><tr><th>Entity</th><th>Found in</th></tr>")?;
+                for (uuid, found_in) in partial {
+                    let found: Vec<String> = found_in.iter().map(|k| escape(&k.to_string())).collect();
+                    writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", escape(&uuid), found.join(", "))?;
+                }
+                writeln!(html, "</table>")?;
+            }
+        }
+
+        writeln!(html, "</body></html>")?;
+        Ok(html)
+    }
+
+    /// Write the HTML page, creating parent directories
+    pub fn write_html<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)
+                .context("Failed to create dashboard directory")?;
+        }
+        std::fs::write(path.as_ref(), self.to_html()?)
+            .context("Failed to write dashboard")
+    }
+
+    /// Horizontal bars per report: current fill rate over the previous one
+    fn fill_rate_chart(&self) -> String {
+        const LABEL_WIDTH: usize = 260;
+        const BAR_WIDTH: f64 = 300.0;
+        const ROW_HEIGHT: usize = 22;
+
+        let height = self.trends.len() * ROW_HEIGHT + 4;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-size=\"12\">",
+            LABEL_WIDTH + BAR_WIDTH as usize + 60, height
+        );
+        for (i, trend) in self.trends.iter().enumerate() {
+            let y = i * ROW_HEIGHT + 2;
+            svg.push_str(&format!("<text x=\"0\" y=\"{}\">{}</text>", y + 14, escape(&trend.key.to_string())));
+            if let Some(previous) = trend.previous() {
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"18\" fill=\"#ddd\"/>",
+                    LABEL_WIDTH, y, previous.fill_rate * BAR_WIDTH
+                ));
+            }
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"10\" fill=\"#0969da\"/>",
+                LABEL_WIDTH, y + 4, trend.current.fill_rate * BAR_WIDTH
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\">{:.1}%</text>",
+                LABEL_WIDTH + BAR_WIDTH as usize + 6, y + 14, trend.current.fill_rate * 100.0
+            ));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Signed change, classed so the page can colour it
+fn delta_cell(delta: Option<f64>, precision: usize) -> String {
+    match delta {
+        None => "-".to_string(),
+        Some(d) if d.abs() < 1e-9 => "<span class=\"flat\">0</span>".to_string(),
+        Some(d) => format!(
+            "<span class=\"{}\">{:+.*}</span>",
+            if d > 0.0 { "up" } else { "down" }, precision, d
+        ),
+    }
+}
+
+/// Small line chart of a series, oldest value first
+fn sparkline(values: &[f64]) -> String {
+    const WIDTH: f64 = 120.0;
+    const HEIGHT: f64 = 24.0;
+    if values.len() < 2 {
+        return "-".to_string();
+    }
+    let max = values.iter().cloned().fold(f64::MIN, f64::max);
+    let min = values.iter().cloned().fold(f64::MAX, f64::min);
+    let span = if max > min { max - min } else { 1.0 };
+    let step = WIDTH / (values.len() - 1) as f64;
+    let points: Vec<String> = values.iter()
+        .enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", i as f64 * step, HEIGHT - 2.0 - (v - min) / span * (HEIGHT - 4.0)))
+        .collect();
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\"><polyline fill=\"none\" stroke=\"#0969da\" points=\"{}\"/></svg>",
+        WIDTH, HEIGHT, points.join(" ")
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuditSystem;
+    use crate::test_support::{at, audit_system, row};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dashboard_trends_and_coverage() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let audit_system = audit_system(temp_dir.path());
+
+        // First run, archived when the second run replaces it
+        audit_system.generate_report_at(&[row("e1", "region", 0.5), row("e2", "", 0.5)], "entities", at(0))?;
+        audit_system.generate_report_at(&[
+            row("e1", "region", 1.0),
+            row("e2", "dungeon", 0.5),
+            row("e3", "faction", 0.75),
+        ], "entities", at(1))?;
+        // Archiving would sweep up entities.csv, which shares the directory
+        let audit_system = AuditSystem::with_config(
+            ReportConfig::new(temp_dir.path()).with_statistics(false).with_archiving(false),
+        );
+        audit_system.generate_report_at(&[row("e1", "region", 1.0), row("e4", "region", 1.0)], "regions", at(2))?;
+
+        let dashboard = ReportAggregator::new(temp_dir.path()).build()?;
+        assert_eq!(dashboard.archives_read, 1);
+        assert_eq!(dashboard.trends.len(), 2);
+
+        let entities = &dashboard.trends[0];
+        assert_eq!(entities.key.to_string(), "analysis/categorization/entities");
+        assert_eq!(entities.history.len(), 1);
+        assert_eq!(entities.row_delta(), Some(1));
+        assert!(entities.fill_rate_delta().unwrap() > 0.0);
+        let accuracy = entities.metric_deltas().into_iter().find(|m| m.name == "accuracy").unwrap();
+        assert_eq!(accuracy.previous, Some(0.5));
+        assert!((accuracy.delta().unwrap() - 0.25).abs() < 1e-9);
+
+        let coverage = dashboard.coverage.as_ref().unwrap();
+        assert_eq!(coverage.entities, 4);
+        assert_eq!(coverage.in_every_report, 1);
+        let partial = coverage.partially_covered(10)?;
+        assert_eq!(partial.iter().map(|(uuid, _)| uuid.as_str()).collect::<Vec<_>>(), ["e2", "e3", "e4"]);
+
+        let html = dashboard.to_html()?;
+        assert!(html.contains("analysis/categorization/entities"));
+        assert!(html.contains("<span class=\"up\">+0.25</span>"));
+        assert!(html.contains("<polyline"));
+        Ok(())
+    }
+}
@@ -0,0 +1,562 @@
+//! Regression diffing between audit archives
+//!
+//! Extracts two archives, pairs their reports by category, subcategory and
+//! report name, and records what changed from one to the other: rows added
+//! and removed, numeric values that moved by more than a tolerance, and
+//! columns added, removed or retyped. Reports with an entity UUID column are
+//! matched row by row on that UUID. Reports without one can only be compared
+//! as sets of whole rows, so an edited row counts as one removed and one added.
+//! The result serializes to JSON and is checked against `DiffThresholds`, so a
+//! pipeline run can fail when its output regresses.
+
+use anyhow::{Context, Result};
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::archive::{ArchiveInfo, ArchiveManager, UNNAMED_ARCHIVE_CATEGORY};
+use crate::dashboard::uuid_column;
+use crate::reports::{AuditReportMetadata, LoadedReport, ReportKey};
+
+/// Absolute difference below which numeric values count as unchanged
+pub const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+/// Marks rows found on the other side of a comparison join
+const PRESENT_COLUMN: &str = "__present";
+
+/// Appended to the later archive's columns when values are joined
+const AFTER_SUFFIX: &str = "__after";
+
+/// Limits a diff is checked against; unset limits are never violated
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffThresholds {
+    /// How far a numeric value may move before it counts as changed
+    pub tolerance: f64,
+    pub max_added_rows: Option<usize>,
+    pub max_removed_rows: Option<usize>,
+    pub max_changed_values: Option<usize>,
+    /// Count column changes, and reports only one archive has, as violations
+    pub fail_on_schema_change: bool,
+}
+
+impl Default for DiffThresholds {
+    fn default() -> Self {
+        Self {
+            tolerance: DEFAULT_TOLERANCE,
+            max_added_rows: None,
+            max_removed_rows: None,
+            max_changed_values: None,
+            fail_on_schema_change: false,
+        }
+    }
+}
+
+impl DiffThresholds {
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Most rows any one report may gain
+    pub fn with_max_added_rows(mut self, max: Option<usize>) -> Self {
+        self.max_added_rows = max;
+        self
+    }
+
+    /// Most rows any one report may lose
+    pub fn with_max_removed_rows(mut self, max: Option<usize>) -> Self {
+        self.max_removed_rows = max;
+        self
+    }
+
+    /// Most numeric values any one report may change beyond the tolerance
+    pub fn with_max_changed_values(mut self, max: Option<usize>) -> Self {
+        self.max_changed_values = max;
+        self
+    }
+
+    pub fn with_fail_on_schema_change(mut self, fail: bool) -> Self {
+        self.fail_on_schema_change = fail;
+        self
+    }
+}
+
+/// Which archives a report appears in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPresence {
+    Both,
+    OnlyBefore,
+    OnlyAfter,
+}
+
+/// A column whose inferred type differs between archives
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ColumnRetype {
+    pub column: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// Columns added, removed or retyped between two versions of a report
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SchemaChange {
+    pub added_columns: Vec<String>,
+    pub removed_columns: Vec<String>,
+    pub retyped_columns: Vec<ColumnRetype>,
+}
+
+impl SchemaChange {
+    pub fn between(before: &DataFrame, after: &DataFrame) -> Self {
+        let before_types: BTreeMap<String, String> = before.get_columns()
+            .iter()
+            .map(|c| (c.name().to_string(), c.dtype().to_string()))
+            .collect();
+        let after_types: BTreeMap<String, String> = after.get_columns()
+            .iter()
+            .map(|c| (c.name().to_string(), c.dtype().to_string()))
+            .collect();
+
+        Self {
+            added_columns: after_types.keys()
+                .filter(|name| !before_types.contains_key(*name))
+                .cloned()
+                .collect(),
+            removed_columns: before_types.keys()
+                .filter(|name| !after_types.contains_key(*name))
+                .cloned()
+                .collect(),
+            retyped_columns: before_types.iter()
+                .filter_map(|(name, before)| {
+                    let after = after_types.get(name).filter(|after| *after != before)?;
+                    Some(ColumnRetype { column: name.clone(), before: before.clone(), after: after.clone() })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty() && self.removed_columns.is_empty() && self.retyped_columns.is_empty()
+    }
+}
+
+/// A numeric value that moved by more than the tolerance, or became or stopped being empty
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueChange {
+    pub entity_uuid: String,
+    pub column: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+/// Differences in one report between two archives
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportDiff {
+    pub key: ReportKey,
+    pub presence: ReportPresence,
+    pub rows_before: usize,
+    pub rows_after: usize,
+    /// UUID column rows were matched on; None when whole rows were compared
+    pub matched_on: Option<String>,
+    pub added_rows: usize,
+    pub removed_rows: usize,
+    pub changed_values: Vec<ValueChange>,
+    pub schema: SchemaChange,
+}
+
+impl ReportDiff {
+    /// Compare one report across archives; a side is None when that archive lacks the report
+    pub fn compare(
+        key: ReportKey,
+        before: Option<&DataFrame>,
+        after: Option<&DataFrame>,
+        tolerance: f64,
+    ) -> Result<Self> {
+        let (before, after) = match (before, after) {
+            (Some(before), Some(after)) => (before, after),
+            (before, after) => {
+                let rows_before = before.map_or(0, |df| df.height());
+                let rows_after = after.map_or(0, |df| df.height());
+                return Ok(Self {
+                    key,
+                    presence: if before.is_some() { ReportPresence::OnlyBefore } else { ReportPresence::OnlyAfter },
+                    rows_before,
+                    rows_after,
+                    matched_on: None,
+                    added_rows: rows_after,
+                    removed_rows: rows_before,
+                    changed_values: Vec::new(),
+                    schema: SchemaChange::default(),
+                });
+            }
+        };
+
+        let uuid = uuid_column(before).filter(|name| uuid_column(after) == Some(*name));
+        let common: Vec<String> = before.get_columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .filter(|name| after.column(name).is_ok())
+            .collect();
+        let keys = match uuid {
+            Some(name) => vec![name.to_string()],
+            None => common.clone(),
+        };
+
+        let changed_values = match uuid {
+            Some(name) => {
+                let numeric: Vec<String> = common.iter()
+                    .filter(|c| c.as_str() != name)
+                    .filter(|c| {
+                        before.column(c).is_ok_and(|s| s.dtype().is_primitive_numeric())
+                            && after.column(c).is_ok_and(|s| s.dtype().is_primitive_numeric())
+                    })
+                    .cloned()
+                    .collect();
+                changed_values(before, after, name, &numeric, tolerance)?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            key,
+            presence: ReportPresence::Both,
+            rows_before: before.height(),
+            rows_after: after.height(),
+            matched_on: uuid.map(str::to_string),
+            added_rows: rows_missing_from(after, before, &keys)?,
+            removed_rows: rows_missing_from(before, after, &keys)?,
+            changed_values,
+            schema: SchemaChange::between(before, after),
+        })
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.presence == ReportPresence::Both
+            && self.added_rows == 0
+            && self.removed_rows == 0
+            && self.changed_values.is_empty()
+            && self.schema.is_empty()
+    }
+}
+
+/// Rows of `frame` whose `keys` match no row of `other`
+fn rows_missing_from(frame: &DataFrame, other: &DataFrame, keys: &[String]) -> Result<usize> {
+    if keys.is_empty() {
+        return Ok(frame.height());
+    }
+
+    // Keys are joined as text, with empty and null alike, since each archive infers its own types
+    let key_exprs = || -> Vec<Expr> {
+        keys.iter()
+            .map(|k| col(k.as_str()).cast(DataType::String).fill_null(lit("")).alias(k.as_str()))
+            .collect()
+    };
+    let join_on: Vec<Expr> = keys.iter().map(|k| col(k.as_str())).collect();
+    let present = other.clone()
+        .lazy()
+        .select(key_exprs())
+        .unique(None, UniqueKeepStrategy::Any)
+        .with_column(lit(true).alias(PRESENT_COLUMN));
+    let missing = frame.clone()
+        .lazy()
+        .select(key_exprs())
+        .join(present, join_on.clone(), join_on, JoinArgs::new(JoinType::Left))
+        .filter(col(PRESENT_COLUMN).is_null())
+        .collect()
+        .context("Failed to match report rows")?;
+
+    Ok(missing.height())
+}
+
+/// Numeric values that differ between entities present in both frames,
+/// comparing the first row each entity has
+fn changed_values(
+    before: &DataFrame,
+    after: &DataFrame,
+    uuid: &str,
+    columns: &[String],
+    tolerance: f64,
+) -> Result<Vec<ValueChange>> {
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let by_entity = |frame: &DataFrame, suffix: &str| {
+        let values: Vec<Expr> = columns.iter()
+            .map(|c| col(c.as_str()).cast(DataType::Float64).first().alias(format!("{}{}", c, suffix)))
+            .collect();
+        frame.clone()
+            .lazy()
+            .with_column(col(uuid).cast(DataType::String))
+            .filter(col(uuid).is_not_null())
+            .group_by([col(uuid)])
+            .agg(values)
+    };
+    let joined = by_entity(before, "")
+        .join(by_entity(after, AFTER_SUFFIX), [col(uuid)], [col(uuid)], JoinArgs::new(JoinType::Inner))
+        .collect()
+        .context("Failed to join report rows on entity UUID")?;
+
+    let mut changes = Vec::new();
+    for column in columns {
+        let after_name = format!("{}{}", column, AFTER_SUFFIX);
+        let (old, new) = (col(column.as_str()), col(after_name.as_str()));
+        let moved = (old.clone() - new.clone()).gt(lit(tolerance))
+            .or((new.clone() - old.clone()).gt(lit(tolerance)))
+            .or(old.clone().is_null().neq(new.clone().is_null()));
+        let rows = joined.clone()
+            .lazy()
+            .filter(moved)
+            .select([col(uuid), old, new])
+            .collect()
+            .with_context(|| format!("Failed to compare column {}", column))?;
+
+        let entities = rows.column(uuid)?.as_materialized_series().str()?.clone();
+        let old_values = rows.column(column)?.as_materialized_series().f64()?.clone();
+        let new_values = rows.column(&after_name)?.as_materialized_series().f64()?.clone();
+        for ((entity, before), after) in entities.into_iter().zip(&old_values).zip(&new_values) {
+            changes.push(ValueChange {
+                entity_uuid: entity.unwrap_or_default().to_string(),
+                column: column.clone(),
+                before,
+                after,
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.entity_uuid.cmp(&b.entity_uuid).then_with(|| a.column.cmp(&b.column)));
+    Ok(changes)
+}
+
+/// Every report compared between two archives, with any threshold violations
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveDiff {
+    pub before: String,
+    pub after: String,
+    pub thresholds: DiffThresholds,
+    pub reports: Vec<ReportDiff>,
+    pub violations: Vec<String>,
+}
+
+impl ArchiveDiff {
+    /// Pair reports by key and compare each pair
+    pub fn from_reports(
+        before: &str,
+        after: &str,
+        before_reports: &[LoadedReport],
+        after_reports: &[LoadedReport],
+        thresholds: &DiffThresholds,
+    ) -> Result<Self> {
+        let mut paired: BTreeMap<&ReportKey, (Option<&DataFrame>, Option<&DataFrame>)> = BTreeMap::new();
+        for report in before_reports {
+            paired.entry(&report.key).or_default().0 = Some(&report.frame);
+        }
+        for report in after_reports {
+            paired.entry(&report.key).or_default().1 = Some(&report.frame);
+        }
+
+        let reports = paired.into_iter()
+            .map(|(key, (old, new))| ReportDiff::compare(key.clone(), old, new, thresholds.tolerance))
+            .collect::<Result<Vec<_>>>()?;
+        let violations = reports.iter()
+            .flat_map(|report| violations_of(report, thresholds))
+            .collect();
+
+        Ok(Self {
+            before: before.to_string(),
+            after: after.to_string(),
+            thresholds: thresholds.clone(),
+            reports,
+            violations,
+        })
+    }
+
+    /// True when no threshold was violated
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize archive diff")
+    }
+
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path.as_ref(), self.to_json()?)
+            .with_context(|| format!("Failed to write diff to {}", path.as_ref().display()))
+    }
+}
+
+fn violations_of(report: &ReportDiff, thresholds: &DiffThresholds) -> Vec<String> {
+    let mut violations = Vec::new();
+    let key = &report.key;
+
+    if thresholds.fail_on_schema_change {
+        match report.presence {
+            ReportPresence::OnlyBefore => violations.push(format!("{}: report removed", key)),
+            ReportPresence::OnlyAfter => violations.push(format!("{}: report added", key)),
+            ReportPresence::Both if !report.schema.is_empty() => violations.push(format!(
+                "{}: schema changed ({} added, {} removed, {} retyped columns)",
+                key,
+                report.schema.added_columns.len(),
+                report.schema.removed_columns.len(),
+                report.schema.retyped_columns.len(),
+            )),
+            ReportPresence::Both => {}
+        }
+    }
+    if let Some(max) = thresholds.max_added_rows.filter(|max| report.added_rows > *max) {
+        violations.push(format!("{}: {} rows added, limit {}", key, report.added_rows, max));
+    }
+    if let Some(max) = thresholds.max_removed_rows.filter(|max| report.removed_rows > *max) {
+        violations.push(format!("{}: {} rows removed, limit {}", key, report.removed_rows, max));
+    }
+    if let Some(max) = thresholds.max_changed_values.filter(|max| report.changed_values.len() > *max) {
+        violations.push(format!("{}: {} values changed, limit {}", key, report.changed_values.len(), max));
+    }
+
+    violations
+}
+
+/// Diffs archives under one reports directory
+pub struct ArchiveDiffer {
+    reports_dir: PathBuf,
+    archive_manager: ArchiveManager,
+    thresholds: DiffThresholds,
+}
+
+impl ArchiveDiffer {
+    pub fn new<P: AsRef<Path>>(reports_dir: P) -> Self {
+        Self {
+            reports_dir: reports_dir.as_ref().to_path_buf(),
+            archive_manager: ArchiveManager::new(reports_dir),
+            thresholds: DiffThresholds::default(),
+        }
+    }
+
+    pub fn with_thresholds(mut self, thresholds: DiffThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// An archive given as a path, or as a file name under the archives directory
+    pub fn locate(&self, archive: &Path) -> PathBuf {
+        if archive.exists() {
+            return archive.to_path_buf();
+        }
+        let archived = self.reports_dir.join("archives").join(archive);
+        if archived.exists() { archived } else { archive.to_path_buf() }
+    }
+
+    /// Extract both archives and compare every report in them
+    pub fn diff(&self, before: &Path, after: &Path) -> Result<ArchiveDiff> {
+        let metadata = AuditReportMetadata::load_all(&self.reports_dir)?;
+        let before = ArchiveInfo::from_path(self.locate(before))?;
+        let after = ArchiveInfo::from_path(self.locate(after))?;
+
+        let mut before_reports = self.archive_manager.load_archive_reports(&before, &metadata)?;
+        let mut after_reports = self.archive_manager.load_archive_reports(&after, &metadata)?;
+        align_unnamed(&mut before_reports, &mut after_reports);
+
+        ArchiveDiff::from_reports(&before.filename, &after.filename, &before_reports, &after_reports, &self.thresholds)
+    }
+}
+
+/// Reports from an archive no metadata names take the other archive's
+/// category and subcategory, so they still pair up by report name
+fn align_unnamed(before: &mut [LoadedReport], after: &mut [LoadedReport]) {
+    let location = |reports: &[LoadedReport]| {
+        reports.first().map(|r| (r.key.category.clone(), r.key.subcategory.clone()))
+    };
+    let named = |reports: &[LoadedReport]| {
+        location(reports).filter(|(category, _)| category != UNNAMED_ARCHIVE_CATEGORY)
+    };
+
+    if let Some((category, subcategory)) = named(before).or_else(|| named(after)).or_else(|| location(before)) {
+        for report in before.iter_mut().chain(after.iter_mut()) {
+            if report.key.category == UNNAMED_ARCHIVE_CATEGORY {
+                report.key.category = category.clone();
+                report.key.subcategory = subcategory.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{at, audit_system, row};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_between_archives() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let audit_system = audit_system(temp_dir.path());
+
+        // Each run archives the one before it
+        audit_system.generate_report_at(&[row("e1", "region", 0.5), row("e2", "region", 0.5)], "entities", at(0))?;
+        audit_system.generate_report_at(&[
+            row("e1", "region", 1.0),
+            row("e2", "region", 0.5 + 1e-12),
+            row("e3", "region", 0.75),
+        ], "entities", at(1))?;
+        audit_system.generate_report_at(&[row("e1", "region", 1.0)], "entities", at(2))?;
+
+        let mut archives = audit_system.list_archives()?;
+        assert_eq!(archives.len(), 2);
+        archives.sort_by_key(|a| a.archived_at());
+
+        let thresholds = DiffThresholds::default().with_max_changed_values(Some(0));
+        let diff = ArchiveDiffer::new(temp_dir.path())
+            .with_thresholds(thresholds)
+            .diff(Path::new(&archives[0].filename), &archives[1].path)?;
+
+        assert_eq!(diff.reports.len(), 1);
+        let report = &diff.reports[0];
+        assert_eq!(report.key.to_string(), "analysis/categorization/entities");
+        assert_eq!(report.matched_on.as_deref(), Some("entity_uuid"));
+        assert_eq!((report.added_rows, report.removed_rows), (1, 0));
+        assert_eq!(report.changed_values, [ValueChange {
+            entity_uuid: "e1".to_string(),
+            column: "accuracy".to_string(),
+            before: Some(0.5),
+            after: Some(1.0),
+        }]);
+        assert!(report.schema.is_empty());
+        assert_eq!(diff.violations, ["analysis/categorization/entities: 1 values changed, limit 0"]);
+        assert!(diff.to_json()?.contains("\"matched_on\": \"entity_uuid\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_whole_row_diff_and_schema_violations() -> Result<()> {
+        let key = |name: &str| ReportKey {
+            category: "world".to_string(),
+            subcategory: "regions".to_string(),
+            report_name: name.to_string(),
+        };
+        let report = |name: &str, frame: DataFrame| LoadedReport { key: key(name), metadata: None, frame };
+
+        let before = [
+            report("biomes", df!("biome" => ["forest", "swamp"], "count" => [3i64, 4])?),
+            report("legacy", df!("name" => ["old"])?),
+        ];
+        let after = [
+            report("biomes", df!("biome" => ["forest", "swamp"], "count" => [3i64, 5], "note" => ["", ""])?),
+        ];
+        let thresholds = DiffThresholds::default().with_fail_on_schema_change(true).with_max_removed_rows(Some(1));
+        let diff = ArchiveDiff::from_reports("a", "b", &before, &after, &thresholds)?;
+
+        let biomes = &diff.reports[0];
+        assert_eq!(biomes.matched_on, None);
+        assert_eq!((biomes.added_rows, biomes.removed_rows), (1, 1));
+        assert_eq!(biomes.schema.added_columns, ["note"]);
+        assert_eq!(diff.reports[1].presence, ReportPresence::OnlyBefore);
+        assert_eq!(diff.violations, [
+            "world/regions/biomes: schema changed (1 added, 0 removed, 0 retyped columns)",
+            "world/regions/legacy: report removed",
+        ]);
+        assert!(!diff.passed());
+        Ok(())
+    }
+}
@@ -4,15 +4,22 @@
 //! Implements rotational archiving to prevent report overwrites.
 
 pub mod archive;
+pub mod dashboard;
 pub mod dataframe;
+pub mod diff;
 pub mod reports;
 pub mod system;
 
+#[cfg(test)]
+mod test_support;
+
 // Re-export main functionality
 pub use system::AuditSystem;
-pub use reports::{AuditReportMetadata, ReportConfig};
+pub use reports::{AuditReportMetadata, LoadedReport, ReportConfig, ReportKey};
 pub use archive::ArchiveManager;
 pub use dataframe::DataFrameBuilder;
+pub use dashboard::{Dashboard, ReportAggregator};
+pub use diff::{ArchiveDiff, ArchiveDiffer, DiffThresholds};
 
 /// Version information
 pub const DL_AUDIT_VERSION: &str = "0.1.0";
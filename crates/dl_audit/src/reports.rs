@@ -6,8 +6,11 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use dl_types::{AuditableType, AuditMetadata};
+use polars::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 /// Configuration for generating audit reports
 #[derive(Debug, Clone)]
@@ -68,6 +71,99 @@ impl ReportConfig {
             .join(T::audit_category())
             .join(T::audit_subcategory())
     }
+    
+    /// Read every current report CSV back, each with its latest metadata
+    /// 
+    /// Reports live at `audits/<category>/<subcategory>/<report>.csv`.
+    pub fn load_current_reports(&self) -> Result<Vec<LoadedReport>> {
+        let audits_dir = self.reports_dir.join("audits");
+        if !audits_dir.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let metadata = AuditReportMetadata::load_all(&self.reports_dir)?;
+        let mut reports = Vec::new();
+        
+        for entry in WalkDir::new(&audits_dir)
+            .min_depth(3)
+            .max_depth(3)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("csv"))
+        {
+            let relative = entry.path().strip_prefix(&audits_dir)?;
+            let parts: Vec<String> = relative.iter().map(|p| p.to_string_lossy().to_string()).collect();
+            let key = ReportKey {
+                category: parts[0].clone(),
+                subcategory: parts[1].clone(),
+                report_name: report_name_of(entry.path()),
+            };
+            // Metadata is loaded oldest first, so the last match is the latest run
+            let latest = metadata.iter().rev().find(|m| ReportKey::from_metadata(m) == key).cloned();
+            reports.push(LoadedReport::read_csv(key, entry.path(), latest)?);
+        }
+        
+        reports.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(reports)
+    }
+}
+
+/// Report name from a CSV path (`narrative.csv` -> `narrative`)
+pub(crate) fn report_name_of(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Identifies one report across runs and archives
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ReportKey {
+    pub category: String,
+    pub subcategory: String,
+    pub report_name: String,
+}
+
+impl ReportKey {
+    pub fn from_metadata(metadata: &AuditReportMetadata) -> Self {
+        Self {
+            category: metadata.category.clone(),
+            subcategory: metadata.subcategory.clone(),
+            report_name: metadata.report_name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ReportKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.category, self.subcategory, self.report_name)
+    }
+}
+
+/// An audit CSV read back into a DataFrame
+#[derive(Debug, Clone)]
+pub struct LoadedReport {
+    pub key: ReportKey,
+    pub metadata: Option<AuditReportMetadata>,
+    pub frame: DataFrame,
+}
+
+impl LoadedReport {
+    /// Read a report CSV, inferring column types from its contents
+    pub fn read_csv<P: AsRef<Path>>(
+        key: ReportKey,
+        path: P,
+        metadata: Option<AuditReportMetadata>,
+    ) -> Result<Self> {
+        let frame = CsvReadOptions::default()
+            .with_has_header(true)
+            .with_infer_schema_length(None)
+            .try_into_reader_with_file_path(Some(path.as_ref().to_path_buf()))?
+            .finish()
+            .with_context(|| format!("Failed to read report {}", path.as_ref().display()))?;
+        
+        Ok(Self { key, metadata, frame })
+    }
 }
 
 /// Metadata about a generated audit report
@@ -162,6 +258,26 @@ impl AuditReportMetadata {
         serde_json::from_str(&json)
             .context("Failed to deserialize metadata")
     }
+    
+    /// Load every metadata file under `reports_dir/metadata`, oldest first
+    pub fn load_all(reports_dir: &Path) -> Result<Vec<Self>> {
+        let metadata_dir = reports_dir.join("metadata");
+        if !metadata_dir.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let mut all = Vec::new();
+        for entry in std::fs::read_dir(&metadata_dir).context("Failed to read metadata directory")? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                all.push(Self::load_metadata(&path)
+                    .with_context(|| format!("Failed to load {}", path.display()))?);
+            }
+        }
+        
+        all.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.report_name.cmp(&b.report_name)));
+        Ok(all)
+    }
 }
 
 /// Report generation summary
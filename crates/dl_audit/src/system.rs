@@ -4,13 +4,16 @@
 //! with Polars DataFrames and rotational archiving.
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use dl_types::AuditableType;
 use polars::prelude::*;
 use std::path::Path;
 use std::time::Instant;
 
 use crate::archive::ArchiveManager;
+use crate::dashboard::{Dashboard, ReportAggregator};
 use crate::dataframe::DataFrameBuilder;
+use crate::diff::{ArchiveDiff, ArchiveDiffer, DiffThresholds};
 use crate::reports::{AuditReportMetadata, ReportConfig, ReportSummary};
 
 /// Main audit system for generating reports from any pipeline stage
@@ -71,6 +74,17 @@ impl AuditSystem {
         &self,
         items: &[T],
         report_name: &str,
+    ) -> Result<AuditReportMetadata> {
+        self.generate_report_at(items, report_name, Utc::now())
+    }
+    
+    /// Generate audit report as of `generated_at`, which names both the
+    /// archive of the previous reports and the new report's metadata
+    pub fn generate_report_at<T: AuditableType>(
+        &self,
+        items: &[T],
+        report_name: &str,
+        generated_at: DateTime<Utc>,
     ) -> Result<AuditReportMetadata> {
         let start_time = Instant::now();
         
@@ -80,7 +94,7 @@ impl AuditSystem {
         
         // Step 1: Archive existing CSV files if they exist
         let archive_created = if self.config.archive_existing {
-            self.archive_manager.archive_existing_reports(&reports_directory)?
+            self.archive_manager.archive_existing_reports_at(&reports_directory, generated_at)?
         } else {
             None
         };
@@ -103,7 +117,7 @@ impl AuditSystem {
         
         // Step 5: Create metadata
         let generation_time = start_time.elapsed().as_millis() as u64;
-        let metadata = AuditReportMetadata::new::<T>(
+        let mut metadata = AuditReportMetadata::new::<T>(
             report_name.to_string(),
             report_path,
             stats.row_count,
@@ -112,6 +126,7 @@ impl AuditSystem {
             archive_created,
             generation_time,
         );
+        metadata.timestamp = generated_at;
         
         // Step 6: Save metadata
         metadata.save_metadata(&self.config.reports_dir)?;
@@ -198,6 +213,24 @@ impl AuditSystem {
         self.archive_manager.list_archives()
     }
     
+    /// Build the cross-report dashboard over current reports and the
+    /// `history` newest archives, and write it as HTML to `output`
+    pub fn generate_dashboard<P: AsRef<Path>>(&self, output: P, history: usize) -> Result<Dashboard> {
+        let dashboard = ReportAggregator::new(&self.config.reports_dir)
+            .with_history(history)
+            .build()?;
+        dashboard.write_html(output)?;
+        Ok(dashboard)
+    }
+    
+    /// Compare the reports in two archives, each given as a path or a file
+    /// name under the archives directory
+    pub fn diff_archives<P: AsRef<Path>>(&self, before: P, after: P, thresholds: DiffThresholds) -> Result<ArchiveDiff> {
+        ArchiveDiffer::new(&self.config.reports_dir)
+            .with_thresholds(thresholds)
+            .diff(before.as_ref(), after.as_ref())
+    }
+    
     /// Extract an archive for data recovery
     pub fn extract_archive<P: AsRef<Path>>(&self, archive_path: P, extract_to: P) -> Result<()> {
         self.archive_manager.extract_archive(archive_path, extract_to)
@@ -284,6 +317,7 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
     use std::collections::HashMap;
+    use std::path::PathBuf;
     
     #[derive(Debug)]
     struct TestAuditData {
@@ -0,0 +1,51 @@
+//! Shared fixtures for the audit crate's tests
+
+use chrono::{DateTime, TimeZone, Utc};
+use dl_types::AuditableType;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{AuditSystem, ReportConfig};
+
+#[derive(Debug)]
+pub struct EntityRow {
+    pub uuid: String,
+    pub category: String,
+    pub accuracy: f64,
+}
+
+impl AuditableType for EntityRow {
+    fn audit_headers() -> Vec<String> {
+        vec!["entity_uuid".to_string(), "category".to_string(), "accuracy".to_string()]
+    }
+
+    fn audit_row(&self) -> Vec<String> {
+        vec![self.uuid.clone(), self.category.clone(), self.accuracy.to_string()]
+    }
+
+    fn audit_category() -> String {
+        "analysis".to_string()
+    }
+
+    fn audit_subcategory() -> String {
+        "categorization".to_string()
+    }
+
+    fn extract_numeric_fields(&self) -> HashMap<String, f64> {
+        HashMap::from([("accuracy".to_string(), self.accuracy)])
+    }
+}
+
+pub fn row(uuid: &str, category: &str, accuracy: f64) -> EntityRow {
+    EntityRow { uuid: uuid.to_string(), category: category.to_string(), accuracy }
+}
+
+pub fn audit_system(root: &Path) -> AuditSystem {
+    AuditSystem::with_config(ReportConfig::new(root).with_statistics(false))
+}
+
+/// A report run `minute` minutes into a fixed hour, so runs get distinct,
+/// ordered archive and metadata names however fast the test goes
+pub fn at(minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 3, 14, minute, 0).unwrap()
+}
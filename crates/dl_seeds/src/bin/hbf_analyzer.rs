@@ -7,11 +7,21 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use dl_seeds::{
     containers::RawEntity,
-    orchestration::RawEntities,
+    data_pools::CategorizedDataPools,
+    labelling::{label_candidates, Labeller},
+    orchestration::{EntityCategory, RawEntities},
+    query::{render_records, EntityQuery, OutputFormat, QueryEngine},
     reporting::generate_all_reports,
+    search::SearchIndex,
 };
+use std::io::{self, BufRead, Write};
 use std::path::PathBuf;
 
+/// Lines of rendered text shown per entity while labelling
+const LABEL_PREVIEW_LINES: usize = 25;
+/// Saved search index, reused until the database is newer
+const SEARCH_INDEX_FILE: &str = "search_index.json";
+
 #[derive(Parser)]
 #[command(name = "hbf-analyzer")]
 #[command(about = "Dragon's Labyrinth HBF Database Analysis Tool")]
@@ -54,6 +64,14 @@ enum Commands {
         /// Limit number of results
         #[arg(short, long, default_value = "10")]
         limit: usize,
+        
+        /// Query expression, e.g. 'cat:creatures band:3-5 hex:0301-0510 "brass dragon"'
+        #[arg(short, long)]
+        query: Option<String>,
+        
+        /// Output format for query results (table, json, ron)
+        #[arg(long, default_value = "table")]
+        format: String,
     },
     /// Inspect HTML processing for specific entities
     InspectHtml {
@@ -74,6 +92,37 @@ enum Commands {
         #[arg(long)]
         apply: bool,
     },
+    /// Full-text search across entities and generated seed pools
+    Search {
+        /// Search terms; quote phrases, e.g. '"village of dokar" haunted'
+        query: Option<String>,
+        
+        /// Find entities mentioning this entity's name instead
+        #[arg(long)]
+        mentions: Option<String>,
+        
+        /// Generated seed pools directory to index as well
+        #[arg(long)]
+        pools: Option<PathBuf>,
+        
+        /// Rebuild the index even if a saved one is current
+        #[arg(long)]
+        rebuild: bool,
+        
+        /// Limit number of results
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+    },
+    /// Label uncategorized and low-confidence entities into the training data
+    Label {
+        /// Training data directory to append examples to
+        #[arg(long, default_value = "training_data")]
+        training_dir: PathBuf,
+        
+        /// Maximum entities to present
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
     /// Export entities for external processing
     Export {
         /// Export format (json, ron, csv)
@@ -100,8 +149,11 @@ fn main() -> Result<()> {
         Commands::AnalyzeAll { reports } => {
             analyze_all_entities(&cli.database, &cli.output, *reports)?;
         }
-        Commands::Query { category, entity, show_html, limit } => {
-            query_entities(&cli.database, category.as_deref(), entity.as_deref(), *show_html, *limit)?;
+        Commands::Query { category, entity, show_html, limit, query, format } => {
+            match query {
+                Some(query) => run_query(&cli.database, category.as_deref(), query, format, *limit)?,
+                None => query_entities(&cli.database, category.as_deref(), entity.as_deref(), *show_html, *limit)?,
+            }
         }
         Commands::InspectHtml { uuid, verbose } => {
             inspect_html_processing(&cli.database, uuid, *verbose)?;
@@ -109,6 +161,12 @@ fn main() -> Result<()> {
         Commands::RefineCategories { test_rules, apply } => {
             refine_categorization_rules(&cli.database, &cli.output, *test_rules, *apply)?;
         }
+        Commands::Search { query, mentions, pools, rebuild, limit } => {
+            search_entities(&cli.database, &cli.output, query.as_deref(), mentions.as_deref(), pools.as_ref(), *rebuild, *limit)?;
+        }
+        Commands::Label { training_dir, limit } => {
+            label_entities(&cli.database, training_dir, *limit)?;
+        }
         Commands::Export { format, category } => {
             export_entities(&cli.database, &cli.output, format, category.as_deref())?;
         }
@@ -136,6 +194,9 @@ fn analyze_all_entities(database_path: &PathBuf, output_dir: &PathBuf, generate_
         // Use enhanced categorization with training data
         let (mut entities, training_repo) = RawEntities::new_with_training(&training_dir)?;
         println!("✅ Loaded {} training examples from TOML files", training_repo.total_examples());
+        for (path, reason) in &training_repo.skipped {
+            println!("⚠️ Skipping training file {}: {}", path.display(), reason);
+        }
         
         // Load entities from database using enhanced categorization
         entities.load_from_hbf_database_with_training(database_path, &training_repo)?;
@@ -186,8 +247,14 @@ fn analyze_all_entities(database_path: &PathBuf, output_dir: &PathBuf, generate_
             &raw_entities.factions,
             &raw_entities.dungeons,
             &raw_entities.uncategorized,
+            &raw_entities.low_confidence,
             &reports_dir,
         )?;
+        if let Some(evaluation) = &raw_entities.evaluation {
+            evaluation.write_csv(&reports_dir.join("confusion_matrix.csv"))?;
+            println!("🎯 Classifier cross-validated accuracy: {:.1}% over {} examples",
+                     evaluation.accuracy() * 100.0, evaluation.total());
+        }
         println!("✅ Reports generated in: {}", reports_dir.display());
     }
     
@@ -248,6 +315,40 @@ fn query_entities(
     Ok(())
 }
 
+fn run_query(
+    database_path: &PathBuf,
+    category: Option<&str>,
+    query: &str,
+    format: &str,
+    limit: usize,
+) -> Result<()> {
+    let mut query: EntityQuery = query.parse()?;
+    if let Some(category) = category {
+        query.filters.extend(format!("category:{}", category).parse::<EntityQuery>()?.filters);
+    }
+    let format: OutputFormat = format.parse()?;
+    
+    let training_dir = std::env::current_dir()?.join("training_data");
+    let raw_entities = if training_dir.exists() {
+        let (mut entities, training_repo) = RawEntities::new_with_training(&training_dir)?;
+        entities.load_from_hbf_database_with_training(database_path, &training_repo)?;
+        entities
+    } else {
+        let mut entities = RawEntities::new();
+        entities.load_from_hbf_database(database_path)?;
+        entities
+    };
+    
+    let engine = QueryEngine::new(&raw_entities)?;
+    let records = engine.run(&query);
+    println!("🔍 {} matching entities{}", records.len(),
+             if records.len() > limit { format!(", showing {}", limit) } else { String::new() });
+    let shown: Vec<_> = records.into_iter().take(limit).collect();
+    println!("{}", render_records(&shown, format)?);
+    
+    Ok(())
+}
+
 fn query_category(
     category_data: &std::collections::HashMap<String, Vec<RawEntity>>,
     entity_name: Option<&str>,
@@ -399,12 +500,194 @@ fn refine_categorization_rules(
     
     if apply {
         println!("⚠️ Apply functionality not yet implemented");
-        println!("   This would update categorization rules and re-process database");
+        println!("   Use the 'label' command to record corrections as training examples");
+    }
+    
+    Ok(())
+}
+
+fn search_entities(
+    database_path: &PathBuf,
+    output_dir: &PathBuf,
+    query: Option<&str>,
+    mentions: Option<&str>,
+    pools: Option<&PathBuf>,
+    rebuild: bool,
+    limit: usize,
+) -> Result<()> {
+    let index = load_search_index(database_path, output_dir, pools, rebuild)?;
+    
+    let hits = match (mentions, query) {
+        (Some(uuid), _) => {
+            let (name, hits) = index.mentions_of(uuid, limit)?;
+            println!("🔎 Mentions of '{}' ({})", name, uuid);
+            hits
+        }
+        (None, Some(query)) => {
+            println!("🔎 Searching for: {}", query);
+            index.search(query, limit)
+        }
+        (None, None) => {
+            println!("❌ Give search terms or --mentions <UUID>");
+            return Ok(());
+        }
+    };
+    
+    if hits.is_empty() {
+        println!("No matches");
+    }
+    for (i, hit) in hits.iter().enumerate() {
+        println!("  {}. [{:.2}] {} ({}, {})", i + 1, hit.score, hit.title, hit.category, hit.id);
+        println!("     {}", hit.snippet);
     }
     
     Ok(())
 }
 
+/// The saved index if it is newer than the database, else a fresh one
+fn load_search_index(
+    database_path: &PathBuf,
+    output_dir: &PathBuf,
+    pools: Option<&PathBuf>,
+    rebuild: bool,
+) -> Result<SearchIndex> {
+    let index_path = output_dir.join(SEARCH_INDEX_FILE);
+    let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let current = match (modified(&index_path), modified(database_path)) {
+        (Some(index_time), Some(database_time)) => index_time >= database_time,
+        _ => false,
+    };
+    if current && !rebuild && pools.is_none() {
+        let index = SearchIndex::load(&index_path)?;
+        println!("📚 Loaded search index of {} documents", index.len());
+        return Ok(index);
+    }
+    
+    println!("🔨 Building search index...");
+    let training_dir = std::env::current_dir()?.join("training_data");
+    let entities = if training_dir.exists() {
+        let (mut entities, training_repo) = RawEntities::new_with_training(&training_dir)?;
+        entities.load_from_hbf_database_with_training(database_path, &training_repo)?;
+        entities
+    } else {
+        let mut entities = RawEntities::new();
+        entities.load_from_hbf_database(database_path)?;
+        entities
+    };
+    let mut index = SearchIndex::from_entities(&entities)?;
+    if let Some(pools_dir) = pools {
+        index.add_seed_pools(&CategorizedDataPools::load_from_dir(pools_dir)?)?;
+    }
+    index.save(&index_path)?;
+    println!("✅ Indexed {} documents into {}", index.len(), index_path.display());
+    Ok(index)
+}
+
+fn label_entities(database_path: &PathBuf, training_dir: &PathBuf, limit: usize) -> Result<()> {
+    println!("🏷️ Labelling entities into {}", training_dir.display());
+    if !training_dir.exists() {
+        println!("❌ Training data directory not found: {}", training_dir.display());
+        return Ok(());
+    }
+    
+    let (mut raw_entities, training_repo) = RawEntities::new_with_training(training_dir)?;
+    raw_entities.load_from_hbf_database_with_training(database_path, &training_repo)?;
+    let accuracy_before = raw_entities.evaluation.as_ref().map_or(0.0, |e| e.accuracy());
+    let uncategorized_before = raw_entities.uncategorized.len();
+    
+    let labeller = Labeller::new(training_dir)?;
+    let candidates = label_candidates(&raw_entities);
+    let count = candidates.len().min(limit);
+    println!("📋 {} uncategorized entities, presenting {}", candidates.len(), count);
+    let categories: Vec<String> = EntityCategory::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, category)| format!("{}={}", i + 1, category.as_str()))
+        .collect();
+    println!("   Categories: {}", categories.join(" "));
+    
+    let mut labelled = 0;
+    'entities: for (index, candidate) in candidates.iter().take(count).enumerate() {
+        let entity = candidate.entity;
+        println!("\n━━━ [{}/{}] {} ({})", index + 1, count, entity.entity_name, entity.uuid);
+        if let Some(classification) = candidate.classification {
+            let guesses: Vec<String> = classification
+                .scores
+                .iter()
+                .take(3)
+                .map(|(category, score)| format!("{} {:.0}%", category.as_str(), score * 100.0))
+                .collect();
+            println!("🤔 Best guesses: {}", guesses.join(", "));
+        }
+        let text = labeller.render_text(&entity.raw_value);
+        for line in text.lines().take(LABEL_PREVIEW_LINES) {
+            println!("   {}", line);
+        }
+        if text.lines().count() > LABEL_PREVIEW_LINES {
+            println!("   ...");
+        }
+        
+        let category = loop {
+            let Some(answer) = prompt("Category (number or name, Enter to skip, q to quit)")? else {
+                break 'entities;
+            };
+            match answer.as_str() {
+                "" => continue 'entities,
+                "q" => break 'entities,
+                _ => {}
+            }
+            let by_number = answer.parse::<usize>().ok().and_then(|n| EntityCategory::ALL.get(n.wrapping_sub(1)));
+            match by_number.copied().or_else(|| EntityCategory::parse(&answer)) {
+                Some(category) => break category,
+                None => println!("❌ Unknown category: {}", answer),
+            }
+        };
+        let markers = split_list(&prompt("Markers (comma separated)")?.unwrap_or_default());
+        let patterns = split_list(&prompt("Content patterns (comma separated)")?.unwrap_or_default());
+        let theme = prompt("Horror theme")?.filter(|theme| !theme.is_empty()).unwrap_or_else(|| "none".to_string());
+        
+        let example = labeller.example(entity, markers, patterns, theme);
+        let path = labeller.record(&training_repo, category, &example)?;
+        println!("✅ Added {} to {}", category.as_str(), path.display());
+        labelled += 1;
+    }
+    
+    if labelled == 0 {
+        println!("No labels recorded");
+        return Ok(());
+    }
+    
+    println!("\n🔄 Retraining with {} new labels...", labelled);
+    let (mut raw_entities, training_repo) = RawEntities::new_with_training(training_dir)?;
+    raw_entities.load_from_hbf_database_with_training(database_path, &training_repo)?;
+    let accuracy_after = raw_entities.evaluation.as_ref().map_or(0.0, |e| e.accuracy());
+    println!("📈 Cross-validated accuracy: {:.1}% -> {:.1}% ({} training examples)",
+             accuracy_before * 100.0, accuracy_after * 100.0, training_repo.total_examples());
+    println!("📉 Uncategorized: {} -> {}", uncategorized_before, raw_entities.uncategorized.len());
+    
+    Ok(())
+}
+
+/// Read one trimmed line after a prompt; `None` at end of input
+fn prompt(label: &str) -> Result<Option<String>> {
+    print!("{}: ", label);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+fn split_list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 fn analyze_uncategorized_patterns(uncategorized: &[RawEntity]) {
     let mut content_patterns = std::collections::HashMap::new();
     
@@ -457,6 +740,7 @@ fn export_entities(
                 &raw_entities.factions,
                 &raw_entities.dungeons,
                 &raw_entities.uncategorized,
+                &raw_entities.low_confidence,
                 &output_dir.join("csv_export"),
             )?;
         }
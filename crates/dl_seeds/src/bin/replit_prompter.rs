@@ -2,16 +2,22 @@
 //! 
 //! Creates markdown files with recommended 3D model prompts and Yarnspinner
 //! dialogue templates for Replit AI to generate GLB models and narrative content.
+//! Prompts are rendered from Tera templates that `--templates` can override
+//! per faction and category, and can also be exported as JSONL and CSV packs.
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use dl_seeds::{
     containers::RawEntity,
     orchestration::RawEntities,
+    query::{EntityQuery, QueryEngine},
+    prompts::{
+        CorruptionProgression, DialoguePrompt, ModelPrompt, PackFormat, PromptPack, PromptTemplates, TechnicalSpecs,
+    },
     utilities::sanitize_name,
     books::{WorldSeed, QuestSeed, DialogueSeed},
 };
-use serde::{Deserialize, Serialize};
+use dl_types::world::{AssetManifest, ASSET_MANIFEST_FILE};
 use std::path::PathBuf;
 use std::collections::HashMap;
 
@@ -32,6 +38,15 @@ struct Cli {
     #[arg(short, long)]
     output: PathBuf,
     
+    /// Directory of `.tera` templates overriding the built-in prompts,
+    /// optionally in `<faction>/` and `<category>/` subdirectories
+    #[arg(long)]
+    templates: Option<PathBuf>,
+    
+    /// Prompt pack formats, comma separated (markdown, jsonl, csv)
+    #[arg(long, value_delimiter = ',', default_value = "markdown")]
+    format: Vec<String>,
+    
     #[command(subcommand)]
     command: Commands,
 }
@@ -53,6 +68,10 @@ enum Commands {
         /// Specific faction/cult
         #[arg(short, long)]
         faction: Option<String>,
+        
+        /// Only prompt for entities matching this query (see hbf-analyzer query)
+        #[arg(short, long)]
+        query: Option<String>,
     },
     /// Generate Yarnspinner dialogue prompts
     Dialogue {
@@ -68,49 +87,10 @@ enum Commands {
     },
 }
 
-/// Prompt template for 3D model generation
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ModelPromptTemplate {
-    model_id: String,
-    display_name: String,
-    category: String,
-    faction: String,
-    primary_prompt: String,
-    style_prompt: String,
-    technical_specs: TechnicalSpecs,
-    corruption_progression: Option<CorruptionProgression>,
-    reference_images: Vec<String>,
-    animation_requirements: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct TechnicalSpecs {
-    target_poly_count: String,
-    texture_resolution: String,
-    required_animations: Vec<String>,
-    bone_structure: String,
-    material_requirements: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct CorruptionProgression {
-    band: u8,
-    theme: String,
-    visual_evolution: String,
-    material_changes: String,
-}
-
-/// Dialogue prompt template for Yarnspinner
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct DialoguePromptTemplate {
-    character_id: String,
-    character_name: String,
-    role: String,
-    personality_prompt: String,
-    trauma_indicators: Vec<String>,
-    speech_patterns: Vec<String>,
-    sample_interactions: Vec<String>,
-    corruption_evolution: Option<String>,
+/// How prompt packs are rendered and exported
+struct PackOptions<'a> {
+    templates: &'a PromptTemplates,
+    formats: &'a [PackFormat],
 }
 
 fn main() -> Result<()> {
@@ -124,15 +104,26 @@ fn main() -> Result<()> {
     // Ensure output directory exists
     std::fs::create_dir_all(&cli.output)?;
     
+    let templates = match &cli.templates {
+        Some(dir) => {
+            let templates = PromptTemplates::with_overrides(dir)?;
+            println!("📄 Templates: {} ({} overrides)", dir.display(), templates.overrides().len());
+            templates
+        }
+        None => PromptTemplates::new()?,
+    };
+    let formats = cli.format.iter().map(|format| format.parse()).collect::<Result<Vec<PackFormat>>>()?;
+    let pack = PackOptions { templates: &templates, formats: &formats };
+    
     match &cli.command {
         Commands::GenerateAll { corruption_themes } => {
-            generate_all_prompts(&cli.input, &cli.assets, &cli.output, *corruption_themes)?;
+            generate_all_prompts(&cli.input, &cli.assets, &cli.output, &pack, *corruption_themes)?;
         }
-        Commands::Models { category, faction } => {
-            generate_model_prompts(&cli.input, &cli.assets, &cli.output, category.as_deref(), faction.as_deref())?;
+        Commands::Models { category, faction, query } => {
+            generate_model_prompts(&cli.input, &cli.assets, &cli.output, &pack, category.as_deref(), faction.as_deref(), query.as_deref())?;
         }
         Commands::Dialogue { companion_trauma } => {
-            generate_dialogue_prompts(&cli.input, &cli.output, *companion_trauma)?;
+            generate_dialogue_prompts(&cli.input, &cli.output, &pack, *companion_trauma)?;
         }
         Commands::Progressions { visual_guides } => {
             generate_progression_docs(&cli.assets, &cli.output, *visual_guides)?;
@@ -146,15 +137,16 @@ fn generate_all_prompts(
     input_dir: &PathBuf,
     assets_dir: &PathBuf,
     output_dir: &PathBuf,
+    pack: &PackOptions,
     corruption_themes: bool,
 ) -> Result<()> {
     println!("🔄 Generating all Replit prompt templates...");
     
     // Generate 3D model prompts
-    generate_model_prompts(input_dir, assets_dir, output_dir, None, None)?;
+    generate_model_prompts(input_dir, assets_dir, output_dir, pack, None, None, None)?;
     
     // Generate dialogue prompts
-    generate_dialogue_prompts(input_dir, output_dir, corruption_themes)?;
+    generate_dialogue_prompts(input_dir, output_dir, pack, corruption_themes)?;
     
     // Generate progression documentation
     generate_progression_docs(assets_dir, output_dir, true)?;
@@ -170,22 +162,38 @@ fn generate_model_prompts(
     input_dir: &PathBuf,
     assets_dir: &PathBuf,
     output_dir: &PathBuf,
+    pack: &PackOptions,
     category_filter: Option<&str>,
     faction_filter: Option<&str>,
+    query: Option<&str>,
 ) -> Result<()> {
     println!("🎨 Generating 3D model prompts...");
     
-    let models_dir = output_dir.join("model_prompts");
-    std::fs::create_dir_all(&models_dir)?;
-    
-    // Load analyzed entities
-    let entities = load_analyzed_entities(input_dir)?;
+    // Load analyzed entities, narrowed to the query's selection if given
+    let mut entities = load_analyzed_entities(input_dir)?;
+    if let Some(query) = query {
+        let query: EntityQuery = query.parse()?;
+        let selected = QueryEngine::new(&entities)?.select(&query);
+        entities = selected;
+        println!("  Query selected {} entities", entities.total_entities);
+    }
     
     // Load existing RON metadata to enhance prompts with asset specifications
     let ron_metadata = load_ron_metadata_from_assets(assets_dir)?;
     println!("  Loaded {} RON metadata files for prompt enhancement", ron_metadata.len());
     
-    // Generate prompts for each faction
+    // Only prompt for entities whose models the manifest lists as missing or stale
+    let manifest_path = assets_dir.join(ASSET_MANIFEST_FILE);
+    let manifest: Option<AssetManifest> = if manifest_path.exists() {
+        Some(ron::from_str(&std::fs::read_to_string(&manifest_path)?)?)
+    } else {
+        println!("  No asset manifest in {}; prompting for every model", assets_dir.display());
+        None
+    };
+    let mut up_to_date = 0;
+    
+    // Collect prompts for each faction
+    let mut prompts = PromptPack::default();
     for (faction_name, faction_entities) in &entities.factions {
         if let Some(filter) = faction_filter {
             if faction_name != filter {
@@ -193,9 +201,7 @@ fn generate_model_prompts(
             }
         }
         
-        let faction_dir = models_dir.join(sanitize_name(faction_name));
-        std::fs::create_dir_all(&faction_dir)?;
-        
+        let mut entity_count = 0;
         for entity in faction_entities {
             // Check if we should skip this entity based on category filter
             if let Some(cat_filter) = category_filter {
@@ -204,36 +210,40 @@ fn generate_model_prompts(
                     continue;
                 }
             }
+            if manifest.as_ref().is_some_and(|manifest| !manifest.source_needs_asset(&entity.uuid)) {
+                up_to_date += 1;
+                continue;
+            }
             
-            let prompt_template = create_model_prompt_from_entity_enhanced(
+            prompts.push(create_model_prompt_from_entity_enhanced(
                 entity, 
                 faction_name, 
+                pack.templates,
                 &ron_metadata
-            );
-            let markdown_content = render_model_prompt_to_markdown(&prompt_template);
-            
-            let filename = format!("{}_prompt.md", sanitize_name(&entity.entity_name));
-            std::fs::write(faction_dir.join(filename), markdown_content)?;
+            )?);
+            entity_count += 1;
         }
         
-        let entity_count = if category_filter.is_some() {
-            faction_entities.iter().filter(|e| {
-                let entity_category = determine_entity_category(&e.raw_value);
-                entity_category == category_filter.unwrap()
-            }).count()
-        } else {
-            faction_entities.len()
-        };
-        
         if entity_count > 0 {
             println!("  Generated {} model prompts for: {}", entity_count, faction_name);
         }
     }
     
+    prompts.finish();
+    let written = prompts.write(pack.templates, output_dir, pack.formats)?;
+    println!("  Wrote {} prompts to {} files", prompts.len(), written.len());
+    
+    if up_to_date > 0 {
+        println!("  Skipped {} entities whose models are present and current", up_to_date);
+    }
     Ok(())
 }
 
-fn create_model_prompt_from_entity(entity: &RawEntity, faction: &str) -> ModelPromptTemplate {
+fn create_model_prompt_from_entity(
+    entity: &RawEntity,
+    faction: &str,
+    templates: &PromptTemplates,
+) -> Result<ModelPrompt> {
     let content = &entity.raw_value;
     let content_lower = content.to_lowercase();
     
@@ -262,20 +272,23 @@ fn create_model_prompt_from_entity(entity: &RawEntity, faction: &str) -> ModelPr
         extract_description_from_content(content)
     );
     
-    let style_prompt = create_faction_style_prompt(faction, &content_lower);
+    // Budgets scale with the corruption band the model is drawn at
+    let corruption_progression = create_corruption_progression(content);
+    let band = corruption_progression.as_ref().map_or(1, |progression| progression.band);
     
-    ModelPromptTemplate {
-        model_id: sanitize_name(&entity.entity_name),
+    Ok(ModelPrompt {
+        id: String::new(),
+        source_uuid: entity.uuid.clone(),
         display_name: entity.entity_name.clone(),
         category: category.to_string(),
         faction: faction.to_string(),
         primary_prompt,
-        style_prompt,
-        technical_specs: create_technical_specs(category),
-        corruption_progression: create_corruption_progression(content),
-        reference_images: create_reference_suggestions(faction, category),
+        style_prompt: templates.style_prompt(faction, category, content)?,
+        technical_specs: TechnicalSpecs::for_category(category, band),
+        corruption_progression,
+        reference_images: templates.reference_images(faction, category)?,
         animation_requirements: extract_animation_requirements(content),
-    }
+    })
 }
 
 fn extract_description_from_content(content: &str) -> String {
@@ -295,105 +308,6 @@ fn extract_description_from_content(content: &str) -> String {
     }
 }
 
-fn create_faction_style_prompt(faction: &str, content: &str) -> String {
-    let base_style = match faction.to_lowercase().as_str() {
-        f if f.contains("crimson") || f.contains("blood") => {
-            "Crimson Covenant aesthetic: Deep reds, blood-infused materials, bone decorations, \
-            ritualistic scarification, flowing robes with crimson trim, copper and bronze metals \
-            with blood-red patina. Emphasize organic, flowing forms with sharp ritual implements."
-        }
-        f if f.contains("deep") || f.contains("water") || f.contains("ocean") => {
-            "Order of the Deep aesthetic: Deep blues and greens, aquatic textures, scaled surfaces, \
-            coral-like growths, barnacle encrustation, tarnished silver and copper. Emphasize \
-            flowing, water-influenced forms with bioluminescent accents."
-        }
-        f if f.contains("void") || f.contains("shadow") || f.contains("dark") => {
-            "Void Seekers aesthetic: Deep purples and blacks, crystalline void structures, \
-            geometric impossibilities, reality-warping materials, obsidian and dark crystals. \
-            Emphasize angular, geometric forms with void-touched corruption."
-        }
-        _ => {
-            "Generic horror aesthetic: Muted colors progressing to darkness, weathered materials, \
-            asymmetrical design elements, non-Euclidean geometry hints. Focus on unsettling \
-            proportions and otherworldly details."
-        }
-    };
-    
-    // Add content-specific style elements
-    let content_additions = if content.contains("priest") || content.contains("ritual") {
-        " Include ceremonial elements, religious iconography, and ritual implements."
-    } else if content.contains("warrior") || content.contains("combat") {
-        " Include battle-worn armor, weapon integration, and combat readiness."
-    } else if content.contains("building") || content.contains("structure") {
-        " Include architectural elements that suggest both function and cosmic significance."
-    } else {
-        " Include subtle horror elements that suggest cosmic awareness."
-    };
-    
-    format!("{}{}", base_style, content_additions)
-}
-
-fn create_technical_specs(category: &str) -> TechnicalSpecs {
-    match category {
-        "warrior_unit" | "priest_unit" | "cultist_unit" => TechnicalSpecs {
-            target_poly_count: "2000-5000 triangles".to_string(),
-            texture_resolution: "1024x1024 diffuse, normal, roughness".to_string(),
-            required_animations: vec![
-                "idle".to_string(),
-                "walk".to_string(),
-                "attack".to_string(),
-                "death".to_string(),
-            ],
-            bone_structure: "Humanoid rig with 20-30 bones".to_string(),
-            material_requirements: vec![
-                "PBR materials".to_string(),
-                "Faction-specific textures".to_string(),
-                "Wear/corruption details".to_string(),
-            ],
-        },
-        "leader_unit" => TechnicalSpecs {
-            target_poly_count: "5000-8000 triangles".to_string(),
-            texture_resolution: "2048x2048 diffuse, normal, roughness, emission".to_string(),
-            required_animations: vec![
-                "idle".to_string(),
-                "walk".to_string(),
-                "attack".to_string(),
-                "cast_spell".to_string(),
-                "command".to_string(),
-                "death".to_string(),
-            ],
-            bone_structure: "Advanced humanoid rig with 35-50 bones".to_string(),
-            material_requirements: vec![
-                "Hero-quality PBR materials".to_string(),
-                "Emissive effects".to_string(),
-                "Advanced shader features".to_string(),
-            ],
-        },
-        "building" => TechnicalSpecs {
-            target_poly_count: "3000-10000 triangles".to_string(),
-            texture_resolution: "2048x2048 tileable textures".to_string(),
-            required_animations: vec![
-                "idle".to_string(),
-                "construction".to_string(),
-                "destruction".to_string(),
-            ],
-            bone_structure: "Static mesh or simple destructible segments".to_string(),
-            material_requirements: vec![
-                "Architectural PBR materials".to_string(),
-                "Weathering and age details".to_string(),
-                "Faction-specific decorations".to_string(),
-            ],
-        },
-        _ => TechnicalSpecs {
-            target_poly_count: "1000-3000 triangles".to_string(),
-            texture_resolution: "512x512 basic textures".to_string(),
-            required_animations: vec!["idle".to_string()],
-            bone_structure: "Simple or static mesh".to_string(),
-            material_requirements: vec!["Basic PBR materials".to_string()],
-        },
-    }
-}
-
 fn create_corruption_progression(content: &str) -> Option<CorruptionProgression> {
     let content_lower = content.to_lowercase();
     
@@ -448,62 +362,6 @@ fn create_corruption_progression(content: &str) -> Option<CorruptionProgression>
     })
 }
 
-fn create_reference_suggestions(faction: &str, category: &str) -> Vec<String> {
-    let mut references = Vec::new();
-    
-    // Faction-specific references
-    match faction.to_lowercase().as_str() {
-        f if f.contains("crimson") || f.contains("blood") => {
-            references.extend(vec![
-                "Bloodborne character designs".to_string(),
-                "Dark Souls 3 cathedral knights".to_string(),
-                "Warhammer Khorne aesthetics".to_string(),
-                "Medieval inquisition imagery".to_string(),
-            ]);
-        }
-        f if f.contains("deep") || f.contains("water") => {
-            references.extend(vec![
-                "Lovecraft Deep Ones concept art".to_string(),
-                "Bioshock underwater aesthetics".to_string(),
-                "Subnautica leviathan designs".to_string(),
-                "Call of Cthulhu aquatic horrors".to_string(),
-            ]);
-        }
-        f if f.contains("void") || f.contains("shadow") => {
-            references.extend(vec![
-                "Event Horizon spacecraft design".to_string(),
-                "Warhammer 40k Chaos aesthetics".to_string(),
-                "Dead Space necromorph designs".to_string(),
-                "Lovecraft void imagery".to_string(),
-            ]);
-        }
-        _ => {
-            references.push("Generic Lovecraftian horror".to_string());
-        }
-    }
-    
-    // Category-specific references
-    match category {
-        "building" => {
-            references.extend(vec![
-                "Gothic cathedral architecture".to_string(),
-                "Ancient temple ruins".to_string(),
-                "Eldritch architectural impossibilities".to_string(),
-            ]);
-        }
-        "leader_unit" => {
-            references.extend(vec![
-                "Dark fantasy leader designs".to_string(),
-                "Cult master aesthetics".to_string(),
-                "Imposing horror antagonists".to_string(),
-            ]);
-        }
-        _ => {}
-    }
-    
-    references
-}
-
 fn extract_animation_requirements(content: &str) -> Vec<String> {
     let mut requirements = vec![
         "Smooth idle animation with subtle breathing/sway".to_string(),
@@ -529,109 +387,34 @@ fn extract_animation_requirements(content: &str) -> Vec<String> {
     requirements
 }
 
-fn render_model_prompt_to_markdown(template: &ModelPromptTemplate) -> String {
-    format!(r#"# 3D Model Generation Prompt: {}
-
-## Model Overview
-- **ID**: `{}`
-- **Category**: {}
-- **Faction**: {}
-
-## Primary Generation Prompt
-```
-{}
-```
-
-## Style Guidelines
-```
-{}
-```
-
-## Technical Specifications
-- **Target Poly Count**: {}
-- **Texture Resolution**: {}
-- **Bone Structure**: {}
-
-### Required Animations
-{}
-
-### Material Requirements
-{}
-
-## Corruption Progression
-{}
-
-## Reference Images/Concepts
-{}
-
-## Animation Requirements
-{}
-
----
-*Generated by Dragon's Labyrinth Replit Prompter*
-*Use this template with Replit's 3D model generation capabilities*
-"#,
-        template.display_name,
-        template.model_id,
-        template.category,
-        template.faction,
-        template.primary_prompt,
-        template.style_prompt,
-        template.technical_specs.target_poly_count,
-        template.technical_specs.texture_resolution,
-        template.technical_specs.bone_structure,
-        template.technical_specs.required_animations.iter()
-            .map(|a| format!("- {}", a))
-            .collect::<Vec<_>>()
-            .join("\n"),
-        template.technical_specs.material_requirements.iter()
-            .map(|m| format!("- {}", m))
-            .collect::<Vec<_>>()
-            .join("\n"),
-        template.corruption_progression.as_ref()
-            .map(|cp| format!("**Band {}**: {} - {}", cp.band, cp.theme, cp.visual_evolution))
-            .unwrap_or_else(|| "Standard progression".to_string()),
-        template.reference_images.iter()
-            .map(|r| format!("- {}", r))
-            .collect::<Vec<_>>()
-            .join("\n"),
-        template.animation_requirements.iter()
-            .map(|a| format!("- {}", a))
-            .collect::<Vec<_>>()
-            .join("\n")
-    )
-}
-
 fn generate_dialogue_prompts(
     input_dir: &PathBuf,
     output_dir: &PathBuf,
+    pack: &PackOptions,
     companion_trauma: bool,
 ) -> Result<()> {
     println!("💬 Generating dialogue prompts...");
     
-    let dialogue_dir = output_dir.join("dialogue_prompts");
-    std::fs::create_dir_all(&dialogue_dir)?;
-    
     // Load analyzed entities
     let entities = load_analyzed_entities(input_dir)?;
     
-    // Generate dialogue prompts for characters
+    // Collect dialogue prompts for characters
+    let mut prompts = PromptPack::default();
     for (faction_name, faction_entities) in &entities.factions {
         for entity in faction_entities {
             if is_dialogue_character(entity) {
-                let dialogue_template = create_dialogue_prompt_from_entity(
+                prompts.push(create_dialogue_prompt_from_entity(
                     entity, 
                     faction_name, 
                     companion_trauma
-                );
-                let markdown_content = render_dialogue_prompt_to_markdown(&dialogue_template);
-                
-                let filename = format!("{}_dialogue.md", sanitize_name(&entity.entity_name));
-                std::fs::write(dialogue_dir.join(filename), markdown_content)?;
+                ));
             }
         }
     }
     
+    prompts.finish();
+    let written = prompts.write(pack.templates, output_dir, pack.formats)?;
+    println!("  Wrote {} dialogue prompts to {} files", prompts.len(), written.len());
     Ok(())
 }
 
@@ -650,7 +433,7 @@ fn create_dialogue_prompt_from_entity(
     entity: &RawEntity,
     faction: &str,
     companion_trauma: bool,
-) -> DialoguePromptTemplate {
+) -> DialoguePrompt {
     let content = &entity.raw_value;
     let role = determine_character_role(content);
     
@@ -670,9 +453,11 @@ fn create_dialogue_prompt_from_entity(
         vec!["Standard psychological progression".to_string()]
     };
     
-    DialoguePromptTemplate {
-        character_id: sanitize_name(&entity.entity_name),
+    DialoguePrompt {
+        id: String::new(),
+        source_uuid: entity.uuid.clone(),
         character_name: entity.entity_name.clone(),
+        faction: faction.to_string(),
         role: role.clone(),
         personality_prompt,
         trauma_indicators,
@@ -793,61 +578,6 @@ fn create_corruption_dialogue_evolution(faction: &str) -> String {
     )
 }
 
-fn render_dialogue_prompt_to_markdown(template: &DialoguePromptTemplate) -> String {
-    format!(r#"# Dialogue Generation Prompt: {}
-
-## Character Overview
-- **ID**: `{}`
-- **Role**: {}
-
-## Personality Prompt
-```
-{}
-```
-
-## Trauma Indicators (Dragon's Labyrinth Integration)
-{}
-
-## Speech Patterns
-{}
-
-## Sample Interaction Types
-{}
-
-## Corruption Evolution
-{}
-
-## Yarnspinner Integration Notes
-- Create `.yarn` files with branching dialogue trees
-- Include trauma state variables for dynamic responses
-- Implement corruption level checks for dialogue variations
-- Add faction-specific terminology and references
-
----
-*Generated by Dragon's Labyrinth Replit Prompter*
-*Use this template for Yarnspinner dialogue generation*
-"#,
-        template.character_name,
-        template.character_id,
-        template.role,
-        template.personality_prompt,
-        template.trauma_indicators.iter()
-            .map(|t| format!("- {}", t))
-            .collect::<Vec<_>>()
-            .join("\n"),
-        template.speech_patterns.iter()
-            .map(|p| format!("- {}", p))
-            .collect::<Vec<_>>()
-            .join("\n"),
-        template.sample_interactions.iter()
-            .map(|i| format!("- {}", i))
-            .collect::<Vec<_>>()
-            .join("\n"),
-        template.corruption_evolution.as_ref()
-            .unwrap_or(&"Standard character development".to_string())
-    )
-}
-
 fn generate_progression_docs(
     assets_dir: &PathBuf,
     output_dir: &PathBuf,
@@ -1044,10 +774,14 @@ This directory contains comprehensive prompt templates for Replit's 3D model and
 ```
 replit_prompts/
 ├── model_prompts/           # 3D model generation prompts
-│   ├── {faction_name}/      # Organized by faction
-│   │   └── {entity}_prompt.md
-├── dialogue_prompts/        # Yarnspinner dialogue prompts
-│   └── {character}_dialogue.md
+│   ├── index.md             # Every prompt by stable ID, with budgets
+│   ├── prompt_pack.jsonl    # With --format jsonl, for batch generation
+│   ├── prompt_pack.csv      # With --format csv
+│   └── {faction_name}/      # Organized by faction
+│       └── {entity}_prompt.md
+├── dialogue_prompts/        # Yarnspinner dialogue prompts, same layout
+│   └── {faction_name}/
+│       └── {character}_dialogue.md
 └── progression_guides/      # Upgrade progression documentation
     ├── upgrade_progressions.md
     └── visual_progression_guide.md
@@ -1063,12 +797,24 @@ replit_prompts/
 5. Follow the style guidelines for faction consistency
 
 ### Dialogue Generation
-1. Open the relevant `dialogue_prompts/{character}_dialogue.md`
+1. Open the relevant `dialogue_prompts/{faction}/{character}_dialogue.md`
 2. Use the personality prompt as the base for character voice
 3. Create Yarnspinner `.yarn` files with the suggested interaction types
 4. Include trauma indicators for dynamic dialogue progression
 5. Implement corruption evolution for character development
 
+### Batch Generation
+Run with `--format markdown,jsonl,csv` to also write `prompt_pack.jsonl` and
+`prompt_pack.csv`. Each record carries the prompt's stable ID, source UUID,
+corruption band and poly/texture budget. IDs only change when an entity is
+renamed, so batch results can be matched back to prompts across runs.
+
+### Custom Templates
+Pass `--templates <dir>` to override the built-in Tera templates
+(`model.tera`, `dialogue.tera`, `style.tera`, `references.tera`). Place a
+template in `{faction}/`, `{category}/` or `{faction}/{category}/` to
+override it for that faction or category only.
+
 ## Integration Features
 
 ### Dragon's Labyrinth Integration
@@ -1141,9 +887,10 @@ fn determine_entity_category(content: &str) -> String {
 fn create_model_prompt_from_entity_enhanced(
     entity: &RawEntity,
     faction: &str,
+    templates: &PromptTemplates,
     ron_metadata: &HashMap<String, String>,
-) -> ModelPromptTemplate {
-    let mut template = create_model_prompt_from_entity(entity, faction);
+) -> Result<ModelPrompt> {
+    let mut template = create_model_prompt_from_entity(entity, faction, templates)?;
     
     // Enhance template with RON metadata if available
     let entity_key = sanitize_name(&entity.entity_name);
@@ -1169,7 +916,7 @@ fn create_model_prompt_from_entity_enhanced(
         }
     }
     
-    template
+    Ok(template)
 }
 
 fn create_upgrade_progression_guide_with_assets(assets_dir: &PathBuf) -> Result<String> {
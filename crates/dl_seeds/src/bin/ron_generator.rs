@@ -7,12 +7,24 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use dl_seeds::{
     containers::RawEntity,
+    dungeon_data::{build_dungeon_layouts, write_dungeon_layouts},
+    faction_data::{build_faction_database, write_faction_database},
+    items::{build_item_database, write_item_database},
+    npc_data::{build_npc_database, write_npc_database},
     orchestration::RawEntities,
+    query::{EntityQuery, QueryEngine},
+    settlement_data::{build_settlement_database, write_settlement_database},
+    upgrades::UpgradeGraph,
     utilities::{determine_biome_type, sanitize_name},
 };
+use dl_types::world::{
+    validate_model_set, AssetManifest, AssetStatus, ManifestEntry, ModelBounds, ModelKind, ModelMetadata, ModelSocket,
+    SoundEvent, ASSET_MANIFEST_FILE, MODEL_FILE_EXTENSIONS, MODEL_METADATA_EXTENSION, MODEL_METADATA_VERSION,
+};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use walkdir::WalkDir;
 
 #[derive(Parser)]
 #[command(name = "ron-generator")]
@@ -41,19 +53,43 @@ enum Commands {
     },
     /// Generate specific asset category
     Generate {
-        /// Category to generate (units, buildings, effects, terrain, leaders)
+        /// Category to generate (units, buildings, effects, terrain, leaders, items, settlements, factions, npcs, dungeons)
         category: String,
         
         /// Specific faction/cult to generate for
         #[arg(short, long)]
         faction: Option<String>,
+        
+        /// Only generate for entities matching this query (see hbf-analyzer query)
+        #[arg(short, long)]
+        query: Option<String>,
     },
+    /// Generate the item database from HBF treasure entities
+    Items,
+    /// Generate the settlement database from HBF settlement pages
+    Settlements,
+    /// Generate the faction database from HBF membership markup
+    Factions,
+    /// Generate the NPC database from HBF character markup
+    Npcs,
+    /// Generate dungeon interior layouts from HBF dungeon areas
+    Dungeons,
     /// Generate upgrade progression chains
     Upgrades {
         /// Generate upgrade paths based on entity relationships
         #[arg(long)]
         auto_detect: bool,
     },
+    /// Check upgrade links for cycles, missing targets and band drops
+    CheckUpgrades {
+        /// Path to existing assets to check
+        assets_path: PathBuf,
+    },
+    /// Compare the asset manifest with the model files on disk
+    ManifestDiff {
+        /// Path to existing assets holding the manifest
+        assets_path: PathBuf,
+    },
     /// Validate existing RON structure
     Validate {
         /// Path to existing assets to validate
@@ -61,49 +97,6 @@ enum Commands {
     },
 }
 
-/// Model metadata structure matching cosmic-cults pattern
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ModelMetadata {
-    id: String,
-    display_name: String,
-    model_path: String,
-    scale: (f32, f32, f32),
-    bounds: ModelBounds,
-    animations: Vec<String>,
-    sockets: Vec<ModelSocket>,
-    tags: Vec<String>,
-    cult: Option<String>,
-    class: Option<String>,
-    upgrades_to: Option<String>,
-    ui_icon: Option<String>,
-    sounds: Vec<SoundEvent>,
-    // Dragon's Labyrinth specific additions
-    corruption_band: Option<u8>,
-    horror_theme: Option<String>,
-    forge_material: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ModelBounds {
-    min: (f32, f32, f32),
-    max: (f32, f32, f32),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ModelSocket {
-    name: String,
-    position: (f32, f32, f32),
-    rotation: (f32, f32, f32, f32),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SoundEvent {
-    event: String,
-    sound_path: String,
-    volume: f32,
-    pitch_variation: f32,
-}
-
 fn main() -> Result<()> {
     let cli = Cli::parse();
     
@@ -118,12 +111,33 @@ fn main() -> Result<()> {
         Commands::GenerateAll { corruption_bands } => {
             generate_all_assets(&cli.input, &cli.output, *corruption_bands)?;
         }
-        Commands::Generate { category, faction } => {
-            generate_category_assets(&cli.input, &cli.output, category, faction.as_deref())?;
+        Commands::Generate { category, faction, query } => {
+            generate_category_assets(&cli.input, &cli.output, category, faction.as_deref(), query.as_deref())?;
+        }
+        Commands::Items => {
+            generate_item_database(&cli.input, &cli.output)?;
+        }
+        Commands::Settlements => {
+            generate_settlement_database(&cli.input, &cli.output)?;
+        }
+        Commands::Factions => {
+            generate_faction_database(&cli.input, &cli.output)?;
+        }
+        Commands::Npcs => {
+            generate_npc_database(&cli.input, &cli.output)?;
+        }
+        Commands::Dungeons => {
+            generate_dungeon_layouts(&cli.input, &cli.output)?;
         }
         Commands::Upgrades { auto_detect } => {
             generate_upgrade_chains(&cli.input, &cli.output, *auto_detect)?;
         }
+        Commands::CheckUpgrades { assets_path } => {
+            check_upgrades(assets_path, &cli.output)?;
+        }
+        Commands::ManifestDiff { assets_path } => {
+            diff_asset_manifest(assets_path)?;
+        }
         Commands::Validate { assets_path } => {
             validate_ron_structure(assets_path)?;
         }
@@ -138,11 +152,18 @@ fn generate_all_assets(input_dir: &PathBuf, output_dir: &PathBuf, use_corruption
     // Load analyzed entities
     let analyzed_data = load_analyzed_entities(input_dir)?;
     
-    // Generate each asset category
-    generate_units_from_entities(&analyzed_data, output_dir, use_corruption_bands)?;
-    generate_buildings_from_entities(&analyzed_data, output_dir, use_corruption_bands)?;
-    generate_leaders_from_entities(&analyzed_data, output_dir, use_corruption_bands)?;
-    generate_terrain_from_entities(&analyzed_data, output_dir, use_corruption_bands)?;
+    // Generate each asset category, recording models in the manifest
+    let mut manifest = AssetManifest::default();
+    generate_units_from_entities(&analyzed_data, output_dir, use_corruption_bands, &mut manifest)?;
+    generate_buildings_from_entities(&analyzed_data, output_dir, use_corruption_bands, &mut manifest)?;
+    generate_leaders_from_entities(&analyzed_data, output_dir, use_corruption_bands, &mut manifest)?;
+    generate_terrain_from_entities(&analyzed_data, output_dir, use_corruption_bands, &mut manifest)?;
+    write_asset_manifest(output_dir, manifest)?;
+    generate_items_from_entities(&analyzed_data, output_dir)?;
+    generate_settlements_from_entities(&analyzed_data, output_dir)?;
+    generate_factions_from_entities(&analyzed_data, output_dir)?;
+    generate_npcs_from_entities(&analyzed_data, output_dir)?;
+    generate_dungeons_from_entities(&analyzed_data, output_dir)?;
     
     println!("✅ All asset RONs generated successfully");
     Ok(())
@@ -165,6 +186,9 @@ fn load_analyzed_entities(input_dir: &PathBuf) -> Result<RawEntities> {
     if let Ok(content) = std::fs::read_to_string(input_dir.join("dungeons.json")) {
         entities.dungeons = serde_json::from_str(&content)?;
     }
+    if let Ok(content) = std::fs::read_to_string(input_dir.join("items.json")) {
+        entities.items = serde_json::from_str(&content)?;
+    }
     if let Ok(content) = std::fs::read_to_string(input_dir.join("uncategorized.json")) {
         entities.uncategorized = serde_json::from_str(&content)?;
     }
@@ -176,10 +200,11 @@ fn generate_units_from_entities(
     entities: &RawEntities,
     output_dir: &PathBuf,
     use_corruption_bands: bool,
+    manifest: &mut AssetManifest,
 ) -> Result<()> {
     println!("⚔️ Generating unit RONs...");
     
-    let units_dir = output_dir.join("units");
+    let units_dir = output_dir.join(ModelKind::Unit.dir());
     
     // Process each faction
     for (faction_name, faction_entities) in &entities.factions {
@@ -193,8 +218,17 @@ fn generate_units_from_entities(
                 use_corruption_bands
             );
             
+            let filename = format!("{}.{}", sanitize_name(&entity.entity_name), MODEL_METADATA_EXTENSION);
+            let metadata_path = format!("{}/{}/{}", ModelKind::Unit.dir(), sanitize_name(faction_name), filename);
+            manifest.insert(ManifestEntry::new(
+                &unit_metadata.id,
+                ModelKind::Unit,
+                &unit_metadata.model_path,
+                &metadata_path,
+                &entity.uuid,
+                &entity.raw_value,
+            ));
             let ron_content = ron::ser::to_string_pretty(&unit_metadata, ron::ser::PrettyConfig::default())?;
-            let filename = format!("{}.meta.ron", sanitize_name(&entity.entity_name));
             std::fs::write(faction_dir.join(filename), ron_content)?;
         }
         
@@ -223,6 +257,7 @@ fn create_unit_metadata_from_entity(
     };
     
     ModelMetadata {
+        version: MODEL_METADATA_VERSION,
         id: format!("{}_model", sanitized_name),
         display_name,
         model_path: format!("units/{}/{}.glb", sanitize_name(faction), sanitized_name),
@@ -455,10 +490,11 @@ fn generate_buildings_from_entities(
     entities: &RawEntities,
     output_dir: &PathBuf,
     use_corruption_bands: bool,
+    manifest: &mut AssetManifest,
 ) -> Result<()> {
     println!("🏰 Generating building RONs...");
     
-    let buildings_dir = output_dir.join("buildings");
+    let buildings_dir = output_dir.join(ModelKind::Building.dir());
     
     // Generate buildings from settlements and some faction data
     for (settlement_name, settlement_entities) in &entities.settlements {
@@ -472,8 +508,17 @@ fn generate_buildings_from_entities(
                 use_corruption_bands,
             );
             
+            let filename = format!("{}.{}", sanitize_name(&entity.entity_name), MODEL_METADATA_EXTENSION);
+            let metadata_path = format!("{}/{}/{}", ModelKind::Building.dir(), sanitize_name(settlement_name), filename);
+            manifest.insert(ManifestEntry::new(
+                &building_metadata.id,
+                ModelKind::Building,
+                &building_metadata.model_path,
+                &metadata_path,
+                &entity.uuid,
+                &entity.raw_value,
+            ));
             let ron_content = ron::ser::to_string_pretty(&building_metadata, ron::ser::PrettyConfig::default())?;
-            let filename = format!("{}.meta.ron", sanitize_name(&entity.entity_name));
             std::fs::write(settlement_dir.join(filename), ron_content)?;
         }
     }
@@ -489,6 +534,7 @@ fn create_building_metadata_from_entity(
     let sanitized_name = sanitize_name(&entity.entity_name);
     
     ModelMetadata {
+        version: MODEL_METADATA_VERSION,
         id: format!("{}_building", sanitized_name),
         display_name: entity.entity_name.clone(),
         model_path: format!("buildings/{}/{}.glb", sanitize_name(settlement), sanitized_name),
@@ -532,10 +578,11 @@ fn generate_leaders_from_entities(
     entities: &RawEntities,
     output_dir: &PathBuf,
     use_corruption_bands: bool,
+    manifest: &mut AssetManifest,
 ) -> Result<()> {
     println!("👑 Generating leader RONs...");
     
-    let leaders_dir = output_dir.join("leaders");
+    let leaders_dir = output_dir.join(ModelKind::Leader.dir());
     std::fs::create_dir_all(&leaders_dir)?;
     
     // Generate leaders from faction entities with leadership indicators
@@ -548,8 +595,17 @@ fn generate_leaders_from_entities(
                     use_corruption_bands,
                 );
                 
+                let filename = format!("{}_leader.{}", sanitize_name(&entity.entity_name), MODEL_METADATA_EXTENSION);
+                let metadata_path = format!("{}/{}", ModelKind::Leader.dir(), filename);
+                manifest.insert(ManifestEntry::new(
+                    &leader_metadata.id,
+                    ModelKind::Leader,
+                    &leader_metadata.model_path,
+                    &metadata_path,
+                    &entity.uuid,
+                    &entity.raw_value,
+                ));
                 let ron_content = ron::ser::to_string_pretty(&leader_metadata, ron::ser::PrettyConfig::default())?;
-                let filename = format!("{}_leader.meta.ron", sanitize_name(&entity.entity_name));
                 std::fs::write(leaders_dir.join(filename), ron_content)?;
                 
                 println!("  Generated leader: {} for {}", entity.entity_name, faction_name);
@@ -579,6 +635,7 @@ fn create_leader_metadata_from_entity(
     let sanitized_name = sanitize_name(&entity.entity_name);
     
     ModelMetadata {
+        version: MODEL_METADATA_VERSION,
         id: format!("{}_leader", sanitized_name),
         display_name: format!("{} (Leader)", entity.entity_name),
         model_path: format!("leaders/{}.glb", sanitized_name),
@@ -655,10 +712,11 @@ fn generate_terrain_from_entities(
     entities: &RawEntities,
     output_dir: &PathBuf,
     use_corruption_bands: bool,
+    manifest: &mut AssetManifest,
 ) -> Result<()> {
     println!("🌍 Generating terrain RONs...");
     
-    let terrain_dir = output_dir.join("terrain");
+    let terrain_dir = output_dir.join(ModelKind::Terrain.dir());
     std::fs::create_dir_all(&terrain_dir)?;
     
     // Generate terrain from region entities
@@ -670,8 +728,17 @@ fn generate_terrain_from_entities(
                 use_corruption_bands,
             );
             
+            let filename = format!("{}_terrain.{}", sanitize_name(&entity.entity_name), MODEL_METADATA_EXTENSION);
+            let metadata_path = format!("{}/{}", ModelKind::Terrain.dir(), filename);
+            manifest.insert(ManifestEntry::new(
+                &terrain_metadata.id,
+                ModelKind::Terrain,
+                &terrain_metadata.model_path,
+                &metadata_path,
+                &entity.uuid,
+                &entity.raw_value,
+            ));
             let ron_content = ron::ser::to_string_pretty(&terrain_metadata, ron::ser::PrettyConfig::default())?;
-            let filename = format!("{}_terrain.meta.ron", sanitize_name(&entity.entity_name));
             std::fs::write(terrain_dir.join(filename), ron_content)?;
         }
     }
@@ -718,6 +785,7 @@ fn create_terrain_metadata_from_entity(
     }
     
     ModelMetadata {
+        version: MODEL_METADATA_VERSION,
         id: format!("{}_{}_terrain", sanitized_name, biome_type),
         display_name: format!("{} {} Terrain", entity.entity_name, biome_type.to_uppercase()),
         model_path: format!("terrain/{}/{}.glb", biome_type, sanitized_name),
@@ -751,11 +819,109 @@ fn create_terrain_metadata_from_entity(
     }
 }
 
+fn generate_item_database(input_dir: &PathBuf, output_dir: &PathBuf) -> Result<()> {
+    let entities = load_analyzed_entities(input_dir)?;
+    generate_items_from_entities(&entities, output_dir)
+}
+
+fn generate_items_from_entities(entities: &RawEntities, output_dir: &PathBuf) -> Result<()> {
+    println!("💰 Generating item database...");
+    
+    // Dungeon area pages carry the monster hoards that treasure rolls award
+    let database = build_item_database(&[&entities.items, &entities.dungeons])?;
+    write_item_database(&database, &output_dir.join("world").join("items.ron"))?;
+    
+    println!("  Generated {} item templates from {} treasure entities", 
+             database.items.len(),
+             entities.items.values().chain(entities.dungeons.values()).map(Vec::len).sum::<usize>());
+    Ok(())
+}
+
+fn generate_settlement_database(input_dir: &PathBuf, output_dir: &PathBuf) -> Result<()> {
+    let entities = load_analyzed_entities(input_dir)?;
+    generate_settlements_from_entities(&entities, output_dir)
+}
+
+fn generate_settlements_from_entities(entities: &RawEntities, output_dir: &PathBuf) -> Result<()> {
+    println!("🏘️ Generating settlement database...");
+    
+    let database = build_settlement_database(&entities.settlements)?;
+    write_settlement_database(&database, &output_dir.join("world").join("settlements.ron"))?;
+    
+    println!("  Generated {} settlements with {} establishments", 
+             database.settlements.len(),
+             database.settlements.values().map(|s| s.establishments.len()).sum::<usize>());
+    Ok(())
+}
+
+fn generate_faction_database(input_dir: &PathBuf, output_dir: &PathBuf) -> Result<()> {
+    let entities = load_analyzed_entities(input_dir)?;
+    generate_factions_from_entities(&entities, output_dir)
+}
+
+fn generate_factions_from_entities(entities: &RawEntities, output_dir: &PathBuf) -> Result<()> {
+    println!("⚔️ Generating faction database...");
+    
+    // Members live on tavern, shop and hex pages, so read every category with NPCs
+    let database = build_faction_database(&[
+        &entities.factions,
+        &entities.settlements,
+        &entities.regions,
+        &entities.characters,
+    ])?;
+    write_faction_database(&database, &output_dir.join("world").join("factions.ron"))?;
+    
+    println!("  Generated {} factions with {} members", 
+             database.factions.len(),
+             database.factions.values().map(|f| f.members.len()).sum::<usize>());
+    Ok(())
+}
+
+fn generate_npc_database(input_dir: &PathBuf, output_dir: &PathBuf) -> Result<()> {
+    let entities = load_analyzed_entities(input_dir)?;
+    generate_npcs_from_entities(&entities, output_dir)
+}
+
+fn generate_npcs_from_entities(entities: &RawEntities, output_dir: &PathBuf) -> Result<()> {
+    println!("🧑 Generating NPC database...");
+    
+    let database = build_npc_database(&[
+        &entities.settlements,
+        &entities.factions,
+        &entities.regions,
+        &entities.characters,
+    ])?;
+    write_npc_database(&database, &output_dir.join("world").join("npcs.ron"))?;
+    
+    println!("  Generated {} NPCs ({} quest givers)", 
+             database.npcs.len(),
+             database.npcs.values().filter(|npc| npc.quest_giver).count());
+    Ok(())
+}
+
+fn generate_dungeon_layouts(input_dir: &PathBuf, output_dir: &PathBuf) -> Result<()> {
+    let entities = load_analyzed_entities(input_dir)?;
+    generate_dungeons_from_entities(&entities, output_dir)
+}
+
+fn generate_dungeons_from_entities(entities: &RawEntities, output_dir: &PathBuf) -> Result<()> {
+    println!("🕳️ Generating dungeon layouts...");
+    
+    let layouts = build_dungeon_layouts(&entities.dungeons)?;
+    write_dungeon_layouts(&layouts, &output_dir.join("world").join("dungeons.ron"))?;
+    
+    println!("  Generated {} dungeons with {} rooms", 
+             layouts.len(),
+             layouts.iter().map(|layout| layout.rooms.len()).sum::<usize>());
+    Ok(())
+}
+
 fn generate_category_assets(
     input_dir: &PathBuf,
     output_dir: &PathBuf,
     category: &str,
     faction_filter: Option<&str>,
+    query: Option<&str>,
 ) -> Result<()> {
     println!("🔄 Generating {} assets...", category);
     
@@ -784,16 +950,35 @@ fn generate_category_assets(
                  entities.factions.len(), entities.settlements.len(), entities.regions.len());
     }
     
+    // Apply query selection if specified
+    if let Some(query) = query {
+        println!("  Selecting entities matching: {}", query);
+        let query: EntityQuery = query.parse()?;
+        let selected = QueryEngine::new(&entities)?.select(&query);
+        entities = selected;
+        println!("  Selected {} entities", entities.total_entities);
+    }
+    
+    let mut manifest = AssetManifest::default();
     match category {
-        "units" => generate_units_from_entities(&entities, output_dir, true)?,
-        "buildings" => generate_buildings_from_entities(&entities, output_dir, true)?,
-        "leaders" => generate_leaders_from_entities(&entities, output_dir, true)?,
-        "terrain" => generate_terrain_from_entities(&entities, output_dir, true)?,
+        "units" => generate_units_from_entities(&entities, output_dir, true, &mut manifest)?,
+        "buildings" => generate_buildings_from_entities(&entities, output_dir, true, &mut manifest)?,
+        "leaders" => generate_leaders_from_entities(&entities, output_dir, true, &mut manifest)?,
+        "terrain" => generate_terrain_from_entities(&entities, output_dir, true, &mut manifest)?,
+        "items" => generate_items_from_entities(&entities, output_dir)?,
+        "settlements" => generate_settlements_from_entities(&entities, output_dir)?,
+        "factions" => generate_factions_from_entities(&entities, output_dir)?,
+        "npcs" => generate_npcs_from_entities(&entities, output_dir)?,
+        "dungeons" => generate_dungeons_from_entities(&entities, output_dir)?,
         _ => {
-            println!("❌ Unknown category: {}. Use: units, buildings, leaders, terrain", category);
+            println!("❌ Unknown category: {}. Use: units, buildings, leaders, terrain, items, settlements, factions, npcs, dungeons", category);
         }
     }
     
+    if !manifest.entries.is_empty() {
+        write_asset_manifest(output_dir, manifest)?;
+    }
+    
     Ok(())
 }
 
@@ -870,69 +1055,144 @@ fn detect_upgrade_relationships(entities: &RawEntities) -> Result<HashMap<String
     Ok(faction_chains)
 }
 
-fn validate_ron_structure(assets_path: &PathBuf) -> Result<()> {
-    println!("🔍 Validating RON structure...");
+fn check_upgrades(assets_path: &Path, output_dir: &Path) -> Result<()> {
+    println!("🔗 Checking upgrade chains...");
     
-    let units_dir = assets_path.join("units");
-    if units_dir.exists() {
-        validate_units_structure(&units_dir)?;
+    let (models, unreadable) = read_all_model_metadata(assets_path);
+    let graph = UpgradeGraph::from_models(&models);
+    let chains_dir = output_dir.join("upgrade_chains");
+    std::fs::create_dir_all(&chains_dir)?;
+    std::fs::write(chains_dir.join("upgrade_graph.dot"), graph.to_dot())?;
+    std::fs::write(chains_dir.join("upgrade_graph.svg"), graph.to_svg())?;
+    std::fs::write(chains_dir.join("upgrade_graph.md"), graph.to_markdown())?;
+    println!("  {} chains across {} models written to {}", graph.chains().len(), graph.len(), chains_dir.display());
+    
+    let issues = graph.issues();
+    for issue in &issues {
+        println!("    ❌ {}", issue);
     }
     
-    let buildings_dir = assets_path.join("buildings");
-    if buildings_dir.exists() {
-        validate_buildings_structure(&buildings_dir)?;
+    if unreadable + issues.len() > 0 {
+        anyhow::bail!("{} unreadable files and {} upgrade issues", unreadable, issues.len());
     }
+    println!("✅ Upgrade chains are consistent");
+    Ok(())
+}
+
+fn validate_ron_structure(assets_path: &PathBuf) -> Result<()> {
+    println!("🔍 Validating RON structure...");
     
-    let leaders_dir = assets_path.join("leaders");
-    if leaders_dir.exists() {
-        validate_leaders_structure(&leaders_dir)?;
+    let (models, unreadable) = read_all_model_metadata(assets_path);
+    let issues = validate_model_set(&models, &|path: &str| assets_path.join(path).exists());
+    for issue in &issues {
+        println!("    ⚠️ {}", issue);
     }
     
-    println!("✅ RON structure validation complete");
+    if unreadable + issues.len() > 0 {
+        anyhow::bail!("{} unreadable files and {} issues across {} models", unreadable, issues.len(), models.len());
+    }
+    println!("✅ RON structure validation complete: {} models", models.len());
     Ok(())
 }
 
-fn validate_units_structure(units_dir: &PathBuf) -> Result<()> {
-    println!("  Validating units structure...");
-    
-    for entry in std::fs::read_dir(units_dir)? {
-        let entry = entry?;
-        if entry.file_type()?.is_dir() {
-            let faction_dir = entry.path();
-            let faction_name = faction_dir.file_name().unwrap().to_string_lossy();
-            
-            let mut unit_count = 0;
-            for unit_entry in std::fs::read_dir(&faction_dir)? {
-                let unit_entry = unit_entry?;
-                if unit_entry.path().extension().and_then(|s| s.to_str()) == Some("ron") {
-                    // Validate RON can be parsed
-                    let content = std::fs::read_to_string(unit_entry.path())?;
-                    match ron::from_str::<ModelMetadata>(&content) {
-                        Ok(_) => unit_count += 1,
-                        Err(e) => println!("    ⚠️ Invalid RON in {}: {}", unit_entry.path().display(), e),
-                    }
+/// Every model metadata file under the assets, with a count of those that
+/// could not be read
+fn read_all_model_metadata(assets_path: &Path) -> (Vec<ModelMetadata>, usize) {
+    let mut models = Vec::new();
+    let mut unreadable = 0;
+    for kind in ModelKind::ALL {
+        let kind_dir = assets_path.join(kind.dir());
+        if !kind_dir.exists() {
+            continue;
+        }
+        
+        let before = models.len();
+        for entry in WalkDir::new(&kind_dir).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.to_string_lossy().ends_with(MODEL_METADATA_EXTENSION) {
+                continue;
+            }
+            match read_model_metadata(path) {
+                Ok(metadata) => models.push(metadata),
+                Err(e) => {
+                    unreadable += 1;
+                    println!("    ⚠️ Invalid RON in {}: {}", path.display(), e);
                 }
             }
-            
-            println!("    ✅ {} units validated in faction: {}", unit_count, faction_name);
         }
+        println!("  ✅ {} {} parsed", models.len() - before, kind.dir());
     }
-    
-    Ok(())
+    (models, unreadable)
 }
 
-fn validate_buildings_structure(buildings_dir: &PathBuf) -> Result<()> {
-    println!("  Validating buildings structure...");
-    // Similar validation logic for buildings
+/// Merge newly generated entries into the manifest on disk, work out each
+/// model's status from the files present, and write it back
+fn write_asset_manifest(assets_path: &Path, generated: AssetManifest) -> Result<()> {
+    let previous = read_asset_manifest(assets_path)?.unwrap_or_default();
+    let mut manifest = previous.clone();
+    manifest.merge(generated);
+    manifest.refresh(&previous, &|path: &str| std::fs::read(assets_path.join(path)).ok());
+    
+    let ron_content = ron::ser::to_string_pretty(&manifest, ron::ser::PrettyConfig::default())?;
+    std::fs::write(assets_path.join(ASSET_MANIFEST_FILE), ron_content)?;
+    println!("📋 Asset manifest: {} models, {} missing, {} stale",
+             manifest.entries.len(),
+             manifest.count(AssetStatus::Missing),
+             manifest.count(AssetStatus::Stale));
     Ok(())
 }
 
-fn validate_leaders_structure(leaders_dir: &PathBuf) -> Result<()> {
-    println!("  Validating leaders structure...");
-    // Similar validation logic for leaders
+fn read_asset_manifest(assets_path: &Path) -> Result<Option<AssetManifest>> {
+    let path = assets_path.join(ASSET_MANIFEST_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(ron::from_str(&std::fs::read_to_string(path)?)?))
+}
+
+fn diff_asset_manifest(assets_path: &Path) -> Result<()> {
+    println!("📋 Comparing asset manifest with {}...", assets_path.display());
+    
+    let Some(manifest) = read_asset_manifest(assets_path)? else {
+        anyhow::bail!("No {} in {}; run generate-all first", ASSET_MANIFEST_FILE, assets_path.display());
+    };
+    
+    let mut model_files = Vec::new();
+    for kind in ModelKind::ALL {
+        for entry in WalkDir::new(assets_path.join(kind.dir())).into_iter().filter_map(|e| e.ok()) {
+            let is_model = entry.path().extension().and_then(|s| s.to_str()).is_some_and(|ext| MODEL_FILE_EXTENSIONS.contains(&ext));
+            if let (true, Ok(relative)) = (is_model, entry.path().strip_prefix(assets_path)) {
+                model_files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    
+    let diff = manifest.diff(&|path: &str| std::fs::read(assets_path.join(path)).ok(), &model_files);
+    for (label, items) in [
+        ("Missing", &diff.missing),
+        ("Stale", &diff.stale),
+        ("Changed since manifest", &diff.changed),
+        ("Untracked", &diff.untracked),
+    ] {
+        println!("  {}: {}", label, items.len());
+        for item in items {
+            println!("    - {}", item);
+        }
+    }
+    
+    if diff.is_clean() {
+        println!("✅ Assets match the manifest");
+    } else if !diff.changed.is_empty() || !diff.untracked.is_empty() {
+        println!("💡 Re-run generation to refresh the manifest");
+    }
     Ok(())
 }
 
+fn read_model_metadata(path: &Path) -> Result<ModelMetadata> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(ron::from_str(&content)?)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct UpgradeChain {
     tier: u8,
@@ -0,0 +1,402 @@
+//! Statistical entity classifier
+//!
+//! A multinomial naive Bayes model over features of an entity's name, its
+//! text and the shape of its HTML: which tags it uses, dice notation, stat
+//! blocks and a leading quantity ("5 Onis", "2 Adult Brass Dragons"). It is
+//! trained from the `training_data/*/*.toml` examples, including HBF
+//! entities labelled by hand: examples that carry the entity's `uuid`.
+//! Every category gets a confidence, and an entity whose best category is
+//! not confident enough is left uncategorized so it can be labelled and fed
+//! back in.
+
+use anyhow::Result;
+use csv::Writer;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::containers::RawEntity;
+use crate::orchestration::{EntityCategory, TrainingRepository};
+use crate::utilities::stem;
+
+/// Below this confidence an entity is left for labelling
+pub const MIN_CONFIDENCE: f64 = 0.6;
+/// Words of page text considered; the opening of a page says most about it
+const MAX_TEXT_TOKENS: usize = 200;
+/// Name features count this many times over text features
+const NAME_WEIGHT: f64 = 3.0;
+/// Laplace smoothing
+const ALPHA: f64 = 1.0;
+const CROSS_VALIDATION_FOLDS: usize = 5;
+
+/// How sure the classifier is about one entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Classification {
+    /// Every category with its probability, most likely first
+    pub scores: Vec<(EntityCategory, f64)>,
+}
+
+impl Classification {
+    pub fn best(&self) -> Option<(EntityCategory, f64)> {
+        self.scores.first().copied()
+    }
+
+    pub fn confidence(&self) -> f64 {
+        self.best().map_or(0.0, |(_, score)| score)
+    }
+
+    /// The best category if it clears `MIN_CONFIDENCE`
+    pub fn category(&self) -> Option<EntityCategory> {
+        self.best()
+            .filter(|(_, score)| *score >= MIN_CONFIDENCE)
+            .map(|(category, _)| category)
+    }
+}
+
+/// One training document: its category and extracted features
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelledDocument {
+    pub category: EntityCategory,
+    pub name: String,
+    pub features: Vec<(String, f64)>,
+}
+
+/// Turns names and HBF HTML into weighted features
+pub struct FeatureExtractor {
+    tags: Regex,
+    tag_name: Regex,
+    word: Regex,
+    dice: Regex,
+    quantity: Regex,
+}
+
+impl FeatureExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            tags: Regex::new(r"<[^>]+>")?,
+            tag_name: Regex::new(r"<([a-zA-Z][a-zA-Z0-9]*)")?,
+            word: Regex::new(r"[a-z][a-z'-]+")?,
+            dice: Regex::new(r"\b\d*d(4|6|8|10|12|20|100)\b")?,
+            quantity: Regex::new(r"^\s*\d+\s+\S")?,
+        })
+    }
+
+    /// Features of an entity's name and raw HTML. Each feature counts once:
+    /// a long page should not outvote a short one by repetition.
+    pub fn extract(&self, name: &str, html: &str) -> Vec<(String, f64)> {
+        let mut name_features = BTreeSet::new();
+        let lower_name = name.to_lowercase();
+        if self.quantity.is_match(name) {
+            name_features.insert("name:#quantity".to_string());
+        }
+        for word in self.word.find_iter(&lower_name) {
+            name_features.insert(format!("name:{}", stem(word.as_str())));
+        }
+
+        let mut text_features = BTreeSet::new();
+        let text = self.tags.replace_all(html, " ").to_lowercase();
+        for word in self.word.find_iter(&text).take(MAX_TEXT_TOKENS) {
+            if word.as_str().len() > 2 {
+                text_features.insert(format!("word:{}", stem(word.as_str())));
+            }
+        }
+        for tag in self.tag_name.captures_iter(html) {
+            text_features.insert(format!("tag:{}", tag[1].to_lowercase()));
+        }
+        if self.dice.is_match(&text) {
+            text_features.insert("html:dice".to_string());
+        }
+        if html.contains("AC") && html.contains("HP") {
+            text_features.insert("html:stat-block".to_string());
+        }
+        if html.contains("spoiler") {
+            text_features.insert("html:spoiler".to_string());
+        }
+
+        name_features
+            .into_iter()
+            .map(|feature| (feature, NAME_WEIGHT))
+            .chain(text_features.into_iter().map(|feature| (feature, 1.0)))
+            .collect()
+    }
+
+    pub fn document(&self, category: EntityCategory, entity: &RawEntity) -> LabelledDocument {
+        LabelledDocument {
+            category,
+            name: entity.entity_name.clone(),
+            features: self.extract(&entity.entity_name, &entity.raw_value),
+        }
+    }
+
+    /// Documents from the TOML examples. An example's name is its name; its
+    /// content patterns and markers stand in for page text. Each file's
+    /// positive indicators make one more document for its category.
+    pub fn training_documents(&self, training: &TrainingRepository) -> Vec<LabelledDocument> {
+        let mut documents = Vec::new();
+        for data in training.all() {
+            let Some(category) = EntityCategory::from_training(&data.category.name, &data.category.subcategory) else {
+                continue;
+            };
+            for example in &data.examples {
+                let text = example.content_patterns.iter().chain(&example.markers).cloned().collect::<Vec<_>>();
+                documents.push(LabelledDocument {
+                    category,
+                    name: example.name.clone(),
+                    features: self.extract(&example.name, &text.join(" ")),
+                });
+            }
+            documents.push(LabelledDocument {
+                category,
+                name: data.category.description.clone(),
+                features: self.extract("", &data.patterns.positive_indicators.join(" ")),
+            });
+        }
+        documents
+    }
+}
+
+/// Multinomial naive Bayes over weighted features
+#[derive(Debug, Clone, Default)]
+pub struct NaiveBayesClassifier {
+    documents: HashMap<EntityCategory, usize>,
+    feature_weights: HashMap<EntityCategory, HashMap<String, f64>>,
+    total_weight: HashMap<EntityCategory, f64>,
+    vocabulary: BTreeSet<String>,
+}
+
+impl NaiveBayesClassifier {
+    pub fn train(documents: &[LabelledDocument]) -> Self {
+        let mut classifier = Self::default();
+        for document in documents {
+            classifier.add(document);
+        }
+        classifier
+    }
+
+    pub fn add(&mut self, document: &LabelledDocument) {
+        *self.documents.entry(document.category).or_default() += 1;
+        let weights = self.feature_weights.entry(document.category).or_default();
+        for (feature, weight) in &document.features {
+            *weights.entry(feature.clone()).or_default() += weight;
+            *self.total_weight.entry(document.category).or_default() += weight;
+            self.vocabulary.insert(feature.clone());
+        }
+    }
+
+    pub fn document_count(&self) -> usize {
+        self.documents.values().sum()
+    }
+
+    /// Probability of each trained category. Log-likelihoods are damped by
+    /// the square root of the features' total weight, so a page with many
+    /// features is not pushed to certainty by their number alone.
+    pub fn classify_features(&self, features: &[(String, f64)]) -> Classification {
+        let total_documents = self.document_count() as f64;
+        let vocabulary = self.vocabulary.len() as f64;
+        let feature_weight: f64 = features.iter().map(|(_, weight)| weight).sum::<f64>().max(1.0);
+
+        let mut log_scores: Vec<(EntityCategory, f64)> = EntityCategory::ALL
+            .iter()
+            .filter_map(|category| {
+                let documents = *self.documents.get(category)? as f64;
+                let weights = &self.feature_weights[category];
+                let total = self.total_weight.get(category).copied().unwrap_or(0.0);
+                let likelihood: f64 = features
+                    .iter()
+                    .map(|(feature, weight)| {
+                        let count = weights.get(feature).copied().unwrap_or(0.0);
+                        weight * ((count + ALPHA) / (total + ALPHA * vocabulary)).ln()
+                    })
+                    .sum();
+                let prior = (documents / total_documents).ln();
+                Some((*category, prior + likelihood / feature_weight.sqrt()))
+            })
+            .collect();
+
+        // Softmax into probabilities
+        let max = log_scores.iter().map(|(_, score)| *score).fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = log_scores.iter().map(|(_, score)| (score - max).exp()).sum();
+        for (_, score) in &mut log_scores {
+            *score = (*score - max).exp() / sum;
+        }
+        log_scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+        Classification { scores: log_scores }
+    }
+
+    pub fn classify(&self, extractor: &FeatureExtractor, entity: &RawEntity) -> Classification {
+        self.classify_features(&extractor.extract(&entity.entity_name, &entity.raw_value))
+    }
+}
+
+/// A trained model together with the features it was trained on
+pub struct EntityClassifier {
+    pub extractor: FeatureExtractor,
+    pub model: NaiveBayesClassifier,
+    /// Cross-validated performance on the training documents
+    pub evaluation: ConfusionMatrix,
+}
+
+impl EntityClassifier {
+    /// Train from the TOML examples plus hand-labelled HBF entities
+    pub fn train(training: &TrainingRepository, labelled: &[(EntityCategory, &RawEntity)]) -> Result<Self> {
+        let extractor = FeatureExtractor::new()?;
+        let mut documents = extractor.training_documents(training);
+        documents.extend(labelled.iter().map(|(category, entity)| extractor.document(*category, entity)));
+        Ok(Self {
+            model: NaiveBayesClassifier::train(&documents),
+            evaluation: cross_validate(&documents, CROSS_VALIDATION_FOLDS),
+            extractor,
+        })
+    }
+
+    pub fn classify(&self, entity: &RawEntity) -> Classification {
+        self.model.classify(&self.extractor, entity)
+    }
+}
+
+/// How predictions line up against known categories
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfusionMatrix {
+    /// (actual, predicted) counts; `None` is left uncategorized
+    counts: HashMap<(EntityCategory, Option<EntityCategory>), usize>,
+}
+
+impl ConfusionMatrix {
+    pub fn record(&mut self, actual: EntityCategory, predicted: Option<EntityCategory>) {
+        *self.counts.entry((actual, predicted)).or_default() += 1;
+    }
+
+    pub fn count(&self, actual: EntityCategory, predicted: Option<EntityCategory>) -> usize {
+        self.counts.get(&(actual, predicted)).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Share of documents given their own category
+    pub fn accuracy(&self) -> f64 {
+        let correct: usize = EntityCategory::ALL.iter().map(|category| self.count(*category, Some(*category))).sum();
+        if self.total() == 0 { 0.0 } else { correct as f64 / self.total() as f64 }
+    }
+
+    /// One row per actual category, one column per prediction plus
+    /// "uncategorized", then the row's recall
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut wtr = Writer::from_path(path)?;
+        let mut header = vec!["Actual \\ Predicted".to_string()];
+        header.extend(EntityCategory::ALL.iter().map(|category| category.as_str().to_string()));
+        header.push("uncategorized".to_string());
+        header.push("Recall".to_string());
+        wtr.write_record(&header)?;
+
+        for actual in EntityCategory::ALL {
+            let row_total: usize = self.counts.iter().filter(|((a, _), _)| *a == actual).map(|(_, n)| n).sum();
+            if row_total == 0 {
+                continue;
+            }
+            let mut record = vec![actual.as_str().to_string()];
+            record.extend(EntityCategory::ALL.iter().map(|predicted| self.count(actual, Some(*predicted)).to_string()));
+            record.push(self.count(actual, None).to_string());
+            record.push(format!("{:.2}", self.count(actual, Some(actual)) as f64 / row_total as f64));
+            wtr.write_record(&record)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Hold out every `folds`th document in turn, train on the rest and score
+/// the held-out ones
+pub fn cross_validate(documents: &[LabelledDocument], folds: usize) -> ConfusionMatrix {
+    let folds = folds.max(2);
+    let mut matrix = ConfusionMatrix::default();
+    for fold in 0..folds {
+        let training: Vec<LabelledDocument> = documents
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % folds != fold)
+            .map(|(_, document)| document.clone())
+            .collect();
+        let classifier = NaiveBayesClassifier::train(&training);
+        for document in documents.iter().skip(fold).step_by(folds) {
+            matrix.record(document.category, classifier.classify_features(&document.features).category());
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(name: &str, html: &str) -> RawEntity {
+        RawEntity::new("uuid".to_string(), "unknown".to_string(), name.to_string(), html.to_string())
+    }
+
+    fn documents(extractor: &FeatureExtractor) -> Vec<LabelledDocument> {
+        let examples = [
+            (EntityCategory::Creatures, "5 Onis", "<p>AC 15 HP 110 attacks 2d8</p>"),
+            (EntityCategory::Creatures, "2 Adult Brass Dragons", "<p>AC 18 HP 172 breath 12d6</p>"),
+            (EntityCategory::Creatures, "4 Ettins", "<p>AC 12 HP 85 two heads 2d8</p>"),
+            (EntityCategory::Characters, "Melibor Sicletrude", "<strong>Melibor</strong>, a level 7 Elf Fighter"),
+            (EntityCategory::Characters, "Old Wenna", "<strong>Wenna</strong>, a level 2 Human Priest"),
+            (EntityCategory::Settlements, "Village of Harad", "<h5>Keeper</h5><ul><li>Inn</li><li>Smith</li></ul>"),
+            (EntityCategory::Settlements, "Town of Ilmar", "<h5>Staff</h5><ul><li>Market</li><li>Temple</li></ul>"),
+        ];
+        examples
+            .iter()
+            .map(|(category, name, html)| extractor.document(*category, &entity(name, html)))
+            .collect()
+    }
+
+    #[test]
+    fn test_features_capture_quantity_and_structure() {
+        let extractor = FeatureExtractor::new().unwrap();
+        let features = extractor.extract("5 Onis", "<p>AC 15 HP 110, claws 2d8</p>");
+        let names: Vec<&str> = features.iter().map(|(name, _)| name.as_str()).collect();
+        assert!(names.contains(&"name:#quantity"));
+        assert!(names.contains(&"name:oni"));
+        assert!(names.contains(&"tag:p"));
+        assert!(names.contains(&"html:dice"));
+        assert!(names.contains(&"html:stat-block"));
+        assert_eq!(features.iter().find(|(name, _)| name == "name:oni").unwrap().1, NAME_WEIGHT);
+    }
+
+    #[test]
+    fn test_classifies_with_confidence() {
+        let extractor = FeatureExtractor::new().unwrap();
+        let classifier = NaiveBayesClassifier::train(&documents(&extractor));
+        assert_eq!(classifier.document_count(), 7);
+
+        let onis = classifier.classify(&extractor, &entity("3 Onis", "<p>AC 15 HP 90 claws 2d8</p>"));
+        assert_eq!(onis.category(), Some(EntityCategory::Creatures));
+        assert!((onis.scores.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(onis.scores.len(), 3);
+
+        let village = classifier.classify(&extractor, &entity("Village of Oss", "<h5>Keeper</h5><ul><li>Inn</li></ul>"));
+        assert_eq!(village.best().map(|(category, _)| category), Some(EntityCategory::Settlements));
+
+        // Nothing like anything seen: not confident enough to file
+        let unknown = classifier.classify(&extractor, &entity("", "<span>lorem</span>"));
+        assert!(unknown.category().is_none());
+    }
+
+    #[test]
+    fn test_cross_validation_fills_confusion_matrix() {
+        let extractor = FeatureExtractor::new().unwrap();
+        let documents = documents(&extractor);
+        let matrix = cross_validate(&documents, 7);
+        assert_eq!(matrix.total(), 7);
+        assert!(matrix.count(EntityCategory::Creatures, Some(EntityCategory::Creatures)) >= 2);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("confusion_matrix.csv");
+        matrix.write_csv(&path).unwrap();
+        let csv = std::fs::read_to_string(path).unwrap();
+        assert!(csv.starts_with("Actual \\ Predicted,regions"));
+        assert!(csv.contains("\ncreatures,"));
+        assert!(!csv.contains("\nspells,"));
+    }
+}
@@ -4,12 +4,20 @@
 //! and consumed by the runtime analysis engine for dynamic seed generation.
 
 use anyhow::Result;
+use dl_types::world::{
+    dilemma_mercy_flag, dilemma_resolved_flag, QuestDefinition, QuestObjective, QuestOutcome, QuestStep, QuestTrigger,
+};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::Path;
 use std::fs;
 
 use crate::books::{WorldSeed, QuestSeed, DialogueSeed};
+use crate::utilities::sanitize_name;
+
+/// Dread a quest's ending adds (dark) or relieves (light), and adds on failure
+const QUEST_ENDING_DREAD: f32 = 5.0;
+const QUEST_FAILURE_DREAD: f32 = 10.0;
 
 /// Organized data pools by category for runtime consumption
 #[derive(Debug, Clone)]
@@ -19,6 +27,8 @@ pub struct CategorizedDataPools {
     pub dungeons: Vec<Value>,
     pub factions: Vec<Value>,
     pub books: Vec<Value>,
+    /// `QuestDefinition`s the game loads into its quest database
+    pub quests: Vec<Value>,
     pub metadata: HashMap<String, PoolMetadata>,
 }
 
@@ -31,6 +41,7 @@ impl CategorizedDataPools {
             dungeons: Vec::new(),
             factions: Vec::new(),
             books: Vec::new(),
+            quests: Vec::new(),
             metadata: HashMap::new(),
         }
     }
@@ -40,7 +51,7 @@ impl CategorizedDataPools {
         world_seeds: &[WorldSeed],
         quest_seeds: &[QuestSeed], 
         dialogue_seeds: &[DialogueSeed],
-    ) -> Self {
+    ) -> Result<Self> {
         let mut pools = Self::new();
         
         // Convert world seeds to regions pool
@@ -57,7 +68,7 @@ impl CategorizedDataPools {
         }
         
         // Convert quest seeds to settlements and factions pools
-        for seed in quest_seeds {
+        for (index, seed) in quest_seeds.iter().enumerate() {
             let settlement_data = serde_json::json!({
                 "id": seed.quest_archetype.chars().take(16).collect::<String>(),
                 "quest_type": &seed.quest_archetype,
@@ -67,6 +78,7 @@ impl CategorizedDataPools {
                 "quest_seed_id": format!("quest-{}", seed.source_book.chars().take(8).collect::<String>()),
             });
             pools.settlements.push(settlement_data);
+            pools.quests.push(serde_json::to_value(quest_from_seed(index, seed))?);
         }
         
         // Convert dialogue seeds to books and factions pools
@@ -88,8 +100,9 @@ impl CategorizedDataPools {
         pools.set_metadata("dungeons", 0, "Dungeon data (to be implemented)");
         pools.set_metadata("factions", 0, "Faction data (to be implemented)"); 
         pools.set_metadata("books", dialogue_seeds.len(), "Literature and dialogue data");
+        pools.set_metadata("quests", quest_seeds.len(), "Quest definitions for the game's quest engine");
         
-        pools
+        Ok(pools)
     }
 
     /// Load categorized pools from directory
@@ -117,6 +130,10 @@ impl CategorizedDataPools {
             pools.books = serde_json::from_str(&content)?;
         }
         
+        if let Ok(content) = fs::read_to_string(dir.join("quests.json")) {
+            pools.quests = serde_json::from_str(&content)?;
+        }
+        
         // Load metadata
         if let Ok(content) = fs::read_to_string(dir.join("metadata.json")) {
             pools.metadata = serde_json::from_str(&content)?;
@@ -155,6 +172,11 @@ impl CategorizedDataPools {
             serde_json::to_string_pretty(&self.books)?,
         )?;
         
+        fs::write(
+            dir.join("quests.json"),
+            serde_json::to_string_pretty(&self.quests)?,
+        )?;
+        
         // Save metadata
         fs::write(
             dir.join("metadata.json"),
@@ -172,6 +194,7 @@ impl CategorizedDataPools {
             "dungeons" => Ok(&self.dungeons),
             "factions" => Ok(&self.factions),
             "books" => Ok(&self.books),
+            "quests" => Ok(&self.quests),
             _ => Err(anyhow::anyhow!("Unknown category: {}", category)),
         }
     }
@@ -184,6 +207,7 @@ impl CategorizedDataPools {
             "dungeons" => Ok(&mut self.dungeons),
             "factions" => Ok(&mut self.factions),
             "books" => Ok(&mut self.books),
+            "quests" => Ok(&mut self.quests),
             _ => Err(anyhow::anyhow!("Unknown category: {}", category)),
         }
     }
@@ -218,6 +242,7 @@ impl CategorizedDataPools {
             "dungeons".to_string(),
             "factions".to_string(),
             "books".to_string(),
+            "quests".to_string(),
         ]
     }
 
@@ -228,6 +253,7 @@ impl CategorizedDataPools {
         + self.dungeons.len()
         + self.factions.len()
         + self.books.len()
+        + self.quests.len()
     }
 
     /// Check if pools are empty
@@ -242,6 +268,7 @@ impl CategorizedDataPools {
         self.dungeons.extend(other.dungeons);
         self.factions.extend(other.factions);
         self.books.extend(other.books);
+        self.quests.extend(other.quests);
         
         // Merge metadata
         for (category, metadata) in other.metadata {
@@ -280,14 +307,59 @@ impl CategorizedDataPools {
             }
         }
         
-        // Copy other categories as-is for now (dungeons, factions)
+        // Copy other categories as-is for now (dungeons, factions, quests)
         filtered.dungeons = self.dungeons.clone();
         filtered.factions = self.factions.clone();
+        filtered.quests = self.quests.clone();
         
         filtered
     }
 }
 
+/// A literature quest seed as a playable quest. The player settles the
+/// dilemma by choosing, which sets the quest's `dilemma_flags`; mercy takes
+/// the light ending, anything else the dark one. `index` is the seed's place
+/// in its list, keeping ids apart for seeds that share a book and archetype.
+pub fn quest_from_seed(index: usize, seed: &QuestSeed) -> QuestDefinition {
+    let id = format!(
+        "{}_{}_{}",
+        sanitize_name(&seed.quest_archetype),
+        sanitize_name(&seed.source_book).chars().take(16).collect::<String>(),
+        index
+    );
+    let flag = |suffix: &str| format!("{}_{}", id, suffix);
+    let ending = |name: &str, dread_change: f32, condition: Option<QuestTrigger>| QuestOutcome {
+        id: name.to_string(),
+        description: seed.companion_impact.clone(),
+        condition,
+        set_flags: HashMap::from([(flag(name), true)]),
+        dread_change,
+        ..Default::default()
+    };
+
+    QuestDefinition {
+        title: seed.quest_archetype.clone(),
+        summary: seed.moral_dilemma.clone(),
+        start_trigger: None,
+        steps: vec![QuestStep {
+            description: seed.moral_dilemma.clone(),
+            objective: QuestObjective::StoryFlag { flag: dilemma_resolved_flag(&id) },
+            fail_if: None,
+        }],
+        outcomes: vec![
+            ending(
+                "mercy",
+                -QUEST_ENDING_DREAD,
+                Some(QuestTrigger::StoryFlag { flag: dilemma_mercy_flag(&id), value: true }),
+            ),
+            ending("sacrifice", QUEST_ENDING_DREAD, None),
+        ],
+        failure: ending("abandoned", QUEST_FAILURE_DREAD, None),
+        time_limit_days: None,
+        id,
+    }
+}
+
 impl Default for CategorizedDataPools {
     fn default() -> Self {
         Self::new()
@@ -305,6 +377,7 @@ pub struct PoolMetadata {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dl_types::world::{dilemma_flags, QuestDatabase, QuestLog, QuestUpdate, QuestWorldSnapshot};
     use tempfile::TempDir;
 
     #[test]
@@ -312,7 +385,7 @@ mod tests {
         let pools = CategorizedDataPools::new();
         assert!(pools.is_empty());
         assert_eq!(pools.total_count(), 0);
-        assert_eq!(pools.get_categories().len(), 5);
+        assert_eq!(pools.get_categories().len(), 6);
     }
 
     #[test]
@@ -375,4 +448,56 @@ mod tests {
         assert_eq!(filtered.regions.len(), 1);
         assert_eq!(filtered.regions[0]["id"], "region1");
     }
+
+    fn dracula_seed() -> QuestSeed {
+        QuestSeed {
+            source_book: "Dracula".to_string(),
+            quest_archetype: "Rescue".to_string(),
+            moral_dilemma: "Save the bitten or end them".to_string(),
+            companion_impact: "Companions remember who you saved".to_string(),
+            forge_relevance: "light".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_quest_seeds_become_quest_definitions() {
+        let pools = CategorizedDataPools::from_seeds(&[], &[dracula_seed(), dracula_seed()], &[]).unwrap();
+        assert_eq!(pools.quests.len(), 2);
+
+        let quest: QuestDefinition = serde_json::from_value(pools.quests[0].clone()).unwrap();
+        assert_eq!(quest.id, "rescue_dracula_0");
+        assert_eq!(
+            quest.steps[0].objective,
+            QuestObjective::StoryFlag { flag: "rescue_dracula_0_resolved".to_string() }
+        );
+        assert_eq!(quest.outcomes[0].id, "mercy");
+        assert!(quest.outcomes[0].dread_change < 0.0);
+        assert_eq!(quest.outcomes[1].condition, None);
+        assert_eq!(quest.failure.set_flags.get("rescue_dracula_0_abandoned"), Some(&true));
+
+        // The same book and archetype twice still make two quests
+        let second: QuestDefinition = serde_json::from_value(pools.quests[1].clone()).unwrap();
+        assert_eq!(second.id, "rescue_dracula_1");
+    }
+
+    #[test]
+    fn test_generated_quest_completes_when_dilemma_is_settled() {
+        let quest = quest_from_seed(0, &dracula_seed());
+        let mut database = QuestDatabase::default();
+        database.insert(quest.clone());
+
+        for (mercy, ending) in [(true, "mercy"), (false, "sacrifice")] {
+            let mut log = QuestLog::default();
+            let mut world = QuestWorldSnapshot::default();
+            assert!(log.start(&database, &quest.id, 1));
+            assert!(log.update(&database, &world).is_empty());
+            assert!(log.awaits_dilemma(&database, &quest.id));
+
+            world.flags.extend(dilemma_flags(&quest.id, mercy));
+            let updates = log.update(&database, &world);
+            assert!(matches!(updates.last(), Some(QuestUpdate::Completed { outcome, .. }) if outcome.id == ending));
+            assert_eq!(log.completed.get(&quest.id).map(String::as_str), Some(ending));
+            assert!(!log.awaits_dilemma(&database, &quest.id));
+        }
+    }
 }
@@ -0,0 +1,262 @@
+//! Dungeon layout generation from HBF dungeon area pages
+//!
+//! Every page in the `dungeons` category is one numbered area ("Cave area #10
+//! in Lair of the Foresaken Desire"). The breadcrumbs link the area to its
+//! dungeon's location UUID, which is the same UUID the overworld uses for
+//! dungeon entrances. Areas are grouped by that UUID into one `DungeonLayout`
+//! per dungeon; doorways, monster stat blocks, hoards and traps become the
+//! room's connections and occupants.
+
+use anyhow::Result;
+use dl_types::world::{DungeonLayout, DungeonRoom, HexCoord, RoomOccupant};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::containers::RawEntity;
+
+/// Build one layout per dungeon from the `dungeons` category of `RawEntities`
+pub fn build_dungeon_layouts(dungeons: &HashMap<String, Vec<RawEntity>>) -> Result<Vec<DungeonLayout>> {
+    let extractor = DungeonExtractor::new()?;
+    // BTreeMap keeps the output order stable between runs
+    let mut grouped: BTreeMap<String, (String, Vec<DungeonRoom>)> = BTreeMap::new();
+
+    for entities in dungeons.values() {
+        for entity in entities {
+            if let Some(area) = extractor.extract(entity) {
+                grouped
+                    .entry(area.dungeon_uuid)
+                    .or_insert_with(|| (area.dungeon_name, Vec::new()))
+                    .1
+                    .push(area.room);
+            }
+        }
+    }
+
+    Ok(grouped
+        .into_iter()
+        .map(|(dungeon_uuid, (name, mut rooms))| {
+            rooms.sort_by_key(|room| room.area_number);
+            rooms.dedup_by_key(|room| room.area_number);
+            DungeonLayout::new(dungeon_uuid, name, rooms)
+        })
+        .collect())
+}
+
+/// Write every dungeon layout to one RON file, which the game loads whole
+pub fn write_dungeon_layouts(layouts: &[DungeonLayout], output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let ron_content = ron::ser::to_string_pretty(layouts, ron::ser::PrettyConfig::default())?;
+    std::fs::write(output_path, ron_content)?;
+    Ok(())
+}
+
+/// One area page resolved to its dungeon
+pub struct ExtractedArea {
+    pub dungeon_uuid: String,
+    pub dungeon_name: String,
+    pub room: DungeonRoom,
+}
+
+/// Compiled patterns for the fragments HBF dungeon area pages use
+pub struct DungeonExtractor {
+    tags: Regex,
+    breadcrumb: Regex,
+    description: Regex,
+    passage: Regex,
+    foreshadowing: Regex,
+    emphasis: Regex,
+    monster: Regex,
+    monster_count: Regex,
+    hoard: Regex,
+    trap: Regex,
+}
+
+impl DungeonExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            tags: Regex::new(r"<[^>]+>")?,
+            breadcrumb: Regex::new(r#"location/([A-Za-z0-9]+)">([^<]+)</a>\s*>\s*Area\s*#\s*(\d+)"#)?,
+            description: Regex::new(r"(?s)<h5>\s*Description\s*</h5>\s*<blockquote>(.*?)</blockquote>")?,
+            passage: Regex::new(r"passage to area (\d+)")?,
+            foreshadowing: Regex::new(r"(?s)<h5>\s*Foreshadowing\s*</h5>\s*<ul>(.*?)</ul>")?,
+            emphasis: Regex::new(r"<strong>([^<]+)</strong>")?,
+            monster: Regex::new(r#"<strong>([^<]+)</strong>\s*(?:<[^>]+>\s*)*<div id="block-([A-Za-z0-9]+)" class="monster-block""#)?,
+            monster_count: Regex::new(r"There are (\d+) ([A-Z][A-Za-z ]+?)\s+inside")?,
+            hoard: Regex::new(r#"reroll\('([A-Za-z0-9]+)'\);"><i class="fa fa-dice"></i></a>\s*Monster Hoard:"#)?,
+            trap: Regex::new(r"<strong>([^<]*\btrap)</strong>")?,
+        })
+    }
+
+    /// The room described by one area page, or `None` for pages that are not
+    /// numbered dungeon areas
+    pub fn extract(&self, entity: &RawEntity) -> Option<ExtractedArea> {
+        let html = &entity.raw_value;
+        let breadcrumb = self.breadcrumb.captures(html)?;
+        let area_number: i32 = breadcrumb[3].parse().ok()?;
+
+        let description = self
+            .description
+            .captures(html)
+            .map(|capture| self.plain_text(&capture[1]))
+            .unwrap_or_default();
+
+        let text = self.plain_text(html);
+        let connected_areas = self
+            .passage
+            .captures_iter(&text)
+            .filter_map(|capture| capture[1].parse().ok())
+            .collect();
+
+        let counts: Vec<(String, u32)> = self
+            .monster_count
+            .captures_iter(&text)
+            .map(|capture| (capture[2].to_string(), capture[1].parse().unwrap_or(1)))
+            .collect();
+        let monsters = self
+            .monster
+            .captures_iter(html)
+            .map(|capture| {
+                let name = capture[1].trim().to_string();
+                // "There are 2 Goblins" introduces the "Goblin" stat block
+                let count = counts
+                    .iter()
+                    .find(|(plural, _)| plural.starts_with(&name))
+                    .map_or(1, |(_, count)| *count);
+                RoomOccupant {
+                    uuid: capture[2].to_string(),
+                    name,
+                    count,
+                }
+            })
+            .collect();
+
+        // Traps have no entity of their own, so they are keyed by area and position
+        let traps = self
+            .trap
+            .captures_iter(html)
+            .enumerate()
+            .map(|(index, capture)| RoomOccupant {
+                uuid: format!("{}_trap_{}", entity.uuid, index),
+                name: capture[1].trim().to_string(),
+                count: 1,
+            })
+            .collect();
+
+        let treasure = self.hoard.captures(html).map(|capture| RoomOccupant {
+            uuid: capture[1].to_string(),
+            name: "Monster Hoard".to_string(),
+            count: 1,
+        });
+
+        let special_features = self
+            .foreshadowing
+            .captures(html)
+            .map(|capture| {
+                self.emphasis
+                    .captures_iter(&capture[1])
+                    .map(|strong| strong[1].trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(ExtractedArea {
+            dungeon_uuid: breadcrumb[1].to_string(),
+            dungeon_name: breadcrumb[2].trim().to_string(),
+            room: DungeonRoom {
+                area_uuid: entity.uuid.clone(),
+                area_number,
+                description,
+                connected_areas,
+                monsters,
+                traps,
+                treasure,
+                special_features,
+                difficulty_level: None,
+                position: HexCoord::origin(),
+            },
+        })
+    }
+
+    fn plain_text(&self, html: &str) -> String {
+        let text = self.tags.replace_all(html, " ");
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Trimmed copy of a real HBF cave area page
+    pub(crate) const CAVE_AREA_PAGE: &str = r#"
+    <div hidden id="doc-title"> Cave area #10 in Lair of the Foresaken Desire </div>
+    <span class="breadcrumbs"> <a href="/sandbox/nTR8nJOW/location/4zPzHbbR">Lair of the Foresaken Desire</a> > Area # 10 </span>
+    <h5> Foreshadowing </h5> <ul>  <li> When listening from outside, <strong>speaking Goblins</strong>  can be faintly heard from inside this area. </li>   </ul>
+    <h5> Doorways </h5> <ul> <li> <strong>W</strong> side - passage to area 11. </li> </ul>
+    <h5> Description </h5> <blockquote> The cave earth here makes the ground feel sticky.  There’s movement inside.  </blockquote>
+    <ul>  <li> <a class="btn-icon" onclick="javascript:window.app.reroll('Jq7nhw9u');"><i class="fa fa-dice"></i></a>   There are 2 Goblins  inside, hammering on the wall.
+    <p> <strong>Goblin</strong>   <hr/> <div id="block-9tTam3Rh" class="monster-block"> <div><span class="section-label">AC:</span> 15 (leather armor,shield)</div> </div>
+    <hr/> <ul>  <li><a class="btn-icon" onclick="javascript:window.app.reroll('8lo3P7jD');"><i class="fa fa-dice"></i></a>Monster Hoard: <ul> <li> <strong>201 gp</strong> in coins </li>   </ul> </li>  </ul> </p> </li>
+    <li> There's a razor-sharp 5' tall <strong>swinging blade trap</strong> here. </li> </ul>
+    "#;
+
+    pub(crate) fn cave_area() -> RawEntity {
+        RawEntity::new(
+            "DmPSseRL".to_string(),
+            "dungeons".to_string(),
+            "speaking Goblins".to_string(),
+            CAVE_AREA_PAGE.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_extracts_area_page() {
+        let extractor = DungeonExtractor::new().unwrap();
+        let area = extractor.extract(&cave_area()).unwrap();
+
+        assert_eq!(area.dungeon_uuid, "4zPzHbbR");
+        assert_eq!(area.dungeon_name, "Lair of the Foresaken Desire");
+        assert_eq!(area.room.area_uuid, "DmPSseRL");
+        assert_eq!(area.room.area_number, 10);
+        assert_eq!(area.room.connected_areas, vec![11]);
+        assert_eq!(
+            area.room.monsters,
+            vec![RoomOccupant { uuid: "9tTam3Rh".to_string(), name: "Goblin".to_string(), count: 2 }]
+        );
+        assert_eq!(area.room.treasure.as_ref().unwrap().uuid, "8lo3P7jD");
+        assert_eq!(area.room.traps[0].name, "swinging blade trap");
+        assert_eq!(area.room.special_features, vec!["speaking Goblins"]);
+        assert!(area.room.description.starts_with("The cave earth"));
+    }
+
+    #[test]
+    fn test_groups_areas_by_dungeon() {
+        let second_area = RawEntity::new(
+            "Xy12Ab34".to_string(),
+            "dungeons".to_string(),
+            "sound of crying".to_string(),
+            CAVE_AREA_PAGE.replace("Area # 10", "Area # 11").replace("passage to area 11", "passage to area 10"),
+        );
+        let other_page = RawEntity::new(
+            "Qw98Er76".to_string(),
+            "dungeons".to_string(),
+            "Barber".to_string(),
+            r#"<div hidden id="doc-title"> Barber in Palemoon </div>"#.to_string(),
+        );
+        let dungeons = HashMap::from([
+            ("speaking Goblins".to_string(), vec![cave_area()]),
+            ("other".to_string(), vec![second_area, other_page]),
+        ]);
+
+        let layouts = build_dungeon_layouts(&dungeons).unwrap();
+        assert_eq!(layouts.len(), 1);
+        let layout = &layouts[0];
+        assert_eq!(layout.dungeon_uuid, "4zPzHbbR");
+        assert_eq!(layout.entrance_area, 10);
+        assert!(layout.are_connected(10, 11));
+        assert_ne!(layout.room(10).unwrap().position, layout.room(11).unwrap().position);
+    }
+}
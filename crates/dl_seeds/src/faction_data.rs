@@ -0,0 +1,166 @@
+//! Faction database generation from HBF membership markup
+//!
+//! HBF has no faction pages of its own. Membership is a spoiler line under an
+//! NPC ("Member of the <a href=\".../faction/Fh55X8l5\"><strong>The Swords Of
+//! Justice</strong>"), following the NPC's anchor, on whatever tavern, shop or
+//! hex page the NPC lives on. Collecting those lines gives each faction its
+//! members, the settlements they hold seats in and the hexes they roam.
+
+use anyhow::Result;
+use dl_types::world::{FactionAlignment, FactionDatabase, FactionRecord};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::containers::RawEntity;
+
+/// Build the faction database from every category that may carry NPCs.
+/// Pages listed in more than one category are only read once.
+pub fn build_faction_database(categories: &[&HashMap<String, Vec<RawEntity>>]) -> Result<FactionDatabase> {
+    let extractor = FactionExtractor::new()?;
+    let mut database = FactionDatabase::default();
+    let mut seen = HashSet::new();
+
+    for entity in categories.iter().flat_map(|category| category.values().flatten()) {
+        if seen.insert(entity.uuid.as_str()) {
+            extractor.extract(entity, &mut database);
+        }
+    }
+
+    for faction in database.factions.values_mut() {
+        faction.members.sort();
+        faction.hex_uuids.sort();
+    }
+    database.seed_relations();
+    Ok(database)
+}
+
+/// Write the database as pretty RON for the game to load at startup
+pub fn write_faction_database(database: &FactionDatabase, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let ron_content = ron::ser::to_string_pretty(database, ron::ser::PrettyConfig::default())?;
+    std::fs::write(output_path, ron_content)?;
+    Ok(())
+}
+
+/// Compiled patterns for the fragments HBF membership markup uses
+pub struct FactionExtractor {
+    membership: Regex,
+    npc_anchor: Regex,
+    settlement: Regex,
+    settlement_link: Regex,
+    hex: Regex,
+}
+
+impl FactionExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            membership: Regex::new(r#"Member of the\s*<a href="[^"]*/faction/([A-Za-z0-9]+)">\s*<strong>([^<]+?)\.?</strong>"#)?,
+            npc_anchor: Regex::new(r#"npc-anchor" id="([A-Za-z0-9]+)""#)?,
+            settlement: Regex::new(r#"data-settlement="([A-Za-z0-9]+)""#)?,
+            settlement_link: Regex::new(r#"location/([A-Za-z0-9]+)">\s*(?i:village|town|city|metropolis) of"#)?,
+            hex: Regex::new(r#"hex="([A-Za-z0-9]+)""#)?,
+        })
+    }
+
+    /// Record every membership on one page
+    pub fn extract(&self, entity: &RawEntity, database: &mut FactionDatabase) {
+        let page = &entity.raw_value;
+        let anchors: Vec<(usize, &str)> = self
+            .npc_anchor
+            .captures_iter(page)
+            .filter_map(|c| c.get(1).map(|m| (m.start(), m.as_str())))
+            .collect();
+        let settlement = self
+            .settlement
+            .captures(page)
+            .or_else(|| self.settlement_link.captures(page))
+            .map(|c| c[1].to_string());
+        let hex = self.hex.captures(page).map(|c| c[1].to_string());
+
+        for capture in self.membership.captures_iter(page) {
+            let position = capture.get(0).map_or(0, |m| m.start());
+            let uuid = capture[1].to_string();
+            let name = capture[2].trim().to_string();
+            let faction = database.factions.entry(uuid.clone()).or_insert_with(|| FactionRecord {
+                uuid,
+                alignment: FactionAlignment::from_name(&name),
+                name,
+                members: Vec::new(),
+                seats: HashMap::new(),
+                hex_uuids: Vec::new(),
+            });
+
+            // The member is the NPC whose anchor most recently preceded the line
+            let member = anchors.iter().take_while(|(start, _)| *start < position).last();
+            if let Some((_, npc)) = member {
+                if faction.members.iter().any(|known| known == npc) {
+                    continue;
+                }
+                faction.members.push(npc.to_string());
+            }
+            if let Some(settlement) = &settlement {
+                *faction.seats.entry(settlement.clone()).or_default() += 1;
+            }
+            if let Some(hex) = hex.as_ref().filter(|hex| !faction.hex_uuids.contains(hex)) {
+                faction.hex_uuids.push(hex.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dl_types::world::Relation;
+
+    fn page(uuid: &str, body: &str) -> RawEntity {
+        RawEntity::new(
+            uuid.to_string(),
+            "factions".to_string(),
+            "the_red_snakes".to_string(),
+            body.to_string(),
+        )
+    }
+
+    const MEMBER: &str = r#"<a class="npc-anchor" id="{npc}"></a> <strong>Someone</strong>, a level 2 Human Rogue. <span class="spoiler"> Member of the <a href="/sandbox/nTR8nJOW/faction/{faction}"> <strong>{name}</strong>. </a> </span>"#;
+
+    fn member(npc: &str, faction: &str, name: &str) -> String {
+        MEMBER.replace("{npc}", npc).replace("{faction}", faction).replace("{name}", name)
+    }
+
+    #[test]
+    fn test_collects_members_seats_and_hexes() {
+        let tavern = format!(
+            r#"<a class="map-coords" hex="Cyw6XrnL"></a><span data-settlement="0SODPUEB"></span>{}<a class="npc-anchor" id="bystndr1"></a>{}"#,
+            member("npcSnake1", "uqf2lypH", "The Red Snakes"),
+            member("npcFist01", "KU2zGOUA", "The Fists Of Justice"),
+        );
+        let hex_page = format!(r#"<a class="map-coords" hex="4MBpzETO"></a>{}"#, member("npcSnake2", "uqf2lypH", "The Red Snakes"));
+        let mut category = HashMap::new();
+        category.insert("the_red_snakes".to_string(), vec![page("P1", &tavern), page("P2", &hex_page)]);
+        // The same tavern filed under settlements must not double count
+        let mut settlements = HashMap::new();
+        settlements.insert("harad".to_string(), vec![page("P1", &tavern)]);
+
+        let database = build_faction_database(&[&category, &settlements]).unwrap();
+        let snakes = database.get("uqf2lypH").unwrap();
+        assert_eq!(snakes.name, "The Red Snakes");
+        assert_eq!(snakes.alignment, FactionAlignment::Corrupt);
+        assert_eq!(snakes.members, vec!["npcSnake1", "npcSnake2"]);
+        assert_eq!(snakes.seats.get("0SODPUEB"), Some(&1));
+        assert_eq!(snakes.hex_uuids, vec!["4MBpzETO", "Cyw6XrnL"]);
+
+        let fists = database.find_by_name("the fists of justice").unwrap();
+        assert_eq!(fists.members, vec!["npcFist01"]);
+        assert_eq!(fists.alignment, FactionAlignment::Lawful);
+        assert_eq!(database.faction_of_npc("npcSnake2").map(|f| f.uuid.as_str()), Some("uqf2lypH"));
+        assert_eq!(database.faction_of_npc("bystndr1"), None);
+
+        // Opposed alignments sharing a settlement start out hostile
+        let seed = &database.relations[0];
+        assert_eq!(Relation::from_standing(seed.standing), Relation::Hostile);
+    }
+}
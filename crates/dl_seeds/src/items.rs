@@ -0,0 +1,290 @@
+//! Item database generation from HBF treasure entities
+//!
+//! HBF has no structured item records; treasure shows up as prose inside hex,
+//! dungeon and NPC pages ("2,640 gp will be awarded", "In the pocket: a dagger").
+//! This module pulls those fragments out of the `items` and `dungeons`
+//! categories and turns them into `ItemTemplate`s for the game's `ItemDatabase`.
+//! Creature stat blocks ("AC: 14 (Chain shirt)", "Sickle +3 to hit") describe
+//! what a monster fights with, not loot, so their gear is left alone.
+//! Each template's `source_uuid` is the page it came from, so a dungeon hoard's
+//! contents are found through the UUID of the area page that holds it.
+
+use anyhow::Result;
+use dl_types::world::{Item, ItemDatabase, ItemTemplate, ItemType};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::containers::RawEntity;
+use crate::utilities::sanitize_name;
+
+/// Build the item database from the treasure-bearing categories of `RawEntities`
+pub fn build_item_database(categories: &[&HashMap<String, Vec<RawEntity>>]) -> Result<ItemDatabase> {
+    let extractor = ItemExtractor::new()?;
+    let mut database = ItemDatabase::default();
+    let mut seen = HashSet::new();
+
+    for entity in categories.iter().flat_map(|category| category.values().flatten()) {
+        if seen.insert(entity.uuid.as_str()) {
+            for template in extractor.extract(entity) {
+                database.insert(template);
+            }
+        }
+    }
+
+    Ok(database)
+}
+
+/// Write the database as pretty RON for the game to load at startup
+pub fn write_item_database(database: &ItemDatabase, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let ron_content = ron::ser::to_string_pretty(database, ron::ser::PrettyConfig::default())?;
+    std::fs::write(output_path, ron_content)?;
+    Ok(())
+}
+
+/// Compiled patterns for the treasure fragments HBF pages use
+pub struct ItemExtractor {
+    tags: Regex,
+    currency: Regex,
+    pocket: Regex,
+}
+
+impl ItemExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            tags: Regex::new(r"<[^>]+>")?,
+            currency: Regex::new(r"(\d[\d,]*)\s*(pp|gp|sp|cp)\b")?,
+            pocket: Regex::new(r"In the pocket:\s*([^.]+)\.")?,
+        })
+    }
+
+    /// All item templates mentioned in one entity's page
+    pub fn extract(&self, entity: &RawEntity) -> Vec<ItemTemplate> {
+        let text = self.tags.replace_all(&entity.raw_value, " ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let corruption_band = corruption_band_for(&text);
+        let mut items = Vec::new();
+
+        for capture in self.currency.captures_iter(&text) {
+            let amount: u32 = capture[1].replace(',', "").parse().unwrap_or(0);
+            if amount > 0 {
+                items.push(Item::coins(&capture[2], amount));
+            }
+        }
+
+        for capture in self.pocket.captures_iter(&text) {
+            for name in split_item_list(&capture[1]) {
+                // Coins in pockets are already picked up by the currency pattern
+                if !self.currency.is_match(&name) {
+                    items.push(pocket_item(&name));
+                }
+            }
+        }
+
+        items
+            .into_iter()
+            .map(|item| ItemTemplate {
+                id: format!("{}_{}", sanitize_name(&item.name), short_uuid(&entity.uuid)),
+                source_uuid: entity.uuid.clone(),
+                item,
+                corruption_band,
+            })
+            .collect()
+    }
+}
+
+fn short_uuid(uuid: &str) -> &str {
+    &uuid[..uuid.len().min(8)]
+}
+
+fn base_item(name: &str, item_type: ItemType, weight: f32, value: u32) -> Item {
+    Item {
+        name: name.to_string(),
+        item_type,
+        quantity: 1,
+        weight,
+        value,
+        description: String::new(),
+    }
+}
+
+/// Loose pocket contents: potions and provisions are consumables, kit is tools,
+/// everything else is a material
+fn pocket_item(name: &str) -> Item {
+    let lower = name.to_lowercase();
+    let display_name = title_case(name);
+
+    if lower.contains("potion") || lower.contains("elixir") || lower.contains("draught") {
+        let effect = lower
+            .split(" of ")
+            .nth(1)
+            .unwrap_or("healing")
+            .to_string();
+        return base_item(
+            &display_name,
+            ItemType::Consumable {
+                effect,
+                duration: 0.0,
+                potency: 10,
+            },
+            0.5,
+            5000,
+        );
+    }
+
+    let provision = if lower.contains("ration") || lower.contains("bread") || lower.contains("jerky") {
+        Some(("food", 30))
+    } else if lower.contains("waterskin") || lower.contains("canteen") || lower.contains("water") {
+        Some(("water", 40))
+    } else {
+        None
+    };
+    if let Some((effect, potency)) = provision {
+        return base_item(
+            &display_name,
+            ItemType::Consumable {
+                effect: effect.to_string(),
+                duration: 0.0,
+                potency,
+            },
+            1.0,
+            50,
+        );
+    }
+
+    let tool_type = ["lockpick", "thieves' tools", "rope", "torch", "lantern", "pick", "shovel", "tinderbox", "bedroll", "pipe", "cards", "dice"]
+        .into_iter()
+        .find(|tool| lower.contains(tool));
+    if let Some(tool_type) = tool_type {
+        return base_item(
+            &display_name,
+            ItemType::Tool {
+                tool_type: tool_type.to_string(),
+                durability: 20,
+                max_durability: 20,
+            },
+            1.0,
+            100,
+        );
+    }
+
+    let rarity = if lower.contains("gem") || lower.contains("ruby") || lower.contains("diamond") || lower.contains("gold") {
+        "rare"
+    } else {
+        "common"
+    };
+    base_item(
+        &display_name,
+        ItemType::Material {
+            material_type: "trinket".to_string(),
+            rarity: rarity.to_string(),
+        },
+        0.5,
+        if rarity == "rare" { 10000 } else { 10 },
+    )
+}
+
+/// Split "a deck of cards, 1 cp and a smoking pipe" into item names
+fn split_item_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .flat_map(|part| part.split(" and "))
+        .map(|part| {
+            let part = part.trim();
+            part.strip_prefix("a ")
+                .or_else(|| part.strip_prefix("an "))
+                .or_else(|| part.strip_prefix("the "))
+                .unwrap_or(part)
+                .trim()
+                .to_string()
+        })
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn title_case(name: &str) -> String {
+    name.split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Corruption band 1-5 from the surrounding page's themes
+pub(crate) fn corruption_band_for(text: &str) -> u8 {
+    let lower = text.to_lowercase();
+    if lower.contains("void") || lower.contains("abyss") {
+        5
+    } else if lower.contains("horror") || lower.contains("defiled") {
+        4
+    } else if lower.contains("cursed") || lower.contains("dread") {
+        3
+    } else if lower.contains("ruin") || lower.contains("haunted") {
+        2
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(raw_value: &str) -> RawEntity {
+        RawEntity::new(
+            "nTR8nJOWabcdef".to_string(),
+            "items".to_string(),
+            "2,640 gp".to_string(),
+            raw_value.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_extracts_treasure_fragments() {
+        let extractor = ItemExtractor::new().unwrap();
+        let templates = extractor.extract(&entity(
+            "<p>Trophy 2,640 gp will be awarded.</p> In the pocket: 1 cp , an empty vial and a smoking pipe . \
+             AC: 14 (Chain shirt) HP: 52 Actions Sickle +3 to hit one target. Inflicts 1d4+1 slashing damage.",
+        ));
+
+        let find = |name: &str| templates.iter().find(|t| t.item.name == name).unwrap();
+        assert_eq!(find("Gold Pieces").item.quantity, 2640);
+        assert_eq!(find("Copper Pieces").item.quantity, 1);
+        assert!(matches!(find("Smoking Pipe").item.item_type, ItemType::Tool { .. }));
+        assert!(matches!(find("Empty Vial").item.item_type, ItemType::Material { .. }));
+        assert!(templates.iter().all(|t| t.source_uuid == "nTR8nJOWabcdef"));
+        // The stat block's armor and weapon are what the creature fights with
+        assert!(templates.iter().all(|t| t.item.name != "Chain Shirt" && t.item.name != "Sickle"));
+    }
+
+    #[test]
+    fn test_dungeon_hoard_items_share_area_uuid() {
+        let area = crate::dungeon_data::tests::cave_area();
+        let dungeons = HashMap::from([(area.entity_name.clone(), vec![area])]);
+
+        let layouts = crate::dungeon_data::build_dungeon_layouts(&dungeons).unwrap();
+        let database = build_item_database(&[&dungeons]).unwrap();
+
+        // The game looks up hoard contents by the room holding the looted treasure
+        let room = layouts[0].rooms.iter().find(|room| room.treasure.is_some()).unwrap();
+        let awarded: Vec<_> = database.from_source(&room.area_uuid).collect();
+        assert_eq!(room.area_uuid, "DmPSseRL");
+        assert!(awarded.iter().any(|t| t.item.name == "Gold Pieces" && t.item.quantity == 201));
+        assert_eq!(database.from_source(&room.treasure.as_ref().unwrap().uuid).count(), 0);
+    }
+
+    #[test]
+    fn test_split_item_list() {
+        assert_eq!(
+            split_item_list("a deck of cards, 1 cp and a smoking pipe "),
+            vec!["deck of cards", "1 cp", "smoking pipe"]
+        );
+    }
+}
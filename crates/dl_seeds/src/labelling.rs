@@ -0,0 +1,170 @@
+//! Labelling workflow for growing the training set
+//!
+//! Uncategorized entities are shown as readable text, least confident first.
+//! A designer picks the category and markers, and the entity is appended as
+//! an example, with its HBF UUID, to that category's `training_data` TOML.
+//! Appending leaves the file's comments and layout as they were.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::classifier::Classification;
+use crate::containers::RawEntity;
+use crate::items::corruption_band_for;
+use crate::orchestration::{
+    EntityCategory, RawEntities, TrainingCategory, TrainingExample, TrainingPatterns, TrainingRepository,
+};
+use crate::utilities::HtmlText;
+
+/// An entity waiting for a label, with the classifier's view of it
+pub struct LabelCandidate<'a> {
+    pub entity: &'a RawEntity,
+    pub classification: Option<&'a Classification>,
+}
+
+/// Uncategorized entities, least confident first; entities the classifier
+/// never scored come last
+pub fn label_candidates(entities: &RawEntities) -> Vec<LabelCandidate<'_>> {
+    let mut candidates: Vec<LabelCandidate> = entities
+        .uncategorized
+        .iter()
+        .map(|entity| LabelCandidate {
+            entity,
+            classification: entities.low_confidence.get(&entity.uuid),
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        let confidence = |c: &LabelCandidate| c.classification.map_or(f64::INFINITY, |c| c.confidence());
+        confidence(a).total_cmp(&confidence(b))
+    });
+    candidates
+}
+
+#[derive(Serialize)]
+struct ExampleEntry<'a> {
+    examples: [&'a TrainingExample; 1],
+}
+
+#[derive(Serialize)]
+struct TrainingFileHeader {
+    category: TrainingCategory,
+    patterns: TrainingPatterns,
+}
+
+/// Renders HBF HTML as text and records labelled examples
+pub struct Labeller {
+    training_dir: PathBuf,
+    html: HtmlText,
+}
+
+impl Labeller {
+    pub fn new<P: AsRef<Path>>(training_dir: P) -> Result<Self> {
+        Ok(Self {
+            training_dir: training_dir.as_ref().to_path_buf(),
+            html: HtmlText::new()?,
+        })
+    }
+
+    /// Readable text of a page
+    pub fn render_text(&self, html: &str) -> String {
+        self.html.render(html)
+    }
+
+    /// An example for a labelled entity; the band is read from its text
+    pub fn example(&self, entity: &RawEntity, markers: Vec<String>, content_patterns: Vec<String>, horror_theme: String) -> TrainingExample {
+        TrainingExample {
+            name: entity.entity_name.clone(),
+            content_patterns,
+            markers,
+            corruption_band: corruption_band_for(&self.render_text(&entity.raw_value)),
+            horror_theme,
+            uuid: Some(entity.uuid.clone()),
+        }
+    }
+
+    /// Append an example to the category's training file, creating one if
+    /// the category has none. Returns the file written.
+    pub fn record(&self, training: &TrainingRepository, category: EntityCategory, example: &TrainingExample) -> Result<PathBuf> {
+        let path = match training.file_for(category) {
+            Some(data) => data.path.clone(),
+            None => self.create_training_file(category)?,
+        };
+
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        writeln!(file)?;
+        write!(file, "{}", toml::to_string(&ExampleEntry { examples: [example] })?)?;
+        Ok(path)
+    }
+
+    fn create_training_file(&self, category: EntityCategory) -> Result<PathBuf> {
+        let dir = self.training_dir.join(category.training_dir());
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("labelled_{}.toml", category.as_str()));
+        if path.exists() {
+            return Ok(path);
+        }
+        let header = TrainingFileHeader {
+            category: TrainingCategory {
+                name: category.training_dir().to_string(),
+                subcategory: category.as_str().to_string(),
+                description: format!("Hand-labelled HBF {}", category.as_str()),
+            },
+            patterns: TrainingPatterns {
+                positive_indicators: Vec::new(),
+                negative_indicators: Vec::new(),
+            },
+        };
+        fs::write(&path, toml::to_string(&header)?)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_text() {
+        let labeller = Labeller::new("training_data").unwrap();
+        let text = labeller.render_text("<h5>Keeper</h5><ul><li>Inn &amp; Stable</li><li>Smith</li></ul><p>A <b>quiet</b>   place</p>");
+        assert_eq!(text, "Keeper\nInn & Stable\nSmith\nA quiet place");
+    }
+
+    #[test]
+    fn test_record_appends_and_reloads() -> Result<()> {
+        let dir = tempdir()?;
+        let spells = dir.path().join("spells");
+        fs::create_dir_all(&spells)?;
+        let original = "# Spell examples\n[category]\nname = \"spells\"\nsubcategory = \"magic\"\ndescription = \"Spells\"\n\n[[examples]]\nname = \"Fireball\"\ncontent_patterns = []\nmarkers = [\"evocation\"]\ncorruption_band = 1\nhorror_theme = \"fire\"\n\n[patterns]\npositive_indicators = [\"spell\"]\nnegative_indicators = []\n";
+        fs::write(spells.join("magic.toml"), original)?;
+
+        let labeller = Labeller::new(dir.path())?;
+        let training = TrainingRepository::load_from_directory(dir.path())?;
+        let entity = RawEntity::new("abc123".to_string(), "unknown".to_string(), "Cursed Bolt".to_string(), "<p>A cursed bolt</p>".to_string());
+        let example = labeller.example(&entity, vec!["necromancy".to_string()], Vec::new(), "decay".to_string());
+        assert_eq!(example.corruption_band, 3);
+
+        let path = labeller.record(&training, EntityCategory::Spells, &example)?;
+        assert_eq!(path, spells.join("magic.toml"));
+        assert!(fs::read_to_string(&path)?.starts_with(original));
+
+        // No regions file yet: one is created under locations
+        let ridge = RawEntity::new("def456".to_string(), "unknown".to_string(), "Ashen Ridge".to_string(), "<p>Hills</p>".to_string());
+        let region = labeller.example(&ridge, Vec::new(), Vec::new(), "none".to_string());
+        let created = labeller.record(&training, EntityCategory::Regions, &region)?;
+        assert_eq!(created, dir.path().join("locations").join("labelled_regions.toml"));
+        assert_eq!(labeller.record(&training, EntityCategory::Regions, &region)?, created);
+
+        let reloaded = TrainingRepository::load_from_directory(dir.path())?;
+        assert_eq!(reloaded.spells[0].examples.len(), 2);
+        assert_eq!(reloaded.spells[0].examples[1], example);
+        assert_eq!(reloaded.label_categories().get("abc123"), Some(&EntityCategory::Spells));
+        assert_eq!(reloaded.label_categories().get("def456"), Some(&EntityCategory::Regions));
+        assert_eq!(reloaded.file_for(EntityCategory::Regions).map(|data| data.examples.len()), Some(2));
+        Ok(())
+    }
+}
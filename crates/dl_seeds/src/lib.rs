@@ -29,6 +29,12 @@ pub mod regions;
 pub mod settlements;
 pub mod dungeons;
 pub mod factions;
+pub mod items;         // HBF treasure -> ItemDatabase
+pub mod settlement_data; // HBF settlement pages -> SettlementDatabase
+pub mod faction_data;    // HBF membership markup -> FactionDatabase
+pub mod npc_data;        // HBF character markup -> NpcDatabase
+pub mod dungeon_data;    // HBF dungeon area pages -> DungeonLayout per dungeon
+pub mod upgrades;        // Upgrade graph over generated model metadata
 
 // Consolidated functionality modules (from other crates)
 pub mod ai_analysis;   // From dl_analysis/src/ai_analysis.rs
@@ -36,6 +42,11 @@ pub mod analysis;      // Existing analysis module
 pub mod containers;    // From dl_analysis/src/containers.rs
 pub mod templates;     // From dl_processors/src/templates.rs
 pub mod orchestration; // Enhanced orchestration (already exists)
+pub mod classifier;    // Naive Bayes entity categorization from training data
+pub mod labelling;     // Recording designer labels into training data
+pub mod query;         // Query language over analyzed entities
+pub mod search;        // Full-text index over entities and seed pools
+pub mod prompts;       // Templated prompt packs for model and dialogue generation
 pub mod reporting;     // From dl_analysis/src/reporting.rs
 pub mod utilities;     // From dl_processors/src/utilities.rs
 pub mod dataframe;     // From dl_audit/src/dataframe.rs
@@ -136,7 +147,7 @@ impl ComprehensiveSeeder {
             &self.books.world_seeds,
             &self.books.quest_seeds,
             &self.books.dialogue_seeds,
-        );
+        )?;
         
         // Save organized pools to disk
         pools.save_to_dir(&pools_dir)?;
@@ -0,0 +1,209 @@
+//! NPC database generation from HBF character markup
+//!
+//! Every NPC in HBF starts with an anchor and a bold name
+//! (`<a class="npc-anchor" id="lIztT4Zj"></a><strong>Melibor Sicletrude</strong>`).
+//! Adventurers carry a stat line (", a level 7 Elf Fighter."), establishment
+//! pages file their people under `<h5>Keeper</h5>` and `<h5>Staff</h5>`, and
+//! faction members have a membership spoiler. Together with the page's
+//! settlement and hex that is enough to say who an NPC is and where they live.
+
+use anyhow::Result;
+use dl_types::world::{classify_npc, NpcDatabase, NpcRecord};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::containers::RawEntity;
+
+/// Build the NPC database from every category that may carry NPCs. Pages
+/// listed in more than one category are only read once, and an NPC keeps
+/// the first page they were found on.
+pub fn build_npc_database(categories: &[&HashMap<String, Vec<RawEntity>>]) -> Result<NpcDatabase> {
+    let extractor = NpcExtractor::new()?;
+    let mut database = NpcDatabase::default();
+    let mut seen = HashSet::new();
+
+    for entity in categories.iter().flat_map(|category| category.values().flatten()) {
+        if seen.insert(entity.uuid.as_str()) {
+            for npc in extractor.extract(entity) {
+                if database.get(&npc.uuid).is_none() {
+                    database.insert(npc);
+                }
+            }
+        }
+    }
+
+    Ok(database)
+}
+
+/// Write the database as pretty RON for the game to load at startup
+pub fn write_npc_database(database: &NpcDatabase, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let ron_content = ron::ser::to_string_pretty(database, ron::ser::PrettyConfig::default())?;
+    std::fs::write(output_path, ron_content)?;
+    Ok(())
+}
+
+/// Compiled patterns for the fragments HBF NPC markup uses
+pub struct NpcExtractor {
+    npc_anchor: Regex,
+    name: Regex,
+    stat_line: Regex,
+    membership: Regex,
+    heading: Regex,
+    doc_title: Regex,
+    settlement: Regex,
+    settlement_link: Regex,
+    hex: Regex,
+}
+
+impl NpcExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            npc_anchor: Regex::new(r#"npc-anchor" id="([A-Za-z0-9]+)""#)?,
+            name: Regex::new(r"^>\s*</a>\s*<strong>([^<]+?)</strong>")?,
+            stat_line: Regex::new(r"^\s*,\s*a level (\d+) ([A-Za-z' -]+?) ([A-Za-z]+)\.")?,
+            membership: Regex::new(r#"Member of the\s*<a href="[^"]*/faction/([A-Za-z0-9]+)">"#)?,
+            heading: Regex::new(r"<h5>([^<]+)</h5>")?,
+            doc_title: Regex::new(r#"id="doc-title">\s*([^<]+?)\s*</div>"#)?,
+            settlement: Regex::new(r#"data-settlement="([A-Za-z0-9]+)""#)?,
+            settlement_link: Regex::new(r#"location/([A-Za-z0-9]+)">\s*(?i:village|town|city|metropolis) of"#)?,
+            hex: Regex::new(r#"hex="([A-Za-z0-9]+)""#)?,
+        })
+    }
+
+    /// Every NPC on one page
+    pub fn extract(&self, entity: &RawEntity) -> Vec<NpcRecord> {
+        let page = &entity.raw_value;
+        let settlement = self
+            .settlement
+            .captures(page)
+            .or_else(|| self.settlement_link.captures(page))
+            .map(|c| c[1].to_string());
+        let hex = self.hex.captures(page).map(|c| c[1].to_string());
+        let establishment = self
+            .doc_title
+            .captures(page)
+            .and_then(|c| establishment_kind(&c[1]));
+
+        let anchors: Vec<(usize, usize, &str)> = self
+            .npc_anchor
+            .captures_iter(page)
+            .filter_map(|c| Some((c.get(0)?.start(), c.get(0)?.end(), c.get(1)?.as_str())))
+            .collect();
+
+        let mut npcs = Vec::new();
+        for (index, (start, end, uuid)) in anchors.iter().enumerate() {
+            // An NPC's block runs up to the next NPC's anchor
+            let block_end = anchors.get(index + 1).map_or(page.len(), |next| next.0);
+            let block = &page[*end..block_end];
+            let Some(name) = self.name.captures(block) else {
+                continue;
+            };
+            let name_end = name.get(0).map_or(0, |m| m.end());
+            let stats = self.stat_line.captures(&block[name_end..]);
+            let class = stats.as_ref().map(|c| c[3].to_string());
+
+            let keeper = self
+                .heading
+                .captures_iter(&page[..*start])
+                .last()
+                .is_some_and(|c| c[1].trim() == "Keeper");
+            let npc_type = classify_npc(
+                establishment.as_deref(),
+                keeper,
+                class.as_deref(),
+                settlement.is_some(),
+            );
+
+            npcs.push(NpcRecord {
+                uuid: uuid.to_string(),
+                name: name[1].trim().to_string(),
+                npc_type,
+                level: stats.as_ref().and_then(|c| c[1].parse().ok()),
+                race: stats.as_ref().map(|c| c[2].trim().to_string()),
+                class,
+                faction: self.membership.captures(block).map(|c| c[1].to_string()),
+                settlement_uuid: settlement.clone(),
+                establishment_uuid: establishment.as_ref().map(|_| entity.uuid.clone()),
+                hex_uuid: hex.clone(),
+                // Adventurers with a stat block have business for the player
+                quest_giver: stats.is_some(),
+            });
+        }
+        npcs
+    }
+}
+
+/// Establishment kind from a page title: "Blacksmith in Dokar" is a
+/// blacksmith, quoted names are taverns. Settlements, districts and hexes
+/// are not establishments.
+fn establishment_kind(title: &str) -> Option<String> {
+    if title.starts_with('"') {
+        return Some("Tavern".to_string());
+    }
+    if title.contains("(district)") || title.starts_with("Hex ") {
+        return None;
+    }
+    title
+        .split_once(" in ")
+        .map(|(kind, _)| kind.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dl_types::world::NPCType;
+
+    fn page(uuid: &str, body: &str) -> RawEntity {
+        RawEntity::new(
+            uuid.to_string(),
+            "settlements".to_string(),
+            "City of Headsmen".to_string(),
+            body.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_extracts_npcs_with_roles() {
+        let tavern = r#"<div id="doc-title">"The Lost Torch Inn" from Headsmen</div><span data-settlement="YVOe8HfG"></span><a class="map-coords" hex="Cyw6XrnL"></a>
+            <h5>Keeper</h5> Owned and managed by <a class="npc-anchor" id="lIztT4Zj"></a><strong>Melibor Sicletrude</strong>. He has glowing black eyes.
+            <h5>Staff</h5> <p> <a class="npc-anchor" id="F1bZ7BBW"></a> <strong>Latilde of Dokar</strong>. She has tired looking eyes.</p>
+            <hr/> <a class="npc-anchor" id="OkmIaNxd"></a> <strong>Helmin of Headbone</strong>, a level 7 Half-Elf Fighter. He has deep blue eyes.
+            <span class="spoiler"> Member of the <a href="/sandbox/nTR8nJOW/faction/uqf2lypH"> <strong>The Red Snakes</strong>. </a></span>"#;
+        let hex_page = r#"<div id="doc-title">Hex 0403</div><a class="map-coords" hex="4MBpzETO"></a>
+            <a class="npc-anchor" id="81bIjlzS"></a> <strong>Kyrianthia Vaux</strong>, a level 7 Halfling Druid."#;
+        let mut settlements = HashMap::new();
+        settlements.insert("headsmen".to_string(), vec![page("P1", tavern)]);
+        let mut regions = HashMap::new();
+        regions.insert("aurora_bushes".to_string(), vec![page("P2", hex_page), page("P1", tavern)]);
+
+        let database = build_npc_database(&[&settlements, &regions]).unwrap();
+        assert_eq!(database.npcs.len(), 4);
+
+        let keeper = database.get("lIztT4Zj").unwrap();
+        assert_eq!(keeper.name, "Melibor Sicletrude");
+        assert_eq!(keeper.npc_type, NPCType::Innkeeper);
+        assert_eq!(keeper.establishment_uuid.as_deref(), Some("P1"));
+        assert_eq!(keeper.settlement_uuid.as_deref(), Some("YVOe8HfG"));
+        assert!(!keeper.quest_giver);
+
+        assert_eq!(database.get("F1bZ7BBW").unwrap().npc_type, NPCType::Villager);
+
+        let fighter = database.get("OkmIaNxd").unwrap();
+        assert_eq!(fighter.npc_type, NPCType::Guard);
+        assert_eq!(fighter.level, Some(7));
+        assert_eq!(fighter.race.as_deref(), Some("Half-Elf"));
+        assert_eq!(fighter.class.as_deref(), Some("Fighter"));
+        assert_eq!(fighter.faction.as_deref(), Some("uqf2lypH"));
+        assert!(fighter.quest_giver);
+
+        let druid = database.get("81bIjlzS").unwrap();
+        assert_eq!(druid.npc_type, NPCType::Hermit);
+        assert_eq!(druid.hex_uuid.as_deref(), Some("4MBpzETO"));
+        assert_eq!(druid.settlement_uuid, None);
+        assert_eq!(database.in_settlement("YVOe8HfG").count(), 3);
+    }
+}
@@ -5,10 +5,11 @@
 
 use anyhow::Result;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
+use crate::classifier::{Classification, ConfusionMatrix, EntityClassifier};
 use crate::containers::RawEntity;
 
 /// Training data for entity categorization
@@ -17,25 +18,31 @@ pub struct TrainingData {
     pub category: TrainingCategory,
     pub examples: Vec<TrainingExample>,
     pub patterns: TrainingPatterns,
+    /// File the data was loaded from
+    #[serde(skip)]
+    pub path: PathBuf,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrainingCategory {
     pub name: String,
     pub subcategory: String,
     pub description: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TrainingExample {
     pub name: String,
     pub content_patterns: Vec<String>,
     pub markers: Vec<String>,
     pub corruption_band: u8,
     pub horror_theme: String,
+    /// HBF entity the example was labelled from, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrainingPatterns {
     pub positive_indicators: Vec<String>,
     pub negative_indicators: Vec<String>,
@@ -50,71 +57,90 @@ pub struct TrainingRepository {
     pub spells: Vec<TrainingData>,
     pub locations: Vec<TrainingData>,
     pub mechanics: Vec<TrainingData>,
+    /// Training files that were left out, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
 }
 
 impl TrainingRepository {
-    /// Load all training data from TOML files
+    /// Load every `*/*.toml` training file, filed by the category it names.
+    /// Files that do not parse or name an unknown category are recorded in
+    /// `skipped` rather than failing the load.
     pub fn load_from_directory<P: AsRef<Path>>(training_dir: P) -> Result<Self> {
         let mut repo = Self::default();
         
         let base_path = training_dir.as_ref();
-        
-        // Load character training data
-        if let Ok(npc_content) = std::fs::read_to_string(base_path.join("characters/npcs.toml")) {
-            if let Ok(training) = toml::from_str::<TrainingData>(&npc_content) {
-                repo.characters.push(training);
-            }
-        }
-        
-        // Load creature training data
-        if let Ok(monster_content) = std::fs::read_to_string(base_path.join("creatures/monsters.toml")) {
-            if let Ok(training) = toml::from_str::<TrainingData>(&monster_content) {
-                repo.creatures.push(training);
-            }
-        }
-        
-        // Load item training data
-        if let Ok(treasure_content) = std::fs::read_to_string(base_path.join("items/treasure.toml")) {
-            if let Ok(training) = toml::from_str::<TrainingData>(&treasure_content) {
-                repo.items.push(training);
-            }
-        }
-        
-        // Load spell training data
-        if let Ok(magic_content) = std::fs::read_to_string(base_path.join("spells/magic_systems.toml")) {
-            if let Ok(training) = toml::from_str::<TrainingData>(&magic_content) {
-                repo.spells.push(training);
-            }
-        }
-        
-        // Load location training data (all subcategories)
-        let location_files = ["dungeons.toml", "regions.toml", "settlements.toml", "factions.toml"];
-        for location_file in location_files {
-            if let Ok(location_content) = std::fs::read_to_string(base_path.join("locations").join(location_file)) {
-                if let Ok(training) = toml::from_str::<TrainingData>(&location_content) {
-                    repo.locations.push(training);
+        let mut files = Vec::new();
+        for category_dir in std::fs::read_dir(base_path)?.flatten() {
+            if !category_dir.path().is_dir() {
+                continue;
+            }
+            for file in std::fs::read_dir(category_dir.path())?.flatten() {
+                if file.path().extension().is_some_and(|ext| ext == "toml") {
+                    files.push(file.path());
                 }
             }
         }
+        files.sort();
         
-        // Load mechanics training data
-        if let Ok(dice_content) = std::fs::read_to_string(base_path.join("mechanics/dice_rules.toml")) {
-            if let Ok(training) = toml::from_str::<TrainingData>(&dice_content) {
-                repo.mechanics.push(training);
+        for path in files {
+            let content = std::fs::read_to_string(&path)?;
+            let training = match toml::from_str::<TrainingData>(&content) {
+                Ok(training) => TrainingData { path: path.clone(), ..training },
+                Err(e) => {
+                    repo.skipped.push((path, e.to_string()));
+                    continue;
+                }
+            };
+            match training.category.name.as_str() {
+                "characters" => repo.characters.push(training),
+                "creatures" => repo.creatures.push(training),
+                "items" => repo.items.push(training),
+                "spells" => repo.spells.push(training),
+                "locations" => repo.locations.push(training),
+                "mechanics" => repo.mechanics.push(training),
+                other => {
+                    let reason = format!("unknown training category '{}'", other);
+                    repo.skipped.push((path, reason));
+                }
             }
         }
         
         Ok(repo)
     }
     
+    /// Every loaded training file
+    pub fn all(&self) -> impl Iterator<Item = &TrainingData> {
+        self.characters
+            .iter()
+            .chain(&self.creatures)
+            .chain(&self.items)
+            .chain(&self.spells)
+            .chain(&self.locations)
+            .chain(&self.mechanics)
+    }
+    
     /// Get total training examples loaded
     pub fn total_examples(&self) -> usize {
-        self.characters.iter().map(|t| t.examples.len()).sum::<usize>() +
-        self.creatures.iter().map(|t| t.examples.len()).sum::<usize>() +
-        self.items.iter().map(|t| t.examples.len()).sum::<usize>() +
-        self.spells.iter().map(|t| t.examples.len()).sum::<usize>() +
-        self.locations.iter().map(|t| t.examples.len()).sum::<usize>() +
-        self.mechanics.iter().map(|t| t.examples.len()).sum::<usize>()
+        self.all().map(|t| t.examples.len()).sum()
+    }
+    
+    /// Category of each entity labelled into an example, by UUID
+    pub fn label_categories(&self) -> HashMap<&str, EntityCategory> {
+        self.all()
+            .filter_map(|data| Some((EntityCategory::from_training(&data.category.name, &data.category.subcategory)?, data)))
+            .flat_map(|(category, data)| {
+                data.examples
+                    .iter()
+                    .filter_map(move |example| Some((example.uuid.as_deref()?, category)))
+            })
+            .collect()
+    }
+    
+    /// The loaded file holding examples of a category
+    pub fn file_for(&self, category: EntityCategory) -> Option<&TrainingData> {
+        self.all().find(|data| {
+            EntityCategory::from_training(&data.category.name, &data.category.subcategory) == Some(category)
+        })
     }
 }
 
@@ -143,6 +169,12 @@ pub struct RawEntities {
     pub uncategorized: Vec<RawEntity>,
     /// Total count of all entities processed
     pub total_entities: usize,
+    /// Classifier scores for entities it was not confident about, by UUID
+    #[serde(skip)]
+    pub low_confidence: HashMap<String, Classification>,
+    /// Cross-validated performance of the classifier used, if any
+    #[serde(skip)]
+    pub evaluation: Option<ConfusionMatrix>,
 }
 
 impl RawEntities {
@@ -160,6 +192,8 @@ impl RawEntities {
             mechanics: HashMap::new(),
             uncategorized: Vec::new(),
             total_entities: 0,
+            low_confidence: HashMap::new(),
+            evaluation: None,
         }
     }
 
@@ -170,148 +204,28 @@ impl RawEntities {
         Ok((entities, training_repo))
     }
 
-    /// Enhanced categorization using training data patterns
-    fn categorize_entity_with_training(&self, entity: &RawEntity, training: &TrainingRepository) -> Option<EntityCategory> {
-        let content = entity.raw_value.to_lowercase();
-        let name = entity.entity_name.to_lowercase();
-        
-        // Check training patterns for each category
-        
-        // Characters (NPCs)
-        for char_training in &training.characters {
-            for example in &char_training.examples {
-                if name.contains(&example.name.to_lowercase()) ||
-                   self.matches_training_patterns(&content, &name, &example.markers) {
-                    return Some(EntityCategory::Characters);
-                }
-            }
-            if self.matches_positive_indicators(&content, &name, &char_training.patterns.positive_indicators) {
-                return Some(EntityCategory::Characters);
-            }
-        }
-        
-        // Creatures  
-        for creature_training in &training.creatures {
-            for example in &creature_training.examples {
-                if name.contains(&example.name.to_lowercase()) ||
-                   self.matches_training_patterns(&content, &name, &example.markers) {
-                    return Some(EntityCategory::Creatures);
-                }
-            }
-            if self.matches_positive_indicators(&content, &name, &creature_training.patterns.positive_indicators) {
-                return Some(EntityCategory::Creatures);
-            }
-        }
-        
-        // Items
-        for item_training in &training.items {
-            for example in &item_training.examples {
-                if name.contains(&example.name.to_lowercase()) ||
-                   self.matches_training_patterns(&content, &name, &example.markers) {
-                    return Some(EntityCategory::Items);
-                }
-            }
-            if self.matches_positive_indicators(&content, &name, &item_training.patterns.positive_indicators) {
-                return Some(EntityCategory::Items);
-            }
-        }
-        
-        // Spells
-        for spell_training in &training.spells {
-            for example in &spell_training.examples {
-                if name.contains(&example.name.to_lowercase()) ||
-                   self.matches_training_patterns(&content, &name, &example.markers) {
-                    return Some(EntityCategory::Spells);
-                }
-            }
-            if self.matches_positive_indicators(&content, &name, &spell_training.patterns.positive_indicators) {
-                return Some(EntityCategory::Spells);
-            }
-        }
-        
-        // Mechanics
-        for mech_training in &training.mechanics {
-            for example in &mech_training.examples {
-                if name.contains(&example.name.to_lowercase()) ||
-                   self.matches_training_patterns(&content, &name, &example.markers) {
-                    return Some(EntityCategory::Mechanics);
-                }
-            }
-            if self.matches_positive_indicators(&content, &name, &mech_training.patterns.positive_indicators) {
-                return Some(EntityCategory::Mechanics);
-            }
-        }
-        
-        // Fall back to original categorization
-        self.categorize_entity(entity)
-    }
-    
-    /// Check if content matches training patterns
-    fn matches_training_patterns(&self, content: &str, name: &str, markers: &[String]) -> bool {
-        for marker in markers {
-            let marker_lower = marker.to_lowercase();
-            if content.contains(&marker_lower) || name.contains(&marker_lower) {
-                return true;
-            }
-        }
-        false
-    }
-    
-    /// Check if content matches positive indicators
-    fn matches_positive_indicators(&self, content: &str, name: &str, indicators: &[String]) -> bool {
-        for indicator in indicators {
-            let indicator_lower = indicator.to_lowercase();
-            if content.contains(&indicator_lower) || name.contains(&indicator_lower) {
-                return true;
-            }
-        }
-        false
-    }
-
     /// Add an entity to the appropriate category or uncategorized list
     pub fn add_entity(&mut self, uuid: String, raw_value: String) {
+        let entity = self.raw_entity(uuid, raw_value);
+        let category = self.categorize_entity(&entity);
+        self.file_entity(entity, category);
+    }
+
+    /// Build a raw entity with its name and rough category read from the HTML
+    fn raw_entity(&self, uuid: String, raw_value: String) -> RawEntity {
         // Extract meaningful entity name and category from content
         let (category, entity_name) = self.extract_category_and_name(&raw_value);
-        let entity = RawEntity::new(uuid, category.clone(), entity_name, raw_value);
+        RawEntity::new(uuid, category, entity_name, raw_value)
+    }
+
+    /// File an entity under its category, or as uncategorized
+    fn file_entity(&mut self, entity: RawEntity, category: Option<EntityCategory>) {
         self.total_entities += 1;
 
-        // Categorize based on extracted information
-        match self.categorize_entity(&entity) {
-            Some(EntityCategory::Regions) => {
-                let key = self.extract_entity_name(&entity, "regions");
-                self.regions.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Settlements) => {
-                let key = self.extract_entity_name(&entity, "settlements");
-                self.settlements.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Factions) => {
-                let key = self.extract_entity_name(&entity, "factions");
-                self.factions.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Dungeons) => {
-                let key = self.extract_entity_name(&entity, "dungeons");
-                self.dungeons.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Characters) => {
-                let key = self.extract_entity_name(&entity, "characters");
-                self.characters.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Creatures) => {
-                let key = self.extract_entity_name(&entity, "creatures");
-                self.creatures.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Items) => {
-                let key = self.extract_entity_name(&entity, "items");
-                self.items.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Spells) => {
-                let key = self.extract_entity_name(&entity, "spells");
-                self.spells.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Mechanics) => {
-                let key = self.extract_entity_name(&entity, "mechanics");
-                self.mechanics.entry(key).or_insert_with(Vec::new).push(entity);
+        match category {
+            Some(category) => {
+                let key = self.extract_entity_name(&entity, category.as_str());
+                self.category_mut(category).entry(key).or_default().push(entity);
             }
             None => {
                 self.uncategorized.push(entity);
@@ -319,6 +233,35 @@ impl RawEntities {
         }
     }
 
+    /// Entities filed under a category, by group name
+    pub fn category(&self, category: EntityCategory) -> &HashMap<String, Vec<RawEntity>> {
+        match category {
+            EntityCategory::Regions => &self.regions,
+            EntityCategory::Settlements => &self.settlements,
+            EntityCategory::Factions => &self.factions,
+            EntityCategory::Dungeons => &self.dungeons,
+            EntityCategory::Characters => &self.characters,
+            EntityCategory::Creatures => &self.creatures,
+            EntityCategory::Items => &self.items,
+            EntityCategory::Spells => &self.spells,
+            EntityCategory::Mechanics => &self.mechanics,
+        }
+    }
+
+    pub fn category_mut(&mut self, category: EntityCategory) -> &mut HashMap<String, Vec<RawEntity>> {
+        match category {
+            EntityCategory::Regions => &mut self.regions,
+            EntityCategory::Settlements => &mut self.settlements,
+            EntityCategory::Factions => &mut self.factions,
+            EntityCategory::Dungeons => &mut self.dungeons,
+            EntityCategory::Characters => &mut self.characters,
+            EntityCategory::Creatures => &mut self.creatures,
+            EntityCategory::Items => &mut self.items,
+            EntityCategory::Spells => &mut self.spells,
+            EntityCategory::Mechanics => &mut self.mechanics,
+        }
+    }
+
     /// Load entities from HBF SQLite database
     pub fn load_from_hbf_database<P: AsRef<Path>>(&mut self, hbf_database_path: P) -> Result<()> {
         let connection = rusqlite::Connection::open(hbf_database_path.as_ref())?;
@@ -341,7 +284,10 @@ impl RawEntities {
         Ok(())
     }
 
-    /// Load entities from HBF SQLite database with enhanced training-based categorization
+    /// Load entities from HBF SQLite database, categorized by a classifier
+    /// trained from the training data and any labelled entities. Labelled
+    /// entities keep their label; the rest go where the classifier is
+    /// confident, or stay uncategorized with their scores kept for review.
     pub fn load_from_hbf_database_with_training<P: AsRef<Path>>(
         &mut self, 
         hbf_database_path: P, 
@@ -359,63 +305,39 @@ impl RawEntities {
             Ok((uuid, value))
         })?;
         
+        let mut entities = Vec::new();
         for row in rows {
             let (uuid, value) = row?;
-            self.add_entity_with_training(uuid, value, training);
+            entities.push(self.raw_entity(uuid, value));
         }
 
-        Ok(())
+        self.add_entities_with_training(entities, training)
     }
 
-    /// Add entity using enhanced training-based categorization
-    pub fn add_entity_with_training(&mut self, uuid: String, raw_value: String, training: &TrainingRepository) {
-        // Extract meaningful entity name and category from content
-        let (category, entity_name) = self.extract_category_and_name(&raw_value);
-        let entity = RawEntity::new(uuid, category.clone(), entity_name, raw_value);
-        self.total_entities += 1;
+    /// Classify and file entities, training on the labelled ones among them
+    pub fn add_entities_with_training(&mut self, entities: Vec<RawEntity>, training: &TrainingRepository) -> Result<()> {
+        let labels = training.label_categories();
+        let labelled: Vec<(EntityCategory, &RawEntity)> = entities
+            .iter()
+            .filter_map(|entity| Some((*labels.get(entity.uuid.as_str())?, entity)))
+            .collect();
+        let classifier = EntityClassifier::train(training, &labelled)?;
 
-        // Use enhanced categorization with training data
-        match self.categorize_entity_with_training(&entity, training) {
-            Some(EntityCategory::Regions) => {
-                let key = self.extract_entity_name(&entity, "regions");
-                self.regions.entry(key).or_insert_with(Vec::new).push(entity);
+        for entity in entities {
+            if let Some(category) = labels.get(entity.uuid.as_str()) {
+                self.file_entity(entity, Some(*category));
+                continue;
             }
-            Some(EntityCategory::Settlements) => {
-                let key = self.extract_entity_name(&entity, "settlements");
-                self.settlements.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Factions) => {
-                let key = self.extract_entity_name(&entity, "factions");
-                self.factions.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Dungeons) => {
-                let key = self.extract_entity_name(&entity, "dungeons");
-                self.dungeons.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Characters) => {
-                let key = self.extract_entity_name(&entity, "characters");
-                self.characters.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Creatures) => {
-                let key = self.extract_entity_name(&entity, "creatures");
-                self.creatures.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Items) => {
-                let key = self.extract_entity_name(&entity, "items");
-                self.items.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Spells) => {
-                let key = self.extract_entity_name(&entity, "spells");
-                self.spells.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            Some(EntityCategory::Mechanics) => {
-                let key = self.extract_entity_name(&entity, "mechanics");
-                self.mechanics.entry(key).or_insert_with(Vec::new).push(entity);
-            }
-            None => {
-                self.uncategorized.push(entity);
+            let classification = classifier.classify(&entity);
+            let category = classification.category();
+            if category.is_none() {
+                self.low_confidence.insert(entity.uuid.clone(), classification);
             }
+            self.file_entity(entity, category);
         }
+        self.evaluation = Some(classifier.evaluation);
+
+        Ok(())
     }
 
     /// Write all clustered entities to disk for processing pipeline
@@ -659,7 +581,7 @@ impl Default for RawEntities {
 }
 
 /// Entity categories for classification
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EntityCategory {
     Regions,
     Settlements,
@@ -673,6 +595,36 @@ pub enum EntityCategory {
 }
 
 impl EntityCategory {
+    pub const ALL: [EntityCategory; 9] = [
+        EntityCategory::Regions,
+        EntityCategory::Settlements,
+        EntityCategory::Factions,
+        EntityCategory::Dungeons,
+        EntityCategory::Characters,
+        EntityCategory::Creatures,
+        EntityCategory::Items,
+        EntityCategory::Spells,
+        EntityCategory::Mechanics,
+    ];
+
+    /// Category from its `as_str` name
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.as_str() == name)
+    }
+
+    /// Category of a training file; locations are split by subcategory
+    pub fn from_training(name: &str, subcategory: &str) -> Option<Self> {
+        if name == "locations" { Self::parse(subcategory) } else { Self::parse(name) }
+    }
+
+    /// Training folder this category's examples live in
+    pub fn training_dir(&self) -> &'static str {
+        match self {
+            EntityCategory::Regions | EntityCategory::Settlements | EntityCategory::Factions | EntityCategory::Dungeons => "locations",
+            other => other.as_str(),
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             EntityCategory::Regions => "regions",
@@ -765,4 +717,28 @@ mod tests {
         let summary = entities.get_analysis_summary();
         assert_eq!(summary.total_entities, 2);
     }
+
+    #[test]
+    fn test_training_labels_and_classifier() -> Result<()> {
+        let training_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("training_data");
+        let mut training = TrainingRepository::load_from_directory(&training_dir)?;
+        assert!(training.total_examples() > 0);
+        let spells = training.spells.first_mut().unwrap();
+        assert!(spells.path.ends_with("spells/magic_systems.toml"));
+        spells.examples[0].uuid = Some("labelled".to_string());
+
+        let mut entities = RawEntities::new();
+        let labelled = RawEntity::new("labelled".to_string(), "unknown".to_string(), "5 Onis".to_string(), String::new());
+        let unknown = RawEntity::new("unknown".to_string(), "unknown".to_string(), String::new(), "<span></span>".to_string());
+        entities.add_entities_with_training(vec![labelled, unknown], &training)?;
+
+        // A label always wins over the classifier
+        assert_eq!(entities.spells.values().flatten().count(), 1);
+        assert_eq!(entities.total_entities, 2);
+        assert!(entities.evaluation.as_ref().is_some_and(|matrix| matrix.total() > 0));
+        if entities.uncategorized.iter().any(|entity| entity.uuid == "unknown") {
+            assert!(entities.low_confidence.contains_key("unknown"));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,771 @@
+//! Prompt packs for model and dialogue generation
+//!
+//! The prompter builds one `ModelPrompt` or `DialoguePrompt` per entity and
+//! renders it through Tera templates. Built-in templates cover every prompt;
+//! a templates directory can override any of them per faction, per category
+//! or both, so a cult with its own look does not need code changes.
+//!
+//! Packs are written as Markdown (one file per prompt), JSONL for batch
+//! generation tools and CSV for spreadsheets, always with an index. Prompt
+//! ids come from the faction and entity name, with the entity uuid added
+//! only when names collide, so they stay the same between runs and do not
+//! depend on the order entities were read in.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+use crate::utilities::sanitize_name;
+
+/// Suffix of prompt template files
+pub const PROMPT_TEMPLATE_EXTENSION: &str = "tera";
+/// Batch export file names, written inside each pack's directory
+pub const PACK_JSONL_FILE: &str = "prompt_pack.jsonl";
+pub const PACK_CSV_FILE: &str = "prompt_pack.csv";
+pub const PACK_INDEX_FILE: &str = "index.md";
+
+const MODEL_TEMPLATE: &str = r#"# 3D Model Generation Prompt: {{ prompt.display_name }}
+
+## Model Overview
+- **ID**: `{{ prompt.id }}`
+- **Category**: {{ prompt.category }}
+- **Faction**: {{ prompt.faction }}
+
+## Primary Generation Prompt
+```
+{{ prompt.primary_prompt }}
+```
+
+## Style Guidelines
+```
+{{ prompt.style_prompt }}
+```
+
+## Technical Specifications
+- **Target Poly Count**: {{ prompt.technical_specs.target_poly_count }}
+- **Texture Resolution**: {{ prompt.technical_specs.texture_resolution }}
+- **Bone Structure**: {{ prompt.technical_specs.bone_structure }}
+- **Corruption Band Budget**: band {{ prompt.technical_specs.corruption_band }}, at most {{ prompt.technical_specs.budget.max_triangles }} triangles and {{ prompt.technical_specs.budget.texture_size }}px textures
+
+### Required Animations
+{% for animation in prompt.technical_specs.required_animations -%}
+- {{ animation }}
+{% endfor %}
+### Material Requirements
+{% for material in prompt.technical_specs.material_requirements -%}
+- {{ material }}
+{% endfor %}
+## Corruption Progression
+{% if prompt.corruption_progression -%}
+**Band {{ prompt.corruption_progression.band }}**: {{ prompt.corruption_progression.theme }} - {{ prompt.corruption_progression.visual_evolution }}
+{%- else -%}
+Standard progression
+{%- endif %}
+
+## Reference Images/Concepts
+{% for reference in prompt.reference_images -%}
+- {{ reference }}
+{% endfor %}
+## Animation Requirements
+{% for requirement in prompt.animation_requirements -%}
+- {{ requirement }}
+{% endfor %}
+---
+*Generated by Dragon's Labyrinth Replit Prompter*
+*Use this template with Replit's 3D model generation capabilities*
+"#;
+
+const DIALOGUE_TEMPLATE: &str = r#"# Dialogue Generation Prompt: {{ prompt.character_name }}
+
+## Character Overview
+- **ID**: `{{ prompt.id }}`
+- **Role**: {{ prompt.role }}
+- **Faction**: {{ prompt.faction }}
+
+## Personality Prompt
+```
+{{ prompt.personality_prompt }}
+```
+
+## Trauma Indicators (Dragon's Labyrinth Integration)
+{% for indicator in prompt.trauma_indicators -%}
+- {{ indicator }}
+{% endfor %}
+## Speech Patterns
+{% for pattern in prompt.speech_patterns -%}
+- {{ pattern }}
+{% endfor %}
+## Sample Interaction Types
+{% for interaction in prompt.sample_interactions -%}
+- {{ interaction }}
+{% endfor %}
+## Corruption Evolution
+{{ prompt.corruption_evolution | default(value="Standard character development") }}
+
+## Yarnspinner Integration Notes
+- Create `.yarn` files with branching dialogue trees
+- Include trauma state variables for dynamic responses
+- Implement corruption level checks for dialogue variations
+- Add faction-specific terminology and references
+
+---
+*Generated by Dragon's Labyrinth Replit Prompter*
+*Use this template for Yarnspinner dialogue generation*
+"#;
+
+const STYLE_TEMPLATE: &str = r#"
+{%- if "crimson" in faction_key or "blood" in faction_key -%}
+Crimson Covenant aesthetic: Deep reds, blood-infused materials, bone decorations, ritualistic scarification, flowing robes with crimson trim, copper and bronze metals with blood-red patina. Emphasize organic, flowing forms with sharp ritual implements.
+{%- elif "deep" in faction_key or "water" in faction_key or "ocean" in faction_key -%}
+Order of the Deep aesthetic: Deep blues and greens, aquatic textures, scaled surfaces, coral-like growths, barnacle encrustation, tarnished silver and copper. Emphasize flowing, water-influenced forms with bioluminescent accents.
+{%- elif "void" in faction_key or "shadow" in faction_key or "dark" in faction_key -%}
+Void Seekers aesthetic: Deep purples and blacks, crystalline void structures, geometric impossibilities, reality-warping materials, obsidian and dark crystals. Emphasize angular, geometric forms with void-touched corruption.
+{%- else -%}
+Generic horror aesthetic: Muted colors progressing to darkness, weathered materials, asymmetrical design elements, non-Euclidean geometry hints. Focus on unsettling proportions and otherworldly details.
+{%- endif %}
+{%- if "priest" in content or "ritual" in content %} Include ceremonial elements, religious iconography, and ritual implements.
+{%- elif "warrior" in content or "combat" in content %} Include battle-worn armor, weapon integration, and combat readiness.
+{%- elif "building" in content or "structure" in content %} Include architectural elements that suggest both function and cosmic significance.
+{%- else %} Include subtle horror elements that suggest cosmic awareness.
+{%- endif -%}
+"#;
+
+const REFERENCES_TEMPLATE: &str = r#"
+{%- if "crimson" in faction_key or "blood" in faction_key %}
+Bloodborne character designs
+Dark Souls 3 cathedral knights
+Warhammer Khorne aesthetics
+Medieval inquisition imagery
+{%- elif "deep" in faction_key or "water" in faction_key %}
+Lovecraft Deep Ones concept art
+Bioshock underwater aesthetics
+Subnautica leviathan designs
+Call of Cthulhu aquatic horrors
+{%- elif "void" in faction_key or "shadow" in faction_key %}
+Event Horizon spacecraft design
+Warhammer 40k Chaos aesthetics
+Dead Space necromorph designs
+Lovecraft void imagery
+{%- else %}
+Generic Lovecraftian horror
+{%- endif %}
+{%- if category == "building" %}
+Gothic cathedral architecture
+Ancient temple ruins
+Eldritch architectural impossibilities
+{%- elif category == "leader_unit" %}
+Dark fantasy leader designs
+Cult master aesthetics
+Imposing horror antagonists
+{%- endif %}
+"#;
+
+/// The prompt pieces a template directory can override
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    /// Markdown document for one model prompt
+    Model,
+    /// Markdown document for one dialogue prompt
+    Dialogue,
+    /// Faction style guidelines inside a model prompt
+    Style,
+    /// Reference images, one per line
+    References,
+}
+
+impl TemplateKind {
+    pub const ALL: [TemplateKind; 4] =
+        [TemplateKind::Model, TemplateKind::Dialogue, TemplateKind::Style, TemplateKind::References];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TemplateKind::Model => "model",
+            TemplateKind::Dialogue => "dialogue",
+            TemplateKind::Style => "style",
+            TemplateKind::References => "references",
+        }
+    }
+
+    fn builtin(self) -> &'static str {
+        match self {
+            TemplateKind::Model => MODEL_TEMPLATE,
+            TemplateKind::Dialogue => DIALOGUE_TEMPLATE,
+            TemplateKind::Style => STYLE_TEMPLATE,
+            TemplateKind::References => REFERENCES_TEMPLATE,
+        }
+    }
+}
+
+/// Tera templates for every prompt piece, with per-faction and
+/// per-category overrides
+///
+/// An override is a `<kind>.tera` file in the templates directory. The most
+/// specific one wins: `<faction>/<category>/`, then `<faction>/`, then
+/// `<category>/`, then the directory root, then the built-in template.
+/// Faction and category directory names are sanitized the same way as the
+/// prompt output directories.
+#[derive(Debug, Clone)]
+pub struct PromptTemplates {
+    tera: tera::Tera,
+    overrides: Vec<String>,
+}
+
+impl PromptTemplates {
+    /// Built-in templates only
+    pub fn new() -> Result<Self> {
+        let mut tera = tera::Tera::default();
+        for kind in TemplateKind::ALL {
+            tera.add_raw_template(&template_name("", kind), kind.builtin())?;
+        }
+        Ok(Self { tera, overrides: Vec::new() })
+    }
+
+    /// Built-in templates plus every override under `dir`
+    pub fn with_overrides(dir: &Path) -> Result<Self> {
+        let mut templates = Self::new()?;
+        if !dir.exists() {
+            bail!("Template directory {} does not exist", dir.display());
+        }
+        for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some(PROMPT_TEMPLATE_EXTENSION) {
+                continue;
+            }
+            let relative = path.strip_prefix(dir)?;
+            let name: Vec<String> = relative.iter().map(|part| part.to_string_lossy().into_owned()).collect();
+            let name = name.join("/");
+            templates.tera.add_raw_template(&name, &std::fs::read_to_string(path)?)?;
+            templates.overrides.push(name);
+        }
+        templates.overrides.sort();
+        Ok(templates)
+    }
+
+    /// Names of the override templates loaded, sorted
+    pub fn overrides(&self) -> &[String] {
+        &self.overrides
+    }
+
+    /// The template used for `kind` with this faction and category
+    pub fn resolve(&self, kind: TemplateKind, faction: &str, category: &str) -> String {
+        let faction = sanitize_name(faction);
+        let category = sanitize_name(category);
+        [format!("{}/{}/", faction, category), format!("{}/", faction), format!("{}/", category)]
+            .iter()
+            .map(|prefix| template_name(prefix, kind))
+            .find(|name| self.tera.get_template(name).is_ok())
+            .unwrap_or_else(|| template_name("", kind))
+    }
+
+    pub fn render(&self, kind: TemplateKind, faction: &str, category: &str, context: &tera::Context) -> Result<String> {
+        let name = self.resolve(kind, faction, category);
+        let mut context = context.clone();
+        context.insert("faction", faction);
+        context.insert("faction_key", &faction.to_lowercase());
+        context.insert("category", category);
+        Ok(self.tera.render(&name, &context)?)
+    }
+
+    /// Style guidelines for a model; `content` is the entity's HBF content
+    pub fn style_prompt(&self, faction: &str, category: &str, content: &str) -> Result<String> {
+        let mut context = tera::Context::new();
+        context.insert("content", &content.to_lowercase());
+        Ok(self.render(TemplateKind::Style, faction, category, &context)?.trim().to_string())
+    }
+
+    /// Reference images and concepts for a model
+    pub fn reference_images(&self, faction: &str, category: &str) -> Result<Vec<String>> {
+        let rendered = self.render(TemplateKind::References, faction, category, &tera::Context::new())?;
+        Ok(rendered.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect())
+    }
+}
+
+fn template_name(prefix: &str, kind: TemplateKind) -> String {
+    format!("{}{}.{}", prefix, kind.name(), PROMPT_TEMPLATE_EXTENSION)
+}
+
+/// Triangle and texture budget for one model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelBudget {
+    pub min_triangles: u32,
+    pub max_triangles: u32,
+    /// Square texture edge in pixels
+    pub texture_size: u32,
+}
+
+impl ModelBudget {
+    /// Budget for a model category at a corruption band. Each band past the
+    /// first allows a quarter more triangles for distortion geometry, and
+    /// bands 4 and 5 double the texture size for the corruption detail.
+    pub fn for_band(category: &str, band: u8) -> Self {
+        let (min_triangles, max_triangles, texture_size) = match category {
+            "warrior_unit" | "priest_unit" | "cultist_unit" => (2000, 5000, 1024),
+            "leader_unit" => (5000, 8000, 2048),
+            "building" => (3000, 10000, 2048),
+            _ => (1000, 3000, 512),
+        };
+        let extra_bands = u32::from(band.clamp(1, 5) - 1);
+        let scale = |triangles: u32| triangles + triangles * extra_bands / 4;
+        Self {
+            min_triangles: scale(min_triangles),
+            max_triangles: scale(max_triangles),
+            texture_size: if band >= 4 { (texture_size * 2).min(4096) } else { texture_size },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechnicalSpecs {
+    pub target_poly_count: String,
+    pub texture_resolution: String,
+    pub required_animations: Vec<String>,
+    pub bone_structure: String,
+    pub material_requirements: Vec<String>,
+    pub corruption_band: u8,
+    pub budget: ModelBudget,
+}
+
+impl TechnicalSpecs {
+    /// Specs for a model category, with budgets for its corruption band
+    pub fn for_category(category: &str, band: u8) -> Self {
+        let budget = ModelBudget::for_band(category, band);
+        let (maps, animations, bone_structure, materials): (&str, &[&str], &str, &[&str]) = match category {
+            "warrior_unit" | "priest_unit" | "cultist_unit" => (
+                "diffuse, normal, roughness",
+                &["idle", "walk", "attack", "death"],
+                "Humanoid rig with 20-30 bones",
+                &["PBR materials", "Faction-specific textures", "Wear/corruption details"],
+            ),
+            "leader_unit" => (
+                "diffuse, normal, roughness, emission",
+                &["idle", "walk", "attack", "cast_spell", "command", "death"],
+                "Advanced humanoid rig with 35-50 bones",
+                &["Hero-quality PBR materials", "Emissive effects", "Advanced shader features"],
+            ),
+            "building" => (
+                "tileable textures",
+                &["idle", "construction", "destruction"],
+                "Static mesh or simple destructible segments",
+                &["Architectural PBR materials", "Weathering and age details", "Faction-specific decorations"],
+            ),
+            _ => ("basic textures", &["idle"], "Simple or static mesh", &["Basic PBR materials"]),
+        };
+        Self {
+            target_poly_count: format!("{}-{} triangles", budget.min_triangles, budget.max_triangles),
+            texture_resolution: format!("{0}x{0} {1}", budget.texture_size, maps),
+            required_animations: animations.iter().map(|a| a.to_string()).collect(),
+            bone_structure: bone_structure.to_string(),
+            material_requirements: materials.iter().map(|m| m.to_string()).collect(),
+            corruption_band: band,
+            budget,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorruptionProgression {
+    pub band: u8,
+    pub theme: String,
+    pub visual_evolution: String,
+    pub material_changes: String,
+}
+
+/// Prompt for one 3D model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrompt {
+    /// Stable pack id, assigned by `PromptPack::finish`
+    pub id: String,
+    pub source_uuid: String,
+    pub display_name: String,
+    pub category: String,
+    pub faction: String,
+    pub primary_prompt: String,
+    pub style_prompt: String,
+    pub technical_specs: TechnicalSpecs,
+    pub corruption_progression: Option<CorruptionProgression>,
+    pub reference_images: Vec<String>,
+    pub animation_requirements: Vec<String>,
+}
+
+/// Prompt for one character's Yarnspinner dialogue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialoguePrompt {
+    /// Stable pack id, assigned by `PromptPack::finish`
+    pub id: String,
+    pub source_uuid: String,
+    pub character_name: String,
+    pub faction: String,
+    pub role: String,
+    pub personality_prompt: String,
+    pub trauma_indicators: Vec<String>,
+    pub speech_patterns: Vec<String>,
+    pub sample_interactions: Vec<String>,
+    pub corruption_evolution: Option<String>,
+}
+
+/// One prompt as a flat record, for JSONL and CSV export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackRecord {
+    pub id: String,
+    pub kind: String,
+    pub source_uuid: String,
+    pub name: String,
+    pub faction: String,
+    pub category: String,
+    pub corruption_band: Option<u8>,
+    pub max_triangles: Option<u32>,
+    pub texture_size: Option<u32>,
+    pub prompt: String,
+    pub style: String,
+    /// Markdown file, relative to the pack directory
+    pub file: String,
+}
+
+/// A prompt that can be collected into a pack
+pub trait PackPrompt: Serialize {
+    /// Pack directory under the prompter output, and the id prefix
+    const PACK: &'static str;
+    /// Markdown file suffix
+    const SUFFIX: &'static str;
+    const TEMPLATE: TemplateKind;
+
+    fn name(&self) -> &str;
+    fn faction(&self) -> &str;
+    fn category(&self) -> &str;
+    fn source_uuid(&self) -> &str;
+    fn id(&self) -> &str;
+    fn set_id(&mut self, id: String);
+    fn record(&self) -> PackRecord;
+}
+
+impl PackPrompt for ModelPrompt {
+    const PACK: &'static str = "model_prompts";
+    const SUFFIX: &'static str = "prompt";
+    const TEMPLATE: TemplateKind = TemplateKind::Model;
+
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+    fn faction(&self) -> &str {
+        &self.faction
+    }
+    fn category(&self) -> &str {
+        &self.category
+    }
+    fn source_uuid(&self) -> &str {
+        &self.source_uuid
+    }
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn record(&self) -> PackRecord {
+        PackRecord {
+            id: self.id.clone(),
+            kind: "model".to_string(),
+            source_uuid: self.source_uuid.clone(),
+            name: self.display_name.clone(),
+            faction: self.faction.clone(),
+            category: self.category.clone(),
+            corruption_band: Some(self.technical_specs.corruption_band),
+            max_triangles: Some(self.technical_specs.budget.max_triangles),
+            texture_size: Some(self.technical_specs.budget.texture_size),
+            prompt: self.primary_prompt.clone(),
+            style: self.style_prompt.clone(),
+            file: markdown_file::<Self>(&self.id),
+        }
+    }
+}
+
+impl PackPrompt for DialoguePrompt {
+    const PACK: &'static str = "dialogue_prompts";
+    const SUFFIX: &'static str = "dialogue";
+    const TEMPLATE: TemplateKind = TemplateKind::Dialogue;
+
+    fn name(&self) -> &str {
+        &self.character_name
+    }
+    fn faction(&self) -> &str {
+        &self.faction
+    }
+    fn category(&self) -> &str {
+        &self.role
+    }
+    fn source_uuid(&self) -> &str {
+        &self.source_uuid
+    }
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn record(&self) -> PackRecord {
+        PackRecord {
+            id: self.id.clone(),
+            kind: "dialogue".to_string(),
+            source_uuid: self.source_uuid.clone(),
+            name: self.character_name.clone(),
+            faction: self.faction.clone(),
+            category: self.role.clone(),
+            corruption_band: None,
+            max_triangles: None,
+            texture_size: None,
+            prompt: self.personality_prompt.clone(),
+            style: self.speech_patterns.join("; "),
+            file: markdown_file::<Self>(&self.id),
+        }
+    }
+}
+
+/// `model_prompts/the_red_cult/acolyte` -> `the_red_cult/acolyte_prompt.md`
+fn markdown_file<P: PackPrompt>(id: &str) -> String {
+    let path = id.strip_prefix(P::PACK).unwrap_or(id).trim_start_matches('/');
+    format!("{}_{}.md", path, P::SUFFIX)
+}
+
+/// How a pack is exported; the index is always written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackFormat {
+    Markdown,
+    Jsonl,
+    Csv,
+}
+
+impl FromStr for PackFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "jsonl" => Ok(Self::Jsonl),
+            "csv" => Ok(Self::Csv),
+            other => bail!("Unknown format: {}. Use: markdown, jsonl, csv", other),
+        }
+    }
+}
+
+/// A set of prompts of one kind, exported together
+#[derive(Debug, Clone)]
+pub struct PromptPack<P: PackPrompt> {
+    pub prompts: Vec<P>,
+}
+
+impl<P: PackPrompt> Default for PromptPack<P> {
+    fn default() -> Self {
+        Self { prompts: Vec::new() }
+    }
+}
+
+impl<P: PackPrompt> PromptPack<P> {
+    pub fn push(&mut self, prompt: P) {
+        self.prompts.push(prompt);
+    }
+
+    pub fn len(&self) -> usize {
+        self.prompts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prompts.is_empty()
+    }
+
+    /// Assign stable ids and sort by them. Ids are
+    /// `<pack>/<faction>/<name>`; when two prompts share one, both get their
+    /// source uuid appended.
+    pub fn finish(&mut self) {
+        let base_ids: Vec<String> = self
+            .prompts
+            .iter()
+            .map(|prompt| format!("{}/{}/{}", P::PACK, sanitize_name(prompt.faction()), sanitize_name(prompt.name())))
+            .collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for id in &base_ids {
+            *counts.entry(id.as_str()).or_default() += 1;
+        }
+        let ids: Vec<String> = base_ids
+            .iter()
+            .zip(&self.prompts)
+            .map(|(id, prompt)| match counts[id.as_str()] {
+                1 => id.clone(),
+                _ => format!("{}_{}", id, sanitize_name(prompt.source_uuid())),
+            })
+            .collect();
+        for (prompt, id) in self.prompts.iter_mut().zip(ids) {
+            prompt.set_id(id);
+        }
+        self.prompts.sort_by(|a, b| a.id().cmp(b.id()).then_with(|| a.source_uuid().cmp(b.source_uuid())));
+    }
+
+    pub fn records(&self) -> Vec<PackRecord> {
+        self.prompts.iter().map(PackPrompt::record).collect()
+    }
+
+    /// Markdown index of every prompt, in id order
+    pub fn index_markdown(&self) -> String {
+        let mut index = format!("# Prompt Pack: {}\n\n{} prompts\n\n", P::PACK, self.prompts.len());
+        index.push_str("| ID | Name | Faction | Category | Band | Max Triangles | Texture | File |\n");
+        index.push_str("|----|------|---------|----------|------|---------------|---------|------|\n");
+        let cell = |value: Option<u32>| value.map_or("-".to_string(), |v| v.to_string());
+        for record in self.records() {
+            let _ = writeln!(
+                index,
+                "| `{}` | {} | {} | {} | {} | {} | {} | [{}]({}) |",
+                record.id,
+                record.name.replace('|', "\\|"),
+                record.faction.replace('|', "\\|"),
+                record.category,
+                cell(record.corruption_band.map(u32::from)),
+                cell(record.max_triangles),
+                cell(record.texture_size),
+                record.file,
+                record.file,
+            );
+        }
+        index
+    }
+
+    /// Write the pack under `output_dir/<pack>/` in each format, plus the
+    /// index. Returns the files written.
+    pub fn write(&self, templates: &PromptTemplates, output_dir: &Path, formats: &[PackFormat]) -> Result<Vec<PathBuf>> {
+        let pack_dir = output_dir.join(P::PACK);
+        std::fs::create_dir_all(&pack_dir)?;
+        let mut written = Vec::new();
+
+        if formats.contains(&PackFormat::Markdown) {
+            for prompt in &self.prompts {
+                let mut context = tera::Context::new();
+                context.insert("prompt", prompt);
+                let markdown = templates.render(P::TEMPLATE, prompt.faction(), prompt.category(), &context)?;
+                let path = pack_dir.join(markdown_file::<P>(prompt.id()));
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, markdown)?;
+                written.push(path);
+            }
+        }
+        if formats.contains(&PackFormat::Jsonl) {
+            let mut jsonl = String::new();
+            for record in self.records() {
+                jsonl.push_str(&serde_json::to_string(&record)?);
+                jsonl.push('\n');
+            }
+            let path = pack_dir.join(PACK_JSONL_FILE);
+            std::fs::write(&path, jsonl)?;
+            written.push(path);
+        }
+        if formats.contains(&PackFormat::Csv) {
+            let path = pack_dir.join(PACK_CSV_FILE);
+            let mut writer = csv::Writer::from_path(&path)?;
+            for record in self.records() {
+                writer.serialize(record)?;
+            }
+            writer.flush()?;
+            written.push(path);
+        }
+
+        let path = pack_dir.join(PACK_INDEX_FILE);
+        std::fs::write(&path, self.index_markdown())?;
+        written.push(path);
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(name: &str, faction: &str, uuid: &str) -> ModelPrompt {
+        ModelPrompt {
+            id: String::new(),
+            source_uuid: uuid.to_string(),
+            display_name: name.to_string(),
+            category: "priest_unit".to_string(),
+            faction: faction.to_string(),
+            primary_prompt: format!("Create a {}", name),
+            style_prompt: "Bloody".to_string(),
+            technical_specs: TechnicalSpecs::for_category("priest_unit", 4),
+            corruption_progression: None,
+            reference_images: vec!["Bloodborne".to_string()],
+            animation_requirements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_budgets_grow_with_band() {
+        let calm = ModelBudget::for_band("leader_unit", 1);
+        assert_eq!(calm, ModelBudget { min_triangles: 5000, max_triangles: 8000, texture_size: 2048 });
+        let corrupt = ModelBudget::for_band("leader_unit", 5);
+        assert_eq!(corrupt, ModelBudget { min_triangles: 10000, max_triangles: 16000, texture_size: 4096 });
+        let specs = TechnicalSpecs::for_category("building", 3);
+        assert_eq!(specs.target_poly_count, "4500-15000 triangles");
+        assert_eq!(specs.texture_resolution, "2048x2048 tileable textures");
+    }
+
+    #[test]
+    fn test_ids_are_stable_and_disambiguated() {
+        let mut pack = PromptPack::default();
+        pack.push(model("The Red Cult", "Red Cult", "f2"));
+        pack.push(model("Acolyte", "Red Cult", "a1"));
+        pack.push(model("The Red Cult", "Red Cult", "f1"));
+        pack.finish();
+
+        let mut reversed = PromptPack::default();
+        for prompt in pack.prompts.iter().rev() {
+            reversed.push(ModelPrompt { id: String::new(), ..prompt.clone() });
+        }
+        reversed.finish();
+
+        let ids: Vec<&str> = pack.prompts.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec![
+            "model_prompts/red_cult/acolyte",
+            "model_prompts/red_cult/the_red_cult_f1",
+            "model_prompts/red_cult/the_red_cult_f2",
+        ]);
+        assert_eq!(reversed.prompts, pack.prompts);
+        assert_eq!(pack.records()[0].file, "red_cult/acolyte_prompt.md");
+        assert_eq!(pack.index_markdown(), reversed.index_markdown());
+    }
+
+    #[test]
+    fn test_overrides_by_faction_and_category() {
+        let dir = std::env::temp_dir().join(format!("dl_prompt_templates_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("crimson_covenant/leader_unit")).unwrap();
+        std::fs::create_dir_all(dir.join("building")).unwrap();
+        std::fs::write(dir.join("crimson_covenant/style.tera"), "Covenant style for {{ category }}").unwrap();
+        std::fs::write(dir.join("crimson_covenant/leader_unit/style.tera"), "Covenant leader").unwrap();
+        std::fs::write(dir.join("building/references.tera"), "Ruins\n\nTowers\n").unwrap();
+        let templates = PromptTemplates::with_overrides(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(templates.style_prompt("Crimson Covenant", "leader_unit", "").unwrap(), "Covenant leader");
+        assert_eq!(templates.style_prompt("Crimson Covenant", "priest_unit", "").unwrap(), "Covenant style for priest_unit");
+        assert!(templates
+            .style_prompt("Blood Sisters", "priest_unit", "a ritual")
+            .unwrap()
+            .ends_with("Include ceremonial elements, religious iconography, and ritual implements."));
+        assert_eq!(templates.reference_images("Void Seekers", "building").unwrap(), vec!["Ruins", "Towers"]);
+        assert_eq!(templates.reference_images("Void Seekers", "leader_unit").unwrap().len(), 7);
+        assert_eq!(templates.overrides(), vec![
+            "building/references.tera",
+            "crimson_covenant/leader_unit/style.tera",
+            "crimson_covenant/style.tera",
+        ]);
+
+        let mut context = tera::Context::new();
+        context.insert("prompt", &model("Acolyte", "Crimson Covenant", "a1"));
+        let markdown = templates.render(TemplateKind::Model, "Crimson Covenant", "priest_unit", &context).unwrap();
+        assert!(markdown.contains("- **Target Poly Count**: 3500-8750 triangles\n"));
+        assert!(markdown.contains("### Required Animations\n- idle\n- walk\n- attack\n- death\n\n### Material"));
+        assert!(markdown.contains("## Corruption Progression\nStandard progression\n"));
+    }
+}
@@ -0,0 +1,478 @@
+//! Query language over analyzed HBF entities
+//!
+//! A query is a list of terms that must all match. A term is either
+//! `field:value` or bare text; quote values with spaces and prefix a term
+//! with `-` to negate it:
+//!
+//! ```text
+//! category:creatures band:3-5 cr:1/2-4 "brass dragon"
+//! hex:0301-0510 faction:"red snakes" -ref:4MBpzETO
+//! ```
+//!
+//! Fields: `category` (or `cat`, including `uncategorized`), `name`, `uuid`,
+//! `hex` (a `CCRR` hex, a `CCRR-CCRR` box or a hex UUID), `band`
+//! (corruption band or range), `cr` (challenge rating or range), `faction`
+//! (name or UUID), `ref` (a UUID the page links to) and `text`.
+//!
+//! `QueryEngine` extracts those facts from each entity once, so the same
+//! engine can answer many queries or pick the entities a generator works on.
+
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use crate::containers::RawEntity;
+use crate::items::corruption_band_for;
+use crate::orchestration::{EntityCategory, RawEntities};
+
+/// Category name used for entities that were not filed
+pub const UNCATEGORIZED: &str = "uncategorized";
+/// Widest name shown in table output
+const TABLE_NAME_WIDTH: usize = 40;
+
+/// One condition on an entity
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// `None` matches uncategorized entities
+    Category(Option<EntityCategory>),
+    Name(String),
+    Uuid(String),
+    HexRange { min: (u32, u32), max: (u32, u32) },
+    HexUuid(String),
+    Band { min: u8, max: u8 },
+    ChallengeRating { min: f32, max: f32 },
+    Faction(String),
+    References(String),
+    Text(String),
+    Not(Box<Filter>),
+}
+
+/// A parsed query: every filter must match
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityQuery {
+    pub filters: Vec<Filter>,
+}
+
+impl FromStr for EntityQuery {
+    type Err = anyhow::Error;
+
+    fn from_str(query: &str) -> Result<Self> {
+        let filters = split_terms(query)?
+            .into_iter()
+            .map(|term| parse_term(&term))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { filters })
+    }
+}
+
+/// Split on whitespace outside double quotes, dropping the quotes
+fn split_terms(query: &str) -> Result<Vec<String>> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    terms.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        bail!("Unclosed quote in query: {}", query);
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+    Ok(terms)
+}
+
+fn parse_term(term: &str) -> Result<Filter> {
+    if let Some(negated) = term.strip_prefix('-').filter(|rest| !rest.is_empty()) {
+        return Ok(Filter::Not(Box::new(parse_term(negated)?)));
+    }
+    let Some((field, value)) = term.split_once(':') else {
+        return Ok(Filter::Text(term.to_lowercase()));
+    };
+    if value.is_empty() {
+        bail!("Missing value for '{}'", field);
+    }
+    Ok(match field.to_lowercase().as_str() {
+        "category" | "cat" if value == UNCATEGORIZED => Filter::Category(None),
+        "category" | "cat" => Filter::Category(Some(
+            EntityCategory::parse(value).ok_or_else(|| anyhow!("Unknown category: {}", value))?,
+        )),
+        "name" => Filter::Name(value.to_lowercase()),
+        "uuid" => Filter::Uuid(value.to_string()),
+        "hex" => match parse_range(value, parse_hex) {
+            Ok((min, max)) => Filter::HexRange { min, max },
+            Err(_) => Filter::HexUuid(value.to_string()),
+        },
+        "band" => {
+            let (min, max) = parse_range(value, |band| Ok(band.parse::<u8>()?))?;
+            Filter::Band { min, max }
+        }
+        "cr" => {
+            let (min, max) = parse_range(value, parse_challenge_rating)?;
+            Filter::ChallengeRating { min, max }
+        }
+        "faction" => Filter::Faction(value.to_lowercase()),
+        "ref" => Filter::References(value.to_string()),
+        "text" => Filter::Text(value.to_lowercase()),
+        other => bail!("Unknown query field: {}", other),
+    })
+}
+
+/// `a` or `a-b`
+fn parse_range<T: Copy>(value: &str, parse: impl Fn(&str) -> Result<T>) -> Result<(T, T)> {
+    match value.split_once('-') {
+        Some((min, max)) => Ok((parse(min)?, parse(max)?)),
+        None => {
+            let single = parse(value)?;
+            Ok((single, single))
+        }
+    }
+}
+
+/// Column and row of a `CCRR` hex number, as in "Hex 0403"
+fn parse_hex(hex: &str) -> Result<(u32, u32)> {
+    if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_digit()) {
+        bail!("Hex must be four digits: {}", hex);
+    }
+    Ok((hex[..2].parse()?, hex[2..].parse()?))
+}
+
+/// "4", "0.5" or "1/2"
+fn parse_challenge_rating(cr: &str) -> Result<f32> {
+    match cr.split_once('/') {
+        Some((numerator, denominator)) => Ok(numerator.parse::<f32>()? / denominator.parse::<f32>()?),
+        None => Ok(cr.parse()?),
+    }
+}
+
+/// A faction an entity's page names as membership
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FactionRef {
+    pub uuid: String,
+    pub name: String,
+}
+
+/// What the query engine knows about one entity
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityRecord<'a> {
+    pub uuid: String,
+    pub category: String,
+    /// Group the entity was filed under, empty when uncategorized
+    pub group: String,
+    pub name: String,
+    pub hex_uuid: Option<String>,
+    /// `CCRR` number of the hex, when its hex page is known
+    pub hex: Option<String>,
+    pub corruption_band: u8,
+    /// Highest challenge rating in the page's stat blocks
+    pub challenge_rating: Option<f32>,
+    pub factions: Vec<FactionRef>,
+    /// UUIDs of pages, hexes and settlements this page links to
+    pub references: Vec<String>,
+    #[serde(skip)]
+    pub text: String,
+    #[serde(skip)]
+    pub entity: &'a RawEntity,
+}
+
+impl EntityRecord<'_> {
+    fn hex_coords(&self) -> Option<(u32, u32)> {
+        self.hex.as_deref().and_then(|hex| parse_hex(hex).ok())
+    }
+
+    pub fn matches(&self, filter: &Filter) -> bool {
+        match filter {
+            Filter::Category(category) => {
+                self.category == category.map_or(UNCATEGORIZED, |category| category.as_str())
+            }
+            Filter::Name(name) => self.name.to_lowercase().contains(name),
+            Filter::Uuid(uuid) => &self.uuid == uuid,
+            Filter::HexRange { min, max } => self.hex_coords().is_some_and(|(column, row)| {
+                (min.0..=max.0).contains(&column) && (min.1..=max.1).contains(&row)
+            }),
+            Filter::HexUuid(uuid) => self.hex_uuid.as_ref() == Some(uuid),
+            Filter::Band { min, max } => (*min..=*max).contains(&self.corruption_band),
+            Filter::ChallengeRating { min, max } => {
+                self.challenge_rating.is_some_and(|cr| (*min..=*max).contains(&cr))
+            }
+            Filter::Faction(faction) => {
+                self.factions
+                    .iter()
+                    .any(|f| f.uuid.to_lowercase() == *faction || f.name.to_lowercase().contains(faction))
+                    || (self.category == EntityCategory::Factions.as_str() && self.group.to_lowercase().contains(faction))
+            }
+            Filter::References(uuid) => self.references.contains(uuid),
+            Filter::Text(text) => self.text.contains(text) || self.name.to_lowercase().contains(text),
+            Filter::Not(filter) => !self.matches(filter),
+        }
+    }
+
+    pub fn matches_query(&self, query: &EntityQuery) -> bool {
+        query.filters.iter().all(|filter| self.matches(filter))
+    }
+}
+
+/// Facts about every analyzed entity, ready to query
+pub struct QueryEngine<'a> {
+    records: Vec<EntityRecord<'a>>,
+}
+
+impl<'a> QueryEngine<'a> {
+    pub fn new(entities: &'a RawEntities) -> Result<Self> {
+        let extractor = RecordExtractor::new()?;
+        let mut filed: Vec<(&str, &str, &RawEntity)> = Vec::new();
+        for category in EntityCategory::ALL {
+            for (group, group_entities) in entities.category(category) {
+                filed.extend(group_entities.iter().map(|entity| (category.as_str(), group.as_str(), entity)));
+            }
+        }
+        filed.extend(entities.uncategorized.iter().map(|entity| (UNCATEGORIZED, "", entity)));
+
+        // Hex pages say which hex number their hex UUID is
+        let hex_numbers: HashMap<String, String> = filed
+            .iter()
+            .filter_map(|(_, _, entity)| extractor.hex_number(entity))
+            .collect();
+
+        let mut records: Vec<EntityRecord> = filed
+            .into_iter()
+            .map(|(category, group, entity)| extractor.record(category, group, entity, &hex_numbers))
+            .collect();
+        records.sort_by(|a, b| a.category.cmp(&b.category).then_with(|| a.name.cmp(&b.name)).then_with(|| a.uuid.cmp(&b.uuid)));
+        Ok(Self { records })
+    }
+
+    pub fn records(&self) -> &[EntityRecord<'a>] {
+        &self.records
+    }
+
+    /// Every record matching the query, by category then name
+    pub fn run(&self, query: &EntityQuery) -> Vec<&EntityRecord<'a>> {
+        self.records.iter().filter(|record| record.matches_query(query)).collect()
+    }
+
+    /// The matching entities, filed as they were, for tools that work on
+    /// `RawEntities`
+    pub fn select(&self, query: &EntityQuery) -> RawEntities {
+        let mut selected = RawEntities::new();
+        for record in self.run(query) {
+            match EntityCategory::parse(&record.category) {
+                Some(category) => selected
+                    .category_mut(category)
+                    .entry(record.group.clone())
+                    .or_default()
+                    .push(record.entity.clone()),
+                None => selected.uncategorized.push(record.entity.clone()),
+            }
+            selected.total_entities += 1;
+        }
+        selected
+    }
+}
+
+struct RecordExtractor {
+    tags: Regex,
+    hex: Regex,
+    hex_title: Regex,
+    challenge_rating: Regex,
+    membership: Regex,
+    reference: Regex,
+}
+
+impl RecordExtractor {
+    fn new() -> Result<Self> {
+        Ok(Self {
+            tags: Regex::new(r"<[^>]+>")?,
+            hex: Regex::new(r#"hex="([A-Za-z0-9]+)""#)?,
+            hex_title: Regex::new(r#"id="doc-title">\s*Hex (\d{4})\b"#)?,
+            challenge_rating: Regex::new(r"(?i)\b(?:CR|Challenge)\s*(\d+(?:/\d+)?)")?,
+            membership: Regex::new(r#"Member of the\s*<a href="[^"]*/faction/([A-Za-z0-9]+)">\s*<strong>([^<]+?)\.?</strong>"#)?,
+            reference: Regex::new(r#"(?:href="[^"]*/|hex="|data-settlement=")([A-Za-z0-9]{8})""#)?,
+        })
+    }
+
+    fn hex_number(&self, entity: &RawEntity) -> Option<(String, String)> {
+        let number = self.hex_title.captures(&entity.raw_value)?[1].to_string();
+        let uuid = self.hex.captures(&entity.raw_value)?[1].to_string();
+        Some((uuid, number))
+    }
+
+    fn record<'a>(&self, category: &str, group: &str, entity: &'a RawEntity, hex_numbers: &HashMap<String, String>) -> EntityRecord<'a> {
+        let html = &entity.raw_value;
+        let text = self.tags.replace_all(html, " ").to_lowercase();
+        let hex_uuid = self.hex.captures(html).map(|c| c[1].to_string());
+        let challenge_rating = self
+            .challenge_rating
+            .captures_iter(html)
+            .filter_map(|c| parse_challenge_rating(&c[1]).ok())
+            .reduce(f32::max);
+        let mut factions: Vec<FactionRef> = Vec::new();
+        for capture in self.membership.captures_iter(html) {
+            let faction = FactionRef { uuid: capture[1].to_string(), name: capture[2].trim().to_string() };
+            if !factions.contains(&faction) {
+                factions.push(faction);
+            }
+        }
+        let mut references: Vec<String> = self.reference.captures_iter(html).map(|c| c[1].to_string()).collect();
+        references.sort();
+        references.dedup();
+
+        EntityRecord {
+            uuid: entity.uuid.clone(),
+            category: category.to_string(),
+            group: group.to_string(),
+            name: entity.entity_name.clone(),
+            hex: hex_uuid.as_ref().and_then(|uuid| hex_numbers.get(uuid).cloned()),
+            hex_uuid,
+            corruption_band: corruption_band_for(&text),
+            challenge_rating,
+            factions,
+            references,
+            text,
+            entity,
+        }
+    }
+}
+
+/// How query results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Ron,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "ron" => Ok(Self::Ron),
+            other => bail!("Unknown format: {}. Use: table, json, ron", other),
+        }
+    }
+}
+
+/// Render query results in the given format
+pub fn render_records(records: &[&EntityRecord], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(records)?),
+        OutputFormat::Ron => Ok(ron::ser::to_string_pretty(records, ron::ser::PrettyConfig::default())?),
+        OutputFormat::Table => {
+            let mut table = String::new();
+            writeln!(table, "{:<10} {:<13} {:<width$} {:<5} {:>4} {:>5}  Factions", "UUID", "Category", "Name", "Hex", "Band", "CR", width = TABLE_NAME_WIDTH)?;
+            for record in records {
+                let name: String = record.name.chars().take(TABLE_NAME_WIDTH).collect();
+                let cr = record.challenge_rating.map_or(String::new(), |cr| cr.to_string());
+                let factions: Vec<&str> = record.factions.iter().map(|f| f.name.as_str()).collect();
+                writeln!(
+                    table,
+                    "{:<10} {:<13} {:<width$} {:<5} {:>4} {:>5}  {}",
+                    record.uuid,
+                    record.category,
+                    name,
+                    record.hex.as_deref().unwrap_or("-"),
+                    record.corruption_band,
+                    cr,
+                    factions.join(", "),
+                    width = TABLE_NAME_WIDTH
+                )?;
+            }
+            Ok(table)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entities() -> RawEntities {
+        let mut entities = RawEntities::new();
+        let page = |uuid: &str, name: &str, html: &str| RawEntity::new(uuid.to_string(), "unknown".to_string(), name.to_string(), html.to_string());
+        entities.regions.insert("Aurora Bushes".to_string(), vec![
+            page("HexPage1", "Hex 0403", r#"<div hidden id="doc-title">Hex 0403</div><a class="map-coords" hex="4MBpzETO"></a> A haunted ruin"#),
+        ]);
+        entities.creatures.insert("5 Onis".to_string(), vec![
+            page("OniPage1", "5 Onis", r#"<a class="map-coords" hex="4MBpzETO"></a><p>5 Onis, CR 7. AC 16 HP 110</p>"#),
+        ]);
+        entities.characters.insert("Melibor".to_string(), vec![
+            page("NpcPage1", "Melibor", r#"<a class="map-coords" hex="Cyw6XrnL"></a><strong>Melibor</strong>. Member of the <a href="/faction/uqf2lypH"><strong>The Red Snakes</strong></a>. Lives in <a href="/location/8FcnTf8q">Village of Dokar</a>, a cursed place"#),
+        ]);
+        entities.uncategorized.push(page("Loose001", "Scrap", "<p>Goblin CR 1/4 ambush</p>"));
+        entities
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query: EntityQuery = r#"cat:creatures band:2-4 hex:0301-0510 cr:1/2 -ref:8FcnTf8q "brass dragon""#.parse().unwrap();
+        assert_eq!(query.filters, vec![
+            Filter::Category(Some(EntityCategory::Creatures)),
+            Filter::Band { min: 2, max: 4 },
+            Filter::HexRange { min: (3, 1), max: (5, 10) },
+            Filter::ChallengeRating { min: 0.5, max: 0.5 },
+            Filter::Not(Box::new(Filter::References("8FcnTf8q".to_string()))),
+            Filter::Text("brass dragon".to_string()),
+        ]);
+        assert_eq!("hex:4MBpzETO".parse::<EntityQuery>().unwrap().filters, vec![Filter::HexUuid("4MBpzETO".to_string())]);
+        assert!("colour:red".parse::<EntityQuery>().is_err());
+        assert!("cat:dragons".parse::<EntityQuery>().is_err());
+        assert!(r#"name:"open"#.parse::<EntityQuery>().is_err());
+    }
+
+    #[test]
+    fn test_engine_filters() {
+        let entities = entities();
+        let engine = QueryEngine::new(&entities).unwrap();
+        let uuids = |query: &str| -> Vec<String> {
+            engine.run(&query.parse().unwrap()).iter().map(|record| record.uuid.clone()).collect()
+        };
+
+        assert_eq!(uuids("hex:0403"), vec!["OniPage1", "HexPage1"]);
+        assert_eq!(uuids("hex:0101-0302"), Vec::<String>::new());
+        assert_eq!(uuids("cr:5-10"), vec!["OniPage1"]);
+        assert_eq!(uuids("cr:0-1"), vec!["Loose001"]);
+        assert_eq!(uuids(r#"faction:"red snakes""#), vec!["NpcPage1"]);
+        assert_eq!(uuids("faction:uqf2lypH"), vec!["NpcPage1"]);
+        assert_eq!(uuids("ref:8FcnTf8q"), vec!["NpcPage1"]);
+        assert_eq!(uuids("band:3"), vec!["NpcPage1"]);
+        assert_eq!(uuids("band:2 -cat:creatures"), vec!["HexPage1"]);
+        assert_eq!(uuids("cat:uncategorized goblin"), vec!["Loose001"]);
+        assert_eq!(uuids(r#""haunted ruin""#), vec!["HexPage1"]);
+    }
+
+    #[test]
+    fn test_select_and_render() {
+        let entities = entities();
+        let engine = QueryEngine::new(&entities).unwrap();
+        let query: EntityQuery = "hex:4MBpzETO".parse().unwrap();
+
+        let selected = engine.select(&query);
+        assert_eq!(selected.total_entities, 2);
+        assert_eq!(selected.creatures["5 Onis"].len(), 1);
+        assert!(selected.characters.is_empty());
+
+        let records = engine.run(&query);
+        let table = render_records(&records, OutputFormat::Table).unwrap();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.contains("0403"));
+        let json = render_records(&records, OutputFormat::Json).unwrap();
+        assert!(json.contains(r#""challenge_rating": 7.0"#));
+        assert!(!json.contains("raw_value"));
+        let ron = render_records(&records, OutputFormat::Ron).unwrap();
+        assert!(ron.contains(r#"uuid: "OniPage1""#));
+    }
+}
@@ -9,6 +9,7 @@ use std::path::{Path, PathBuf};
 use csv::Writer;
 use anyhow::Result;
 
+use crate::classifier::Classification;
 use crate::containers::RawEntity;
 
 /// Get the reports directory from environment or use default
@@ -29,6 +30,7 @@ pub fn generate_all_reports(
     factions: &std::collections::HashMap<String, Vec<RawEntity>>,
     dungeons: &std::collections::HashMap<String, Vec<RawEntity>>,
     uncategorized: &[RawEntity],
+    classifications: &std::collections::HashMap<String, Classification>,
     reports_dir: &Path
 ) -> Result<()> {
     // Create reports directory if it doesn't exist
@@ -43,7 +45,7 @@ pub fn generate_all_reports(
     
     // Generate uncategorized report if there are any
     if !uncategorized.is_empty() {
-        generate_uncategorized_report(uncategorized, reports_dir)?;
+        generate_uncategorized_report(uncategorized, classifications, reports_dir)?;
     }
     
     Ok(())
@@ -352,8 +354,12 @@ fn generate_analysis_summary(
 }
 
 /// Generate split reports of uncategorized entities (manageable chunks)
+/// 
+/// Where the classifier scored an entity, its best guess and confidence are
+/// reported; otherwise the potential category comes from keyword hints.
 pub fn generate_uncategorized_report(
     uncategorized: &[RawEntity],
+    classifications: &std::collections::HashMap<String, Classification>,
     reports_dir: &Path
 ) -> Result<()> {
     if uncategorized.is_empty() {
@@ -372,7 +378,8 @@ pub fn generate_uncategorized_report(
         "Category",
         "Entity Name", 
         "Content Preview",
-        "Potential Category"
+        "Potential Category",
+        "Confidence"
     ])?;
     
     // Write sample with categorization hints
@@ -383,7 +390,10 @@ pub fn generate_uncategorized_report(
             entity.raw_value.clone()
         };
         
-        let potential_category = detect_potential_category(&entity.raw_value, &entity.entity_name);
+        let (potential_category, confidence) = match classifications.get(&entity.uuid).and_then(|c| c.best()) {
+            Some((category, confidence)) => (category.as_str().to_string(), format!("{:.2}", confidence)),
+            None => (detect_potential_category(&entity.raw_value, &entity.entity_name), String::new()),
+        };
         
         sample_wtr.write_record(&[
             &entity.uuid,
@@ -391,6 +401,7 @@ pub fn generate_uncategorized_report(
             &entity.entity_name,
             &content_preview,
             &potential_category,
+            &confidence,
         ])?;
     }
     sample_wtr.flush()?;
@@ -0,0 +1,409 @@
+//! Full-text search over HBF entities and generated seed pools
+//!
+//! An inverted index from stemmed terms to the documents and positions they
+//! occur at. HTML is stripped before indexing. Searches are ranked with
+//! BM25; quoted phrases must appear in order. Stop words are not indexed
+//! but keep their positions, so "Village of Dokar" still matches as a
+//! phrase. The index is plain serde data and can be saved next to the
+//! analysis output and loaded by other tools.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::containers::RawEntity;
+use crate::data_pools::CategorizedDataPools;
+use crate::orchestration::{EntityCategory, RawEntities};
+use crate::utilities::{stem, HtmlText};
+
+/// BM25 term frequency saturation
+const BM25_K1: f64 = 1.2;
+/// BM25 document length normalisation
+const BM25_B: f64 = 0.75;
+/// Characters of context either side of a hit in snippets
+const SNIPPET_CONTEXT: usize = 60;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "in", "is", "it", "its", "of", "on",
+    "or", "that", "the", "to", "was", "with",
+];
+
+/// Lowercased, stemmed terms with their positions, stop words skipped
+fn tokenize(word: &Regex, text: &str) -> Vec<(u32, String)> {
+    word.find_iter(&text.to_lowercase())
+        .enumerate()
+        .filter(|(_, token)| !STOP_WORDS.contains(&token.as_str()))
+        .map(|(position, token)| (position as u32, stem(token.as_str())))
+        .collect()
+}
+
+/// One indexed entity or seed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedDocument {
+    /// Entity UUID, or `seeds/<pool>/<n>` for seed pool entries
+    pub id: String,
+    pub category: String,
+    pub title: String,
+    /// Text as indexed, HTML stripped
+    pub text: String,
+    length: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Posting {
+    document: u32,
+    positions: Vec<u32>,
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub id: String,
+    pub category: String,
+    pub title: String,
+    pub score: f64,
+    pub snippet: String,
+}
+
+/// What a search asks for: loose terms, which rank, and phrases, which
+/// every hit must contain
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    pub terms: Vec<String>,
+    pub phrases: Vec<Vec<(u32, String)>>,
+}
+
+/// Inverted index over entity and seed text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIndex {
+    documents: Vec<IndexedDocument>,
+    postings: HashMap<String, Vec<Posting>>,
+    total_length: u64,
+    #[serde(skip, default = "word_regex")]
+    word: Regex,
+}
+
+fn word_regex() -> Regex {
+    Regex::new(r"[a-z0-9]+(?:'[a-z]+)?").expect("word pattern is valid")
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        Self {
+            documents: Vec::new(),
+            postings: HashMap::new(),
+            total_length: 0,
+            word: word_regex(),
+        }
+    }
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index every analyzed entity, filed or not
+    pub fn from_entities(entities: &RawEntities) -> Result<Self> {
+        let html = HtmlText::new()?;
+        let mut index = Self::new();
+        for category in EntityCategory::ALL {
+            for group in entities.category(category).values() {
+                for entity in group {
+                    index.add_entity(&html, category.as_str(), entity);
+                }
+            }
+        }
+        for entity in &entities.uncategorized {
+            index.add_entity(&html, "uncategorized", entity);
+        }
+        Ok(index)
+    }
+
+    pub fn add_entity(&mut self, html: &HtmlText, category: &str, entity: &RawEntity) {
+        // Extracted names can run on past the heading; keep its first line
+        let name = html.render(&entity.entity_name);
+        let title = name.lines().next().unwrap_or_default();
+        let text = html.render(&entity.raw_value);
+        self.add_document(&entity.uuid, category, title, &text);
+    }
+
+    /// Index every entry of the generated seed pools
+    pub fn add_seed_pools(&mut self, pools: &CategorizedDataPools) -> Result<()> {
+        for pool in pools.get_categories() {
+            for (n, value) in pools.get_category_data(&pool)?.iter().enumerate() {
+                let title = ["name", "title", "id"]
+                    .iter()
+                    .find_map(|key| value.get(*key).and_then(Value::as_str))
+                    .unwrap_or(&pool)
+                    .to_string();
+                let mut strings = Vec::new();
+                collect_strings(value, &mut strings);
+                self.add_document(&format!("seeds/{}/{}", pool, n), &format!("seeds/{}", pool), &title, &strings.join("\n"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Index a document; its title is searched along with its text
+    pub fn add_document(&mut self, id: &str, category: &str, title: &str, text: &str) {
+        let document = self.documents.len() as u32;
+        let tokens = tokenize(&self.word, &format!("{}\n{}", title, text));
+        let length = tokens.len() as u32;
+
+        let mut positions: HashMap<String, Vec<u32>> = HashMap::new();
+        for (position, term) in tokens {
+            positions.entry(term).or_default().push(position);
+        }
+        for (term, positions) in positions {
+            self.postings.entry(term).or_default().push(Posting { document, positions });
+        }
+
+        self.total_length += length as u64;
+        self.documents.push(IndexedDocument {
+            id: id.to_string(),
+            category: category.to_string(),
+            title: title.to_string(),
+            text: text.to_string(),
+            length,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub fn document(&self, id: &str) -> Option<&IndexedDocument> {
+        self.documents.iter().find(|document| document.id == id)
+    }
+
+    /// Split a query into loose terms and "quoted phrases"
+    pub fn parse_query(&self, query: &str) -> SearchQuery {
+        let mut parsed = SearchQuery::default();
+        for (n, part) in query.split('"').enumerate() {
+            if n % 2 == 1 {
+                let phrase = tokenize(&self.word, part);
+                if !phrase.is_empty() {
+                    parsed.phrases.push(phrase);
+                }
+            } else {
+                parsed.terms.extend(tokenize(&self.word, part).into_iter().map(|(_, term)| term));
+            }
+        }
+        parsed
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.search_query(&self.parse_query(query), limit, None)
+    }
+
+    /// Documents ranked by BM25 over all query terms. With phrases, only
+    /// documents containing every phrase are considered.
+    pub fn search_query(&self, query: &SearchQuery, limit: usize, exclude: Option<&str>) -> Vec<SearchHit> {
+        let phrase_terms = query.phrases.iter().flatten().map(|(_, term)| term.clone());
+        let terms: HashSet<String> = query.terms.iter().cloned().chain(phrase_terms).collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let document_count = self.documents.len() as f64;
+        let average_length = self.total_length as f64 / document_count.max(1.0);
+        let mut scores: HashMap<u32, f64> = HashMap::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let frequency = postings.len() as f64;
+            let idf = ((document_count - frequency + 0.5) / (frequency + 0.5) + 1.0).ln();
+            for posting in postings {
+                let tf = posting.positions.len() as f64;
+                let length = self.documents[posting.document as usize].length as f64;
+                let norm = tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length / average_length.max(1.0)));
+                *scores.entry(posting.document).or_default() += idf * norm;
+            }
+        }
+
+        let mut hits: Vec<(u32, f64)> = scores
+            .into_iter()
+            .filter(|(document, _)| exclude != Some(self.documents[*document as usize].id.as_str()))
+            .filter(|(document, _)| query.phrases.iter().all(|phrase| self.contains_phrase(*document, phrase)))
+            .collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        hits.into_iter()
+            .take(limit)
+            .map(|(document, score)| {
+                let document = &self.documents[document as usize];
+                SearchHit {
+                    id: document.id.clone(),
+                    category: document.category.clone(),
+                    title: document.title.clone(),
+                    score,
+                    snippet: self.snippet(&document.text, &terms),
+                }
+            })
+            .collect()
+    }
+
+    /// Documents mentioning the named entity by name, itself excluded
+    pub fn mentions_of(&self, id: &str, limit: usize) -> Result<(String, Vec<SearchHit>)> {
+        let document = self.document(id).ok_or_else(|| anyhow!("No indexed entity with UUID {}", id))?;
+        let phrase = tokenize(&self.word, &document.title);
+        if phrase.is_empty() {
+            return Ok((document.title.clone(), Vec::new()));
+        }
+        let query = SearchQuery { terms: Vec::new(), phrases: vec![phrase] };
+        Ok((document.title.clone(), self.search_query(&query, limit, Some(id))))
+    }
+
+    fn positions(&self, term: &str, document: u32) -> Option<&[u32]> {
+        let postings = self.postings.get(term)?;
+        let found = postings.binary_search_by_key(&document, |posting| posting.document).ok()?;
+        Some(&postings[found].positions)
+    }
+
+    fn contains_phrase(&self, document: u32, phrase: &[(u32, String)]) -> bool {
+        let Some(((first_offset, first), rest)) = phrase.split_first() else {
+            return true;
+        };
+        let Some(starts) = self.positions(first, document) else {
+            return false;
+        };
+        starts.iter().any(|start| {
+            rest.iter().all(|(offset, term)| {
+                self.positions(term, document)
+                    .zip((start + offset).checked_sub(*first_offset))
+                    .is_some_and(|(positions, position)| positions.contains(&position))
+            })
+        })
+    }
+
+    /// The text around the first word matching a search term
+    fn snippet(&self, text: &str, terms: &HashSet<String>) -> String {
+        let lower = text.to_lowercase();
+        let Some(hit) = self.word.find_iter(&lower).find(|word| terms.contains(&stem(word.as_str()))) else {
+            return text.chars().take(SNIPPET_CONTEXT * 2).collect::<String>().replace('\n', " ");
+        };
+        let start = floor_char_boundary(text, hit.start().saturating_sub(SNIPPET_CONTEXT).min(text.len()));
+        let end = floor_char_boundary(text, (hit.end() + SNIPPET_CONTEXT).min(text.len()));
+        let prefix = if start > 0 { "..." } else { "" };
+        let suffix = if end < text.len() { "..." } else { "" };
+        format!("{}{}{}", prefix, &text[start..end], suffix).replace('\n', " ")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(s) => strings.push(s.clone()),
+        Value::Array(values) => values.iter().for_each(|value| collect_strings(value, strings)),
+        Value::Object(map) => map.values().for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SearchIndex {
+        let mut entities = RawEntities::new();
+        let page = |uuid: &str, name: &str, html: &str| RawEntity::new(uuid.to_string(), "unknown".to_string(), name.to_string(), html.to_string());
+        entities.settlements.insert("Dokar".to_string(), vec![
+            page("Dokar001", "Village of Dokar", "<p>A haunted village of the <b>Red Snakes</b>.</p>"),
+        ]);
+        entities.characters.insert("Hrolf".to_string(), vec![
+            page("Hrolf001", "Hrolf", "<p>Blacksmith in the Village of Dokar. Haunting dreams.</p>"),
+        ]);
+        entities.creatures.insert("Onis".to_string(), vec![
+            page("Onis0001", "5 Onis", "<p>Onis haunt the road to Dokar and the village beyond.</p>"),
+        ]);
+        entities.uncategorized.push(page("Loose001", "Scrap", "<p>A village far away. Dokar is unknown.</p>"));
+        SearchIndex::from_entities(&entities).unwrap()
+    }
+
+    #[test]
+    fn test_stem() {
+        assert_eq!(stem("haunted"), "haunt");
+        assert_eq!(stem("haunting"), "haunt");
+        assert_eq!(stem("haunts"), "haunt");
+        assert_eq!(stem("bodies"), "body");
+        assert_eq!(stem("snakes"), "snake");
+        assert_eq!(stem("boxes"), "box");
+        assert_eq!(stem("onis"), "oni");
+        assert_eq!(stem("moss"), "moss");
+        assert_eq!(stem("is"), "is");
+    }
+
+    #[test]
+    fn test_ranked_search() {
+        let index = index();
+        assert_eq!(index.len(), 4);
+        let hits = index.search("haunting", 10);
+        let ids: Vec<&str> = hits.iter().map(|hit| hit.id.as_str()).collect();
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&"Loose001"));
+        assert!(hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert!(hits.iter().all(|hit| !hit.snippet.contains('<')));
+        assert!(index.search("the", 10).is_empty());
+    }
+
+    #[test]
+    fn test_phrase_search() {
+        let index = index();
+        let ids = |query: &str| -> Vec<String> {
+            let mut ids: Vec<String> = index.search(query, 10).into_iter().map(|hit| hit.id).collect();
+            ids.sort();
+            ids
+        };
+        // Stop words keep their place, so "village far away. Dokar" is no match
+        assert_eq!(ids(r#""village of dokar""#), vec!["Dokar001", "Hrolf001"]);
+        assert_eq!(ids(r#""red snakes""#), vec!["Dokar001"]);
+        assert_eq!(ids(r#""snakes red""#), Vec::<String>::new());
+        assert_eq!(ids(r#"blacksmith "village of dokar""#), vec!["Dokar001", "Hrolf001"]);
+    }
+
+    #[test]
+    fn test_mentions_and_persistence() -> Result<()> {
+        let mut index = index();
+        let mut pools = CategorizedDataPools::new();
+        pools.add_to_category("quests", serde_json::json!({ "title": "Ashes of Dokar", "summary": "Avenge the Village of Dokar" }))?;
+        index.add_seed_pools(&pools)?;
+
+        let (name, hits) = index.mentions_of("Dokar001", 10)?;
+        assert_eq!(name, "Village of Dokar");
+        let mut ids: Vec<&str> = hits.iter().map(|hit| hit.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["Hrolf001", "seeds/quests/0"]);
+        assert!(index.mentions_of("missing", 10).is_err());
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("search_index.json");
+        index.save(&path)?;
+        let loaded = SearchIndex::load(&path)?;
+        assert_eq!(loaded.len(), index.len());
+        assert_eq!(loaded.search("avenge", 10), index.search("avenge", 10));
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+//! Settlement database generation from HBF settlement pages
+//!
+//! Every page in a settlement group is either the settlement itself
+//! ("Village of Dokar from The Lands of Vo'il", with its population), an
+//! establishment ("Blacksmith in Dokar", "\"The King's Torch Lodge\" from
+//! Dokar"), a district or the hex it sits on. Establishments decide which
+//! services the settlement offers; the rest of the figures come from counting
+//! NPC anchors and prices across the group.
+
+use anyhow::Result;
+use dl_types::world::{Establishment, ServiceType, Settlement, SettlementDatabase, SettlementScale};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::containers::RawEntity;
+use crate::utilities::sanitize_name;
+
+/// Starting corruption never exceeds this; the rest has to be earned in play
+const MAX_SEEDED_CORRUPTION: f32 = 30.0;
+
+/// Build the settlement database from the `settlements` category of `RawEntities`
+pub fn build_settlement_database(settlements: &HashMap<String, Vec<RawEntity>>) -> Result<SettlementDatabase> {
+    let extractor = SettlementExtractor::new()?;
+    let mut database = SettlementDatabase::default();
+
+    for (name, entities) in settlements {
+        database.insert(extractor.extract(name, entities));
+    }
+
+    Ok(database)
+}
+
+/// Write the database as pretty RON for the game to load at startup
+pub fn write_settlement_database(database: &SettlementDatabase, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let ron_content = ron::ser::to_string_pretty(database, ron::ser::PrettyConfig::default())?;
+    std::fs::write(output_path, ron_content)?;
+    Ok(())
+}
+
+/// Compiled patterns for the fragments HBF settlement pages use
+pub struct SettlementExtractor {
+    doc_title: Regex,
+    base_name: Regex,
+    settlement_title: Regex,
+    settlement_link: Regex,
+    hex: Regex,
+    population: Regex,
+    price: Regex,
+}
+
+impl SettlementExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            doc_title: Regex::new(r#"id="doc-title">\s*([^<]+?)\s*</div>"#)?,
+            base_name: Regex::new(r#"data-attr="BaseName">([^<]+)<"#)?,
+            settlement_title: Regex::new(r"^(Village|Town|City|Metropolis) of (.+?) from ")?,
+            settlement_link: Regex::new(r#"location/([A-Za-z0-9]+)">\s*(?i:village|town|city|metropolis) of"#)?,
+            hex: Regex::new(r#"hex="([A-Za-z0-9]+)""#)?,
+            population: Regex::new(r"Population:\s*(\d+)")?,
+            price: Regex::new(r"\d[\d,]*\s*gp\b")?,
+        })
+    }
+
+    /// One settlement from all the pages grouped under its name
+    pub fn extract(&self, group_name: &str, entities: &[RawEntity]) -> Settlement {
+        let mut name = group_name.to_string();
+        let mut scale = None;
+        let mut population = 0;
+        let mut own_uuid = None;
+        let mut establishments = Vec::new();
+        let mut link_counts: HashMap<String, usize> = HashMap::new();
+        let mut hex_counts: HashMap<String, usize> = HashMap::new();
+        let mut npc_count = 0;
+        let mut economic_activity = 0;
+        let mut corruption_mentions = 0;
+
+        for entity in entities {
+            let page = &entity.raw_value;
+            npc_count += page.matches("npc-anchor").count() as u32;
+            economic_activity += self.price.find_iter(page).count() as u32;
+            corruption_mentions += count_corruption_mentions(page);
+            for capture in self.settlement_link.captures_iter(page) {
+                *link_counts.entry(capture[1].to_string()).or_default() += 1;
+            }
+            for capture in self.hex.captures_iter(page) {
+                *hex_counts.entry(capture[1].to_string()).or_default() += 1;
+            }
+
+            let Some(title) = self.doc_title.captures(page).map(|c| c[1].to_string()) else {
+                continue;
+            };
+
+            if let Some(capture) = self.settlement_title.captures(&title) {
+                name = format!("{} of {}", &capture[1], &capture[2]);
+                scale = Some(scale_from_prefix(&capture[1]));
+                own_uuid = Some(entity.uuid.clone());
+                if let Some(capture) = self.population.captures(page) {
+                    population = capture[1].parse().unwrap_or(0);
+                }
+            } else if let Some(establishment) = self.establishment(entity, &title) {
+                establishments.push(establishment);
+            }
+        }
+
+        let scale = match (population, scale) {
+            (0, Some(scale)) => scale,
+            (0, None) => SettlementScale::Village,
+            (population, _) => SettlementScale::from_population(population),
+        };
+
+        let mut service_types: Vec<ServiceType> = Vec::new();
+        for service in establishments.iter().filter_map(|e| e.service) {
+            if !service_types.contains(&service) {
+                service_types.push(service);
+            }
+        }
+
+        let mut corruption_resistance = scale.base_resistance();
+        if service_types.contains(&ServiceType::Religious) {
+            corruption_resistance += 1;
+        }
+
+        Settlement {
+            uuid: own_uuid
+                .or_else(|| most_common(&link_counts))
+                .unwrap_or_else(|| sanitize_name(&name)),
+            name,
+            hex_uuid: most_common(&hex_counts),
+            scale,
+            population,
+            economic_activity,
+            corruption_resistance: corruption_resistance.min(10),
+            service_types,
+            npc_count,
+            establishment_count: establishments.len() as u32,
+            establishments,
+            corruption: (corruption_mentions as f32 * 2.0).min(MAX_SEEDED_CORRUPTION),
+        }
+    }
+
+    /// "Blacksmith in Dokar" or "\"The Lost Torch Inn\" from Kothian";
+    /// districts and hex pages are not establishments
+    fn establishment(&self, entity: &RawEntity, title: &str) -> Option<Establishment> {
+        if title.contains("(district)") || title.starts_with("Hex ") {
+            return None;
+        }
+
+        let (name, kind) = if let Some(quoted) = title.strip_prefix('"') {
+            // Quoted titles are always taverns and inns
+            let name = quoted.split('"').next()?.to_string();
+            (name, "Tavern".to_string())
+        } else {
+            let kind = title.split(" in ").next()?.trim().to_string();
+            let name = self
+                .base_name
+                .captures(&entity.raw_value)
+                .map(|c| c[1].trim().to_string())
+                .unwrap_or_else(|| kind.clone());
+            (name, kind)
+        };
+
+        Some(Establishment {
+            uuid: entity.uuid.clone(),
+            service: ServiceType::for_establishment(&kind),
+            name,
+            kind,
+        })
+    }
+}
+
+fn scale_from_prefix(prefix: &str) -> SettlementScale {
+    match prefix {
+        "Town" => SettlementScale::Town,
+        "City" => SettlementScale::City,
+        "Metropolis" => SettlementScale::Metropolis,
+        _ => SettlementScale::Village,
+    }
+}
+
+fn count_corruption_mentions(page: &str) -> usize {
+    let lower = page.to_lowercase();
+    ["cursed", "defiled", "corrupt", "undead", "cultist"]
+        .iter()
+        .map(|word| lower.matches(word).count())
+        .sum()
+}
+
+/// Most frequent key, ties broken alphabetically so output is stable
+fn most_common(counts: &HashMap<String, usize>) -> Option<String> {
+    counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(key, _)| key.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(uuid: &str, body: &str) -> RawEntity {
+        RawEntity::new(
+            uuid.to_string(),
+            "settlements".to_string(),
+            "Village of Dokar".to_string(),
+            body.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_extracts_settlement_from_pages() {
+        let extractor = SettlementExtractor::new().unwrap();
+        let entities = vec![
+            page(
+                "8FcnTf8q",
+                r#"<a class="map-coords" hex="4MBpzETO"></a><div hidden id="doc-title"> Village of Dokar from The Lands of Vo'il</div> Population: 465 villagers"#,
+            ),
+            page(
+                "Q1smith0",
+                r#"<a class="map-coords" hex="4MBpzETO"></a><div hidden id="doc-title">Blacksmith in Dokar</div><span id="editable-title" data-attr="BaseName">Hrolf's Anvil</span> <a class="npc-anchor"></a> Longsword 15 gp <a href="/location/8FcnTf8q">Village of dokar</a>"#,
+            ),
+            page(
+                "Q2tavern",
+                r#"<div hidden id="doc-title"> "The King's Torch Lodge" from Dokar in Vo'il</div><a class="npc-anchor"></a> Room 5 gp"#,
+            ),
+            page("Q3distr", r#"<div hidden id="doc-title">Village District (district) in Dokar</div>"#),
+        ];
+
+        let settlement = extractor.extract("dokar", &entities);
+        assert_eq!(settlement.uuid, "8FcnTf8q");
+        assert_eq!(settlement.name, "Village of Dokar");
+        assert_eq!(settlement.hex_uuid.as_deref(), Some("4MBpzETO"));
+        assert_eq!(settlement.scale, SettlementScale::Village);
+        assert_eq!(settlement.population, 465);
+        assert_eq!(settlement.npc_count, 2);
+        assert_eq!(settlement.economic_activity, 2);
+        assert_eq!(settlement.establishment_count, 2);
+        assert_eq!(settlement.establishments[0].name, "Hrolf's Anvil");
+        assert_eq!(settlement.service_types, vec![ServiceType::Crafting, ServiceType::Lodging]);
+        assert_eq!(settlement.corruption, 0.0);
+    }
+
+    #[test]
+    fn test_falls_back_to_linked_uuid() {
+        let extractor = SettlementExtractor::new().unwrap();
+        let entities = vec![page(
+            "Q4temple",
+            r#"<div hidden id="doc-title">Temple in Harad</div> cursed <a href="/location/Zz9HaRad">Village of harad</a>"#,
+        )];
+
+        let settlement = extractor.extract("harad", &entities);
+        assert_eq!(settlement.uuid, "Zz9HaRad");
+        assert_eq!(settlement.corruption_resistance, 7);
+        assert_eq!(settlement.corruption, 2.0);
+    }
+}
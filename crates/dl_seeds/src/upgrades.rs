@@ -0,0 +1,416 @@
+//! Upgrade graph over generated model metadata
+//!
+//! Each model may name the model it upgrades into. Following those links
+//! gives upgrade chains. The checks here catch links that loop back, point
+//! at models that do not exist, or lead into a lower corruption band, and
+//! the chains render as DOT, SVG and Markdown for review.
+
+use dl_types::world::{escape_xml, ModelMetadata};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Write};
+
+/// SVG box size and spacing
+const SVG_BOX_WIDTH: f32 = 170.0;
+const SVG_BOX_HEIGHT: f32 = 44.0;
+const SVG_GAP: f32 = 40.0;
+const SVG_MARGIN: f32 = 20.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpgradeIssue {
+    /// Models that upgrade into each other in a loop, smallest id first
+    Cycle { ids: Vec<String> },
+    /// An upgrade into a model that is not in the set
+    Dangling { id: String, target: String },
+    /// An upgrade into a lower corruption band
+    BandInversion { id: String, band: u8, target: String, target_band: u8 },
+}
+
+impl fmt::Display for UpgradeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpgradeIssue::Cycle { ids } => write!(f, "upgrade cycle: {} -> {}", ids.join(" -> "), ids[0]),
+            UpgradeIssue::Dangling { id, target } => write!(f, "{} upgrades to missing model {}", id, target),
+            UpgradeIssue::BandInversion { id, band, target, target_band } => {
+                write!(f, "{} (band {}) upgrades down to {} (band {})", id, band, target, target_band)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeNode {
+    pub id: String,
+    pub display_name: String,
+    pub corruption_band: Option<u8>,
+    pub upgrades_to: Option<String>,
+}
+
+/// One path along the upgrade links, from a model nothing upgrades into
+#[derive(Debug)]
+pub struct UpgradeChain<'a> {
+    pub steps: Vec<&'a UpgradeNode>,
+    /// Target the last step names but the set lacks
+    pub dangling: Option<&'a str>,
+    /// Step the last one loops back to
+    pub loops_to: Option<&'a str>,
+}
+
+impl UpgradeChain<'_> {
+    /// Whether the upgrade out of `step` lowers the corruption band
+    fn drops_band(&self, step: usize) -> bool {
+        match (self.steps.get(step), self.steps.get(step + 1)) {
+            (Some(from), Some(to)) => matches!((from.corruption_band, to.corruption_band), (Some(a), Some(b)) if b < a),
+            _ => false,
+        }
+    }
+
+    fn status(&self) -> String {
+        let mut problems = Vec::new();
+        if let Some(step) = (0..self.steps.len()).find(|step| self.drops_band(*step)) {
+            problems.push(format!("band drops after {}", self.steps[step].id));
+        }
+        if let Some(target) = self.dangling {
+            problems.push(format!("missing {}", target));
+        }
+        if let Some(target) = self.loops_to {
+            problems.push(format!("loops to {}", target));
+        }
+        if problems.is_empty() { "ok".to_string() } else { problems.join("; ") }
+    }
+}
+
+/// Upgrade links between models, keyed by id
+#[derive(Debug, Default)]
+pub struct UpgradeGraph {
+    nodes: BTreeMap<String, UpgradeNode>,
+}
+
+impl UpgradeGraph {
+    pub fn from_models<'a>(models: impl IntoIterator<Item = &'a ModelMetadata>) -> Self {
+        let nodes = models
+            .into_iter()
+            .map(|model| {
+                (model.id.clone(), UpgradeNode {
+                    id: model.id.clone(),
+                    display_name: model.display_name.clone(),
+                    corruption_band: model.corruption_band,
+                    upgrades_to: model.upgrades_to.clone(),
+                })
+            })
+            .collect();
+        Self { nodes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn target(&self, node: &UpgradeNode) -> Option<&UpgradeNode> {
+        node.upgrades_to.as_ref().and_then(|target| self.nodes.get(target))
+    }
+
+    /// Cycles, dangling targets and band inversions, in id order
+    pub fn issues(&self) -> Vec<UpgradeIssue> {
+        let mut issues = Vec::new();
+        for node in self.nodes.values() {
+            let Some(target) = &node.upgrades_to else {
+                continue;
+            };
+            match self.nodes.get(target) {
+                None => issues.push(UpgradeIssue::Dangling { id: node.id.clone(), target: target.clone() }),
+                Some(next) => match (node.corruption_band, next.corruption_band) {
+                    (Some(band), Some(target_band)) if target_band < band => issues.push(UpgradeIssue::BandInversion {
+                        id: node.id.clone(),
+                        band,
+                        target: target.clone(),
+                        target_band,
+                    }),
+                    _ => {}
+                },
+            }
+        }
+        issues.extend(self.cycles().into_iter().map(|ids| UpgradeIssue::Cycle { ids }));
+        issues
+    }
+
+    /// Each loop once, rotated to start at its smallest id. Every model has
+    /// at most one upgrade, so walking forward from each model finds them all.
+    fn cycles(&self) -> Vec<Vec<String>> {
+        let mut done: HashSet<&str> = HashSet::new();
+        let mut cycles = Vec::new();
+        for start in self.nodes.values() {
+            let mut path: Vec<&str> = Vec::new();
+            let mut current = Some(start);
+            while let Some(node) = current {
+                if done.contains(node.id.as_str()) {
+                    break;
+                }
+                if let Some(position) = path.iter().position(|id| *id == node.id) {
+                    let mut ids: Vec<String> = path[position..].iter().map(|id| id.to_string()).collect();
+                    let smallest = (0..ids.len()).min_by_key(|i| &ids[*i]).unwrap_or(0);
+                    ids.rotate_left(smallest);
+                    cycles.push(ids);
+                    break;
+                }
+                path.push(&node.id);
+                current = self.target(node);
+            }
+            done.extend(path);
+        }
+        cycles
+    }
+
+    /// Every chain, starting at models nothing upgrades into; loops with no
+    /// way in start at their smallest id. Models with no links are left out.
+    pub fn chains(&self) -> Vec<UpgradeChain<'_>> {
+        let targeted: HashSet<&str> = self.nodes.values().filter_map(|node| node.upgrades_to.as_deref()).collect();
+        let linked = |node: &&UpgradeNode| node.upgrades_to.is_some() || targeted.contains(node.id.as_str());
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut chains = Vec::new();
+
+        let roots = self.nodes.values().filter(linked).filter(|node| !targeted.contains(node.id.as_str()));
+        let mut starts: Vec<&UpgradeNode> = roots.collect();
+        for start in self.nodes.values().filter(linked) {
+            if !starts.iter().any(|root| root.id == start.id) {
+                starts.push(start);
+            }
+        }
+
+        for start in starts {
+            if visited.contains(start.id.as_str()) {
+                continue;
+            }
+            let mut chain = UpgradeChain { steps: Vec::new(), dangling: None, loops_to: None };
+            let mut current = start;
+            loop {
+                chain.steps.push(current);
+                visited.insert(&current.id);
+                let Some(target) = current.upgrades_to.as_deref() else {
+                    break;
+                };
+                match self.nodes.get(target) {
+                    None => chain.dangling = Some(target),
+                    Some(next) if chain.steps.iter().any(|step| step.id == next.id) => chain.loops_to = Some(&next.id),
+                    Some(next) => {
+                        current = next;
+                        continue;
+                    }
+                }
+                break;
+            }
+            chains.push(chain);
+        }
+        chains
+    }
+
+    /// Graphviz source; problem links are drawn red
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph upgrades {\n    rankdir=LR;\n    node [shape=box];\n");
+        for chain in self.chains() {
+            for (i, step) in chain.steps.iter().enumerate() {
+                let _ = writeln!(dot, "    \"{}\" [label=\"{}\\n{}\"];", escape_dot(&step.id), escape_dot(&step.display_name), band_label(step));
+                if let Some(next) = chain.steps.get(i + 1) {
+                    let colour = if chain.drops_band(i) { " [color=red]" } else { "" };
+                    let _ = writeln!(dot, "    \"{}\" -> \"{}\"{};", escape_dot(&step.id), escape_dot(&next.id), colour);
+                }
+            }
+            let last = escape_dot(&chain.steps[chain.steps.len() - 1].id);
+            if let Some(target) = chain.dangling {
+                let _ = writeln!(dot, "    \"{}\" [label=\"missing\\n{}\", color=red, style=dashed];", escape_dot(target), escape_dot(target));
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\" [color=red, style=dashed];", last, escape_dot(target));
+            }
+            if let Some(target) = chain.loops_to {
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\" [color=red];", last, escape_dot(target));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// One row of boxes per chain, left to right; problems are red
+    pub fn to_svg(&self) -> String {
+        let chains = self.chains();
+        let columns = chains.iter().map(|chain| chain.steps.len() + 1).max().unwrap_or(1) as f32;
+        let width = SVG_MARGIN * 2.0 + columns * SVG_BOX_WIDTH + (columns - 1.0) * SVG_GAP;
+        let height = SVG_MARGIN * 2.0 + chains.len() as f32 * (SVG_BOX_HEIGHT + SVG_GAP / 2.0);
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {:.1} {:.1}" font-family="sans-serif" font-size="12">"#,
+            width, height
+        );
+        let _ = writeln!(svg, r##"<rect width="100%" height="100%" fill="#111"/>"##);
+        for (row, chain) in chains.iter().enumerate() {
+            let y = SVG_MARGIN + row as f32 * (SVG_BOX_HEIGHT + SVG_GAP / 2.0);
+            let x_of = |column: usize| SVG_MARGIN + column as f32 * (SVG_BOX_WIDTH + SVG_GAP);
+            for (column, step) in chain.steps.iter().enumerate() {
+                svg_box(&mut svg, x_of(column), y, &step.display_name, &band_label(step), false);
+                if column + 1 < chain.steps.len() {
+                    svg_arrow(&mut svg, x_of(column) + SVG_BOX_WIDTH, x_of(column + 1), y, chain.drops_band(column));
+                }
+            }
+            let end = chain.steps.len();
+            if let Some(target) = chain.dangling {
+                svg_arrow(&mut svg, x_of(end - 1) + SVG_BOX_WIDTH, x_of(end), y, true);
+                svg_box(&mut svg, x_of(end), y, target, "missing", true);
+            }
+            if let Some(target) = chain.loops_to {
+                let _ = writeln!(
+                    svg,
+                    r##"<text x="{:.1}" y="{:.1}" fill="#e74c3c">&#8634; {}</text>"##,
+                    x_of(end - 1) + SVG_BOX_WIDTH + 6.0,
+                    y + SVG_BOX_HEIGHT / 2.0 + 4.0,
+                    escape_xml(target)
+                );
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// A table of chains followed by the issue list
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Upgrade Chains\n\n| # | Chain | Bands | Status |\n|---|---|---|---|\n");
+        for (i, chain) in self.chains().iter().enumerate() {
+            let names: Vec<String> = chain.steps.iter().map(|step| escape_markdown(&step.display_name)).collect();
+            let bands: Vec<String> = chain
+                .steps
+                .iter()
+                .map(|step| step.corruption_band.map_or("-".to_string(), |band| band.to_string()))
+                .collect();
+            let _ = writeln!(markdown, "| {} | {} | {} | {} |", i + 1, names.join(" → "), bands.join(" → "), escape_markdown(&chain.status()));
+        }
+
+        markdown.push_str("\n## Issues\n\n");
+        let issues = self.issues();
+        if issues.is_empty() {
+            markdown.push_str("No issues.\n");
+        }
+        for issue in issues {
+            let _ = writeln!(markdown, "- {}", escape_markdown(&issue.to_string()));
+        }
+        markdown
+    }
+}
+
+fn band_label(node: &UpgradeNode) -> String {
+    node.corruption_band.map_or("no band".to_string(), |band| format!("band {}", band))
+}
+
+fn svg_box(svg: &mut String, x: f32, y: f32, title: &str, subtitle: &str, problem: bool) {
+    let (stroke, dash) = if problem { ("#e74c3c", r#" stroke-dasharray="4,3""#) } else { ("#ccc", "") };
+    let _ = writeln!(
+        svg,
+        r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="4" fill="#222" stroke="{}"{}/>"##,
+        x, y, SVG_BOX_WIDTH, SVG_BOX_HEIGHT, stroke, dash
+    );
+    let _ = writeln!(svg, r##"<text x="{:.1}" y="{:.1}" fill="#eee">{}</text>"##, x + 8.0, y + 18.0, escape_xml(title));
+    let _ = writeln!(svg, r##"<text x="{:.1}" y="{:.1}" fill="#999">{}</text>"##, x + 8.0, y + 34.0, escape_xml(subtitle));
+}
+
+fn svg_arrow(svg: &mut String, from_x: f32, to_x: f32, y: f32, problem: bool) {
+    let colour = if problem { "#e74c3c" } else { "#ccc" };
+    let mid = y + SVG_BOX_HEIGHT / 2.0;
+    let _ = writeln!(svg, r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}"/>"#, from_x, mid, to_x - 6.0, mid, colour);
+    let _ = writeln!(
+        svg,
+        r#"<polygon points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" fill="{}"/>"#,
+        to_x, mid, to_x - 6.0, mid - 4.0, to_x - 6.0, mid + 4.0, colour
+    );
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dl_types::world::{ModelBounds, MODEL_METADATA_VERSION};
+
+    fn model(id: &str, band: Option<u8>, upgrades_to: Option<&str>) -> ModelMetadata {
+        ModelMetadata {
+            version: MODEL_METADATA_VERSION,
+            id: id.to_string(),
+            display_name: id.trim_end_matches("_model").replace('_', " "),
+            model_path: format!("units/cult/{}.glb", id),
+            scale: (1.0, 1.0, 1.0),
+            bounds: ModelBounds { min: (-0.5, 0.0, -0.5), max: (0.5, 2.0, 0.5) },
+            animations: Vec::new(),
+            sockets: Vec::new(),
+            tags: Vec::new(),
+            cult: None,
+            class: None,
+            upgrades_to: upgrades_to.map(str::to_string),
+            ui_icon: None,
+            sounds: Vec::new(),
+            corruption_band: band,
+            horror_theme: None,
+            forge_material: None,
+        }
+    }
+
+    fn sample() -> Vec<ModelMetadata> {
+        vec![
+            model("acolyte_model", Some(1), Some("cultist_model")),
+            model("cultist_model", Some(2), Some("priest_model")),
+            model("priest_model", Some(1), Some("archon_model")),
+            model("wolf_model", None, None),
+            model("shade_model", Some(4), Some("wraith_model")),
+            model("wraith_model", Some(4), Some("shade_model")),
+        ]
+    }
+
+    #[test]
+    fn test_issues_find_cycles_dangling_and_inversions() {
+        let models = sample();
+        let graph = UpgradeGraph::from_models(&models);
+        assert_eq!(graph.issues(), vec![
+            UpgradeIssue::BandInversion {
+                id: "cultist_model".to_string(),
+                band: 2,
+                target: "priest_model".to_string(),
+                target_band: 1,
+            },
+            UpgradeIssue::Dangling { id: "priest_model".to_string(), target: "archon_model".to_string() },
+            UpgradeIssue::Cycle { ids: vec!["shade_model".to_string(), "wraith_model".to_string()] },
+        ]);
+
+        let clean = [model("acolyte_model", Some(1), Some("cultist_model")), model("cultist_model", Some(2), None)];
+        assert!(UpgradeGraph::from_models(&clean).issues().is_empty());
+    }
+
+    #[test]
+    fn test_chains_and_renderings() {
+        let models = sample();
+        let graph = UpgradeGraph::from_models(&models);
+        let chains = graph.chains();
+        assert_eq!(chains.len(), 2);
+        let ids: Vec<&str> = chains[0].steps.iter().map(|step| step.id.as_str()).collect();
+        assert_eq!(ids, ["acolyte_model", "cultist_model", "priest_model"]);
+        assert_eq!(chains[0].dangling, Some("archon_model"));
+        assert_eq!(chains[1].loops_to, Some("shade_model"));
+
+        let markdown = graph.to_markdown();
+        assert!(markdown.contains("| 1 | acolyte → cultist → priest | 1 → 2 → 1 | band drops after cultist_model; missing archon_model |"));
+        assert!(markdown.contains("- upgrade cycle: shade_model -> wraith_model -> shade_model"));
+        assert!(!markdown.contains("wolf"));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"cultist_model\" -> \"priest_model\" [color=red];"));
+        assert!(dot.contains("\"wraith_model\" -> \"shade_model\" [color=red];"));
+
+        let svg = graph.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect x=").count(), 6);
+    }
+}
@@ -171,14 +171,69 @@ pub fn extract_connection_from_line(line: &str) -> String {
     }
 }
 
+/// Renders HBF HTML as readable text: block elements become lines, tags go
+/// and common character entities are decoded
+pub struct HtmlText {
+    block_end: Regex,
+    tags: Regex,
+    spaces: Regex,
+}
+
+impl HtmlText {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            block_end: Regex::new(r"(?i)<br\s*/?>|</(p|div|li|h[1-6]|tr|ul|ol|table|blockquote)>")?,
+            tags: Regex::new(r"<[^>]+>")?,
+            spaces: Regex::new(r"[ \t]+")?,
+        })
+    }
+
+    pub fn render(&self, html: &str) -> String {
+        let text = self.block_end.replace_all(html, "\n");
+        let text = self.tags.replace_all(&text, "");
+        let text = text
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&");
+        let text = self.spaces.replace_all(&text, " ");
+        text.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>().join("\n")
+    }
+}
+
 /// Sanitize name for use as Rust identifier
 pub fn sanitize_name(name: &str) -> String {
     name.replace(['-', ' ', '\''], "_").to_lowercase()
 }
 
+/// Light suffix stripping so "haunted", "haunting" and "haunts" meet
+pub fn stem(word: &str) -> String {
+    if word.len() <= 3 {
+        return word.to_string();
+    }
+    if let Some(base) = word.strip_suffix("ies").filter(|base| base.len() >= 2) {
+        return format!("{}y", base);
+    }
+    for suffix in ["ing", "ed"] {
+        if let Some(base) = word.strip_suffix(suffix).filter(|base| base.len() >= 3) {
+            return base.to_string();
+        }
+    }
+    let sibilant = |base: &&str| base.ends_with(['s', 'x', 'z']) || base.ends_with("ch") || base.ends_with("sh");
+    if let Some(base) = word.strip_suffix("es").filter(sibilant) {
+        return base.to_string();
+    }
+    if let Some(base) = word.strip_suffix('s').filter(|base| !base.ends_with(['s', 'u'])) {
+        return base.to_string();
+    }
+    word.to_string()
+}
+
 /// Simple hash function for generating consistent coordinates from UUID
 pub fn simple_hash(s: &str) -> u32 {
-    s.bytes().fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32))
+    dl_types::world::seeded::fnv1a(s.as_bytes()) as u32
 }
 
 /// Determine biome type from hex coordinates
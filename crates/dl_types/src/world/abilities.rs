@@ -0,0 +1,483 @@
+//! Abilities and skills
+//!
+//! Abilities are data: each one names its costs in health, sanity and
+//! fatigue, a cooldown in game hours, what hexes it reaches and what it does.
+//! The same definition works in combat and on the overworld. Abilities unlock
+//! as the player levels through the 180 levels of the journey. Dread-tainted
+//! abilities grow stronger as dread rises, but every use corrupts the one who
+//! calls on them.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::world::hex::HexCoord;
+use crate::world::items::ItemEffect;
+
+pub const MAX_LEVEL: u32 = 180;
+/// Dread level at which a tainted ability reaches its full `power_per_dread`
+const FULL_TAINT_DREAD: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbilityKind {
+    Active,
+    /// Always on once learned
+    Passive,
+}
+
+/// Where an active ability can be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AbilityContext {
+    Combat,
+    Overworld,
+    #[default]
+    Anywhere,
+}
+
+impl AbilityContext {
+    pub fn allows(&self, context: AbilityContext) -> bool {
+        *self == AbilityContext::Anywhere || *self == context
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct AbilityCost {
+    #[serde(default)]
+    pub health: f32,
+    #[serde(default)]
+    pub sanity: f32,
+    #[serde(default)]
+    pub fatigue: f32,
+}
+
+/// What an active ability can be aimed at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AbilityTarget {
+    /// The caster's own hex
+    #[default]
+    Caster,
+    /// One hex within `range`
+    Hex { range: u32 },
+    /// Every hex within `radius` of a hex within `range`
+    Area { range: u32, radius: u32 },
+}
+
+impl AbilityTarget {
+    /// Hexes affected when cast from `origin` at `target`, or None when the
+    /// target is out of range
+    pub fn hexes(&self, origin: HexCoord, target: HexCoord) -> Option<Vec<HexCoord>> {
+        match *self {
+            AbilityTarget::Caster => Some(vec![origin]),
+            AbilityTarget::Hex { range } => (origin.distance_to(&target) <= range).then(|| vec![target]),
+            AbilityTarget::Area { range, radius } => {
+                if origin.distance_to(&target) > range {
+                    return None;
+                }
+                Some(target.within(radius))
+            }
+        }
+    }
+}
+
+/// One thing an active ability does to its target hexes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AbilityEffect {
+    Damage { amount: f32 },
+    Heal { amount: f32 },
+    RestoreSanity { amount: f32 },
+    /// Reduces fatigue
+    Vigor { amount: f32 },
+    /// A timed buff on the caster, the same as a consumable's
+    Buff { effect: ItemEffect, potency: u32, hours: f32 },
+    Dread { amount: f32 },
+    /// Pushes corruption back from the target hexes
+    Cleanse { amount: f32 },
+    /// Reveals the target hexes on the map
+    Reveal,
+}
+
+impl AbilityEffect {
+    /// The effect with its magnitude multiplied by `factor`
+    pub fn scaled(&self, factor: f32) -> Self {
+        match self {
+            AbilityEffect::Damage { amount } => AbilityEffect::Damage { amount: amount * factor },
+            AbilityEffect::Heal { amount } => AbilityEffect::Heal { amount: amount * factor },
+            AbilityEffect::RestoreSanity { amount } => AbilityEffect::RestoreSanity { amount: amount * factor },
+            AbilityEffect::Vigor { amount } => AbilityEffect::Vigor { amount: amount * factor },
+            AbilityEffect::Buff { effect, potency, hours } => AbilityEffect::Buff {
+                effect: effect.clone(),
+                potency: (*potency as f32 * factor).round() as u32,
+                hours: *hours,
+            },
+            AbilityEffect::Cleanse { amount } => AbilityEffect::Cleanse { amount: amount * factor },
+            // Dread and sight do not grow with power
+            AbilityEffect::Dread { .. } | AbilityEffect::Reveal => self.clone(),
+        }
+    }
+}
+
+/// Always-on bonuses from a passive ability
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PassiveBonus {
+    #[serde(default)]
+    pub attack: i32,
+    #[serde(default)]
+    pub defense: i32,
+    #[serde(default)]
+    pub dread_resistance: f32,
+}
+
+impl PassiveBonus {
+    pub fn add(&mut self, other: &PassiveBonus) {
+        self.attack += other.attack;
+        self.defense += other.defense;
+        self.dread_resistance += other.dread_resistance;
+    }
+}
+
+/// The void's mark on an ability
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Taint {
+    /// Extra power at full dread: 1.0 doubles the ability's effects
+    pub power_per_dread: f32,
+    /// Corruption the user takes on with every use
+    pub corruption: f32,
+}
+
+impl Taint {
+    pub fn power(&self, dread: f32) -> f32 {
+        1.0 + self.power_per_dread * (dread / FULL_TAINT_DREAD).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: AbilityKind,
+    #[serde(default)]
+    pub context: AbilityContext,
+    /// Player level at which the ability is learned, 1..=MAX_LEVEL
+    pub unlock_level: u32,
+    #[serde(default)]
+    pub cost: AbilityCost,
+    #[serde(default)]
+    pub cooldown_hours: f32,
+    #[serde(default)]
+    pub target: AbilityTarget,
+    #[serde(default)]
+    pub effects: Vec<AbilityEffect>,
+    #[serde(default)]
+    pub passive: PassiveBonus,
+    #[serde(default)]
+    pub taint: Option<Taint>,
+}
+
+/// Every ability in the game, keyed by id
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AbilityDatabase {
+    pub abilities: HashMap<String, AbilityDefinition>,
+}
+
+impl AbilityDatabase {
+    pub fn insert(&mut self, ability: AbilityDefinition) {
+        self.abilities.insert(ability.id.clone(), ability);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AbilityDefinition> {
+        self.abilities.get(id)
+    }
+
+    /// Abilities learned by `level`, in unlock order
+    pub fn unlocked_by(&self, level: u32) -> Vec<&AbilityDefinition> {
+        let mut unlocked: Vec<_> = self
+            .abilities
+            .values()
+            .filter(|ability| ability.unlock_level <= level.min(MAX_LEVEL))
+            .collect();
+        unlocked.sort_by(|a, b| a.unlock_level.cmp(&b.unlock_level).then_with(|| a.id.cmp(&b.id)));
+        unlocked
+    }
+}
+
+/// What the caster has to spend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AbilityResources {
+    pub health: f32,
+    pub sanity: f32,
+    pub fatigue: f32,
+    pub max_fatigue: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbilityError {
+    NotLearned,
+    Passive,
+    WrongContext,
+    OnCooldown { remaining_hours: f32 },
+    OutOfRange,
+    Insufficient { resource: &'static str },
+}
+
+impl fmt::Display for AbilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbilityError::NotLearned => write!(f, "not learned"),
+            AbilityError::Passive => write!(f, "passive abilities are always on"),
+            AbilityError::WrongContext => write!(f, "cannot be used here"),
+            AbilityError::OnCooldown { remaining_hours } => write!(f, "ready in {:.1} hours", remaining_hours),
+            AbilityError::OutOfRange => write!(f, "target out of range"),
+            AbilityError::Insufficient { resource } => write!(f, "not enough {}", resource),
+        }
+    }
+}
+
+/// A successful use: costs to pay, the hexes hit and the (scaled) effects
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbilityUse {
+    pub ability_id: String,
+    pub cost: AbilityCost,
+    pub hexes: Vec<HexCoord>,
+    pub effects: Vec<AbilityEffect>,
+    /// Corruption taken on from a tainted ability
+    pub corruption: f32,
+}
+
+/// Abilities an entity has learned, their cooldowns and the corruption
+/// tainted abilities have left on them
+#[derive(Component, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AbilityBook {
+    pub known: Vec<String>,
+    pub cooldowns: HashMap<String, f32>,
+    pub corruption: f32,
+}
+
+impl AbilityBook {
+    pub fn knows(&self, id: &str) -> bool {
+        self.known.iter().any(|known| known == id)
+    }
+
+    /// Learn everything unlocked by `level` and return the ids newly learned
+    pub fn learn_up_to(&mut self, database: &AbilityDatabase, level: u32) -> Vec<String> {
+        let mut learned = Vec::new();
+        for ability in database.unlocked_by(level) {
+            if !self.knows(&ability.id) {
+                self.known.push(ability.id.clone());
+                learned.push(ability.id.clone());
+            }
+        }
+        learned
+    }
+
+    pub fn cooldown(&self, id: &str) -> f32 {
+        self.cooldowns.get(id).copied().unwrap_or(0.0)
+    }
+
+    /// Advance cooldowns by game hours
+    pub fn tick(&mut self, hours: f32) {
+        for remaining in self.cooldowns.values_mut() {
+            *remaining -= hours;
+        }
+        self.cooldowns.retain(|_, remaining| *remaining > 0.0);
+    }
+
+    /// Combined bonuses of every passive ability known
+    pub fn passive_bonus(&self, database: &AbilityDatabase) -> PassiveBonus {
+        let mut bonus = PassiveBonus::default();
+        for ability in self.known.iter().filter_map(|id| database.get(id)) {
+            if ability.kind == AbilityKind::Passive {
+                bonus.add(&ability.passive);
+            }
+        }
+        bonus
+    }
+
+    /// Use an active ability from `origin` at `target`. On success the
+    /// cooldown starts and any taint is taken on; the caller pays the costs
+    /// and applies the effects.
+    pub fn use_ability(
+        &mut self,
+        ability: &AbilityDefinition,
+        resources: &AbilityResources,
+        context: AbilityContext,
+        origin: HexCoord,
+        target: HexCoord,
+        dread: f32,
+    ) -> Result<AbilityUse, AbilityError> {
+        if !self.knows(&ability.id) {
+            return Err(AbilityError::NotLearned);
+        }
+        if ability.kind == AbilityKind::Passive {
+            return Err(AbilityError::Passive);
+        }
+        if !ability.context.allows(context) {
+            return Err(AbilityError::WrongContext);
+        }
+        let remaining_hours = self.cooldown(&ability.id);
+        if remaining_hours > 0.0 {
+            return Err(AbilityError::OnCooldown { remaining_hours });
+        }
+
+        let cost = ability.cost;
+        // Paying in blood may not kill the caster
+        if cost.health > 0.0 && cost.health >= resources.health {
+            return Err(AbilityError::Insufficient { resource: "health" });
+        }
+        if cost.sanity > resources.sanity {
+            return Err(AbilityError::Insufficient { resource: "sanity" });
+        }
+        if cost.fatigue > 0.0 && resources.fatigue + cost.fatigue > resources.max_fatigue {
+            return Err(AbilityError::Insufficient { resource: "stamina" });
+        }
+        let hexes = ability.target.hexes(origin, target).ok_or(AbilityError::OutOfRange)?;
+
+        let power = ability.taint.map_or(1.0, |taint| taint.power(dread));
+        let corruption = ability.taint.map_or(0.0, |taint| taint.corruption);
+        self.corruption += corruption;
+        if ability.cooldown_hours > 0.0 {
+            self.cooldowns.insert(ability.id.clone(), ability.cooldown_hours);
+        }
+
+        Ok(AbilityUse {
+            ability_id: ability.id.clone(),
+            cost,
+            hexes,
+            effects: ability.effects.iter().map(|effect| effect.scaled(power)).collect(),
+            corruption,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ability(id: &str, unlock_level: u32) -> AbilityDefinition {
+        AbilityDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            kind: AbilityKind::Active,
+            context: AbilityContext::Anywhere,
+            unlock_level,
+            cost: AbilityCost::default(),
+            cooldown_hours: 0.0,
+            target: AbilityTarget::Caster,
+            effects: Vec::new(),
+            passive: PassiveBonus::default(),
+            taint: None,
+        }
+    }
+
+    fn resources() -> AbilityResources {
+        AbilityResources {
+            health: 50.0,
+            sanity: 50.0,
+            fatigue: 0.0,
+            max_fatigue: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_abilities_unlock_with_level() {
+        let mut database = AbilityDatabase::default();
+        database.insert(ability("second_wind", 1));
+        database.insert(ability("void_lance", 61));
+        let mut ward = ability("iron_will", 21);
+        ward.kind = AbilityKind::Passive;
+        ward.passive = PassiveBonus {
+            attack: 0,
+            defense: 2,
+            dread_resistance: 0.1,
+        };
+        database.insert(ward);
+
+        let mut book = AbilityBook::default();
+        assert_eq!(book.learn_up_to(&database, 30), vec!["second_wind", "iron_will"]);
+        assert!(book.learn_up_to(&database, 30).is_empty());
+        assert_eq!(book.learn_up_to(&database, 500), vec!["void_lance"]);
+        assert_eq!(book.passive_bonus(&database).defense, 2);
+    }
+
+    #[test]
+    fn test_costs_cooldowns_and_targeting() {
+        let mut fireball = ability("fireball", 1);
+        fireball.cost.fatigue = 20.0;
+        fireball.cooldown_hours = 2.0;
+        fireball.context = AbilityContext::Combat;
+        fireball.target = AbilityTarget::Area { range: 3, radius: 1 };
+        fireball.effects = vec![AbilityEffect::Damage { amount: 12.0 }];
+
+        let mut book = AbilityBook {
+            known: vec!["fireball".to_string()],
+            ..Default::default()
+        };
+        let origin = HexCoord::origin();
+        assert_eq!(
+            book.use_ability(&fireball, &resources(), AbilityContext::Overworld, origin, origin, 0.0),
+            Err(AbilityError::WrongContext)
+        );
+        assert_eq!(
+            book.use_ability(&fireball, &resources(), AbilityContext::Combat, origin, HexCoord::new(4, 0), 0.0),
+            Err(AbilityError::OutOfRange)
+        );
+        let tired = AbilityResources {
+            fatigue: 90.0,
+            ..resources()
+        };
+        assert_eq!(
+            book.use_ability(&fireball, &tired, AbilityContext::Combat, origin, origin, 0.0),
+            Err(AbilityError::Insufficient { resource: "stamina" })
+        );
+
+        let cast = book
+            .use_ability(&fireball, &resources(), AbilityContext::Combat, origin, HexCoord::new(2, 0), 0.0)
+            .unwrap();
+        assert_eq!(cast.hexes.len(), 7);
+        assert!(cast.hexes.contains(&HexCoord::new(2, 0)));
+        assert!(matches!(
+            book.use_ability(&fireball, &resources(), AbilityContext::Combat, origin, origin, 0.0),
+            Err(AbilityError::OnCooldown { .. })
+        ));
+        book.tick(2.0);
+        assert!(book.use_ability(&fireball, &resources(), AbilityContext::Combat, origin, origin, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_tainted_abilities_grow_with_dread_and_corrupt() {
+        let mut drain = ability("blood_drain", 41);
+        drain.cost.health = 10.0;
+        drain.effects = vec![AbilityEffect::Damage { amount: 10.0 }];
+        drain.taint = Some(Taint {
+            power_per_dread: 1.0,
+            corruption: 3.0,
+        });
+        let mut book = AbilityBook {
+            known: vec!["blood_drain".to_string()],
+            ..Default::default()
+        };
+        let origin = HexCoord::origin();
+
+        let calm = book
+            .use_ability(&drain, &resources(), AbilityContext::Combat, origin, origin, 0.0)
+            .unwrap();
+        let dreadful = book
+            .use_ability(&drain, &resources(), AbilityContext::Combat, origin, origin, 100.0)
+            .unwrap();
+        assert_eq!(calm.effects, vec![AbilityEffect::Damage { amount: 10.0 }]);
+        assert_eq!(dreadful.effects, vec![AbilityEffect::Damage { amount: 20.0 }]);
+        assert_eq!(book.corruption, 6.0);
+
+        let wounded = AbilityResources {
+            health: 10.0,
+            ..resources()
+        };
+        assert_eq!(
+            book.use_ability(&drain, &wounded, AbilityContext::Combat, origin, origin, 0.0),
+            Err(AbilityError::Insufficient { resource: "health" })
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Moral alignment and the consequences of player choices
+//!
+//! The arc of the game is becoming what you fought against. Three axes track
+//! it: mercy against cruelty, the light against the void's pacts, and loyalty
+//! to companions against looking out for yourself. Every choice nudges them
+//! and carries consequences, some at once and some that come due bands later,
+//! long after the player has moved on. The ledger keeps all of it so designers
+//! can see why the world turned out the way it did.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+pub const AXIS_LIMIT: f32 = 100.0;
+/// Light axis value past which the player is committed to a path
+const PATH_THRESHOLD: f32 = 25.0;
+
+/// A position on the three moral axes, or a shift along them. Positive is
+/// mercy, light and loyalty; negative is cruelty, void and self-interest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Alignment {
+    pub mercy: f32,
+    pub light: f32,
+    pub loyalty: f32,
+}
+
+impl Alignment {
+    pub fn shift(&mut self, shift: &Alignment) {
+        self.mercy = (self.mercy + shift.mercy).clamp(-AXIS_LIMIT, AXIS_LIMIT);
+        self.light = (self.light + shift.light).clamp(-AXIS_LIMIT, AXIS_LIMIT);
+        self.loyalty = (self.loyalty + shift.loyalty).clamp(-AXIS_LIMIT, AXIS_LIMIT);
+    }
+
+    pub fn path(&self) -> MoralPath {
+        if self.light >= PATH_THRESHOLD {
+            MoralPath::Light
+        } else if self.light <= -PATH_THRESHOLD {
+            MoralPath::Dark
+        } else {
+            MoralPath::Undecided
+        }
+    }
+
+    /// How far the player has become what they fought, 0.0..=1.0: the depth
+    /// of their cruelty, void pacts and betrayals
+    pub fn descent(&self) -> f32 {
+        [self.mercy, self.light, self.loyalty]
+            .iter()
+            .map(|value| (-value).max(0.0) / AXIS_LIMIT)
+            .sum::<f32>()
+            / 3.0
+    }
+
+    pub fn stage(&self) -> Becoming {
+        match self.descent() {
+            d if d < 0.15 => Becoming::Untouched,
+            d if d < 0.35 => Becoming::Shadowed,
+            d if d < 0.6 => Becoming::Compromised,
+            _ => Becoming::Mirror,
+        }
+    }
+}
+
+/// The Holy and Blood Pact paths of the design bible
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoralPath {
+    Undecided,
+    Light,
+    Dark,
+}
+
+/// Stages of becoming what you fought against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Becoming {
+    Untouched,
+    Shadowed,
+    Compromised,
+    /// The player now mirrors the dragon they set out to kill
+    Mirror,
+}
+
+/// One thing a choice does to the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConsequenceEffect {
+    Dread { amount: f32 },
+    /// Trust change for one companion by name, or all of them
+    CompanionTrust { companion: Option<String>, amount: f32 },
+    /// Reputation change with a faction by uuid
+    Reputation { faction: String, amount: i32 },
+    StoryFlag { flag: String, value: bool },
+    /// Corruption spreading out from wherever the player stands
+    CorruptionSpread { radius: u32, intensity: f32 },
+    VoidIncursion { stability: f32 },
+    CompanionCrisis { companion: String, crisis: String },
+}
+
+impl ConsequenceEffect {
+    pub fn describe(&self) -> String {
+        match self {
+            ConsequenceEffect::Dread { amount } => format!("dread {:+.1}", amount),
+            ConsequenceEffect::CompanionTrust { companion, amount } => {
+                format!("{} trust {:+.0}", companion.as_deref().unwrap_or("all companions"), amount)
+            }
+            ConsequenceEffect::Reputation { faction, amount } => format!("{} reputation {:+}", faction, amount),
+            ConsequenceEffect::StoryFlag { flag, value } => format!("flag {} = {}", flag, value),
+            ConsequenceEffect::CorruptionSpread { radius, intensity } => {
+                format!("corruption spreads {} hexes at {:.2}", radius, intensity)
+            }
+            ConsequenceEffect::VoidIncursion { stability } => format!("void incursion (stability {:.2})", stability),
+            ConsequenceEffect::CompanionCrisis { companion, crisis } => format!("{} crisis: {}", companion, crisis),
+        }
+    }
+}
+
+/// Trust a companion gains or loses from how they reacted to a choice
+/// ("approves", "horrified", ...)
+pub fn companion_reaction_trust(reaction: &str) -> f32 {
+    let reaction = reaction.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|word| reaction.contains(word));
+
+    // Check the negatives first so "disapproves" is not read as "approves"
+    if has(&["disapprov", "disgust", "horrif", "betray", "furious", "hate"]) {
+        -15.0
+    } else if has(&["uneasy", "worried", "doubt", "afraid", "sad"]) {
+        -5.0
+    } else if has(&["approv", "admir", "grateful", "proud", "relieved", "trust"]) {
+        10.0
+    } else {
+        0.0
+    }
+}
+
+/// A consequence that comes due some bands after the choice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DelayedConsequence {
+    pub bands_later: u32,
+    pub description: String,
+    pub effects: Vec<ConsequenceEffect>,
+}
+
+/// A choice as the alignment system sees it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoralChoice {
+    pub id: String,
+    pub text: String,
+    pub alignment: Alignment,
+    pub effects: Vec<ConsequenceEffect>,
+    pub delayed: Vec<DelayedConsequence>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledConsequence {
+    pub choice_id: String,
+    pub due_band: u32,
+    pub description: String,
+    pub effects: Vec<ConsequenceEffect>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub choice_id: String,
+    pub text: String,
+    pub band: u32,
+    pub shift: Alignment,
+    pub consequences: Vec<String>,
+}
+
+/// The player's alignment and every choice that shaped it
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChoiceLedger {
+    pub alignment: Alignment,
+    pub entries: Vec<LedgerEntry>,
+    pub pending: Vec<ScheduledConsequence>,
+    pub resolved: Vec<ScheduledConsequence>,
+}
+
+impl ChoiceLedger {
+    /// Record a choice made in `band`: shift alignment, schedule its delayed
+    /// consequences and return the effects to apply now
+    pub fn record(&mut self, choice: &MoralChoice, band: u32) -> Vec<ConsequenceEffect> {
+        self.alignment.shift(&choice.alignment);
+
+        let mut consequences: Vec<String> = choice.effects.iter().map(ConsequenceEffect::describe).collect();
+        for delayed in &choice.delayed {
+            let due_band = band + delayed.bands_later.max(1);
+            consequences.push(format!("band {}: {}", due_band, delayed.description));
+            self.pending.push(ScheduledConsequence {
+                choice_id: choice.id.clone(),
+                due_band,
+                description: delayed.description.clone(),
+                effects: delayed.effects.clone(),
+            });
+        }
+
+        self.entries.push(LedgerEntry {
+            choice_id: choice.id.clone(),
+            text: choice.text.clone(),
+            band,
+            shift: choice.alignment,
+            consequences,
+        });
+        choice.effects.clone()
+    }
+
+    /// Consequences that have come due by `band`, in the order they were
+    /// scheduled
+    pub fn take_due(&mut self, band: u32) -> Vec<ScheduledConsequence> {
+        let (due, pending): (Vec<_>, Vec<_>) = self.pending.drain(..).partition(|scheduled| scheduled.due_band <= band);
+        self.pending = pending;
+        self.resolved.extend(due.iter().cloned());
+        due
+    }
+
+    /// Plain-text view of the ledger for designers
+    pub fn report(&self) -> String {
+        let alignment = &self.alignment;
+        let mut report = format!(
+            "Alignment: mercy {:+.0}, light {:+.0}, loyalty {:+.0} ({:?} path, {:?})\n",
+            alignment.mercy,
+            alignment.light,
+            alignment.loyalty,
+            alignment.path(),
+            alignment.stage()
+        );
+        for entry in &self.entries {
+            let _ = writeln!(
+                report,
+                "[band {}] {} \"{}\" (mercy {:+.0}, light {:+.0}, loyalty {:+.0})",
+                entry.band, entry.choice_id, entry.text, entry.shift.mercy, entry.shift.light, entry.shift.loyalty
+            );
+            for consequence in &entry.consequences {
+                let _ = writeln!(report, "    - {}", consequence);
+            }
+        }
+        for scheduled in &self.pending {
+            let _ = writeln!(
+                report,
+                "Pending band {}: {} (from {})",
+                scheduled.due_band, scheduled.description, scheduled.choice_id
+            );
+        }
+        for scheduled in &self.resolved {
+            let _ = writeln!(
+                report,
+                "Resolved band {}: {} (from {})",
+                scheduled.due_band, scheduled.description, scheduled.choice_id
+            );
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blood_pact() -> MoralChoice {
+        MoralChoice {
+            id: "blood_pact".to_string(),
+            text: "Sacrifice the prisoners to learn the dragon's lair".to_string(),
+            alignment: Alignment {
+                mercy: -30.0,
+                light: -40.0,
+                loyalty: 0.0,
+            },
+            effects: vec![ConsequenceEffect::Dread { amount: 10.0 }],
+            delayed: vec![DelayedConsequence {
+                bands_later: 20,
+                description: "The cult comes to collect".to_string(),
+                effects: vec![ConsequenceEffect::VoidIncursion { stability: 0.4 }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_choices_shift_alignment_and_schedule_consequences() {
+        let mut ledger = ChoiceLedger::default();
+        let immediate = ledger.record(&blood_pact(), 12);
+        assert_eq!(immediate, vec![ConsequenceEffect::Dread { amount: 10.0 }]);
+        assert_eq!(ledger.alignment.path(), MoralPath::Dark);
+        assert_eq!(ledger.alignment.stage(), Becoming::Shadowed);
+
+        assert!(ledger.take_due(31).is_empty());
+        let due = ledger.take_due(32);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].effects, vec![ConsequenceEffect::VoidIncursion { stability: 0.4 }]);
+        assert!(ledger.pending.is_empty());
+        assert_eq!(ledger.resolved.len(), 1);
+
+        // A second descent into cruelty leaves the player a mirror of the dragon
+        let mut worse = blood_pact();
+        worse.alignment.loyalty = -100.0;
+        worse.delayed.clear();
+        ledger.record(&worse, 40);
+        ledger.record(&worse, 41);
+        assert_eq!(ledger.alignment.stage(), Becoming::Mirror);
+        assert_eq!(ledger.alignment.light, -AXIS_LIMIT);
+
+        let report = ledger.report();
+        assert!(report.contains("Dark path"));
+        assert!(report.contains("Resolved band 32: The cult comes to collect"));
+    }
+
+    #[test]
+    fn test_companion_reactions() {
+        assert_eq!(companion_reaction_trust("Approves"), 10.0);
+        assert_eq!(companion_reaction_trust("disapproves strongly"), -15.0);
+        assert_eq!(companion_reaction_trust("uneasy"), -5.0);
+        assert_eq!(companion_reaction_trust("shrugs"), 0.0);
+    }
+}
@@ -0,0 +1,411 @@
+//! The player's atlas of the world
+//!
+//! Every hex starts unseen. Rumours put distant hexes on the map without
+//! saying what is there, sight from where the player stands reveals as far
+//! as the terrain allows, and walking a hex marks it visited. Settlements,
+//! dungeons and landmarks are annotated as they come into view. The atlas is
+//! saved with the game and can be drawn as SVG for design review.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::world::hex::HexCoord;
+use crate::world::tiles::BiomeType;
+
+/// Hexes the player can see from level ground
+pub const BASE_SIGHT: u32 = 2;
+/// How far above the ground the player's eyes are, in elevation steps
+const EYE_HEIGHT: f32 = 0.5;
+/// Elevation assumed for terrain that has not been generated yet
+const DEFAULT_ELEVATION: u32 = 1;
+/// Hex radius in SVG units
+const SVG_HEX_SIZE: f32 = 12.0;
+
+/// How much the player knows about a hex; only ever rises
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Discovery {
+    #[default]
+    Unseen,
+    /// Heard about but not seen: on the map, terrain unknown
+    Rumoured,
+    Seen,
+    Visited,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MarkerKind {
+    Settlement,
+    Dungeon,
+    Landmark,
+}
+
+/// Something worth drawing on the map
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtlasMarker {
+    pub kind: MarkerKind,
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AtlasHex {
+    pub hex: HexCoord,
+    pub discovery: Discovery,
+    /// Known once the hex has been seen
+    #[serde(default)]
+    pub biome: Option<BiomeType>,
+    #[serde(default)]
+    pub markers: Vec<AtlasMarker>,
+}
+
+/// Elevation steps above the water line: 0 for water and marsh, 1 for
+/// lowland, 2 for foothills and 3 for mountains
+pub fn elevation(biome: &BiomeType) -> u32 {
+    match biome {
+        BiomeType::Water
+        | BiomeType::Swamp
+        | BiomeType::SwampWater
+        | BiomeType::CorruptedWater
+        | BiomeType::CorruptedSwamp
+        | BiomeType::VoidWater
+        | BiomeType::VoidSwamp => 0,
+        BiomeType::MountainForest | BiomeType::DesertMountain => 2,
+        BiomeType::Mountain
+        | BiomeType::SnowMountain
+        | BiomeType::CorruptedMountain
+        | BiomeType::VoidMountain => 3,
+        _ => 1,
+    }
+}
+
+/// How many hexes the player can see from ground of this elevation
+pub fn sight_range(elevation: u32) -> u32 {
+    BASE_SIGHT + elevation
+}
+
+fn cube_round(x: f32, y: f32, z: f32) -> HexCoord {
+    let (mut rx, mut ry, mut rz) = (x.round(), y.round(), z.round());
+    let (dx, dy, dz) = ((rx - x).abs(), (ry - y).abs(), (rz - z).abs());
+    if dx > dy && dx > dz {
+        rx = -ry - rz;
+    } else if dy > dz {
+        ry = -rx - rz;
+    } else {
+        rz = -rx - ry;
+    }
+    HexCoord::from_cube(rx as i32, ry as i32, rz as i32)
+}
+
+/// The hexes on a straight line from `from` to `to`, both ends included
+pub fn hex_line(from: HexCoord, to: HexCoord) -> Vec<HexCoord> {
+    let steps = from.distance_to(&to);
+    if steps == 0 {
+        return vec![from];
+    }
+    let (ax, ay, az) = from.to_cube();
+    let (bx, by, bz) = to.to_cube();
+    // Nudge off exact corners so ties round the same way every time
+    let (ax, ay, az) = (ax as f32 + 1e-6, ay as f32 + 1e-6, az as f32 - 2e-6);
+    (0..=steps)
+        .map(|i| {
+            let t = i as f32 / steps as f32;
+            cube_round(ax + (bx as f32 - ax) * t, ay + (by as f32 - ay) * t, az + (bz as f32 - az) * t)
+        })
+        .collect()
+}
+
+/// Whether `to` can be seen from `from`: no hex in between rises above the
+/// sight line from the viewer's eyes to the ground at the target
+pub fn line_of_sight(from: HexCoord, to: HexCoord, elevation_at: impl Fn(HexCoord) -> u32) -> bool {
+    let line = hex_line(from, to);
+    let steps = line.len() - 1;
+    if steps <= 1 {
+        return true;
+    }
+    let eye = elevation_at(from) as f32 + EYE_HEIGHT;
+    let target = elevation_at(to) as f32;
+    line[1..steps].iter().enumerate().all(|(i, hex)| {
+        let t = (i + 1) as f32 / steps as f32;
+        elevation_at(*hex) as f32 <= eye + (target - eye) * t
+    })
+}
+
+/// Hexes are keyed as "q,r" so the atlas saves as plain maps
+fn hex_key(hex: HexCoord) -> String {
+    format!("{},{}", hex.q, hex.r)
+}
+
+/// What the player has discovered of the world
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Atlas {
+    hexes: HashMap<String, AtlasHex>,
+}
+
+impl Atlas {
+    pub fn get(&self, hex: HexCoord) -> Option<&AtlasHex> {
+        self.hexes.get(&hex_key(hex))
+    }
+
+    pub fn discovery(&self, hex: HexCoord) -> Discovery {
+        self.get(hex).map_or(Discovery::Unseen, |entry| entry.discovery)
+    }
+
+    /// Every hex the player knows anything about
+    pub fn known(&self) -> impl Iterator<Item = &AtlasHex> {
+        self.hexes.values().filter(|entry| entry.discovery > Discovery::Unseen)
+    }
+
+    pub fn count(&self, discovery: Discovery) -> usize {
+        self.hexes.values().filter(|entry| entry.discovery == discovery).count()
+    }
+
+    fn entry(&mut self, hex: HexCoord) -> &mut AtlasHex {
+        self.hexes.entry(hex_key(hex)).or_insert_with(|| AtlasHex {
+            hex,
+            discovery: Discovery::Unseen,
+            biome: None,
+            markers: Vec::new(),
+        })
+    }
+
+    /// Raise what is known about a hex; returns whether it rose. Terrain is
+    /// only recorded from a hex that has been seen.
+    pub fn discover(&mut self, hex: HexCoord, discovery: Discovery, biome: Option<&BiomeType>) -> bool {
+        let entry = self.entry(hex);
+        if let Some(biome) = biome.filter(|_| discovery >= Discovery::Seen) {
+            entry.biome = Some(biome.clone());
+        }
+        if discovery <= entry.discovery {
+            return false;
+        }
+        entry.discovery = discovery;
+        true
+    }
+
+    /// Mark the hexes around `center` as rumoured; returns those that were
+    /// new to the map
+    pub fn rumour(&mut self, center: HexCoord, radius: u32) -> Vec<HexCoord> {
+        center.within(radius)
+            .into_iter()
+            .filter(|hex| self.discover(*hex, Discovery::Rumoured, None))
+            .collect()
+    }
+
+    /// Stand on `origin` and look around. Hills and mountains see further,
+    /// and higher ground in between hides what lies behind it. Returns the
+    /// hexes that were seen for the first time.
+    pub fn reveal_from(&mut self, origin: HexCoord, terrain: &HashMap<HexCoord, BiomeType>) -> Vec<HexCoord> {
+        let elevation_at = |hex: HexCoord| terrain.get(&hex).map_or(DEFAULT_ELEVATION, elevation);
+        let mut revealed = Vec::new();
+        if self.discovery(origin) < Discovery::Seen {
+            revealed.push(origin);
+        }
+        self.discover(origin, Discovery::Visited, terrain.get(&origin));
+        for hex in origin.within(sight_range(elevation_at(origin))) {
+            if hex == origin || !line_of_sight(origin, hex, elevation_at) {
+                continue;
+            }
+            let was_seen = self.discovery(hex) >= Discovery::Seen;
+            self.discover(hex, Discovery::Seen, terrain.get(&hex));
+            if !was_seen {
+                revealed.push(hex);
+            }
+        }
+        revealed
+    }
+
+    /// Put a marker on a hex, once
+    pub fn annotate(&mut self, hex: HexCoord, marker: AtlasMarker) -> bool {
+        let entry = self.entry(hex);
+        if entry.markers.contains(&marker) {
+            return false;
+        }
+        entry.markers.push(marker);
+        true
+    }
+
+    /// Draw the known world as SVG: terrain colours for seen hexes, grey
+    /// outlines for rumours, a heavier edge where the player has walked and
+    /// a dot per marker
+    pub fn to_svg(&self) -> String {
+        let mut known: Vec<&AtlasHex> = self.known().collect();
+        known.sort_by_key(|entry| (entry.hex.r, entry.hex.q));
+        let centers: Vec<(f32, f32)> = known.iter().map(|entry| svg_center(entry.hex)).collect();
+
+        let margin = SVG_HEX_SIZE * 2.0;
+        let min_x = centers.iter().map(|c| c.0).fold(0.0_f32, f32::min) - margin;
+        let min_y = centers.iter().map(|c| c.1).fold(0.0_f32, f32::min) - margin;
+        let max_x = centers.iter().map(|c| c.0).fold(0.0_f32, f32::max) + margin;
+        let max_y = centers.iter().map(|c| c.1).fold(0.0_f32, f32::max) + margin;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.1} {:.1} {:.1} {:.1}">"#,
+            min_x,
+            min_y,
+            max_x - min_x,
+            max_y - min_y
+        );
+        let _ = writeln!(svg, r##"<rect x="{:.1}" y="{:.1}" width="100%" height="100%" fill="#111"/>"##, min_x, min_y);
+        for (entry, (x, y)) in known.iter().zip(&centers) {
+            let points: Vec<String> = (0..6)
+                .map(|corner| {
+                    let angle = (60.0 * corner as f32 - 30.0).to_radians();
+                    format!("{:.1},{:.1}", x + SVG_HEX_SIZE * angle.cos(), y + SVG_HEX_SIZE * angle.sin())
+                })
+                .collect();
+            let (fill, stroke, extra) = match entry.discovery {
+                Discovery::Rumoured => ("none", "#888", r#" stroke-dasharray="2,2""#),
+                Discovery::Visited => (entry.biome.as_ref().map_or("#555", biome_colour), "#fff", ""),
+                _ => (entry.biome.as_ref().map_or("#555", biome_colour), "#222", ""),
+            };
+            let _ = writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{}" stroke="{}"{}><title>{},{} {:?}</title></polygon>"#,
+                points.join(" "),
+                fill,
+                stroke,
+                extra,
+                entry.hex.q,
+                entry.hex.r,
+                entry.discovery
+            );
+            for marker in &entry.markers {
+                let colour = match marker.kind {
+                    MarkerKind::Settlement => "#f5d442",
+                    MarkerKind::Dungeon => "#c0392b",
+                    MarkerKind::Landmark => "#8e44ad",
+                };
+                let _ = writeln!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{}"><title>{:?}: {}</title></circle>"#,
+                    x,
+                    y,
+                    colour,
+                    marker.kind,
+                    escape_xml(&marker.id)
+                );
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Pointy-top layout, matching the game world
+fn svg_center(hex: HexCoord) -> (f32, f32) {
+    let root3 = 3.0_f32.sqrt();
+    let x = SVG_HEX_SIZE * (root3 * hex.q as f32 + root3 / 2.0 * hex.r as f32);
+    let y = SVG_HEX_SIZE * 1.5 * hex.r as f32;
+    (x, y)
+}
+
+fn biome_colour(biome: &BiomeType) -> &'static str {
+    match biome {
+        BiomeType::Grassland | BiomeType::ForestGrassland => "#7cb342",
+        BiomeType::Forest | BiomeType::MountainForest => "#2e7d32",
+        BiomeType::Mountain | BiomeType::DesertMountain => "#8d6e63",
+        BiomeType::Desert => "#e0c068",
+        BiomeType::Swamp | BiomeType::SwampWater => "#556b2f",
+        BiomeType::Water => "#1e88e5",
+        BiomeType::Snow | BiomeType::SnowMountain => "#eceff1",
+        BiomeType::Lava => "#e65100",
+        BiomeType::Void => "#000",
+        BiomeType::CorruptedGrassland
+        | BiomeType::CorruptedForest
+        | BiomeType::CorruptedMountain
+        | BiomeType::CorruptedDesert
+        | BiomeType::CorruptedSwamp
+        | BiomeType::CorruptedWater
+        | BiomeType::CorruptedSnow => "#6a1b9a",
+        BiomeType::VoidGrassland
+        | BiomeType::VoidForest
+        | BiomeType::VoidMountain
+        | BiomeType::VoidDesert
+        | BiomeType::VoidSwamp
+        | BiomeType::VoidWater
+        | BiomeType::VoidSnow
+        | BiomeType::VoidLava => "#311b92",
+    }
+}
+
+/// Escape text for an SVG or other XML text node
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_only_rises() {
+        let mut atlas = Atlas::default();
+        let hex = HexCoord::new(4, -2);
+        assert_eq!(atlas.discovery(hex), Discovery::Unseen);
+        assert_eq!(atlas.rumour(hex, 1).len(), 7);
+        assert!(atlas.rumour(hex, 1).is_empty());
+        assert!(atlas.discover(hex, Discovery::Visited, Some(&BiomeType::Forest)));
+        assert!(!atlas.discover(hex, Discovery::Seen, None));
+        assert_eq!(atlas.discovery(hex), Discovery::Visited);
+        assert_eq!(atlas.get(hex).unwrap().biome, Some(BiomeType::Forest));
+        assert_eq!(atlas.count(Discovery::Rumoured), 6);
+
+        let marker = AtlasMarker {
+            kind: MarkerKind::Dungeon,
+            id: "barrow".to_string(),
+        };
+        assert!(atlas.annotate(hex, marker.clone()));
+        assert!(!atlas.annotate(hex, marker));
+
+        let saved = serde_json::to_string(&atlas).unwrap();
+        assert_eq!(serde_json::from_str::<Atlas>(&saved).unwrap(), atlas);
+    }
+
+    #[test]
+    fn test_mountains_see_further_and_block_sight() {
+        let origin = HexCoord::origin();
+        let mut terrain: HashMap<HexCoord, BiomeType> = origin.within(6)
+            .into_iter()
+            .map(|hex| (hex, BiomeType::Grassland))
+            .collect();
+        terrain.insert(HexCoord::new(1, 0), BiomeType::Mountain);
+
+        let mut atlas = Atlas::default();
+        let revealed = atlas.reveal_from(origin, &terrain);
+        assert_eq!(atlas.discovery(origin), Discovery::Visited);
+        assert!(revealed.contains(&HexCoord::new(1, 0)));
+        // The mountain hides the hex behind it but not those off to the side
+        assert_eq!(atlas.discovery(HexCoord::new(2, 0)), Discovery::Unseen);
+        assert_eq!(atlas.discovery(HexCoord::new(0, 2)), Discovery::Seen);
+        assert_eq!(atlas.discovery(HexCoord::new(0, 3)), Discovery::Seen);
+        assert_eq!(atlas.discovery(HexCoord::new(0, 4)), Discovery::Unseen);
+
+        // From the summit the view reaches five hexes
+        atlas.reveal_from(HexCoord::new(1, 0), &terrain);
+        assert_eq!(atlas.discovery(HexCoord::new(6, 0)), Discovery::Seen);
+        assert_eq!(atlas.discovery(HexCoord::new(7, 0)), Discovery::Unseen);
+    }
+
+    #[test]
+    fn test_svg_draws_known_hexes_and_markers() {
+        let mut atlas = Atlas::default();
+        atlas.discover(HexCoord::origin(), Discovery::Visited, Some(&BiomeType::Grassland));
+        atlas.rumour(HexCoord::new(5, 5), 0);
+        atlas.annotate(
+            HexCoord::origin(),
+            AtlasMarker {
+                kind: MarkerKind::Settlement,
+                id: "Hearth & Home".to_string(),
+            },
+        );
+
+        let svg = atlas.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<polygon").count(), 2);
+        assert!(svg.contains("stroke-dasharray"));
+        assert!(svg.contains("Settlement: Hearth &amp; Home"));
+    }
+}
@@ -132,7 +132,7 @@ pub struct NPC {
     pub reputation: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NPCType {
     Villager,
     Merchant,
@@ -37,7 +37,8 @@ pub enum TraumaLevel {
     Critical,   // Complete breakdown, requires special care
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Optional background attached alongside `Companion`
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CompanionType {
     Scholar {
         expertise: Vec<String>,
@@ -65,6 +66,16 @@ pub enum CompanionType {
     },
 }
 
+impl CompanionType {
+    /// Haggling skill a companion brings to trade; only merchants have any
+    pub fn negotiation_skill(&self) -> u32 {
+        match self {
+            CompanionType::Merchant { negotiation_skill, .. } => *negotiation_skill,
+            _ => 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmotionalResponse {
     pub stress_modifier: f32,
@@ -0,0 +1,557 @@
+//! Character creation
+//!
+//! A `CharacterDraft` is built up one choice at a time: name, gender,
+//! background, appearance, ability scores (point-buy or rolled) and the
+//! companion who sets out with the player. `build` validates the draft into a
+//! `CharacterSheet`. Nothing here needs a window, so tests and bots create
+//! characters through the same API as the creation screen.
+
+use bevy_color::Color;
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::world::character::{
+    CharacterAppearance, CharacterData, CharacterStats, ClothingSet, Gender, HairStyle, SkinTone,
+};
+use crate::world::companions::CompanionType;
+use crate::world::seeded::SplitMix64;
+
+pub const POINT_BUY_BUDGET: u32 = 27;
+/// Lowest and highest score point-buy allows before background bonuses
+pub const POINT_BUY_MIN: u32 = 8;
+pub const POINT_BUY_MAX: u32 = 15;
+/// Height and build scaling allowed for the player model
+pub const APPEARANCE_SCALE_RANGE: (f32, f32) = (0.8, 1.2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Attribute {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 6] = [
+        Attribute::Strength,
+        Attribute::Dexterity,
+        Attribute::Constitution,
+        Attribute::Intelligence,
+        Attribute::Wisdom,
+        Attribute::Charisma,
+    ];
+}
+
+impl CharacterStats {
+    pub fn get(&self, attribute: Attribute) -> u32 {
+        match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Dexterity => self.dexterity,
+            Attribute::Constitution => self.constitution,
+            Attribute::Intelligence => self.intelligence,
+            Attribute::Wisdom => self.wisdom,
+            Attribute::Charisma => self.charisma,
+        }
+    }
+
+    pub fn get_mut(&mut self, attribute: Attribute) -> &mut u32 {
+        match attribute {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Dexterity => &mut self.dexterity,
+            Attribute::Constitution => &mut self.constitution,
+            Attribute::Intelligence => &mut self.intelligence,
+            Attribute::Wisdom => &mut self.wisdom,
+            Attribute::Charisma => &mut self.charisma,
+        }
+    }
+
+    /// The usual (score - 10) / 2 modifier, rounded down
+    pub fn modifier(&self, attribute: Attribute) -> i32 {
+        (self.get(attribute) as i32 - 10).div_euclid(2)
+    }
+}
+
+/// Point-buy cost of a score, or None outside the point-buy range
+pub fn point_buy_cost(score: u32) -> Option<u32> {
+    match score {
+        8..=13 => Some(score - 8),
+        14 => Some(7),
+        15 => Some(9),
+        _ => None,
+    }
+}
+
+/// Who the player was before the dragon came
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Background {
+    #[default]
+    Farmhand,
+    Soldier,
+    Scholar,
+    Merchant,
+    Pilgrim,
+    Outlaw,
+}
+
+impl Background {
+    pub const ALL: [Background; 6] = [
+        Background::Farmhand,
+        Background::Soldier,
+        Background::Scholar,
+        Background::Merchant,
+        Background::Pilgrim,
+        Background::Outlaw,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Background::Farmhand => "You worked the fields until the fields stopped growing",
+            Background::Soldier => "You held a spear for a lord who is no longer there",
+            Background::Scholar => "You read of the dragon long before anyone believed it",
+            Background::Merchant => "You know the roads, the prices and who can be bought",
+            Background::Pilgrim => "You walked to the old shrines and came back changed",
+            Background::Outlaw => "You lived by what you could take and keep",
+        }
+    }
+
+    /// Scores the background adds on top of the player's own
+    pub fn bonuses(&self) -> [(Attribute, u32); 2] {
+        match self {
+            Background::Farmhand => [(Attribute::Constitution, 2), (Attribute::Strength, 1)],
+            Background::Soldier => [(Attribute::Strength, 2), (Attribute::Constitution, 1)],
+            Background::Scholar => [(Attribute::Intelligence, 2), (Attribute::Wisdom, 1)],
+            Background::Merchant => [(Attribute::Charisma, 2), (Attribute::Intelligence, 1)],
+            Background::Pilgrim => [(Attribute::Wisdom, 2), (Attribute::Charisma, 1)],
+            Background::Outlaw => [(Attribute::Dexterity, 2), (Attribute::Strength, 1)],
+        }
+    }
+
+    pub fn clothing(&self) -> ClothingSet {
+        match self {
+            Background::Farmhand => ClothingSet::Peasant,
+            Background::Soldier => ClothingSet::Warrior,
+            Background::Scholar => ClothingSet::Scholar,
+            Background::Merchant => ClothingSet::Merchant,
+            Background::Pilgrim => ClothingSet::Peasant,
+            Background::Outlaw => ClothingSet::Ranger,
+        }
+    }
+}
+
+/// A companion the player can set out with
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartingCompanion {
+    pub name: &'static str,
+    pub title: &'static str,
+    pub companion_type: CompanionType,
+}
+
+impl StartingCompanion {
+    /// Label stored in `Companion::companion_type`
+    pub fn type_name(&self) -> &'static str {
+        match self.companion_type {
+            CompanionType::Scholar { .. } => "scholar",
+            CompanionType::Warrior { .. } => "warrior",
+            CompanionType::Guide { .. } => "guide",
+            CompanionType::Mystic { .. } => "mystic",
+            CompanionType::Merchant { .. } => "merchant",
+            CompanionType::Refugee { .. } => "refugee",
+        }
+    }
+}
+
+pub fn starting_companions() -> Vec<StartingCompanion> {
+    vec![
+        StartingCompanion {
+            name: "Einar",
+            title: "The Loyal Friend",
+            companion_type: CompanionType::Warrior {
+                combat_style: "shield and sword".to_string(),
+                weapon_proficiency: vec!["sword".to_string(), "shield".to_string()],
+            },
+        },
+        StartingCompanion {
+            name: "Mira",
+            title: "The Wayfinder",
+            companion_type: CompanionType::Guide {
+                known_regions: Vec::new(),
+                survival_skills: 3,
+            },
+        },
+        StartingCompanion {
+            name: "Sorin",
+            title: "The Seeker",
+            companion_type: CompanionType::Scholar {
+                expertise: vec!["dragon lore".to_string()],
+                research_notes: HashMap::new(),
+            },
+        },
+    ]
+}
+
+pub fn starting_companion(name: &str) -> Option<StartingCompanion> {
+    starting_companions().into_iter().find(|companion| companion.name == name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StatMethod {
+    #[default]
+    PointBuy,
+    /// 4d6, lowest die dropped, for each score in order
+    Rolled { seed: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CreationError {
+    EmptyName,
+    NoCompanion,
+    UnknownCompanion(String),
+    ScoreOutOfRange(Attribute),
+    OverBudget { spent: u32 },
+    /// Point-buy changes on rolled scores
+    ScoresRolled,
+    AppearanceOutOfRange,
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreationError::EmptyName => write!(f, "the character needs a name"),
+            CreationError::NoCompanion => write!(f, "choose a companion"),
+            CreationError::UnknownCompanion(name) => write!(f, "no companion named {}", name),
+            CreationError::ScoreOutOfRange(attribute) => write!(f, "{:?} is out of range", attribute),
+            CreationError::OverBudget { spent } => {
+                write!(f, "{} of {} points spent", spent, POINT_BUY_BUDGET)
+            }
+            CreationError::ScoresRolled => write!(f, "rolled scores cannot be bought"),
+            CreationError::AppearanceOutOfRange => write!(f, "height and build must be between 0.8 and 1.2"),
+        }
+    }
+}
+
+/// A finished character
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterSheet {
+    pub data: CharacterData,
+    pub background: Background,
+    pub stat_method: StatMethod,
+    pub companion: String,
+}
+
+impl CharacterSheet {
+    pub fn model_path(&self) -> String {
+        self.data.appearance.get_player_model_path(&self.data.gender)
+    }
+
+    /// Maximum health at level 1; `Experience::max_health` adds levels gained
+    pub fn max_health(&self) -> f32 {
+        100.0 + 10.0 * self.data.stats.modifier(Attribute::Constitution) as f32
+    }
+
+    /// Maximum sanity at level 1; `Experience::max_sanity` adds levels gained
+    pub fn max_sanity(&self) -> f32 {
+        100.0 + 10.0 * self.data.stats.modifier(Attribute::Wisdom) as f32
+    }
+}
+
+/// A character being made. Scores are the player's own; background bonuses
+/// are added by `final_stats`.
+#[derive(Debug, Clone)]
+pub struct CharacterDraft {
+    pub name: String,
+    pub gender: Gender,
+    pub appearance: CharacterAppearance,
+    pub background: Background,
+    pub scores: CharacterStats,
+    pub stat_method: StatMethod,
+    pub companion: Option<String>,
+}
+
+impl Default for CharacterDraft {
+    fn default() -> Self {
+        let background = Background::default();
+        Self {
+            name: String::new(),
+            gender: Gender::Male,
+            appearance: CharacterAppearance {
+                clothing_set: background.clothing(),
+                ..Default::default()
+            },
+            background,
+            scores: minimum_scores(),
+            stat_method: StatMethod::PointBuy,
+            companion: None,
+        }
+    }
+}
+
+impl CharacterDraft {
+    /// A complete random character, for bots and tests
+    pub fn random(name: &str, seed: u64) -> Self {
+        let mut rng = SplitMix64::new(seed);
+        let mut pick = |count: usize| (rng.next_u64() % count as u64) as usize;
+
+        let background = Background::ALL[pick(Background::ALL.len())];
+        let gender = [Gender::Male, Gender::Female][pick(2)].clone();
+        let hair_style = [
+            HairStyle::Short,
+            HairStyle::Medium,
+            HairStyle::Long,
+            HairStyle::Braided,
+            HairStyle::Shaved,
+            HairStyle::Curly,
+        ][pick(6)]
+        .clone();
+        let skin_tone = [
+            SkinTone::Pale,
+            SkinTone::Fair,
+            SkinTone::Olive,
+            SkinTone::Tan,
+            SkinTone::Brown,
+            SkinTone::Dark,
+        ][pick(6)]
+        .clone();
+        let companions = starting_companions();
+        let companion = companions[pick(companions.len())].name.to_string();
+        let height = 0.8 + pick(41) as f32 * 0.01;
+        let weight = 0.8 + pick(41) as f32 * 0.01;
+        let hair_color = Color::srgb(0.1 + pick(60) as f32 * 0.01, 0.1 + pick(40) as f32 * 0.01, 0.05);
+
+        let mut draft = Self {
+            name: name.to_string(),
+            gender,
+            ..Default::default()
+        };
+        draft.set_background(background);
+        draft.appearance = CharacterAppearance {
+            hair_style,
+            hair_color,
+            skin_tone,
+            height,
+            weight,
+            ..draft.appearance
+        };
+        draft.roll(seed);
+        draft.companion = Some(companion);
+        draft
+    }
+
+    /// Change background; clothing follows it
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+        self.appearance.clothing_set = background.clothing();
+    }
+
+    pub fn points_spent(&self) -> u32 {
+        Attribute::ALL
+            .iter()
+            .filter_map(|attribute| point_buy_cost(self.scores.get(*attribute)))
+            .sum()
+    }
+
+    pub fn points_remaining(&self) -> i32 {
+        POINT_BUY_BUDGET as i32 - self.points_spent() as i32
+    }
+
+    /// Buy one more point of a score
+    pub fn raise(&mut self, attribute: Attribute) -> Result<(), CreationError> {
+        if self.stat_method != StatMethod::PointBuy {
+            return Err(CreationError::ScoresRolled);
+        }
+        let score = self.scores.get(attribute);
+        let (Some(current), Some(next)) = (point_buy_cost(score), point_buy_cost(score + 1)) else {
+            return Err(CreationError::ScoreOutOfRange(attribute));
+        };
+        let spent = self.points_spent() - current + next;
+        if spent > POINT_BUY_BUDGET {
+            return Err(CreationError::OverBudget { spent });
+        }
+        *self.scores.get_mut(attribute) += 1;
+        Ok(())
+    }
+
+    /// Sell back one point of a score
+    pub fn lower(&mut self, attribute: Attribute) -> Result<(), CreationError> {
+        if self.stat_method != StatMethod::PointBuy {
+            return Err(CreationError::ScoresRolled);
+        }
+        if self.scores.get(attribute) <= POINT_BUY_MIN {
+            return Err(CreationError::ScoreOutOfRange(attribute));
+        }
+        *self.scores.get_mut(attribute) -= 1;
+        Ok(())
+    }
+
+    /// Roll every score; the same seed always rolls the same scores
+    pub fn roll(&mut self, seed: u64) {
+        let mut rng = SplitMix64::new(seed);
+        for attribute in Attribute::ALL {
+            let mut dice: Vec<u32> = (0..4).map(|_| (rng.next_u64() % 6) as u32 + 1).collect();
+            dice.sort_unstable();
+            *self.scores.get_mut(attribute) = dice[1..].iter().sum();
+        }
+        self.stat_method = StatMethod::Rolled { seed };
+    }
+
+    /// Go back to point-buy with every score at the minimum
+    pub fn use_point_buy(&mut self) {
+        self.scores = minimum_scores();
+        self.stat_method = StatMethod::PointBuy;
+    }
+
+    /// Scores with the background's bonuses added
+    pub fn final_stats(&self) -> CharacterStats {
+        let mut stats = self.scores.clone();
+        for (attribute, bonus) in self.background.bonuses() {
+            *stats.get_mut(attribute) += bonus;
+        }
+        stats
+    }
+
+    pub fn validate(&self) -> Result<(), CreationError> {
+        if self.name.trim().is_empty() {
+            return Err(CreationError::EmptyName);
+        }
+        let (low, high) = APPEARANCE_SCALE_RANGE;
+        let in_range = |value: f32| (low..=high).contains(&value);
+        if !in_range(self.appearance.height) || !in_range(self.appearance.weight) {
+            return Err(CreationError::AppearanceOutOfRange);
+        }
+        match self.stat_method {
+            StatMethod::PointBuy => {
+                for attribute in Attribute::ALL {
+                    if point_buy_cost(self.scores.get(attribute)).is_none() {
+                        return Err(CreationError::ScoreOutOfRange(attribute));
+                    }
+                }
+                let spent = self.points_spent();
+                if spent > POINT_BUY_BUDGET {
+                    return Err(CreationError::OverBudget { spent });
+                }
+            }
+            StatMethod::Rolled { .. } => {
+                for attribute in Attribute::ALL {
+                    if !(3..=18).contains(&self.scores.get(attribute)) {
+                        return Err(CreationError::ScoreOutOfRange(attribute));
+                    }
+                }
+            }
+        }
+        let companion = self.companion.as_ref().ok_or(CreationError::NoCompanion)?;
+        if starting_companion(companion).is_none() {
+            return Err(CreationError::UnknownCompanion(companion.clone()));
+        }
+        Ok(())
+    }
+
+    pub fn build(&self) -> Result<CharacterSheet, CreationError> {
+        self.validate()?;
+        Ok(CharacterSheet {
+            data: CharacterData {
+                name: self.name.trim().to_string(),
+                gender: self.gender.clone(),
+                appearance: self.appearance.clone(),
+                stats: self.final_stats(),
+            },
+            background: self.background,
+            stat_method: self.stat_method,
+            companion: self.companion.clone().unwrap_or_default(),
+        })
+    }
+}
+
+fn minimum_scores() -> CharacterStats {
+    CharacterStats {
+        strength: POINT_BUY_MIN,
+        dexterity: POINT_BUY_MIN,
+        constitution: POINT_BUY_MIN,
+        intelligence: POINT_BUY_MIN,
+        wisdom: POINT_BUY_MIN,
+        charisma: POINT_BUY_MIN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_buy_respects_budget_and_range() {
+        let mut draft = CharacterDraft::default();
+        assert_eq!(draft.points_remaining(), 27);
+        for _ in 0..7 {
+            draft.raise(Attribute::Strength).unwrap();
+        }
+        assert_eq!(draft.scores.strength, 15);
+        assert_eq!(draft.points_spent(), 9);
+        assert_eq!(
+            draft.raise(Attribute::Strength),
+            Err(CreationError::ScoreOutOfRange(Attribute::Strength))
+        );
+        for _ in 0..7 {
+            draft.raise(Attribute::Constitution).unwrap();
+            draft.raise(Attribute::Wisdom).unwrap();
+        }
+        assert_eq!(draft.points_remaining(), 0);
+        assert_eq!(
+            draft.raise(Attribute::Charisma),
+            Err(CreationError::OverBudget { spent: 28 })
+        );
+        draft.lower(Attribute::Wisdom).unwrap();
+        assert_eq!(draft.points_remaining(), 2);
+        assert_eq!(
+            draft.lower(Attribute::Charisma),
+            Err(CreationError::ScoreOutOfRange(Attribute::Charisma))
+        );
+    }
+
+    #[test]
+    fn test_build_applies_background_and_validates() {
+        let mut draft = CharacterDraft::default();
+        assert_eq!(draft.build().unwrap_err(), CreationError::EmptyName);
+        draft.name = "  Hild ".to_string();
+        assert_eq!(draft.build().unwrap_err(), CreationError::NoCompanion);
+        draft.companion = Some("Nobody".to_string());
+        assert_eq!(
+            draft.build().unwrap_err(),
+            CreationError::UnknownCompanion("Nobody".to_string())
+        );
+        draft.companion = Some("Einar".to_string());
+        draft.set_background(Background::Soldier);
+        draft.raise(Attribute::Constitution).unwrap();
+
+        let sheet = draft.build().unwrap();
+        assert_eq!(sheet.data.name, "Hild");
+        assert_eq!(sheet.data.stats.strength, 10);
+        assert_eq!(sheet.data.stats.constitution, 10);
+        assert!(matches!(sheet.data.appearance.clothing_set, ClothingSet::Warrior));
+        assert_eq!(sheet.max_health(), 100.0);
+        assert_eq!(sheet.max_sanity(), 90.0);
+    }
+
+    #[test]
+    fn test_rolled_and_random_characters_are_repeatable() {
+        let mut first = CharacterDraft::default();
+        let mut second = CharacterDraft::default();
+        first.roll(42);
+        second.roll(42);
+        assert_eq!(first.scores.strength, second.scores.strength);
+        assert_eq!(first.raise(Attribute::Strength), Err(CreationError::ScoresRolled));
+        for attribute in Attribute::ALL {
+            assert!((3..=18).contains(&first.scores.get(attribute)));
+        }
+        first.use_point_buy();
+        assert_eq!(first.points_spent(), 0);
+
+        for seed in 0..20 {
+            let bot = CharacterDraft::random("Bot", seed).build().unwrap();
+            let again = CharacterDraft::random("Bot", seed).build().unwrap();
+            assert_eq!(bot.companion, again.companion);
+            assert_eq!(bot.background, again.background);
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Dungeon interior types built from HBF dungeon areas
+//!
+//! A `DungeonLayout` is the runtime form of one HBF dungeon: every numbered
+//! area becomes a room placed on a local hex grid so that connected areas sit
+//! next to each other. `DungeonProgress` records what the player has done in a
+//! dungeon and is keyed by dungeon UUID in save data.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::world::hex::HexCoord;
+
+/// Complete interior of a single dungeon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DungeonLayout {
+    pub dungeon_uuid: String,
+    pub name: String,
+    pub entrance_area: i32,
+    pub rooms: Vec<DungeonRoom>,
+}
+
+/// One HBF dungeon area laid out as a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DungeonRoom {
+    pub area_uuid: String,
+    pub area_number: i32,
+    pub description: String,
+    pub connected_areas: Vec<i32>,
+    pub monsters: Vec<RoomOccupant>,
+    pub traps: Vec<RoomOccupant>,
+    pub treasure: Option<RoomOccupant>,
+    pub special_features: Vec<String>,
+    pub difficulty_level: Option<i32>,
+    /// Position on the dungeon's local hex grid, assigned by `layout_rooms`
+    pub position: HexCoord,
+}
+
+/// Monster, trap or treasure referenced by a dungeon area
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomOccupant {
+    pub uuid: String,
+    pub name: String,
+    pub count: u32,
+}
+
+impl DungeonLayout {
+    pub fn new(dungeon_uuid: String, name: String, rooms: Vec<DungeonRoom>) -> Self {
+        let entrance_area = rooms.iter().map(|room| room.area_number).min().unwrap_or(1);
+        let mut layout = Self {
+            dungeon_uuid,
+            name,
+            entrance_area,
+            rooms,
+        };
+        layout.normalize_connections();
+        layout.layout_rooms();
+        layout
+    }
+
+    pub fn room(&self, area_number: i32) -> Option<&DungeonRoom> {
+        self.rooms.iter().find(|room| room.area_number == area_number)
+    }
+
+    pub fn entrance(&self) -> Option<&DungeonRoom> {
+        self.room(self.entrance_area)
+    }
+
+    /// Areas reachable in one step from the given area
+    pub fn neighbors(&self, area_number: i32) -> Vec<i32> {
+        self.room(area_number)
+            .map(|room| room.connected_areas.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn are_connected(&self, from: i32, to: i32) -> bool {
+        self.neighbors(from).contains(&to)
+    }
+
+    /// Make every connection two-way and drop links to areas that do not exist.
+    /// HBF area descriptions frequently only mention the exit in one direction.
+    pub fn normalize_connections(&mut self) {
+        let known: HashSet<i32> = self.rooms.iter().map(|room| room.area_number).collect();
+        let mut links: HashMap<i32, BTreeSet<i32>> = HashMap::new();
+
+        for room in &self.rooms {
+            for &other in &room.connected_areas {
+                if other == room.area_number || !known.contains(&other) {
+                    continue;
+                }
+                links.entry(room.area_number).or_default().insert(other);
+                links.entry(other).or_default().insert(room.area_number);
+            }
+        }
+
+        for room in &mut self.rooms {
+            room.connected_areas = links
+                .remove(&room.area_number)
+                .map(|set| set.into_iter().collect())
+                .unwrap_or_default();
+        }
+    }
+
+    /// Assign each room a hex on the local grid by walking the connection graph
+    /// breadth-first from the entrance. Each room is placed on the first free
+    /// neighbour of the room it was discovered from; disconnected rooms are
+    /// chained outward from the last placed room so nothing overlaps.
+    pub fn layout_rooms(&mut self) {
+        let mut placed: HashMap<i32, HexCoord> = HashMap::new();
+        let mut occupied: HashSet<HexCoord> = HashSet::new();
+        let mut order: Vec<i32> = self.rooms.iter().map(|room| room.area_number).collect();
+        order.sort_unstable();
+        order.retain(|&area| area != self.entrance_area);
+        order.insert(0, self.entrance_area);
+
+        let mut last = HexCoord::origin();
+        for start in order {
+            if placed.contains_key(&start) || self.room(start).is_none() {
+                continue;
+            }
+
+            let origin = if placed.is_empty() {
+                HexCoord::origin()
+            } else {
+                free_hex_near(last, &occupied)
+            };
+            placed.insert(start, origin);
+            occupied.insert(origin);
+            last = origin;
+
+            let mut queue = VecDeque::from([start]);
+            while let Some(area) = queue.pop_front() {
+                let parent = placed[&area];
+                for next in self.neighbors(area) {
+                    if placed.contains_key(&next) {
+                        continue;
+                    }
+                    let position = free_hex_near(parent, &occupied);
+                    placed.insert(next, position);
+                    occupied.insert(position);
+                    last = position;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        for room in &mut self.rooms {
+            if let Some(position) = placed.get(&room.area_number) {
+                room.position = *position;
+            }
+        }
+    }
+}
+
+/// First unoccupied hex around `center`, searching outward ring by ring
+fn free_hex_near(center: HexCoord, occupied: &HashSet<HexCoord>) -> HexCoord {
+    let mut frontier = vec![center];
+    let mut seen: HashSet<HexCoord> = HashSet::from([center]);
+
+    loop {
+        let mut next_frontier = Vec::new();
+        for hex in &frontier {
+            for neighbor in hex.neighbors() {
+                if !occupied.contains(&neighbor) {
+                    return neighbor;
+                }
+                if seen.insert(neighbor) {
+                    next_frontier.push(neighbor);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+}
+
+/// Persistent per-dungeon progress, stored in save data by dungeon UUID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DungeonProgress {
+    pub visited_areas: BTreeSet<i32>,
+    pub revealed_areas: BTreeSet<i32>,
+    pub cleared_areas: BTreeSet<i32>,
+    pub looted_treasure: BTreeSet<String>,
+    /// Traps that have already gone off
+    pub sprung_traps: BTreeSet<String>,
+}
+
+impl DungeonProgress {
+    /// Mark an area as visited and reveal everything connected to it
+    pub fn visit(&mut self, layout: &DungeonLayout, area_number: i32) {
+        self.visited_areas.insert(area_number);
+        self.revealed_areas.insert(area_number);
+        self.revealed_areas.extend(layout.neighbors(area_number));
+    }
+
+    pub fn fog_for(&self, area_number: i32) -> RoomFog {
+        if self.visited_areas.contains(&area_number) {
+            RoomFog::Visited
+        } else if self.revealed_areas.contains(&area_number) {
+            RoomFog::Revealed
+        } else {
+            RoomFog::Hidden
+        }
+    }
+
+    pub fn is_cleared(&self, area_number: i32) -> bool {
+        self.cleared_areas.contains(&area_number)
+    }
+
+    pub fn is_looted(&self, treasure_uuid: &str) -> bool {
+        self.looted_treasure.contains(treasure_uuid)
+    }
+
+    pub fn is_sprung(&self, trap_uuid: &str) -> bool {
+        self.sprung_traps.contains(trap_uuid)
+    }
+
+    pub fn completion(&self, layout: &DungeonLayout) -> f32 {
+        if layout.rooms.is_empty() {
+            return 1.0;
+        }
+        self.cleared_areas.len() as f32 / layout.rooms.len() as f32
+    }
+}
+
+/// Fog-of-war state of a dungeon room
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoomFog {
+    Hidden,   // Never seen, not rendered
+    Revealed, // Adjacent to a visited room, outline only
+    Visited,  // Player has stood in this room
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(area_number: i32, connected_areas: Vec<i32>) -> DungeonRoom {
+        DungeonRoom {
+            area_uuid: format!("area-{}", area_number),
+            area_number,
+            description: String::new(),
+            connected_areas,
+            monsters: Vec::new(),
+            traps: Vec::new(),
+            treasure: None,
+            special_features: Vec::new(),
+            difficulty_level: None,
+            position: HexCoord::origin(),
+        }
+    }
+
+    #[test]
+    fn test_connections_are_symmetric() {
+        let layout = DungeonLayout::new(
+            "dungeon".to_string(),
+            "Test Crypt".to_string(),
+            vec![room(1, vec![2]), room(2, vec![3, 99]), room(3, vec![])],
+        );
+
+        assert_eq!(layout.neighbors(1), vec![2]);
+        assert_eq!(layout.neighbors(2), vec![1, 3]);
+        assert_eq!(layout.neighbors(3), vec![2]);
+    }
+
+    #[test]
+    fn test_layout_places_rooms_without_overlap() {
+        let layout = DungeonLayout::new(
+            "dungeon".to_string(),
+            "Test Crypt".to_string(),
+            vec![
+                room(1, vec![2, 3, 4]),
+                room(2, vec![5]),
+                room(3, vec![]),
+                room(4, vec![]),
+                room(5, vec![]),
+                room(6, vec![]), // Disconnected area
+            ],
+        );
+
+        let positions: HashSet<HexCoord> = layout.rooms.iter().map(|r| r.position).collect();
+        assert_eq!(positions.len(), layout.rooms.len());
+        assert_eq!(layout.entrance().unwrap().position, HexCoord::origin());
+
+        let first = layout.room(1).unwrap().position;
+        let second = layout.room(2).unwrap().position;
+        assert_eq!(first.distance_to(&second), 1);
+    }
+
+    #[test]
+    fn test_progress_fog() {
+        let layout = DungeonLayout::new(
+            "dungeon".to_string(),
+            "Test Crypt".to_string(),
+            vec![room(1, vec![2]), room(2, vec![3]), room(3, vec![])],
+        );
+        let mut progress = DungeonProgress::default();
+        progress.visit(&layout, 1);
+
+        assert_eq!(progress.fog_for(1), RoomFog::Visited);
+        assert_eq!(progress.fog_for(2), RoomFog::Revealed);
+        assert_eq!(progress.fog_for(3), RoomFog::Hidden);
+    }
+}
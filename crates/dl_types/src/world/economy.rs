@@ -0,0 +1,462 @@
+//! Regional economy: per-settlement supply, scarcity pricing and shop stock
+//!
+//! Each settlement's `Market` tracks supply per `GoodsCategory`. Baseline
+//! supply comes from the HBF establishments that produce those goods, jittered
+//! by the world seed so every world has its own regional price differences but
+//! the same world always prices the same. Supply shocks from spreading
+//! corruption drain it; trade and time move it back toward the baseline.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::world::items::ItemDatabase;
+use crate::world::player::{Item, ItemType};
+use crate::world::seeded::{fnv1a, seeded_hash, seeded_unit};
+use crate::world::settlements::{Settlement, SettlementScale};
+
+pub const MIN_SUPPLY: f32 = 0.25;
+pub const MAX_SUPPLY: f32 = 2.0;
+/// Hexes from the corruption front beyond which prices stop climbing
+pub const FRONT_RANGE: u32 = 20;
+/// Fraction of the gap to baseline supply closed each hour
+const SUPPLY_RECOVERY_PER_HOUR: f32 = 0.02;
+/// Supply moved by one unit of goods bought or sold
+const SUPPLY_PER_UNIT: f32 = 0.02;
+/// Template items stocked per category, at most
+const STOCK_PER_CATEGORY: usize = 3;
+const MAX_REPUTATION: i32 = 100;
+const MAX_BARTER: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GoodsCategory {
+    Provisions,
+    Weapons,
+    Armor,
+    Tools,
+    Remedies,
+    Materials,
+    Luxuries,
+}
+
+impl GoodsCategory {
+    pub const ALL: [GoodsCategory; 7] = [
+        GoodsCategory::Provisions,
+        GoodsCategory::Weapons,
+        GoodsCategory::Armor,
+        GoodsCategory::Tools,
+        GoodsCategory::Remedies,
+        GoodsCategory::Materials,
+        GoodsCategory::Luxuries,
+    ];
+
+    /// Market category of an item; coin and quest items are not traded
+    pub fn for_item(item: &Item) -> Option<Self> {
+        match &item.item_type {
+            ItemType::Weapon { .. } => Some(GoodsCategory::Weapons),
+            ItemType::Armor { .. } => Some(GoodsCategory::Armor),
+            ItemType::Tool { .. } => Some(GoodsCategory::Tools),
+            ItemType::Consumable { effect, .. } if effect == "food" || effect == "water" => Some(GoodsCategory::Provisions),
+            ItemType::Consumable { .. } => Some(GoodsCategory::Remedies),
+            ItemType::Material { rarity, .. } if rarity == "rare" => Some(GoodsCategory::Luxuries),
+            ItemType::Material { .. } => Some(GoodsCategory::Materials),
+            ItemType::Currency { .. } | ItemType::QuestItem { .. } => None,
+        }
+    }
+
+    /// Categories an HBF establishment kind produces or sells
+    pub fn supplied_by(kind: &str) -> Vec<Self> {
+        let kind = kind.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|word| kind.contains(word));
+        let mut categories = Vec::new();
+
+        if has(&["blacksmith", "armor", "weapon"]) {
+            categories.extend([GoodsCategory::Weapons, GoodsCategory::Armor, GoodsCategory::Tools]);
+        }
+        if has(&["herbalist", "physician", "apothecary", "alchemist"]) {
+            categories.push(GoodsCategory::Remedies);
+        }
+        if has(&[
+            "general goods", "market", "grocer", "bakery", "butchery", "smokehouse", "liquor", "distillery",
+            "brewery", "winery", "tavern", "inn", "lodge",
+        ]) {
+            categories.push(GoodsCategory::Provisions);
+        }
+        if has(&["general goods", "tinkerer", "tin worker", "carpenter", "supplies", "trade post"]) {
+            categories.push(GoodsCategory::Tools);
+        }
+        if has(&["tanner", "leatherworker", "weaver", "glass blower", "craft", "tailor"]) {
+            categories.push(GoodsCategory::Materials);
+        }
+        if has(&["jeweler", "exotic", "hatter", "clothing", "flower", "dark market"]) {
+            categories.push(GoodsCategory::Luxuries);
+        }
+        categories
+    }
+
+    /// How hard a supply shock hits; people hoard food and medicine first
+    fn shock_sensitivity(&self) -> f32 {
+        match self {
+            GoodsCategory::Provisions | GoodsCategory::Remedies => 1.5,
+            GoodsCategory::Luxuries => 0.5,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Everything outside the market itself that moves a price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceModifiers {
+    pub corruption_band: u8,
+    /// Hexes to the nearest spreading corruption, if any is known
+    pub front_distance: Option<u32>,
+    /// Fraction in the player's favour, from `barter_modifier`
+    pub barter: f32,
+}
+
+impl PriceModifiers {
+    fn danger_factor(&self) -> f32 {
+        let band = 1.0 + 0.15 * self.corruption_band.saturating_sub(1) as f32;
+        let front = match self.front_distance {
+            Some(distance) if distance < FRONT_RANGE => 1.0 + 0.5 * (1.0 - distance as f32 / FRONT_RANGE as f32),
+            _ => 1.0,
+        };
+        band * front
+    }
+}
+
+/// Haggling edge from the party's best negotiator and standing with the
+/// settlement; negative reputation works against the player
+pub fn barter_modifier(negotiation_skill: u32, reputation: i32) -> f32 {
+    (negotiation_skill as f32 * 0.01 + reputation as f32 * 0.001).clamp(-MAX_BARTER, MAX_BARTER)
+}
+
+/// Supply and standing at one settlement's market
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Market {
+    pub settlement_uuid: String,
+    pub baseline: HashMap<GoodsCategory, f32>,
+    pub supply: HashMap<GoodsCategory, f32>,
+    pub reputation: i32, // -100..=100
+}
+
+impl Market {
+    /// Baseline supply from the settlement's establishments and trade, with a
+    /// per-world regional jitter
+    pub fn generate(settlement: &Settlement, world_seed: u64) -> Self {
+        let activity = 1.0 + (settlement.economic_activity as f32 / 100.0).min(1.0) * 0.25;
+        let baseline: HashMap<_, _> = GoodsCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let producers = producer_count(settlement, category).min(3);
+                // Goods nobody local produces have to be carted in
+                let local = if producers == 0 { 0.5 } else { 0.6 + 0.4 * producers as f32 };
+                let jitter = 0.85 + 0.3 * seeded_unit(world_seed, &settlement.uuid, category as u64);
+                (category, (local * activity * jitter).clamp(MIN_SUPPLY, MAX_SUPPLY))
+            })
+            .collect();
+
+        Self {
+            settlement_uuid: settlement.uuid.clone(),
+            supply: baseline.clone(),
+            baseline,
+            reputation: 0,
+        }
+    }
+
+    pub fn supply_of(&self, category: GoodsCategory) -> f32 {
+        self.supply.get(&category).copied().unwrap_or(1.0)
+    }
+
+    /// Price multiplier from supply: scarce goods cost up to four times as much
+    pub fn scarcity(&self, category: GoodsCategory) -> f32 {
+        (1.0 / self.supply_of(category)).clamp(0.5, 4.0)
+    }
+
+    /// Copper per unit the shop charges, or None for goods it will not trade
+    pub fn buy_price(&self, item: &Item, scale: SettlementScale, modifiers: &PriceModifiers) -> Option<u32> {
+        let category = GoodsCategory::for_item(item)?;
+        let price = item.value as f32
+            * scale.price_multiplier()
+            * self.scarcity(category)
+            * modifiers.danger_factor()
+            * (1.0 - modifiers.barter);
+        Some((price.round() as u32).max(1))
+    }
+
+    /// Copper per unit the shop pays: half value, more for what it lacks, but
+    /// always less than it would charge so nothing can be bought and sold back
+    /// at a profit
+    pub fn sell_price(&self, item: &Item, scale: SettlementScale, modifiers: &PriceModifiers) -> Option<u32> {
+        let category = GoodsCategory::for_item(item)?;
+        let price = item.value as f32 * 0.5 * self.scarcity(category).sqrt() * (1.0 + modifiers.barter);
+        let buy_price = self.buy_price(item, scale, modifiers)?;
+        Some((price.round() as u32).min(buy_price.saturating_sub(1)))
+    }
+
+    /// Goods leaving (negative) or entering the market through trade
+    pub fn record_trade(&mut self, category: GoodsCategory, units: i32, value: u32) {
+        let supply = self.supply.entry(category).or_insert(1.0);
+        *supply = (*supply + units as f32 * SUPPLY_PER_UNIT).clamp(MIN_SUPPLY, MAX_SUPPLY);
+        // Every 5 gp of business earns a little goodwill
+        self.reputation = (self.reputation + (value / 500).max(1) as i32).min(MAX_REPUTATION);
+    }
+
+    pub fn adjust_reputation(&mut self, amount: i32) {
+        self.reputation = (self.reputation + amount).clamp(-MAX_REPUTATION, MAX_REPUTATION);
+    }
+
+    /// A world event cuts supply; `intensity` is 0-1 at the market
+    pub fn apply_shock(&mut self, intensity: f32) {
+        for (category, supply) in self.supply.iter_mut() {
+            let loss = (intensity * 0.5 * category.shock_sensitivity()).clamp(0.0, 0.9);
+            *supply = (*supply * (1.0 - loss)).max(MIN_SUPPLY);
+        }
+    }
+
+    /// Drift back toward baseline supply as hours pass
+    pub fn recover(&mut self, hours: f32) {
+        let closed = 1.0 - (1.0 - SUPPLY_RECOVERY_PER_HOUR).powf(hours);
+        for (category, supply) in self.supply.iter_mut() {
+            let baseline = self.baseline.get(category).copied().unwrap_or(1.0);
+            *supply += (baseline - *supply) * closed;
+        }
+    }
+
+    /// Shop shelves: provisions plus HBF item templates in the categories the
+    /// settlement produces, no more corrupted than the settlement, in
+    /// quantities that follow supply. Reproducible from the world seed.
+    pub fn stock(&self, settlement: &Settlement, items: &ItemDatabase, world_seed: u64) -> Vec<Item> {
+        let band = settlement.corruption_band();
+        let quantity = |category: GoodsCategory, per_unit_supply: f32| {
+            ((self.supply_of(category) * per_unit_supply).round() as u32).max(1)
+        };
+
+        let mut stock = vec![
+            provision("Trail Rations", "food", 30, quantity(GoodsCategory::Provisions, 10.0)),
+            provision("Waterskin", "water", 40, quantity(GoodsCategory::Provisions, 5.0)),
+        ];
+
+        let mut templates: Vec<_> = items
+            .items
+            .values()
+            .filter(|template| template.corruption_band <= band)
+            .filter_map(|template| Some((GoodsCategory::for_item(&template.item)?, template)))
+            .filter(|(category, _)| producer_count(settlement, *category) > 0)
+            .collect();
+        templates.sort_by_key(|(_, template)| {
+            (seeded_hash(world_seed, &settlement.uuid, fnv1a(template.id.as_bytes())), template.id.clone())
+        });
+
+        let mut stocked: HashMap<GoodsCategory, usize> = HashMap::new();
+        for (category, template) in templates {
+            let count = stocked.entry(category).or_default();
+            if *count >= STOCK_PER_CATEGORY {
+                continue;
+            }
+            *count += 1;
+            let mut item = template.item.clone();
+            item.quantity = if item.is_stackable() { quantity(category, 3.0) } else { 1 };
+            stock.push(item);
+        }
+        stock
+    }
+}
+
+/// Establishments in the settlement that produce or sell a category
+fn producer_count(settlement: &Settlement, category: GoodsCategory) -> usize {
+    settlement
+        .establishments
+        .iter()
+        .filter(|establishment| GoodsCategory::supplied_by(&establishment.kind).contains(&category))
+        .count()
+}
+
+fn provision(name: &str, effect: &str, potency: u32, quantity: u32) -> Item {
+    Item {
+        name: name.to_string(),
+        item_type: ItemType::Consumable {
+            effect: effect.to_string(),
+            duration: 0.0,
+            potency,
+        },
+        quantity,
+        weight: 1.0,
+        value: 50,
+        description: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::items::ItemTemplate;
+    use crate::world::settlements::{Establishment, ServiceType};
+
+    fn settlement(kinds: &[&str]) -> Settlement {
+        Settlement {
+            uuid: "8FcnTf8q".to_string(),
+            name: "Village of Dokar".to_string(),
+            hex_uuid: None,
+            scale: SettlementScale::Village,
+            population: 465,
+            economic_activity: 64,
+            corruption_resistance: 6,
+            service_types: vec![ServiceType::Commerce],
+            npc_count: 15,
+            establishment_count: kinds.len() as u32,
+            establishments: kinds
+                .iter()
+                .map(|kind| Establishment {
+                    uuid: kind.to_string(),
+                    name: kind.to_string(),
+                    kind: kind.to_string(),
+                    service: ServiceType::for_establishment(kind),
+                })
+                .collect(),
+            corruption: 0.0,
+        }
+    }
+
+    fn sword() -> Item {
+        Item {
+            name: "Longsword".to_string(),
+            item_type: ItemType::Weapon {
+                damage: 4,
+                weapon_type: "longsword".to_string(),
+                enchantments: Vec::new(),
+            },
+            quantity: 1,
+            weight: 3.0,
+            value: 1500,
+            description: String::new(),
+        }
+    }
+
+    const CALM: PriceModifiers = PriceModifiers {
+        corruption_band: 1,
+        front_distance: None,
+        barter: 0.0,
+    };
+
+    #[test]
+    fn test_market_is_reproducible_from_seed() {
+        let dokar = settlement(&["Blacksmith", "Herbalist"]);
+        assert_eq!(Market::generate(&dokar, 42), Market::generate(&dokar, 42));
+        assert_ne!(Market::generate(&dokar, 42), Market::generate(&dokar, 43));
+
+        let mut items = ItemDatabase::default();
+        for id in ["sword_a", "sword_b", "sword_c", "sword_d"] {
+            items.insert(ItemTemplate {
+                id: id.to_string(),
+                source_uuid: id.to_string(),
+                item: sword(),
+                corruption_band: 1,
+            });
+        }
+        let market = Market::generate(&dokar, 42);
+        let stock = market.stock(&dokar, &items, 42);
+        assert_eq!(stock, market.stock(&dokar, &items, 42));
+        // Provisions plus at most three weapons
+        assert_eq!(stock.len(), 2 + STOCK_PER_CATEGORY);
+    }
+
+    #[test]
+    fn test_stock_skips_categories_nobody_produces() {
+        let mut items = ItemDatabase::default();
+        items.insert(ItemTemplate {
+            id: "sword".to_string(),
+            source_uuid: "sword".to_string(),
+            item: sword(),
+            corruption_band: 1,
+        });
+        items.insert(ItemTemplate {
+            id: "ruby".to_string(),
+            source_uuid: "ruby".to_string(),
+            item: Item {
+                name: "Ruby".to_string(),
+                item_type: ItemType::Material {
+                    material_type: "trinket".to_string(),
+                    rarity: "rare".to_string(),
+                },
+                quantity: 1,
+                weight: 0.5,
+                value: 10000,
+                description: String::new(),
+            },
+            corruption_band: 1,
+        });
+
+        // Every seed: imported baselines can jitter above 0.5 without making
+        // the settlement a producer
+        let smithy = settlement(&["Blacksmith"]);
+        for seed in 0..32 {
+            let stock = Market::generate(&smithy, seed).stock(&smithy, &items, seed);
+            assert!(stock.iter().any(|item| item.name == "Longsword"));
+            assert!(stock.iter().all(|item| item.name != "Ruby"));
+        }
+    }
+
+    #[test]
+    fn test_scarcity_danger_and_barter_move_prices() {
+        let smithy = Market::generate(&settlement(&["Blacksmith", "Armor & Weapons"]), 7);
+        let no_smithy = Market::generate(&settlement(&["Herbalist"]), 7);
+        let local = smithy.buy_price(&sword(), SettlementScale::Village, &CALM).unwrap();
+        let imported = no_smithy.buy_price(&sword(), SettlementScale::Village, &CALM).unwrap();
+        assert!(imported > local);
+
+        let at_front = PriceModifiers {
+            corruption_band: 3,
+            front_distance: Some(2),
+            ..CALM
+        };
+        assert!(smithy.buy_price(&sword(), SettlementScale::Village, &at_front).unwrap() > local);
+
+        let haggled = PriceModifiers {
+            barter: barter_modifier(20, 50),
+            ..CALM
+        };
+        assert!(smithy.buy_price(&sword(), SettlementScale::Village, &haggled).unwrap() < local);
+        let village = SettlementScale::Village;
+        assert!(smithy.sell_price(&sword(), village, &haggled).unwrap() > smithy.sell_price(&sword(), village, &CALM).unwrap());
+    }
+
+    #[test]
+    fn test_selling_back_never_pays_more_than_buying() {
+        // A glutted market and the best haggler: scarcity halves what the shop
+        // charges but only takes a third off what it pays
+        let mut market = Market::generate(&settlement(&["Blacksmith"]), 3);
+        for supply in market.supply.values_mut() {
+            *supply = MAX_SUPPLY;
+        }
+        let haggled = PriceModifiers {
+            barter: MAX_BARTER,
+            ..CALM
+        };
+        for scale in [SettlementScale::Village, SettlementScale::Town, SettlementScale::City, SettlementScale::Metropolis] {
+            let buy = market.buy_price(&sword(), scale, &haggled).unwrap();
+            let sell = market.sell_price(&sword(), scale, &haggled).unwrap();
+            assert!(sell < buy, "{:?}: sells for {} but buys for {}", scale, sell, buy);
+        }
+
+        let cheap = Item {
+            value: 1,
+            ..sword()
+        };
+        assert_eq!(market.sell_price(&cheap, SettlementScale::Village, &haggled), Some(0));
+    }
+
+    #[test]
+    fn test_shock_and_recovery() {
+        let mut market = Market::generate(&settlement(&["General Goods"]), 1);
+        let before = market.supply_of(GoodsCategory::Provisions);
+        market.apply_shock(0.8);
+        let shocked = market.supply_of(GoodsCategory::Provisions);
+        assert!(shocked < before);
+        assert!(market.supply_of(GoodsCategory::Luxuries) / market.baseline[&GoodsCategory::Luxuries] > shocked / before);
+
+        market.recover(48.0);
+        let recovered = market.supply_of(GoodsCategory::Provisions);
+        assert!(recovered > shocked && recovered <= before);
+    }
+}
@@ -0,0 +1,586 @@
+//! Faction simulation: influence field, relations and player reputation
+//!
+//! HBF marks faction membership on NPCs ("Member of the The Red Snakes") in
+//! the establishments and hexes where they live. Those members are the
+//! factions' seats of power: each one radiates influence over nearby hexes.
+//! Every faction turn, contested ground sours relations, shared enemies bring
+//! factions together, wars bleed strength and strong factions push their
+//! borders outward. The simulation is deterministic, so a world replays the
+//! same way from the same seed data.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::world::hex::HexCoord;
+
+pub const MAX_STANDING: i32 = 100;
+/// Hexes beyond which a seat of power exerts no influence
+pub const INFLUENCE_REACH: u32 = 6;
+const WAR_THRESHOLD: i32 = -60;
+const HOSTILE_THRESHOLD: i32 = -20;
+const ALLIANCE_THRESHOLD: i32 = 60;
+/// Influence above which a faction contests a hex
+const CONTEST_THRESHOLD: f32 = 0.3;
+/// Strength a faction needs before it expands
+const EXPANSION_STRENGTH: f32 = 1.5;
+const EXPANSION_COST: f32 = 0.2;
+const BASE_GROWTH: f32 = 0.05;
+const WAR_ATTRITION: f32 = 0.05;
+const MIN_STRENGTH: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FactionAlignment {
+    Lawful,
+    Neutral,
+    Corrupt,
+}
+
+impl FactionAlignment {
+    pub fn from_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|word| name.contains(word));
+        if has(&["justice", "order", "holy", "light", "guard", "crown"]) {
+            FactionAlignment::Lawful
+        } else if has(&["defiled", "cursed", "void", "blood", "dark", "snake", "shadow", "cult"]) {
+            FactionAlignment::Corrupt
+        } else {
+            FactionAlignment::Neutral
+        }
+    }
+
+    /// Starting standing between two factions of these alignments
+    fn affinity(&self, other: &FactionAlignment) -> i32 {
+        match (self, other) {
+            (a, b) if a == b && *a != FactionAlignment::Neutral => 30,
+            (FactionAlignment::Lawful, FactionAlignment::Corrupt)
+            | (FactionAlignment::Corrupt, FactionAlignment::Lawful) => -50,
+            _ => 0,
+        }
+    }
+}
+
+/// One faction as found in the HBF data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactionRecord {
+    pub uuid: String,
+    pub name: String,
+    pub alignment: FactionAlignment,
+    /// HBF NPC UUIDs of known members
+    pub members: Vec<String>,
+    /// Member count per settlement UUID
+    pub seats: HashMap<String, u32>,
+    /// HBF hex UUIDs where members were found
+    pub hex_uuids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactionRelationSeed {
+    pub a: String,
+    pub b: String,
+    pub standing: i32,
+}
+
+/// All factions, keyed by HBF faction UUID, with their starting relations
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactionDatabase {
+    pub factions: HashMap<String, FactionRecord>,
+    pub relations: Vec<FactionRelationSeed>,
+}
+
+impl FactionDatabase {
+    pub fn insert(&mut self, faction: FactionRecord) {
+        self.factions.insert(faction.uuid.clone(), faction);
+    }
+
+    pub fn get(&self, uuid: &str) -> Option<&FactionRecord> {
+        self.factions.get(uuid)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&FactionRecord> {
+        self.factions
+            .values()
+            .find(|faction| faction.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Faction an HBF NPC belongs to, if any
+    pub fn faction_of_npc(&self, npc_uuid: &str) -> Option<&FactionRecord> {
+        self.factions
+            .values()
+            .find(|faction| faction.members.iter().any(|member| member == npc_uuid))
+    }
+
+    /// Seed relations from alignment, soured by every settlement the two
+    /// factions both keep members in
+    pub fn seed_relations(&mut self) {
+        let mut uuids: Vec<&String> = self.factions.keys().collect();
+        uuids.sort();
+        let mut relations = Vec::new();
+        for (i, a) in uuids.iter().enumerate() {
+            for b in &uuids[i + 1..] {
+                let (fa, fb) = (&self.factions[*a], &self.factions[*b]);
+                let shared_seats = fa.seats.keys().filter(|seat| fb.seats.contains_key(*seat)).count() as i32;
+                relations.push(FactionRelationSeed {
+                    a: (*a).clone(),
+                    b: (*b).clone(),
+                    standing: (fa.alignment.affinity(&fb.alignment) - shared_seats * 5).clamp(-MAX_STANDING, MAX_STANDING),
+                });
+            }
+        }
+        self.relations = relations;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Relation {
+    War,
+    Hostile,
+    Neutral,
+    Allied,
+}
+
+impl Relation {
+    pub fn from_standing(standing: i32) -> Self {
+        match standing {
+            s if s <= WAR_THRESHOLD => Relation::War,
+            s if s < HOSTILE_THRESHOLD => Relation::Hostile,
+            s if s >= ALLIANCE_THRESHOLD => Relation::Allied,
+            _ => Relation::Neutral,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ReputationTier {
+    Hated,
+    Hostile,
+    Unfriendly,
+    Neutral,
+    Friendly,
+    Honored,
+}
+
+impl ReputationTier {
+    pub fn from_reputation(reputation: i32) -> Self {
+        match reputation {
+            r if r <= -75 => ReputationTier::Hated,
+            r if r <= -40 => ReputationTier::Hostile,
+            r if r <= -10 => ReputationTier::Unfriendly,
+            r if r < 20 => ReputationTier::Neutral,
+            r if r < 60 => ReputationTier::Friendly,
+            _ => ReputationTier::Honored,
+        }
+    }
+}
+
+/// How a faction member greets the player
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NpcAttitude {
+    Hostile,
+    Wary,
+    Neutral,
+    Friendly,
+}
+
+impl NpcAttitude {
+    pub fn from_tier(tier: ReputationTier) -> Self {
+        match tier {
+            ReputationTier::Hated | ReputationTier::Hostile => NpcAttitude::Hostile,
+            ReputationTier::Unfriendly => NpcAttitude::Wary,
+            ReputationTier::Neutral => NpcAttitude::Neutral,
+            ReputationTier::Friendly | ReputationTier::Honored => NpcAttitude::Friendly,
+        }
+    }
+}
+
+/// A faction's entry in a hex's encounter table
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactionEncounter {
+    pub faction: String,
+    pub weight: f32,
+    pub hostile: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FactionTurnOutcome {
+    Expanded { faction: String, hex: HexCoord },
+    RelationChanged { a: String, b: String, relation: Relation },
+    Skirmish { a: String, b: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactionState {
+    pub uuid: String,
+    pub name: String,
+    pub alignment: FactionAlignment,
+    pub strength: f32,
+    /// Seats of power and the influence each radiates
+    pub sources: Vec<(HexCoord, f32)>,
+}
+
+/// Live faction state: relations, player reputation and the influence field
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactionSimulation {
+    pub factions: HashMap<String, FactionState>,
+    /// Standing between two factions, keyed by `relation_key`
+    pub relations: HashMap<String, i32>,
+    /// Player reputation per faction UUID, -100..=100
+    pub reputation: HashMap<String, i32>,
+    pub turn: u32,
+    #[serde(skip)]
+    influence: HashMap<HexCoord, Vec<(String, f32)>>,
+}
+
+fn relation_key(a: &str, b: &str) -> String {
+    if a < b { format!("{}|{}", a, b) } else { format!("{}|{}", b, a) }
+}
+
+impl FactionSimulation {
+    pub fn from_database(database: &FactionDatabase) -> Self {
+        let factions = database
+            .factions
+            .values()
+            .map(|record| {
+                let state = FactionState {
+                    uuid: record.uuid.clone(),
+                    name: record.name.clone(),
+                    alignment: record.alignment,
+                    strength: 1.0 + record.members.len() as f32 * 0.1,
+                    sources: Vec::new(),
+                };
+                (record.uuid.clone(), state)
+            })
+            .collect();
+        let relations = database
+            .relations
+            .iter()
+            .map(|seed| (relation_key(&seed.a, &seed.b), seed.standing))
+            .collect();
+
+        Self {
+            factions,
+            relations,
+            ..Default::default()
+        }
+    }
+
+    /// Sorted faction UUIDs, so turns resolve in the same order every run
+    fn faction_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.factions.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Register a seat of power; a stronger source at the same hex wins.
+    /// Returns true if the sources changed, in which case the caller should
+    /// `rebuild_influence` once it has added everything.
+    pub fn add_source(&mut self, faction: &str, hex: HexCoord, strength: f32) -> bool {
+        let Some(state) = self.factions.get_mut(faction) else {
+            return false;
+        };
+        match state.sources.iter_mut().find(|(source, _)| *source == hex) {
+            Some((_, existing)) if *existing >= strength => return false,
+            Some((_, existing)) => *existing = strength,
+            None => state.sources.push((hex, strength)),
+        }
+        true
+    }
+
+    /// Recompute the influence field from every faction's sources
+    pub fn rebuild_influence(&mut self) {
+        let mut field: HashMap<HexCoord, Vec<(String, f32)>> = HashMap::new();
+        for id in self.faction_ids() {
+            let state = &self.factions[&id];
+            let mut reach: HashMap<HexCoord, f32> = HashMap::new();
+            for (source, source_strength) in &state.sources {
+                for hex in source.within(INFLUENCE_REACH) {
+                    let falloff = 1.0 - source.distance_to(&hex) as f32 / (INFLUENCE_REACH + 1) as f32;
+                    let value = (source_strength * falloff * state.strength.min(2.0) / 2.0).min(1.0);
+                    let entry = reach.entry(hex).or_default();
+                    *entry = entry.max(value);
+                }
+            }
+            for (hex, value) in reach {
+                field.entry(hex).or_default().push((id.clone(), value));
+            }
+        }
+        self.influence = field;
+    }
+
+    /// Influence 0-1 a faction holds over a hex
+    pub fn influence_at(&self, hex: HexCoord, faction: &str) -> f32 {
+        self.influence
+            .get(&hex)
+            .and_then(|entries| entries.iter().find(|(id, _)| id == faction))
+            .map_or(0.0, |(_, value)| *value)
+    }
+
+    /// Strongest faction over a hex
+    pub fn dominant_at(&self, hex: HexCoord) -> Option<(&str, f32)> {
+        self.influence.get(&hex)?.iter().fold(None, |best, (id, value)| match best {
+            Some((_, best_value)) if best_value >= *value => best,
+            _ => Some((id.as_str(), *value)),
+        })
+    }
+
+    pub fn standing(&self, a: &str, b: &str) -> i32 {
+        self.relations.get(&relation_key(a, b)).copied().unwrap_or(0)
+    }
+
+    pub fn relation(&self, a: &str, b: &str) -> Relation {
+        Relation::from_standing(self.standing(a, b))
+    }
+
+    pub fn adjust_standing(&mut self, a: &str, b: &str, amount: i32) {
+        let standing = self.relations.entry(relation_key(a, b)).or_insert(0);
+        *standing = (*standing + amount).clamp(-MAX_STANDING, MAX_STANDING);
+    }
+
+    pub fn reputation(&self, faction: &str) -> i32 {
+        self.reputation.get(faction).copied().unwrap_or(0)
+    }
+
+    /// Change the player's reputation with a faction. Its allies hear of it
+    /// and share half the feeling; its enemies at war take the opposite view.
+    pub fn adjust_reputation(&mut self, faction: &str, amount: i32) {
+        if !self.factions.contains_key(faction) {
+            return;
+        }
+        let mut changes = vec![(faction.to_string(), amount)];
+        for other in self.faction_ids() {
+            if other == faction {
+                continue;
+            }
+            match self.relation(faction, &other) {
+                Relation::Allied => changes.push((other, amount / 2)),
+                Relation::War => changes.push((other, -amount / 2)),
+                _ => {}
+            }
+        }
+        for (id, change) in changes {
+            let reputation = self.reputation.entry(id).or_insert(0);
+            *reputation = (*reputation + change).clamp(-MAX_STANDING, MAX_STANDING);
+        }
+    }
+
+    pub fn npc_attitude(&self, faction: &str) -> NpcAttitude {
+        NpcAttitude::from_tier(ReputationTier::from_reputation(self.reputation(faction)))
+    }
+
+    /// Factions that may be met on a hex, weighted by their influence there.
+    /// Members turn hostile once the player's reputation sinks to unfriendly,
+    /// and corrupt factions are only civil to those they call friends.
+    pub fn encounter_table(&self, hex: HexCoord) -> Vec<FactionEncounter> {
+        let Some(entries) = self.influence.get(&hex) else {
+            return Vec::new();
+        };
+        entries
+            .iter()
+            .filter(|(_, value)| *value > 0.05)
+            .map(|(id, value)| {
+                let tier = ReputationTier::from_reputation(self.reputation(id));
+                let corrupt = self.factions.get(id).is_some_and(|f| f.alignment == FactionAlignment::Corrupt);
+                FactionEncounter {
+                    faction: id.clone(),
+                    weight: *value,
+                    hostile: tier <= ReputationTier::Unfriendly || (corrupt && tier < ReputationTier::Friendly),
+                }
+            })
+            .collect()
+    }
+
+    /// Advance the simulation one faction turn
+    pub fn run_turn(&mut self) -> Vec<FactionTurnOutcome> {
+        self.turn += 1;
+        let ids = self.faction_ids();
+        let before: HashMap<String, Relation> = self
+            .relations
+            .iter()
+            .map(|(key, standing)| (key.clone(), Relation::from_standing(*standing)))
+            .collect();
+        let mut outcomes = Vec::new();
+
+        // Contested ground breeds friction; like-minded factions warm to each other
+        let mut contested: HashMap<String, i32> = HashMap::new();
+        for entries in self.influence.values() {
+            let strong: Vec<&String> = entries
+                .iter()
+                .filter(|(_, value)| *value > CONTEST_THRESHOLD)
+                .map(|(id, _)| id)
+                .collect();
+            for (i, a) in strong.iter().enumerate() {
+                for b in &strong[i + 1..] {
+                    *contested.entry(relation_key(a, b)).or_default() += 1;
+                }
+            }
+        }
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                let key = relation_key(a, b);
+                let friction = contested.get(&key).map_or(0, |hexes| (*hexes / 10).clamp(1, 5));
+                let allied = self.relation(a, b) == Relation::Allied;
+                let affinity = self.factions[a].alignment.affinity(&self.factions[b].alignment).signum() * 2;
+                let drift = affinity - if allied { 0 } else { friction };
+                self.adjust_standing(a, b, drift);
+            }
+        }
+
+        // A common enemy makes allies
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                let common_enemy = ids
+                    .iter()
+                    .any(|c| c != a && c != b && self.relation(a, c) == Relation::War && self.relation(b, c) == Relation::War);
+                if common_enemy {
+                    self.adjust_standing(a, b, 5);
+                }
+            }
+        }
+
+        // Wars bleed both sides
+        let mut losses: HashMap<String, f32> = HashMap::new();
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                if self.relation(a, b) != Relation::War {
+                    continue;
+                }
+                *losses.entry(a.clone()).or_default() += self.factions[b].strength * WAR_ATTRITION;
+                *losses.entry(b.clone()).or_default() += self.factions[a].strength * WAR_ATTRITION;
+                outcomes.push(FactionTurnOutcome::Skirmish { a: a.clone(), b: b.clone() });
+            }
+        }
+
+        // Strong factions at peace (or fighting a single war) push outward
+        for id in &ids {
+            let wars = ids.iter().filter(|other| *other != id && self.relation(id, other) == Relation::War).count();
+            let turn = self.turn as usize;
+            let state = self.factions.get_mut(id).expect("faction ids come from the map");
+            state.strength = (state.strength + BASE_GROWTH - losses.get(id).copied().unwrap_or(0.0)).max(MIN_STRENGTH);
+
+            if state.strength < EXPANSION_STRENGTH || wars > 1 {
+                continue;
+            }
+            let Some((seat, seat_strength)) = state
+                .sources
+                .iter()
+                .copied()
+                .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| (b.0.q, b.0.r).cmp(&(a.0.q, a.0.r))))
+            else {
+                continue;
+            };
+            let direction = turn + id.bytes().map(|b| b as usize).sum::<usize>();
+            let mut frontier = seat;
+            for _ in 0..=(state.sources.len() % 3) {
+                frontier = frontier.neighbor(direction);
+            }
+            if state.sources.iter().any(|(source, _)| *source == frontier) {
+                continue;
+            }
+            state.sources.push((frontier, seat_strength * 0.5));
+            state.strength -= EXPANSION_COST;
+            outcomes.push(FactionTurnOutcome::Expanded {
+                faction: id.clone(),
+                hex: frontier,
+            });
+        }
+
+        for (key, standing) in &self.relations {
+            let relation = Relation::from_standing(*standing);
+            if before.get(key).copied().unwrap_or(Relation::Neutral) == relation {
+                continue;
+            }
+            if let Some((a, b)) = key.split_once('|') {
+                outcomes.push(FactionTurnOutcome::RelationChanged {
+                    a: a.to_string(),
+                    b: b.to_string(),
+                    relation,
+                });
+            }
+        }
+
+        self.rebuild_influence();
+        outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(uuid: &str, name: &str, seats: &[&str]) -> FactionRecord {
+        FactionRecord {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            alignment: FactionAlignment::from_name(name),
+            members: vec![format!("{}_member", uuid)],
+            seats: seats.iter().map(|seat| (seat.to_string(), 2)).collect(),
+            hex_uuids: Vec::new(),
+        }
+    }
+
+    fn simulation() -> FactionSimulation {
+        let mut database = FactionDatabase::default();
+        database.insert(record("fists", "The Fists Of Justice", &["dokar"]));
+        database.insert(record("swords", "The Swords Of Justice", &["harad"]));
+        database.insert(record("wolves", "The Defiled Wolves", &["dokar", "harad"]));
+        database.seed_relations();
+
+        let mut simulation = FactionSimulation::from_database(&database);
+        simulation.add_source("fists", HexCoord::new(0, 0), 1.0);
+        simulation.add_source("swords", HexCoord::new(10, 0), 1.0);
+        simulation.add_source("wolves", HexCoord::new(2, 0), 1.0);
+        simulation.rebuild_influence();
+        simulation
+    }
+
+    #[test]
+    fn test_seeded_relations_and_influence() {
+        let simulation = simulation();
+        assert_eq!(simulation.relation("fists", "swords"), Relation::Neutral);
+        assert_eq!(simulation.relation("fists", "wolves"), Relation::Hostile);
+        assert_eq!(simulation.standing("fists", "wolves"), simulation.standing("wolves", "fists"));
+
+        let home = simulation.influence_at(HexCoord::new(0, 0), "fists");
+        assert!(home > simulation.influence_at(HexCoord::new(3, 0), "fists"));
+        assert_eq!(simulation.influence_at(HexCoord::new(20, 0), "fists"), 0.0);
+        assert_eq!(simulation.dominant_at(HexCoord::new(10, 0)).map(|(id, _)| id), Some("swords"));
+    }
+
+    #[test]
+    fn test_reputation_ripples_to_allies_and_enemies() {
+        let mut simulation = simulation();
+        simulation.adjust_standing("fists", "swords", 60);
+        simulation.adjust_standing("fists", "wolves", -40);
+        simulation.adjust_reputation("fists", 40);
+
+        assert_eq!(simulation.reputation("fists"), 40);
+        assert_eq!(simulation.reputation("swords"), 20);
+        assert_eq!(simulation.reputation("wolves"), -20);
+        assert_eq!(simulation.npc_attitude("fists"), NpcAttitude::Friendly);
+        assert_eq!(simulation.npc_attitude("wolves"), NpcAttitude::Wary);
+
+        let table = simulation.encounter_table(HexCoord::new(1, 0));
+        let wolves = table.iter().find(|e| e.faction == "wolves").unwrap();
+        assert!(wolves.hostile);
+        assert!(!table.iter().find(|e| e.faction == "fists").unwrap().hostile);
+    }
+
+    #[test]
+    fn test_turns_are_deterministic_and_drive_war() {
+        let mut a = simulation();
+        let mut b = simulation();
+        let mut outcomes = Vec::new();
+        for _ in 0..20 {
+            let turn = a.run_turn();
+            assert_eq!(turn, b.run_turn());
+            outcomes.extend(turn);
+        }
+
+        // Lawful and corrupt neighbours on contested ground end up at war,
+        // and the two lawful orders close ranks against them
+        assert_eq!(a.relation("fists", "wolves"), Relation::War);
+        assert_eq!(a.relation("fists", "swords"), Relation::Allied);
+        assert!(outcomes.iter().any(|o| matches!(o, FactionTurnOutcome::Skirmish { .. })));
+        assert!(outcomes.iter().any(|o| matches!(o, FactionTurnOutcome::Expanded { .. })));
+    }
+}
@@ -75,6 +75,18 @@ impl HexCoord {
         ]
     }
     
+    /// Every hex within `radius` of this one, itself included
+    pub fn within(&self, radius: u32) -> Vec<HexCoord> {
+        let radius = radius as i32;
+        let mut hexes = Vec::new();
+        for dq in -radius..=radius {
+            for dr in (-radius).max(-dq - radius)..=radius.min(-dq + radius) {
+                hexes.push(HexCoord::new(self.q + dq, self.r + dr));
+            }
+        }
+        hexes
+    }
+    
     /// Get neighbor in specific direction (0-5)
     pub fn neighbor(&self, direction: usize) -> HexCoord {
         self.neighbors()[direction % 6]
@@ -128,6 +140,17 @@ mod tests {
         assert_eq!(neighbors[directions::SOUTHEAST], HexCoord::new(0, 1));
     }
     
+    #[test]
+    fn test_within_covers_exactly_the_radius() {
+        let center = HexCoord::new(2, -1);
+        let hexes = center.within(2);
+
+        assert_eq!(hexes.len(), 19);
+        assert!(hexes.contains(&center));
+        assert!(hexes.iter().all(|hex| center.distance_to(hex) <= 2));
+        assert_eq!(center.within(0), vec![center]);
+    }
+    
     #[test]
     fn test_cube_conversion() {
         let hex = HexCoord::new(1, 2);
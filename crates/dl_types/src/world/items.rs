@@ -0,0 +1,438 @@
+//! Equipment, consumable effects and the item database
+//!
+//! `Inventory` (in `player`) holds what the player carries. This module covers
+//! what carried items do: equipped gear feeds `CombatStats`, consumables apply
+//! instant or timed `ActiveEffects`, and `ItemDatabase` is the catalogue of
+//! item templates generated from HBF treasure entities.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::world::character::CharacterStats;
+use crate::world::player::{Item, ItemType};
+
+/// Body slot an item occupies when equipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EquipmentSlot {
+    Head,
+    Body,
+    Hands,
+    Feet,
+    MainHand,
+    OffHand,
+    Trinket,
+}
+
+impl EquipmentSlot {
+    /// Slot for an item, or `None` if it cannot be equipped.
+    /// Armor is placed by its `armor_type` keyword; anything unrecognised is body armor.
+    pub fn for_item(item: &Item) -> Option<Self> {
+        match &item.item_type {
+            ItemType::Weapon { .. } => Some(EquipmentSlot::MainHand),
+            ItemType::Armor { armor_type, .. } => {
+                let armor_type = armor_type.to_lowercase();
+                Some(if armor_type.contains("helm") || armor_type.contains("hood") || armor_type.contains("hat") {
+                    EquipmentSlot::Head
+                } else if armor_type.contains("shield") {
+                    EquipmentSlot::OffHand
+                } else if armor_type.contains("glove") || armor_type.contains("gauntlet") {
+                    EquipmentSlot::Hands
+                } else if armor_type.contains("boot") || armor_type.contains("greave") {
+                    EquipmentSlot::Feet
+                } else if armor_type.contains("amulet") || armor_type.contains("ring") || armor_type.contains("charm") {
+                    EquipmentSlot::Trinket
+                } else {
+                    EquipmentSlot::Body
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Items currently worn or wielded
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Equipment {
+    pub slots: HashMap<EquipmentSlot, Item>,
+}
+
+impl Equipment {
+    /// Equip an item, returning whatever previously occupied its slot.
+    /// Items that cannot be equipped are handed back as the error.
+    pub fn equip(&mut self, item: Item) -> Result<Option<Item>, Item> {
+        match EquipmentSlot::for_item(&item) {
+            Some(slot) => Ok(self.slots.insert(slot, Item { quantity: 1, ..item })),
+            None => Err(item),
+        }
+    }
+
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> Option<Item> {
+        self.slots.remove(&slot)
+    }
+
+    pub fn get(&self, slot: EquipmentSlot) -> Option<&Item> {
+        self.slots.get(&slot)
+    }
+
+    /// Attack bonus from the wielded weapon, including "+N" enchantments
+    pub fn attack_bonus(&self) -> i32 {
+        self.slots
+            .values()
+            .map(|item| match &item.item_type {
+                ItemType::Weapon { damage, enchantments, .. } => *damage as i32 + enchantment_bonus(enchantments),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Defense bonus from all worn armor, including "+N" enchantments
+    pub fn defense_bonus(&self) -> i32 {
+        self.slots
+            .values()
+            .map(|item| match &item.item_type {
+                ItemType::Armor { protection, enchantments, .. } => *protection as i32 + enchantment_bonus(enchantments),
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+/// Sum of numeric enchantments such as "+1" or "+2 vs undead"
+fn enchantment_bonus(enchantments: &[String]) -> i32 {
+    enchantments
+        .iter()
+        .filter_map(|enchantment| {
+            let token = enchantment.split_whitespace().next()?;
+            token.strip_prefix('+')?.parse::<i32>().ok()
+        })
+        .sum()
+}
+
+/// What a consumable does, parsed from its `effect` string
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemEffect {
+    Heal,          // Restores health; over time if it has a duration
+    RestoreSanity, // Restores sanity; over time if it has a duration
+    Strength,      // Attack bonus while active
+    Protection,    // Defense bonus while active
+    Vigor,         // Reduces fatigue
+    DreadWard,     // Dread resistance while active
+    Nourish,       // Reduces hunger
+    Hydrate,       // Reduces thirst
+    Other(String),
+}
+
+impl ItemEffect {
+    /// Match whole words only, so "forest" is not "rest" and "shieldbearer"
+    /// is not "shield"
+    pub fn from_name(effect: &str) -> Self {
+        let effect = effect.to_lowercase();
+        let words: Vec<&str> = effect
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let has = |names: &[&str]| words.iter().any(|word| names.contains(word));
+
+        if has(&["heal", "healing", "health", "cure", "curing"]) {
+            ItemEffect::Heal
+        } else if has(&["sanity", "calm", "calming", "clarity"]) {
+            ItemEffect::RestoreSanity
+        } else if has(&["strength", "might", "giant"]) {
+            ItemEffect::Strength
+        } else if has(&["protect", "protection", "shield", "shielding", "stoneskin"]) {
+            ItemEffect::Protection
+        } else if has(&["food", "ration", "rations", "nourish", "nourishment"]) {
+            ItemEffect::Nourish
+        } else if has(&["water", "drink", "hydrate", "hydration"]) {
+            ItemEffect::Hydrate
+        } else if has(&["vigor", "vigour", "stamina", "rest"]) {
+            ItemEffect::Vigor
+        } else if has(&["dread", "ward", "warding", "courage"]) {
+            ItemEffect::DreadWard
+        } else {
+            ItemEffect::Other(effect)
+        }
+    }
+}
+
+/// A timed effect from a consumable. Durations are in game hours.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveEffect {
+    pub source: String,
+    pub effect: ItemEffect,
+    pub potency: u32,
+    pub remaining_hours: f32,
+}
+
+/// Timed consumable effects on an entity
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActiveEffects {
+    pub effects: Vec<ActiveEffect>,
+}
+
+impl ActiveEffects {
+    /// Add a timed effect. Re-applying the same effect does not stack; it
+    /// keeps the stronger potency and the longer remaining duration.
+    pub fn apply(&mut self, source: &str, effect: ItemEffect, potency: u32, duration_hours: f32) {
+        if let Some(existing) = self.effects.iter_mut().find(|active| active.effect == effect) {
+            existing.potency = existing.potency.max(potency);
+            existing.remaining_hours = existing.remaining_hours.max(duration_hours);
+            existing.source = source.to_string();
+            return;
+        }
+
+        self.effects.push(ActiveEffect {
+            source: source.to_string(),
+            effect,
+            potency,
+            remaining_hours: duration_hours,
+        });
+    }
+
+    /// Advance all effects and return the ones that expired
+    pub fn tick(&mut self, hours: f32) -> Vec<ActiveEffect> {
+        for active in &mut self.effects {
+            active.remaining_hours -= hours;
+        }
+        let (expired, remaining): (Vec<_>, Vec<_>) =
+            self.effects.drain(..).partition(|active| active.remaining_hours <= 0.0);
+        self.effects = remaining;
+        expired
+    }
+
+    pub fn potency_of(&self, effect: &ItemEffect) -> u32 {
+        self.effects
+            .iter()
+            .filter(|active| &active.effect == effect)
+            .map(|active| active.potency)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn is_active(&self, effect: &ItemEffect) -> bool {
+        self.effects.iter().any(|active| &active.effect == effect)
+    }
+}
+
+/// Derived combat numbers, recomputed whenever equipment or effects change
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CombatStats {
+    pub attack: i32,
+    pub defense: i32,
+    pub dread_resistance: f32,
+}
+
+impl CombatStats {
+    pub fn calculate(stats: &CharacterStats, equipment: &Equipment, effects: &ActiveEffects) -> Self {
+        let modifier = |score: u32| (score as i32 - 10).div_euclid(2);
+
+        Self {
+            attack: modifier(stats.strength)
+                + equipment.attack_bonus()
+                + effects.potency_of(&ItemEffect::Strength) as i32,
+            defense: 10
+                + modifier(stats.dexterity)
+                + equipment.defense_bonus()
+                + effects.potency_of(&ItemEffect::Protection) as i32,
+            dread_resistance: effects.potency_of(&ItemEffect::DreadWard) as f32 * 0.05,
+        }
+    }
+}
+
+/// Catalogue entry for an item found in the HBF data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemTemplate {
+    pub id: String,
+    pub source_uuid: String,
+    pub item: Item,
+    pub corruption_band: u8,
+}
+
+/// All known item templates, keyed by id
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ItemDatabase {
+    pub items: HashMap<String, ItemTemplate>,
+}
+
+impl ItemDatabase {
+    /// Insert a template. A taken id gets a numeric suffix, so two items of
+    /// the same name on one page are both kept.
+    pub fn insert(&mut self, mut template: ItemTemplate) {
+        if self.items.contains_key(&template.id) {
+            template.id = (2..)
+                .map(|n| format!("{}_{}", template.id, n))
+                .find(|id| !self.items.contains_key(id))
+                .expect("some suffix is free");
+        }
+        self.items.insert(template.id.clone(), template);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ItemTemplate> {
+        self.items.get(id)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&ItemTemplate> {
+        self.items
+            .values()
+            .find(|template| template.item.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Templates that came from one HBF page (e.g. the dungeon area holding a hoard)
+    pub fn from_source<'a>(&'a self, source_uuid: &'a str) -> impl Iterator<Item = &'a ItemTemplate> + 'a {
+        self.items
+            .values()
+            .filter(move |template| template.source_uuid == source_uuid)
+    }
+
+    /// A fresh copy of a template's item with the given quantity
+    pub fn instantiate(&self, id: &str, quantity: u32) -> Option<Item> {
+        self.get(id).map(|template| Item {
+            quantity,
+            ..template.item.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::player::Inventory;
+
+    fn item(name: &str, item_type: ItemType) -> Item {
+        Item {
+            name: name.to_string(),
+            item_type,
+            quantity: 1,
+            weight: 1.0,
+            value: 10,
+            description: String::new(),
+        }
+    }
+
+    fn potion(quantity: u32) -> Item {
+        Item {
+            quantity,
+            ..item(
+                "Potion of Healing",
+                ItemType::Consumable {
+                    effect: "healing".to_string(),
+                    duration: 0.0,
+                    potency: 10,
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_duplicate_ids_are_kept_under_a_suffix() {
+        let template = |name: &str| ItemTemplate {
+            id: "dagger_nTR8nJOW".to_string(),
+            source_uuid: "nTR8nJOWabcdef".to_string(),
+            item: item(name, ItemType::Material {
+                material_type: "trinket".to_string(),
+                rarity: "common".to_string(),
+            }),
+            corruption_band: 1,
+        };
+        let mut database = ItemDatabase::default();
+        database.insert(template("Dagger"));
+        database.insert(template("Rusty Dagger"));
+        database.insert(template("Bent Dagger"));
+
+        assert_eq!(database.get("dagger_nTR8nJOW").unwrap().item.name, "Dagger");
+        assert_eq!(database.get("dagger_nTR8nJOW_2").unwrap().id, "dagger_nTR8nJOW_2");
+        assert_eq!(database.get("dagger_nTR8nJOW_3").unwrap().item.name, "Bent Dagger");
+    }
+
+    #[test]
+    fn test_stacking_and_quest_lock() {
+        let mut inventory = Inventory::new(2, 100.0);
+        assert!(inventory.add_item(potion(2)));
+        assert!(inventory.add_item(potion(3)));
+        assert_eq!(inventory.items.len(), 1);
+        assert_eq!(inventory.count_item("Potion of Healing"), 5);
+
+        let relic = item(
+            "Bone Key",
+            ItemType::QuestItem {
+                quest_id: "crypt".to_string(),
+                unique: true,
+            },
+        );
+        assert!(inventory.add_item(relic));
+        assert!(inventory.discard_item("Bone Key", 1).is_none());
+        assert_eq!(inventory.release_quest_items("crypt").len(), 1);
+        assert!(!inventory.has_item("Bone Key"));
+        assert!((inventory.current_weight - 5.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_effect_names_match_whole_words() {
+        assert_eq!(ItemEffect::from_name("healing"), ItemEffect::Heal);
+        assert_eq!(ItemEffect::from_name("giant strength"), ItemEffect::Strength);
+        assert_eq!(ItemEffect::from_name("Rest"), ItemEffect::Vigor);
+        assert_eq!(ItemEffect::from_name("forest"), ItemEffect::Other("forest".to_string()));
+        assert_eq!(ItemEffect::from_name("wardrobe"), ItemEffect::Other("wardrobe".to_string()));
+        assert_eq!(ItemEffect::from_name("breadth"), ItemEffect::Other("breadth".to_string()));
+    }
+
+    #[test]
+    fn test_tool_breaks_at_zero_durability() {
+        let mut inventory = Inventory::new(4, 100.0);
+        inventory.add_item(item(
+            "Lockpicks",
+            ItemType::Tool {
+                tool_type: "lockpick".to_string(),
+                durability: 2,
+                max_durability: 2,
+            },
+        ));
+
+        assert_eq!(inventory.wear_tool("lockpick", 1), Some(1));
+        assert_eq!(inventory.wear_tool("lockpick", 1), Some(0));
+        assert_eq!(inventory.wear_tool("lockpick", 1), None);
+        assert!(inventory.items.is_empty());
+    }
+
+    #[test]
+    fn test_equipment_and_effects_feed_combat_stats() {
+        let mut equipment = Equipment::default();
+        let sword = item(
+            "Longsword",
+            ItemType::Weapon {
+                damage: 4,
+                weapon_type: "sword".to_string(),
+                enchantments: vec!["+1".to_string()],
+            },
+        );
+        let shield = item(
+            "Shield",
+            ItemType::Armor {
+                protection: 2,
+                armor_type: "shield".to_string(),
+                enchantments: Vec::new(),
+            },
+        );
+        assert_eq!(equipment.equip(sword), Ok(None));
+        assert_eq!(equipment.equip(shield), Ok(None));
+        assert!(equipment.equip(potion(1)).is_err());
+
+        let mut effects = ActiveEffects::default();
+        effects.apply("Giant's Draught", ItemEffect::Strength, 2, 1.0);
+        effects.apply("Giant's Draught", ItemEffect::Strength, 1, 3.0);
+
+        let stats = CharacterStats {
+            strength: 14,
+            dexterity: 12,
+            ..CharacterStats::default()
+        };
+        let combat = CombatStats::calculate(&stats, &equipment, &effects);
+        assert_eq!(combat.attack, 2 + 5 + 2);
+        assert_eq!(combat.defense, 10 + 1 + 2);
+
+        assert!(effects.tick(2.0).is_empty());
+        assert_eq!(effects.tick(1.0).len(), 1);
+        assert!(!effects.is_active(&ItemEffect::Strength));
+    }
+}
@@ -0,0 +1,192 @@
+//! Experience and levels
+//!
+//! The player climbs from level 1 to 180 by fighting, finishing quests and
+//! pushing further out from where the journey began. Each level raises health
+//! and sanity, every tenth level grants an attribute point, and abilities
+//! unlock along the way. The world's progression band follows both how
+//! strong the player has become and how far they have come.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::world::abilities::MAX_LEVEL;
+use crate::world::creation::CharacterSheet;
+
+/// XP from one level to the next is XP_BASE + XP_PER_LEVEL * (level - 1)
+const XP_BASE: u64 = 100;
+const XP_PER_LEVEL: u64 = 25;
+pub const BASE_HEALTH: f32 = 100.0;
+pub const BASE_SANITY: f32 = 100.0;
+pub const HEALTH_PER_LEVEL: f32 = 5.0;
+pub const SANITY_PER_LEVEL: f32 = 2.0;
+/// Levels between attribute points
+pub const ATTRIBUTE_POINT_INTERVAL: u32 = 10;
+
+/// XP needed to go from `level` to the next
+pub fn xp_to_next(level: u32) -> u64 {
+    XP_BASE + XP_PER_LEVEL * (level.max(1) as u64 - 1)
+}
+
+/// Total XP needed to reach `level`
+pub fn xp_for_level(level: u32) -> u64 {
+    let steps = level.max(1) as u64 - 1;
+    steps * XP_BASE + XP_PER_LEVEL * steps * steps.saturating_sub(1) / 2
+}
+
+/// Progression band from player level and distance from the start. Both
+/// count: a strong player who stays home and a weak one who runs far are
+/// each only halfway along.
+pub fn progression_band(level: u32, distance: u32) -> u32 {
+    ((level.min(MAX_LEVEL) + distance.min(MAX_LEVEL)) / 2).clamp(1, MAX_LEVEL)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExperienceSource {
+    /// A fight won against foes of this threat level
+    Combat { threat: u32 },
+    QuestStep,
+    Quest { steps: u32 },
+    /// New rings of distance reached from the start of the journey
+    Frontier { rings: u32 },
+    Other { amount: u64 },
+}
+
+impl ExperienceSource {
+    pub fn amount(&self) -> u64 {
+        match self {
+            ExperienceSource::Combat { threat } => 20 * (*threat).max(1) as u64,
+            ExperienceSource::QuestStep => 25,
+            ExperienceSource::Quest { steps } => 100 + 50 * *steps as u64,
+            ExperienceSource::Frontier { rings } => 15 * *rings as u64,
+            ExperienceSource::Other { amount } => *amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelUp {
+    pub level: u32,
+    pub attribute_point: bool,
+}
+
+/// The player's experience, level and how far the journey has reached
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Experience {
+    pub total: u64,
+    pub level: u32,
+    /// Attribute points earned and not yet spent
+    pub attribute_points: u32,
+    /// Furthest distance in hexes from where the journey began
+    pub furthest_distance: u32,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self {
+            total: 0,
+            level: 1,
+            attribute_points: 0,
+            furthest_distance: 0,
+        }
+    }
+}
+
+impl Experience {
+    /// Add XP and return every level gained
+    pub fn gain(&mut self, amount: u64) -> Vec<LevelUp> {
+        self.total += amount;
+        let mut level_ups = Vec::new();
+        while self.level < MAX_LEVEL && self.total >= xp_for_level(self.level + 1) {
+            self.level += 1;
+            let attribute_point = self.level.is_multiple_of(ATTRIBUTE_POINT_INTERVAL);
+            if attribute_point {
+                self.attribute_points += 1;
+            }
+            level_ups.push(LevelUp {
+                level: self.level,
+                attribute_point,
+            });
+        }
+        level_ups
+    }
+
+    pub fn award(&mut self, source: &ExperienceSource) -> Vec<LevelUp> {
+        self.gain(source.amount())
+    }
+
+    /// Record reaching `distance` hexes from the start; returns how many new
+    /// rings that is, if any
+    pub fn reach(&mut self, distance: u32) -> Option<u32> {
+        if distance <= self.furthest_distance {
+            return None;
+        }
+        let rings = distance - self.furthest_distance;
+        self.furthest_distance = distance;
+        Some(rings)
+    }
+
+    /// Share of the way to the next level, 0.0..1.0
+    pub fn progress(&self) -> f32 {
+        if self.level >= MAX_LEVEL {
+            return 1.0;
+        }
+        let into_level = self.total - xp_for_level(self.level);
+        into_level as f32 / xp_to_next(self.level) as f32
+    }
+
+    pub fn band(&self) -> u32 {
+        progression_band(self.level, self.furthest_distance)
+    }
+
+    /// Maximum health from the character's constitution and levels gained
+    pub fn max_health(&self, sheet: Option<&CharacterSheet>) -> f32 {
+        sheet.map_or(BASE_HEALTH, CharacterSheet::max_health) + HEALTH_PER_LEVEL * (self.level - 1) as f32
+    }
+
+    /// Maximum sanity from the character's wisdom and levels gained
+    pub fn max_sanity(&self, sheet: Option<&CharacterSheet>) -> f32 {
+        sheet.map_or(BASE_SANITY, CharacterSheet::max_sanity) + SANITY_PER_LEVEL * (self.level - 1) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_follow_the_xp_curve() {
+        assert_eq!(xp_for_level(1), 0);
+        assert_eq!(xp_for_level(2), 100);
+        assert_eq!(xp_for_level(3), 225);
+        assert_eq!(xp_for_level(4), xp_for_level(3) + xp_to_next(3));
+
+        let mut experience = Experience::default();
+        assert!(experience.gain(99).is_empty());
+        assert_eq!(experience.gain(126).len(), 2);
+        assert_eq!(experience.level, 3);
+        assert_eq!(experience.progress(), 0.0);
+
+        let level_ups = experience.gain(xp_for_level(10) - experience.total);
+        assert_eq!(level_ups.last(), Some(&LevelUp { level: 10, attribute_point: true }));
+        assert_eq!(experience.attribute_points, 1);
+        assert_eq!(experience.max_health(None), BASE_HEALTH + 45.0);
+
+        experience.gain(u64::MAX / 2);
+        assert_eq!(experience.level, MAX_LEVEL);
+        assert_eq!(experience.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_band_follows_level_and_distance() {
+        let mut experience = Experience::default();
+        assert_eq!(experience.band(), 1);
+        assert_eq!(experience.reach(40), Some(40));
+        assert_eq!(experience.reach(30), None);
+        // 600 XP for forty new rings takes the player to level 5
+        assert_eq!(experience.award(&ExperienceSource::Frontier { rings: 40 }).len(), 4);
+        assert_eq!(experience.band(), 22);
+        assert_eq!(progression_band(180, 180), 180);
+        assert_eq!(progression_band(1, 0), 1);
+        assert_eq!(progression_band(60, 20), 40);
+    }
+}
@@ -0,0 +1,258 @@
+//! Asset manifest shared by the generator, the prompter and the game
+//!
+//! `ron-generator` records every model it writes metadata for: where the
+//! model file is expected, which HBF entity it came from and a hash of that
+//! entity's content. Comparing against the assets tree says which models are
+//! missing, which are present, and which were made from content that has
+//! since changed. The prompter only asks for models that are missing or
+//! stale, and the game falls back for the missing ones.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::world::models::ModelKind;
+use crate::world::seeded::fnv1a;
+
+/// Manifest location under the assets root
+pub const ASSET_MANIFEST_FILE: &str = "manifest.ron";
+
+/// Model file types the manifest tracks
+pub const MODEL_FILE_EXTENSIONS: [&str; 2] = ["glb", "gltf"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssetStatus {
+    Missing,
+    Present,
+    /// Present, but made from content that has since changed
+    Stale,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub id: String,
+    pub kind: ModelKind,
+    /// Model file, relative to the assets root
+    pub path: String,
+    /// The model's metadata file, relative to the assets root
+    #[serde(default)]
+    pub metadata_path: String,
+    pub source_uuid: String,
+    /// Hash of the source entity's content at generation time
+    pub source_hash: String,
+    /// Source hash the model file on disk was made from
+    pub built_from: Option<String>,
+    /// Hash of the model file on disk
+    pub content_hash: Option<String>,
+    pub status: AssetStatus,
+}
+
+impl ManifestEntry {
+    /// An entry for a freshly generated model; status comes from `refresh`
+    pub fn new(
+        id: &str,
+        kind: ModelKind,
+        path: &str,
+        metadata_path: &str,
+        source_uuid: &str,
+        source_content: &str,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            kind,
+            path: path.to_string(),
+            metadata_path: metadata_path.to_string(),
+            source_uuid: source_uuid.to_string(),
+            source_hash: content_hash(source_content.as_bytes()),
+            built_from: None,
+            content_hash: None,
+            status: AssetStatus::Missing,
+        }
+    }
+
+    pub fn needs_asset(&self) -> bool {
+        self.status != AssetStatus::Present
+    }
+}
+
+/// Every generated model, keyed by id
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssetManifest {
+    pub entries: BTreeMap<String, ManifestEntry>,
+}
+
+/// How the assets tree differs from the manifest
+#[derive(Debug, Default, PartialEq)]
+pub struct ManifestDiff {
+    /// Ids whose model file is not on disk
+    pub missing: Vec<String>,
+    /// Ids whose model file was made from older content
+    pub stale: Vec<String>,
+    /// Ids whose model file changed since the manifest was written
+    pub changed: Vec<String>,
+    /// Model files on disk that no entry expects
+    pub untracked: Vec<String>,
+}
+
+impl ManifestDiff {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.stale.is_empty() && self.changed.is_empty() && self.untracked.is_empty()
+    }
+}
+
+impl AssetManifest {
+    pub fn get(&self, id: &str) -> Option<&ManifestEntry> {
+        self.entries.get(id)
+    }
+
+    pub fn insert(&mut self, entry: ManifestEntry) {
+        self.entries.insert(entry.id.clone(), entry);
+    }
+
+    /// Add `newer`'s entries, replacing any with the same id
+    pub fn merge(&mut self, newer: AssetManifest) {
+        self.entries.extend(newer.entries);
+    }
+
+    /// Entries made from one HBF entity
+    pub fn for_source<'a>(&'a self, uuid: &'a str) -> impl Iterator<Item = &'a ManifestEntry> {
+        self.entries.values().filter(move |entry| entry.source_uuid == uuid)
+    }
+
+    /// Whether an entity still needs a model made: true when it has no
+    /// entries, or any of them is missing or stale
+    pub fn source_needs_asset(&self, uuid: &str) -> bool {
+        let mut entries = self.for_source(uuid).peekable();
+        entries.peek().is_none() || entries.any(ManifestEntry::needs_asset)
+    }
+
+    pub fn count(&self, status: AssetStatus) -> usize {
+        self.entries.values().filter(|entry| entry.status == status).count()
+    }
+
+    /// Recompute hashes and status from the model files. A file unchanged
+    /// since `previous` keeps the source it was made from; a new or changed
+    /// file is taken as made from the current source.
+    pub fn refresh(&mut self, previous: &AssetManifest, read_file: &dyn Fn(&str) -> Option<Vec<u8>>) {
+        for entry in self.entries.values_mut() {
+            let Some(bytes) = read_file(&entry.path) else {
+                entry.content_hash = None;
+                entry.built_from = None;
+                entry.status = AssetStatus::Missing;
+                continue;
+            };
+            let hash = content_hash(&bytes);
+            entry.built_from = previous
+                .get(&entry.id)
+                .filter(|old| old.content_hash.as_ref() == Some(&hash))
+                .and_then(|old| old.built_from.clone())
+                .or_else(|| Some(entry.source_hash.clone()));
+            entry.status = if entry.built_from.as_ref() == Some(&entry.source_hash) {
+                AssetStatus::Present
+            } else {
+                AssetStatus::Stale
+            };
+            entry.content_hash = Some(hash);
+        }
+    }
+
+    /// Compare against the assets tree; `model_files` lists the model files
+    /// found under it, relative to the root
+    pub fn diff(&self, read_file: &dyn Fn(&str) -> Option<Vec<u8>>, model_files: &[String]) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+        for entry in self.entries.values() {
+            match read_file(&entry.path) {
+                None => diff.missing.push(entry.id.clone()),
+                Some(bytes) if entry.content_hash.as_ref() != Some(&content_hash(&bytes)) => {
+                    diff.changed.push(entry.id.clone())
+                }
+                Some(_) if entry.status == AssetStatus::Stale => diff.stale.push(entry.id.clone()),
+                Some(_) => {}
+            }
+        }
+        let expected: HashSet<&str> = self.entries.values().map(|entry| entry.path.as_str()).collect();
+        diff.untracked = model_files.iter().filter(|path| !expected.contains(path.as_str())).cloned().collect();
+        diff.untracked.sort();
+        diff
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, as hex
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:016x}", fnv1a(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn manifest(source: &str) -> AssetManifest {
+        let mut manifest = AssetManifest::default();
+        manifest.insert(ManifestEntry::new(
+            "acolyte_model",
+            ModelKind::Unit,
+            "units/cult/acolyte.glb",
+            "units/cult/acolyte.meta.ron",
+            "u1",
+            source,
+        ));
+        manifest.insert(ManifestEntry::new(
+            "shrine_building",
+            ModelKind::Building,
+            "buildings/town/shrine.glb",
+            "buildings/town/shrine.meta.ron",
+            "u2",
+            "shrine",
+        ));
+        manifest
+    }
+
+    #[test]
+    fn test_refresh_tracks_missing_present_and_stale() {
+        let files = HashMap::from([("units/cult/acolyte.glb", b"mesh v1".to_vec())]);
+        let read = |path: &str| files.get(path).cloned();
+
+        let mut first = manifest("<p>An acolyte</p>");
+        first.refresh(&AssetManifest::default(), &read);
+        assert_eq!(first.get("acolyte_model").unwrap().status, AssetStatus::Present);
+        assert_eq!(first.get("shrine_building").unwrap().status, AssetStatus::Missing);
+        assert!(!first.source_needs_asset("u1"));
+        assert!(first.source_needs_asset("u2"));
+        assert!(first.source_needs_asset("unknown"));
+
+        // The entity changes but the model file does not: stale
+        let mut second = manifest("<p>A scarred acolyte</p>");
+        second.refresh(&first, &read);
+        assert_eq!(second.get("acolyte_model").unwrap().status, AssetStatus::Stale);
+
+        // A new model file is taken as made from the current content
+        let files = HashMap::from([("units/cult/acolyte.glb", b"mesh v2".to_vec())]);
+        let mut third = manifest("<p>A scarred acolyte</p>");
+        third.refresh(&second, &|path: &str| files.get(path).cloned());
+        assert_eq!(third.get("acolyte_model").unwrap().status, AssetStatus::Present);
+        assert_eq!(third.count(AssetStatus::Missing), 1);
+    }
+
+    #[test]
+    fn test_diff_against_tree() {
+        let files = HashMap::from([("units/cult/acolyte.glb", b"mesh v1".to_vec())]);
+        let mut recorded = manifest("<p>An acolyte</p>");
+        recorded.refresh(&AssetManifest::default(), &|path: &str| files.get(path).cloned());
+
+        let files = HashMap::from([
+            ("units/cult/acolyte.glb", b"mesh v2".to_vec()),
+            ("units/cult/stray.glb", b"stray".to_vec()),
+        ]);
+        let on_disk: Vec<String> = files.keys().map(|path| path.to_string()).collect();
+        let diff = recorded.diff(&|path: &str| files.get(path).cloned(), &on_disk);
+        assert_eq!(diff, ManifestDiff {
+            missing: vec!["shrine_building".to_string()],
+            stale: Vec::new(),
+            changed: vec!["acolyte_model".to_string()],
+            untracked: vec!["units/cult/stray.glb".to_string()],
+        });
+        assert!(!diff.is_clean());
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+    }
+}
@@ -1,16 +1,48 @@
 //! World-related types moved from apps/game/src/world/components
 
+pub mod abilities;
+pub mod alignment;
+pub mod atlas;
 pub mod character;
 pub mod companions;
+pub mod creation;
 pub mod dread;
+pub mod dungeons;
+pub mod economy;
+pub mod factions;
 pub mod hex;
+pub mod items;
+pub mod leveling;
+pub mod manifest;
+pub mod models;
+pub mod npcs;
 pub mod player;
+pub mod quests;
+pub mod seeded;
+pub mod settlements;
 pub mod tiles;
+pub mod weather;
 
 // Re-export all world types (specific to avoid ambiguity)
+pub use abilities::*;
+pub use alignment::*;
+pub use atlas::*;
 pub use character::{CharacterData, CharacterAppearance, CharacterStats, Gender, HairStyle, SkinTone, ClothingSet, NPC, NPCType, Monster, MonsterType, AIState, CharacterModel};
 pub use companions::*;
+pub use creation::*;
 pub use dread::*;
+pub use dungeons::*;
+pub use economy::*;
+pub use factions::*;
 pub use hex::*;
-pub use player::{Player, Mount, MountType, Item, ItemType, Inventory};
+pub use items::*;
+pub use leveling::*;
+pub use manifest::*;
+pub use models::*;
+pub use npcs::*;
+pub use player::{Player, Mount, Mounted, MountType, Item, ItemType, Inventory, MOUNT_PANIC_THRESHOLD, MOUNT_BOLT_THRESHOLD};
+pub use quests::*;
+pub use seeded::*;
+pub use settlements::*;
 pub use tiles::*;
+pub use weather::*;
@@ -0,0 +1,267 @@
+//! Model metadata shared by the asset pipeline and the game
+//!
+//! `ron-generator` writes one `.meta.ron` file per unit, building, leader and
+//! terrain piece. The game reads them to find each model's scene, sockets and
+//! sounds. Files carry a schema version, so output from an older or newer
+//! generator is refused rather than misread.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Schema version written by the generator; files without one are version 1
+pub const MODEL_METADATA_VERSION: u32 = 1;
+/// Suffix of model metadata files
+pub const MODEL_METADATA_EXTENSION: &str = "meta.ron";
+
+fn first_version() -> u32 {
+    1
+}
+
+/// The asset families that carry model metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModelKind {
+    Unit,
+    Building,
+    Leader,
+    Terrain,
+}
+
+impl ModelKind {
+    pub const ALL: [ModelKind; 4] = [ModelKind::Unit, ModelKind::Building, ModelKind::Leader, ModelKind::Terrain];
+
+    /// Directory under the assets root holding this kind's metadata
+    pub fn dir(self) -> &'static str {
+        match self {
+            ModelKind::Unit => "units",
+            ModelKind::Building => "buildings",
+            ModelKind::Leader => "leaders",
+            ModelKind::Terrain => "terrain",
+        }
+    }
+}
+
+/// Model metadata in the cosmic-cults layout, with Dragon's Labyrinth additions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelMetadata {
+    #[serde(default = "first_version")]
+    pub version: u32,
+    pub id: String,
+    pub display_name: String,
+    /// Relative to the assets root
+    pub model_path: String,
+    pub scale: (f32, f32, f32),
+    pub bounds: ModelBounds,
+    pub animations: Vec<String>,
+    pub sockets: Vec<ModelSocket>,
+    pub tags: Vec<String>,
+    pub cult: Option<String>,
+    pub class: Option<String>,
+    /// Id of the model this one upgrades into
+    pub upgrades_to: Option<String>,
+    pub ui_icon: Option<String>,
+    pub sounds: Vec<SoundEvent>,
+    pub corruption_band: Option<u8>,
+    pub horror_theme: Option<String>,
+    pub forge_material: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelBounds {
+    pub min: (f32, f32, f32),
+    pub max: (f32, f32, f32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelSocket {
+    pub name: String,
+    pub position: (f32, f32, f32),
+    pub rotation: (f32, f32, f32, f32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoundEvent {
+    pub event: String,
+    /// Relative to the assets root
+    pub sound_path: String,
+    pub volume: f32,
+    pub pitch_variation: f32,
+}
+
+/// Something wrong with a model's metadata
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataIssue {
+    UnsupportedVersion { id: String, version: u32 },
+    DuplicateId { id: String },
+    MissingFile { id: String, path: String },
+    UnknownUpgrade { id: String, target: String },
+    InvalidBounds { id: String, reason: String },
+    InvalidScale { id: String },
+}
+
+impl fmt::Display for MetadataIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataIssue::UnsupportedVersion { id, version } => {
+                write!(f, "{}: schema version {} (expected {})", id, version, MODEL_METADATA_VERSION)
+            }
+            MetadataIssue::DuplicateId { id } => write!(f, "{}: id used by more than one model", id),
+            MetadataIssue::MissingFile { id, path } => write!(f, "{}: missing {}", id, path),
+            MetadataIssue::UnknownUpgrade { id, target } => write!(f, "{}: upgrades to unknown model {}", id, target),
+            MetadataIssue::InvalidBounds { id, reason } => write!(f, "{}: bounds {}", id, reason),
+            MetadataIssue::InvalidScale { id } => write!(f, "{}: scale must be positive", id),
+        }
+    }
+}
+
+impl ModelBounds {
+    /// Why these bounds cannot enclose a model, if they cannot
+    pub fn problem(&self) -> Option<String> {
+        let min = [self.min.0, self.min.1, self.min.2];
+        let max = [self.max.0, self.max.1, self.max.2];
+        if min.iter().chain(&max).any(|value| !value.is_finite()) {
+            return Some("are not finite".to_string());
+        }
+        ["x", "y", "z"]
+            .iter()
+            .zip(min.iter().zip(&max))
+            .find(|(_, (low, high))| low >= high)
+            .map(|(axis, (low, high))| format!("are empty on {} ({} to {})", axis, low, high))
+    }
+}
+
+impl ModelMetadata {
+    pub fn is_supported_version(&self) -> bool {
+        (1..=MODEL_METADATA_VERSION).contains(&self.version)
+    }
+
+    /// Every asset file the metadata points at
+    pub fn referenced_paths(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.model_path.as_str())
+            .chain(self.ui_icon.as_deref())
+            .chain(self.sounds.iter().map(|sound| sound.sound_path.as_str()))
+    }
+
+    /// Problems with this model on its own; `file_exists` is asked about
+    /// each referenced path
+    pub fn issues(&self, file_exists: &dyn Fn(&str) -> bool) -> Vec<MetadataIssue> {
+        let mut issues = Vec::new();
+        if !self.is_supported_version() {
+            issues.push(MetadataIssue::UnsupportedVersion { id: self.id.clone(), version: self.version });
+        }
+        let (x, y, z) = self.scale;
+        if ![x, y, z].iter().all(|value| value.is_finite() && *value > 0.0) {
+            issues.push(MetadataIssue::InvalidScale { id: self.id.clone() });
+        }
+        if let Some(reason) = self.bounds.problem() {
+            issues.push(MetadataIssue::InvalidBounds { id: self.id.clone(), reason });
+        }
+        issues.extend(self.referenced_paths().filter(|path| !file_exists(path)).map(|path| MetadataIssue::MissingFile {
+            id: self.id.clone(),
+            path: path.to_string(),
+        }));
+        issues
+    }
+}
+
+/// Problems across a set of models: each model's own issues, ids used
+/// twice and upgrades into models that are not in the set
+pub fn validate_model_set<'a>(
+    models: impl IntoIterator<Item = &'a ModelMetadata>,
+    file_exists: &dyn Fn(&str) -> bool,
+) -> Vec<MetadataIssue> {
+    let models: Vec<&ModelMetadata> = models.into_iter().collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for model in &models {
+        *counts.entry(model.id.as_str()).or_default() += 1;
+    }
+
+    let mut issues = Vec::new();
+    let mut duplicates: Vec<&str> = counts.iter().filter(|(_, count)| **count > 1).map(|(id, _)| *id).collect();
+    duplicates.sort();
+    issues.extend(duplicates.into_iter().map(|id| MetadataIssue::DuplicateId { id: id.to_string() }));
+
+    for model in &models {
+        issues.extend(model.issues(file_exists));
+        if let Some(target) = model.upgrades_to.as_ref().filter(|target| !counts.contains_key(target.as_str())) {
+            issues.push(MetadataIssue::UnknownUpgrade { id: model.id.clone(), target: target.clone() });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str, upgrades_to: Option<&str>) -> ModelMetadata {
+        ModelMetadata {
+            version: MODEL_METADATA_VERSION,
+            id: id.to_string(),
+            display_name: id.to_string(),
+            model_path: format!("units/cult/{}.glb", id),
+            scale: (1.0, 1.0, 1.0),
+            bounds: ModelBounds { min: (-0.5, 0.0, -0.5), max: (0.5, 2.0, 0.5) },
+            animations: vec!["idle".to_string()],
+            sockets: Vec::new(),
+            tags: Vec::new(),
+            cult: Some("cult".to_string()),
+            class: None,
+            upgrades_to: upgrades_to.map(str::to_string),
+            ui_icon: None,
+            sounds: vec![SoundEvent {
+                event: "attack".to_string(),
+                sound_path: "sounds/attack.ogg".to_string(),
+                volume: 1.0,
+                pitch_variation: 0.1,
+            }],
+            corruption_band: Some(1),
+            horror_theme: None,
+            forge_material: None,
+        }
+    }
+
+    #[test]
+    fn test_model_issues() {
+        let all_present = |_: &str| true;
+        let mut acolyte = model("acolyte_model", None);
+        assert!(acolyte.issues(&all_present).is_empty());
+
+        acolyte.version = MODEL_METADATA_VERSION + 1;
+        acolyte.scale = (1.0, 0.0, 1.0);
+        acolyte.bounds.max.1 = -1.0;
+        let issues = acolyte.issues(&|path: &str| path.ends_with(".glb"));
+        assert_eq!(issues.len(), 4);
+        assert!(matches!(&issues[2], MetadataIssue::InvalidBounds { reason, .. } if reason.contains("on y")));
+        assert_eq!(issues[3], MetadataIssue::MissingFile {
+            id: "acolyte_model".to_string(),
+            path: "sounds/attack.ogg".to_string(),
+        });
+
+        acolyte.bounds.min.0 = f32::NAN;
+        assert_eq!(acolyte.bounds.problem(), Some("are not finite".to_string()));
+    }
+
+    #[test]
+    fn test_model_set_checks_ids_and_upgrades() {
+        let models = [
+            model("acolyte_model", Some("cultist_model")),
+            model("cultist_model", Some("high_priest_model")),
+            model("acolyte_model", None),
+        ];
+        let issues = validate_model_set(&models, &|_: &str| true);
+        assert_eq!(issues, vec![
+            MetadataIssue::DuplicateId { id: "acolyte_model".to_string() },
+            MetadataIssue::UnknownUpgrade { id: "cultist_model".to_string(), target: "high_priest_model".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_unversioned_metadata_is_version_one() {
+        let mut json = serde_json::to_value(model("acolyte_model", None)).unwrap();
+        json.as_object_mut().unwrap().remove("version");
+        let metadata: ModelMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(metadata.version, 1);
+        assert!(metadata.is_supported_version());
+    }
+}
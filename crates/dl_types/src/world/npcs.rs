@@ -0,0 +1,456 @@
+//! NPCs: HBF character records, daily schedules, memory and corruption
+//!
+//! HBF lists NPCs on the pages of the places they live: the keeper and staff
+//! of an establishment, the notable folk of a settlement, the odd hermit on a
+//! hex page. Each record keeps who an NPC is and where they belong. In play an
+//! NPC follows a daily routine, remembers what the player did to them and, as
+//! dread deepens, wavers and finally turns cultist or flees.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::world::character::{NPC, NPCType};
+use crate::world::dread::DreadPhase;
+use crate::world::factions::NpcAttitude;
+use crate::world::settlements::ServiceType;
+
+/// Memories an NPC keeps before the oldest fade
+pub const MAX_MEMORIES: usize = 16;
+/// Disposition at or below which an NPC will not deal with the player
+pub const REFUSAL_DISPOSITION: i32 = -10;
+/// Disposition at or below which an NPC turns on the player
+pub const HOSTILE_DISPOSITION: i32 = -25;
+/// Share of its resolve the pressure must reach before an NPC wavers
+const WAVER_RATIO: f32 = 0.6;
+/// Share of its resolve the pressure must fall below for a wavering NPC to recover
+const RECOVER_RATIO: f32 = 0.4;
+
+/// One NPC as HBF describes them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NpcRecord {
+    pub uuid: String,
+    pub name: String,
+    pub npc_type: NPCType,
+    /// Level, race and class are only known for NPCs with a stat block
+    #[serde(default)]
+    pub level: Option<u32>,
+    #[serde(default)]
+    pub race: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub faction: Option<String>,
+    #[serde(default)]
+    pub settlement_uuid: Option<String>,
+    /// Establishment the NPC keeps or works in
+    #[serde(default)]
+    pub establishment_uuid: Option<String>,
+    #[serde(default)]
+    pub hex_uuid: Option<String>,
+    #[serde(default)]
+    pub quest_giver: bool,
+}
+
+impl NpcRecord {
+    /// How long the NPC holds out against dread, 0.0..=1.0. Priests and
+    /// guards are steadier than most; experience helps everyone.
+    pub fn resolve(&self) -> f32 {
+        let base = match self.npc_type {
+            NPCType::Priest => 0.8,
+            NPCType::Guard | NPCType::Hermit => 0.7,
+            NPCType::Noble => 0.4,
+            _ => 0.5,
+        };
+        (base + self.level.unwrap_or(0) as f32 * 0.02).min(1.0)
+    }
+
+    /// The `NPC` component this record hydrates into. Shopkeepers get an
+    /// empty inventory; their stock is the settlement's shop.
+    pub fn to_component(&self) -> NPC {
+        NPC {
+            npc_type: self.npc_type.clone(),
+            name: self.name.clone(),
+            dialogue_tree: self.npc_type.get_default_dialogue(),
+            shop_inventory: matches!(
+                self.npc_type,
+                NPCType::Merchant | NPCType::Blacksmith | NPCType::Innkeeper
+            )
+            .then(Vec::new),
+            quest_giver: self.quest_giver,
+            reputation: 0,
+        }
+    }
+}
+
+/// Role of an NPC from where HBF lists them: the kind of establishment they
+/// keep or staff ("Blacksmith", "Tavern"), their class if they have a stat
+/// block, and whether they live in a settlement at all
+pub fn classify_npc(establishment: Option<&str>, keeper: bool, class: Option<&str>, settled: bool) -> NPCType {
+    if !settled {
+        return NPCType::Hermit;
+    }
+    let kind = establishment.map(str::to_lowercase).unwrap_or_default();
+    if keeper && ["blacksmith", "armor", "weapon"].iter().any(|word| kind.contains(word)) {
+        return NPCType::Blacksmith;
+    }
+
+    match establishment.and_then(ServiceType::for_establishment) {
+        Some(ServiceType::Lodging) if keeper => NPCType::Innkeeper,
+        Some(ServiceType::Religious) => NPCType::Priest,
+        Some(ServiceType::Defense) => NPCType::Guard,
+        Some(ServiceType::Government) if keeper => NPCType::Noble,
+        Some(ServiceType::Commerce | ServiceType::Crafting | ServiceType::Medical) if keeper => NPCType::Merchant,
+        _ => match class.map(str::to_lowercase).as_deref() {
+            Some("cleric" | "paladin") => NPCType::Priest,
+            Some("fighter") => NPCType::Guard,
+            _ => NPCType::Villager,
+        },
+    }
+}
+
+/// Every NPC the game knows, keyed by HBF uuid
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NpcDatabase {
+    pub npcs: HashMap<String, NpcRecord>,
+}
+
+impl NpcDatabase {
+    pub fn insert(&mut self, npc: NpcRecord) {
+        self.npcs.insert(npc.uuid.clone(), npc);
+    }
+
+    pub fn get(&self, uuid: &str) -> Option<&NpcRecord> {
+        self.npcs.get(uuid)
+    }
+
+    pub fn in_settlement<'a>(&'a self, settlement_uuid: &'a str) -> impl Iterator<Item = &'a NpcRecord> + 'a {
+        self.npcs
+            .values()
+            .filter(move |npc| npc.settlement_uuid.as_deref() == Some(settlement_uuid))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleActivity {
+    Working,
+    Patrolling,
+    Socializing,
+    Resting,
+    Asleep,
+}
+
+impl ScheduleActivity {
+    /// Whether the NPC will answer the door
+    pub fn is_available(&self) -> bool {
+        !matches!(self, ScheduleActivity::Resting | ScheduleActivity::Asleep)
+    }
+}
+
+/// An NPC's day: each entry starts an activity at an hour
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NpcSchedule {
+    pub entries: Vec<(u32, ScheduleActivity)>,
+    pub current: ScheduleActivity,
+}
+
+impl NpcSchedule {
+    pub fn for_type(npc_type: &NPCType) -> Self {
+        use ScheduleActivity::*;
+        let entries = match npc_type {
+            // Taverns keep late hours
+            NPCType::Innkeeper => vec![(0, Working), (3, Asleep), (10, Working)],
+            NPCType::Guard => vec![(0, Patrolling), (4, Asleep), (12, Patrolling)],
+            NPCType::Priest => vec![(0, Asleep), (5, Working), (20, Resting), (22, Asleep)],
+            NPCType::Merchant | NPCType::Blacksmith => {
+                vec![(0, Asleep), (7, Working), (18, Socializing), (22, Asleep)]
+            }
+            NPCType::Noble => vec![(0, Asleep), (9, Working), (17, Socializing), (23, Asleep)],
+            NPCType::Hermit => vec![(0, Asleep), (6, Socializing), (20, Asleep)],
+            NPCType::Villager | NPCType::QuestGiver => {
+                vec![(0, Asleep), (6, Working), (17, Socializing), (21, Resting), (22, Asleep)]
+            }
+        };
+        let mut schedule = Self {
+            entries,
+            current: Asleep,
+        };
+        schedule.current = schedule.activity_at(0.0);
+        schedule
+    }
+
+    pub fn activity_at(&self, hour: f32) -> ScheduleActivity {
+        let hour = hour.rem_euclid(24.0) as u32;
+        self.entries
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= hour)
+            .or(self.entries.last())
+            .map_or(ScheduleActivity::Resting, |(_, activity)| *activity)
+    }
+
+    /// Move to the activity for `hour`; true if it changed
+    pub fn update(&mut self, hour: f32) -> bool {
+        let activity = self.activity_at(hour);
+        let changed = activity != self.current;
+        self.current = activity;
+        changed
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemoryKind {
+    Helped,
+    Harmed,
+    Traded,
+    Threatened,
+    QuestCompleted,
+    QuestFailed,
+}
+
+impl MemoryKind {
+    /// How much the memory sways the NPC's disposition towards the player
+    pub fn weight(&self) -> i32 {
+        match self {
+            MemoryKind::Helped => 5,
+            MemoryKind::Harmed => -10,
+            MemoryKind::Traded => 1,
+            MemoryKind::Threatened => -5,
+            MemoryKind::QuestCompleted => 8,
+            MemoryKind::QuestFailed => -4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memory {
+    pub kind: MemoryKind,
+    pub day: u32,
+}
+
+/// What an NPC remembers of the player
+#[derive(Component, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NpcMemory {
+    pub memories: Vec<Memory>,
+    /// Disposition left behind by memories that have faded
+    pub settled: i32,
+}
+
+impl NpcMemory {
+    /// Remember something the player did. Past `MAX_MEMORIES` the oldest
+    /// memory fades, leaving half its weight behind.
+    pub fn remember(&mut self, kind: MemoryKind, day: u32) {
+        self.memories.push(Memory { kind, day });
+        if self.memories.len() > MAX_MEMORIES {
+            let faded = self.memories.remove(0);
+            self.settled += faded.kind.weight() / 2;
+        }
+    }
+
+    pub fn recalls(&self, kind: MemoryKind) -> bool {
+        self.memories.iter().any(|memory| memory.kind == kind)
+    }
+
+    pub fn disposition(&self) -> i32 {
+        self.settled + self.memories.iter().map(|memory| memory.kind.weight()).sum::<i32>()
+    }
+}
+
+/// How far dread has taken hold of an NPC
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NpcCorruption {
+    #[default]
+    Loyal,
+    Wavering,
+    Cultist,
+    Fled,
+}
+
+impl NpcCorruption {
+    /// Dread pressure on an NPC, 0.0..=1.0, from the world's dread phase and
+    /// the corruption (0..=100) of the settlement around them
+    pub fn pressure(phase: &DreadPhase, settlement_corruption: f32) -> f32 {
+        let phase = match phase {
+            DreadPhase::Peace => 0.0,
+            DreadPhase::Unease => 0.2,
+            DreadPhase::Dread => 0.45,
+            DreadPhase::Terror => 0.7,
+            DreadPhase::Void => 0.9,
+            DreadPhase::BeyondVoid => 1.0,
+        };
+        (phase * 0.7 + (settlement_corruption / 100.0).clamp(0.0, 1.0) * 0.3).min(1.0)
+    }
+
+    /// The next state under `pressure`, one step at a time. A wavering NPC
+    /// breaks once the pressure exceeds their resolve: to the cult if
+    /// corruption already has a hold on them, otherwise they flee. Neither
+    /// comes back.
+    pub fn next(self, pressure: f32, resolve: f32, drawn_to_cult: bool) -> Self {
+        match self {
+            NpcCorruption::Loyal if pressure > resolve * WAVER_RATIO => NpcCorruption::Wavering,
+            NpcCorruption::Wavering if pressure > resolve => {
+                if drawn_to_cult {
+                    NpcCorruption::Cultist
+                } else {
+                    NpcCorruption::Fled
+                }
+            }
+            NpcCorruption::Wavering if pressure < resolve * RECOVER_RATIO => NpcCorruption::Loyal,
+            state => state,
+        }
+    }
+}
+
+/// Where talking to an NPC leads
+#[derive(Debug, Clone, PartialEq)]
+pub enum NpcInteraction {
+    Dialogue { dialogue_tree: String },
+    Shop,
+    Quest { quest_id: String },
+    Refuse { reason: String },
+    Hostile,
+    /// The NPC has fled
+    Absent,
+}
+
+/// Route the player's approach to an NPC. `quest` is a quest the NPC could
+/// offer right now; `attitude` comes from the NPC's faction, if any.
+pub fn route_interaction(
+    npc: &NPC,
+    corruption: NpcCorruption,
+    activity: ScheduleActivity,
+    disposition: i32,
+    attitude: Option<NpcAttitude>,
+    quest: Option<&str>,
+) -> NpcInteraction {
+    match corruption {
+        NpcCorruption::Fled => return NpcInteraction::Absent,
+        NpcCorruption::Cultist => return NpcInteraction::Hostile,
+        _ => {}
+    }
+    if attitude == Some(NpcAttitude::Hostile) || disposition <= HOSTILE_DISPOSITION {
+        return NpcInteraction::Hostile;
+    }
+    if !activity.is_available() {
+        return NpcInteraction::Refuse {
+            reason: format!("{} is {:?}", npc.name, activity).to_lowercase(),
+        };
+    }
+    if disposition <= REFUSAL_DISPOSITION
+        || (attitude == Some(NpcAttitude::Wary) && corruption == NpcCorruption::Wavering)
+    {
+        return NpcInteraction::Refuse {
+            reason: format!("{} will not speak with you", npc.name),
+        };
+    }
+
+    if let Some(quest_id) = quest.filter(|_| npc.quest_giver) {
+        NpcInteraction::Quest {
+            quest_id: quest_id.to_string(),
+        }
+    } else if npc.shop_inventory.is_some() && activity == ScheduleActivity::Working {
+        NpcInteraction::Shop
+    } else {
+        NpcInteraction::Dialogue {
+            dialogue_tree: npc.dialogue_tree.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(npc_type: NPCType) -> NpcRecord {
+        NpcRecord {
+            uuid: "lIztT4Zj".to_string(),
+            name: "Melibor Sicletrude".to_string(),
+            npc_type,
+            level: None,
+            race: None,
+            class: None,
+            faction: None,
+            settlement_uuid: Some("YVOe8HfG".to_string()),
+            establishment_uuid: None,
+            hex_uuid: None,
+            quest_giver: false,
+        }
+    }
+
+    #[test]
+    fn test_classify_and_schedule() {
+        assert_eq!(classify_npc(Some("Tavern"), true, None, true), NPCType::Innkeeper);
+        assert_eq!(classify_npc(Some("Tavern"), false, None, true), NPCType::Villager);
+        assert_eq!(classify_npc(Some("Blacksmith"), true, None, true), NPCType::Blacksmith);
+        assert_eq!(classify_npc(Some("Temple"), false, None, true), NPCType::Priest);
+        assert_eq!(classify_npc(None, false, Some("Fighter"), true), NPCType::Guard);
+        assert_eq!(classify_npc(None, false, Some("Wizard"), false), NPCType::Hermit);
+
+        let mut schedule = NpcSchedule::for_type(&NPCType::Merchant);
+        assert_eq!(schedule.current, ScheduleActivity::Asleep);
+        assert!(schedule.update(9.5));
+        assert_eq!(schedule.current, ScheduleActivity::Working);
+        assert!(!schedule.update(12.0));
+        assert_eq!(schedule.activity_at(23.0), ScheduleActivity::Asleep);
+        assert_eq!(NpcSchedule::for_type(&NPCType::Innkeeper).activity_at(1.0), ScheduleActivity::Working);
+    }
+
+    #[test]
+    fn test_memory_fades_into_disposition() {
+        let mut memory = NpcMemory::default();
+        memory.remember(MemoryKind::Harmed, 1);
+        assert_eq!(memory.disposition(), -10);
+        for day in 2..=MAX_MEMORIES as u32 + 1 {
+            memory.remember(MemoryKind::Traded, day);
+        }
+        // The harm faded, but half of it lingers
+        assert!(!memory.recalls(MemoryKind::Harmed));
+        assert_eq!(memory.memories.len(), MAX_MEMORIES);
+        assert_eq!(memory.disposition(), -5 + MAX_MEMORIES as i32);
+    }
+
+    #[test]
+    fn test_corruption_and_interaction_routing() {
+        let merchant = record(NPCType::Merchant);
+        let resolve = merchant.resolve();
+        let calm = NpcCorruption::pressure(&DreadPhase::Peace, 10.0);
+        let terror = NpcCorruption::pressure(&DreadPhase::BeyondVoid, 80.0);
+
+        assert_eq!(NpcCorruption::Loyal.next(calm, resolve, true), NpcCorruption::Loyal);
+        let wavering = NpcCorruption::Loyal.next(terror, resolve, true);
+        assert_eq!(wavering, NpcCorruption::Wavering);
+        assert_eq!(wavering.next(calm, resolve, true), NpcCorruption::Loyal);
+        assert_eq!(wavering.next(terror, resolve, true), NpcCorruption::Cultist);
+        assert_eq!(wavering.next(terror, resolve, false), NpcCorruption::Fled);
+
+        let npc = merchant.to_component();
+        let route = |corruption, activity, disposition, quest| {
+            route_interaction(&npc, corruption, activity, disposition, None, quest)
+        };
+        assert_eq!(route(NpcCorruption::Loyal, ScheduleActivity::Working, 0, None), NpcInteraction::Shop);
+        assert!(matches!(
+            route(NpcCorruption::Loyal, ScheduleActivity::Socializing, 0, None),
+            NpcInteraction::Dialogue { .. }
+        ));
+        assert!(matches!(
+            route(NpcCorruption::Loyal, ScheduleActivity::Asleep, 0, None),
+            NpcInteraction::Refuse { .. }
+        ));
+        assert!(matches!(
+            route(NpcCorruption::Loyal, ScheduleActivity::Working, REFUSAL_DISPOSITION, None),
+            NpcInteraction::Refuse { .. }
+        ));
+        assert_eq!(route(NpcCorruption::Cultist, ScheduleActivity::Working, 0, None), NpcInteraction::Hostile);
+        assert_eq!(route(NpcCorruption::Fled, ScheduleActivity::Working, 0, None), NpcInteraction::Absent);
+
+        let mut giver = record(NPCType::Villager);
+        giver.quest_giver = true;
+        let giver = giver.to_component();
+        assert_eq!(
+            route_interaction(&giver, NpcCorruption::Loyal, ScheduleActivity::Working, 0, None, Some("q1")),
+            NpcInteraction::Quest {
+                quest_id: "q1".to_string()
+            }
+        );
+    }
+}
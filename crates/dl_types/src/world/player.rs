@@ -1,6 +1,9 @@
 use bevy_ecs::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::world::items::ItemEffect;
+use crate::world::tiles::BiomeType;
+
 #[derive(Component, Debug, Clone)]
 pub struct Player {
     pub health: f32,
@@ -11,8 +14,9 @@ pub struct Player {
     pub mount: Option<Entity>,
 }
 
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct Mount {
+    pub name: String,
     pub mount_type: MountType,
     pub speed_multiplier: f32,
     pub terrain_bonuses: std::collections::HashMap<String, f32>,
@@ -20,6 +24,101 @@ pub struct Mount {
     pub max_health: f32,
     pub stamina: f32,
     pub max_stamina: f32,
+    pub fear: f32, // 0.0 = calm, 100.0 = bolts
+}
+
+/// Marker on the player while riding the mount referenced by `Player::mount`
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Mounted;
+
+/// Fear at which a mount may panic and throw its rider
+pub const MOUNT_PANIC_THRESHOLD: f32 = 50.0;
+/// Fear at which a panicking mount bolts and is lost
+pub const MOUNT_BOLT_THRESHOLD: f32 = 100.0;
+
+impl Mount {
+    pub fn new(name: String, mount_type: MountType) -> Self {
+        let max_stamina = mount_type.get_base_stamina();
+        Self {
+            name,
+            speed_multiplier: mount_type.get_base_speed_multiplier(),
+            terrain_bonuses: mount_type.get_terrain_penalties(),
+            health: 100.0,
+            max_health: 100.0,
+            stamina: max_stamina,
+            max_stamina,
+            fear: 0.0,
+            mount_type,
+        }
+    }
+
+    /// Speed relative to walking when ridden through a biome. An exhausted
+    /// mount has to be led, which is no faster than walking.
+    pub fn travel_multiplier(&self, biome: &BiomeType) -> f32 {
+        if self.is_exhausted() {
+            return 1.0;
+        }
+        let terrain = self
+            .terrain_bonuses
+            .get(biome.terrain_category())
+            .copied()
+            .unwrap_or(1.0);
+        (self.speed_multiplier * biome.get_mounted_multiplier() * terrain).max(0.1)
+    }
+
+    /// Stamina spent crossing one hex of the given biome
+    pub fn stamina_cost(&self, biome: &BiomeType) -> f32 {
+        // Poor footing (low mounted multiplier) is more tiring
+        5.0 / biome.get_mounted_multiplier().max(0.2)
+    }
+
+    pub fn spend_stamina(&mut self, amount: f32) {
+        self.stamina = (self.stamina - amount).max(0.0);
+    }
+
+    /// Stamina and fear recover while resting or being led
+    pub fn recover(&mut self, hours: f32) {
+        self.stamina = (self.stamina + hours * 10.0).min(self.max_stamina);
+        self.fear = (self.fear - hours * 5.0).max(0.0);
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.stamina <= 0.0
+    }
+
+    /// Fear change for entering a hex: void terrain terrifies, corruption
+    /// unsettles, and clean ground calms the animal down
+    pub fn fear_change(&self, biome: &BiomeType) -> f32 {
+        let gain = if biome.is_void() {
+            25.0
+        } else if biome.is_corrupted() {
+            10.0
+        } else {
+            -5.0
+        };
+        if gain > 0.0 {
+            gain * (1.0 - self.mount_type.get_fear_resistance())
+        } else {
+            gain
+        }
+    }
+
+    pub fn add_fear(&mut self, amount: f32) {
+        self.fear = (self.fear + amount).clamp(0.0, MOUNT_BOLT_THRESHOLD);
+    }
+
+    /// Chance (0-1) that the mount panics on this hex
+    pub fn panic_chance(&self) -> f32 {
+        ((self.fear - MOUNT_PANIC_THRESHOLD) / (MOUNT_BOLT_THRESHOLD - MOUNT_PANIC_THRESHOLD)).clamp(0.0, 1.0)
+    }
+
+    pub fn will_bolt(&self) -> bool {
+        self.fear >= MOUNT_BOLT_THRESHOLD
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -54,6 +153,37 @@ impl MountType {
         }
     }
     
+    pub fn get_base_stamina(&self) -> f32 {
+        match self {
+            MountType::Horse { .. } => 100.0,
+            MountType::Ox { endurance, .. } => 120.0 + *endurance as f32,
+            MountType::ExoticMount { .. } => 90.0,
+            MountType::CorruptedMount { original_type, .. } => original_type.get_base_stamina() * 1.2,
+        }
+    }
+    
+    /// Price in copper pieces at a stable
+    pub fn get_base_price(&self) -> u32 {
+        match self {
+            MountType::Horse { .. } => 7500,
+            MountType::Ox { .. } => 1500,
+            MountType::ExoticMount { .. } => 40000,
+            MountType::CorruptedMount { original_type, corruption_level } => {
+                (original_type.get_base_price() as f32 * (1.0 - corruption_level * 0.5)).max(100.0) as u32
+            }
+        }
+    }
+    
+    /// Fraction of corruption fear ignored (0.0 = none, 1.0 = fearless)
+    pub fn get_fear_resistance(&self) -> f32 {
+        match self {
+            MountType::Horse { .. } => 0.0,
+            MountType::Ox { .. } => 0.2,
+            MountType::ExoticMount { .. } => 0.4,
+            MountType::CorruptedMount { corruption_level, .. } => (0.5 + corruption_level * 0.5).min(1.0),
+        }
+    }
+    
     pub fn get_terrain_penalties(&self) -> std::collections::HashMap<String, f32> {
         let mut penalties = std::collections::HashMap::new();
         
@@ -127,7 +257,47 @@ pub enum ItemType {
     },
 }
 
-#[derive(Component, Debug)]
+impl Item {
+    /// Consumables, currency and materials stack; so do quest items unless unique
+    pub fn is_stackable(&self) -> bool {
+        match &self.item_type {
+            ItemType::Consumable { .. } | ItemType::Currency { .. } | ItemType::Material { .. } => true,
+            ItemType::QuestItem { unique, .. } => !unique,
+            ItemType::Weapon { .. } | ItemType::Armor { .. } | ItemType::Tool { .. } => false,
+        }
+    }
+
+    pub fn can_stack_with(&self, other: &Item) -> bool {
+        self.is_stackable() && self.name == other.name && self.item_type == other.item_type
+    }
+
+    /// Stack of coins; `value` is per coin, in copper pieces
+    pub fn coins(currency_type: &str, quantity: u32) -> Self {
+        let (name, value) = match currency_type {
+            "pp" => ("Platinum Pieces", 1000),
+            "gp" => ("Gold Pieces", 100),
+            "sp" => ("Silver Pieces", 10),
+            _ => ("Copper Pieces", 1),
+        };
+        Self {
+            name: name.to_string(),
+            item_type: ItemType::Currency {
+                currency_type: currency_type.to_string(),
+            },
+            quantity,
+            weight: 0.02,
+            value,
+            description: String::new(),
+        }
+    }
+
+    /// Quest items cannot be dropped or sold until their quest releases them
+    pub fn is_quest_locked(&self) -> bool {
+        matches!(self.item_type, ItemType::QuestItem { .. })
+    }
+}
+
+#[derive(Component, Debug, Clone)]
 pub struct Inventory {
     pub items: Vec<Item>,
     pub capacity: u32,
@@ -146,18 +316,23 @@ impl Inventory {
     }
     
     pub fn can_add_item(&self, item: &Item) -> bool {
-        self.items.len() < self.capacity as usize 
+        let needs_slot = !self.items.iter().any(|existing| existing.can_stack_with(item));
+        (!needs_slot || self.items.len() < self.capacity as usize)
             && (self.current_weight + item.weight * item.quantity as f32) <= self.max_weight
     }
     
     pub fn add_item(&mut self, item: Item) -> bool {
-        if self.can_add_item(&item) {
-            self.current_weight += item.weight * item.quantity as f32;
-            self.items.push(item);
-            true
+        if !self.can_add_item(&item) {
+            return false;
+        }
+
+        self.current_weight += item.weight * item.quantity as f32;
+        if let Some(stack) = self.items.iter_mut().find(|existing| existing.can_stack_with(&item)) {
+            stack.quantity += item.quantity;
         } else {
-            false
+            self.items.push(item);
         }
+        true
     }
     
     pub fn remove_item(&mut self, item_name: &str, quantity: u32) -> Option<Item> {
@@ -168,7 +343,10 @@ impl Inventory {
                 self.current_weight -= item.weight * quantity as f32;
                 
                 if item.quantity == 0 {
-                    Some(self.items.remove(index))
+                    Some(Item {
+                        quantity,
+                        ..self.items.remove(index)
+                    })
                 } else {
                     Some(Item {
                         quantity,
@@ -182,4 +360,231 @@ impl Inventory {
             None
         }
     }
+
+    /// Drop or sell an item. Quest-locked items are refused.
+    pub fn discard_item(&mut self, item_name: &str, quantity: u32) -> Option<Item> {
+        let item = self.items.iter().find(|item| item.name == item_name)?;
+        if item.is_quest_locked() {
+            return None;
+        }
+        self.remove_item(item_name, quantity)
+    }
+
+    /// Remove every quest item tied to a finished quest
+    pub fn release_quest_items(&mut self, quest_id: &str) -> Vec<Item> {
+        let (released, kept): (Vec<Item>, Vec<Item>) = self.items.drain(..).partition(|item| {
+            matches!(&item.item_type, ItemType::QuestItem { quest_id: id, .. } if id == quest_id)
+        });
+        self.items = kept;
+        self.current_weight -= released.iter().map(|item| item.weight * item.quantity as f32).sum::<f32>();
+        released
+    }
+
+    /// Total worth of carried coins, in copper pieces
+    pub fn currency_total(&self) -> u32 {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.item_type, ItemType::Currency { .. }))
+            .map(|item| item.value * item.quantity)
+            .sum()
+    }
+
+    /// Pay a price in copper pieces. Coins are pooled and change is returned
+    /// in the fewest standard coins. Returns false if the purse is too light
+    /// or the change would not fit in the inventory; nothing is paid then.
+    pub fn spend_currency(&mut self, cost: u32) -> bool {
+        let total = self.currency_total();
+        if total < cost {
+            return false;
+        }
+
+        let mut change = total - cost;
+        let mut change_coins = Vec::new();
+        for (currency_type, value) in [("pp", 1000), ("gp", 100), ("sp", 10), ("cp", 1)] {
+            let count = change / value;
+            if count > 0 {
+                change_coins.push(Item::coins(currency_type, count));
+                change %= value;
+            }
+        }
+
+        let is_currency = |item: &Item| matches!(item.item_type, ItemType::Currency { .. });
+        let kept_slots = self.items.iter().filter(|item| !is_currency(item)).count();
+        let paid_weight: f32 = self
+            .items
+            .iter()
+            .filter(|item| is_currency(item))
+            .map(|item| item.weight * item.quantity as f32)
+            .sum();
+        let change_weight: f32 = change_coins.iter().map(|item| item.weight * item.quantity as f32).sum();
+        if kept_slots + change_coins.len() > self.capacity as usize
+            || self.current_weight - paid_weight + change_weight > self.max_weight
+        {
+            return false;
+        }
+
+        self.items.retain(|item| !is_currency(item));
+        self.current_weight += change_weight - paid_weight;
+        self.items.extend(change_coins);
+        true
+    }
+
+    pub fn count_item(&self, item_name: &str) -> u32 {
+        self.items
+            .iter()
+            .filter(|item| item.name == item_name)
+            .map(|item| item.quantity)
+            .sum()
+    }
+
+    pub fn has_item(&self, item_name: &str) -> bool {
+        self.count_item(item_name) > 0
+    }
+
+    /// Take one of the first consumable with the given effect, e.g. a ration
+    /// for `ItemEffect::Nourish`
+    pub fn consume_by_effect(&mut self, effect: &ItemEffect) -> Option<Item> {
+        let name = self
+            .items
+            .iter()
+            .find(|item| matches!(&item.item_type, ItemType::Consumable { effect: e, .. } if ItemEffect::from_name(e) == *effect))?
+            .name
+            .clone();
+        self.remove_item(&name, 1)
+    }
+
+    /// Wear down the first tool of the given type. Returns the remaining
+    /// durability, or `None` if no such tool is carried. A tool that reaches
+    /// zero durability breaks and is removed.
+    pub fn wear_tool(&mut self, tool_type: &str, wear: u32) -> Option<u32> {
+        let index = self.items.iter().position(|item| {
+            matches!(&item.item_type, ItemType::Tool { tool_type: t, durability, .. } if t == tool_type && *durability > 0)
+        })?;
+
+        let ItemType::Tool { durability, .. } = &mut self.items[index].item_type else {
+            return None;
+        };
+        *durability = durability.saturating_sub(wear);
+        let remaining = *durability;
+
+        if remaining == 0 {
+            let broken = self.items.remove(index);
+            self.current_weight -= broken.weight * broken.quantity as f32;
+        }
+        Some(remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn horse() -> Mount {
+        Mount::new(
+            "Ash".to_string(),
+            MountType::Horse {
+                breed: "courser".to_string(),
+                temperament: "steady".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_mount_terrain_and_exhaustion() {
+        let mut mount = horse();
+        assert!(mount.travel_multiplier(&BiomeType::Grassland) > 1.0);
+        assert!(mount.travel_multiplier(&BiomeType::Swamp) < 1.0);
+
+        mount.spend_stamina(mount.max_stamina);
+        assert!(mount.is_exhausted());
+        assert_eq!(mount.travel_multiplier(&BiomeType::Grassland), 1.0);
+
+        mount.recover(2.0);
+        assert!(!mount.is_exhausted());
+    }
+
+    #[test]
+    fn test_mount_fear_in_void() {
+        let mut mount = horse();
+        for _ in 0..4 {
+            let change = mount.fear_change(&BiomeType::VoidGrassland);
+            mount.add_fear(change);
+        }
+        assert!(mount.will_bolt());
+        assert_eq!(mount.panic_chance(), 1.0);
+
+        let mut corrupted = Mount::new(
+            "Hollow".to_string(),
+            MountType::CorruptedMount {
+                original_type: Box::new(horse().mount_type),
+                corruption_level: 1.0,
+            },
+        );
+        corrupted.add_fear(corrupted.fear_change(&BiomeType::Void));
+        assert_eq!(corrupted.fear, 0.0);
+    }
+
+    #[test]
+    fn test_spend_currency_makes_change() {
+        let mut inventory = Inventory::new(10, 100.0);
+        inventory.add_item(Item::coins("gp", 3));
+        inventory.add_item(Item::coins("cp", 5));
+
+        assert!(!inventory.spend_currency(1000));
+        assert!(inventory.spend_currency(150));
+        assert_eq!(inventory.currency_total(), 155);
+        assert_eq!(inventory.count_item("Gold Pieces"), 1);
+        assert_eq!(inventory.count_item("Silver Pieces"), 5);
+        assert_eq!(inventory.count_item("Copper Pieces"), 5);
+    }
+
+    #[test]
+    fn test_spend_currency_refuses_change_that_does_not_fit() {
+        let mut inventory = Inventory::new(3, 100.0);
+        inventory.add_item(Item::coins("gp", 1));
+        for name in ["Rope", "Torch"] {
+            inventory.add_item(Item {
+                name: name.to_string(),
+                item_type: ItemType::Material {
+                    material_type: "trinket".to_string(),
+                    rarity: "common".to_string(),
+                },
+                quantity: 1,
+                weight: 1.0,
+                value: 10,
+                description: String::new(),
+            });
+        }
+
+        // 99 cp of change needs a silver and a copper stack: one slot too many
+        assert!(!inventory.spend_currency(1));
+        assert_eq!(inventory.count_item("Gold Pieces"), 1);
+        assert!(inventory.items.len() <= inventory.capacity as usize);
+
+        assert!(inventory.spend_currency(100));
+        assert_eq!(inventory.currency_total(), 0);
+        assert_eq!(inventory.items.len(), 2);
+    }
+
+    #[test]
+    fn test_consume_by_effect() {
+        let mut inventory = Inventory::new(10, 100.0);
+        inventory.add_item(Item {
+            name: "Trail Rations".to_string(),
+            item_type: ItemType::Consumable {
+                effect: "food".to_string(),
+                duration: 0.0,
+                potency: 30,
+            },
+            quantity: 2,
+            weight: 1.0,
+            value: 50,
+            description: String::new(),
+        });
+
+        assert!(inventory.consume_by_effect(&ItemEffect::Hydrate).is_none());
+        let ration = inventory.consume_by_effect(&ItemEffect::Nourish).unwrap();
+        assert_eq!(ration.quantity, 1);
+        assert_eq!(inventory.count_item("Trail Rations"), 1);
+    }
 }
@@ -0,0 +1,493 @@
+//! Quest definitions and the quest log that runs them
+//!
+//! A quest is a list of steps, each with one objective, followed by
+//! branching outcomes. Steps complete in order as their objectives are met
+//! against the live world; when the last step is done the first outcome
+//! whose condition holds is applied. A step's `fail_if` trigger, or running
+//! out of days, ends the quest with its failure outcome instead.
+//!
+//! The log never touches the world itself: it reads it through
+//! [`QuestWorld`] and returns [`QuestUpdate`]s for the game to apply.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::world::alignment::{Alignment, ConsequenceEffect};
+use crate::world::hex::HexCoord;
+
+/// What the world looks like to the quest log this frame
+pub trait QuestWorld {
+    fn player_hex(&self) -> Option<HexCoord>;
+    fn story_flag(&self, flag: &str) -> bool;
+    fn dread(&self) -> f32;
+    fn day(&self) -> u32;
+    fn dungeon_cleared(&self, dungeon_uuid: &str) -> bool;
+    fn reputation(&self, faction: &str) -> i32;
+    /// The player spoke with this NPC since the last update
+    fn talked_to(&self, npc_uuid: &str) -> bool;
+    fn carries_quest_item(&self, quest_id: &str, item_name: &str) -> bool;
+}
+
+/// A fixed world for running quests outside the game, such as checking
+/// generated quests in tooling and tests
+#[derive(Debug, Clone, Default)]
+pub struct QuestWorldSnapshot {
+    pub hex: Option<HexCoord>,
+    pub flags: HashMap<String, bool>,
+    pub dread: f32,
+    pub day: u32,
+    pub cleared_dungeons: Vec<String>,
+    pub talked_to: Vec<String>,
+    /// (quest id, item name) pairs the player carries
+    pub items: Vec<(String, String)>,
+}
+
+impl QuestWorld for QuestWorldSnapshot {
+    fn player_hex(&self) -> Option<HexCoord> {
+        self.hex
+    }
+    fn story_flag(&self, flag: &str) -> bool {
+        self.flags.get(flag).copied().unwrap_or(false)
+    }
+    fn dread(&self) -> f32 {
+        self.dread
+    }
+    fn day(&self) -> u32 {
+        self.day
+    }
+    fn dungeon_cleared(&self, dungeon_uuid: &str) -> bool {
+        self.cleared_dungeons.iter().any(|dungeon| dungeon == dungeon_uuid)
+    }
+    fn reputation(&self, _faction: &str) -> i32 {
+        0
+    }
+    fn talked_to(&self, npc_uuid: &str) -> bool {
+        self.talked_to.iter().any(|npc| npc == npc_uuid)
+    }
+    fn carries_quest_item(&self, quest_id: &str, item_name: &str) -> bool {
+        self.items.iter().any(|(quest, item)| quest == quest_id && item == item_name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QuestObjective {
+    ReachHex { hex: HexCoord },
+    TalkTo { npc_uuid: String },
+    ClearDungeon { dungeon_uuid: String },
+    /// Hand this quest's item to an NPC by talking to them while carrying it
+    DeliverItem { item_name: String, npc_uuid: String },
+    /// Completed by dialogue or scripted scenes setting the flag
+    StoryFlag { flag: String },
+}
+
+/// A condition on world state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QuestTrigger {
+    StoryFlag { flag: String, value: bool },
+    DreadAtLeast(f32),
+    DreadBelow(f32),
+    PlayerAt(HexCoord),
+    ReputationAtLeast { faction: String, amount: i32 },
+    ReputationBelow { faction: String, amount: i32 },
+    DayAtLeast(u32),
+    All(Vec<QuestTrigger>),
+    Any(Vec<QuestTrigger>),
+}
+
+impl QuestTrigger {
+    pub fn holds(&self, world: &impl QuestWorld) -> bool {
+        match self {
+            QuestTrigger::StoryFlag { flag, value } => world.story_flag(flag) == *value,
+            QuestTrigger::DreadAtLeast(dread) => world.dread() >= *dread,
+            QuestTrigger::DreadBelow(dread) => world.dread() < *dread,
+            QuestTrigger::PlayerAt(hex) => world.player_hex() == Some(*hex),
+            QuestTrigger::ReputationAtLeast { faction, amount } => world.reputation(faction) >= *amount,
+            QuestTrigger::ReputationBelow { faction, amount } => world.reputation(faction) < *amount,
+            QuestTrigger::DayAtLeast(day) => world.day() >= *day,
+            QuestTrigger::All(triggers) => triggers.iter().all(|trigger| trigger.holds(world)),
+            QuestTrigger::Any(triggers) => triggers.iter().any(|trigger| trigger.holds(world)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestStep {
+    pub description: String,
+    pub objective: QuestObjective,
+    #[serde(default)]
+    pub fail_if: Option<QuestTrigger>,
+}
+
+impl QuestStep {
+    fn is_met(&self, quest_id: &str, world: &impl QuestWorld) -> bool {
+        match &self.objective {
+            QuestObjective::ReachHex { hex } => world.player_hex() == Some(*hex),
+            QuestObjective::TalkTo { npc_uuid } => world.talked_to(npc_uuid),
+            QuestObjective::ClearDungeon { dungeon_uuid } => world.dungeon_cleared(dungeon_uuid),
+            QuestObjective::DeliverItem { item_name, npc_uuid } => {
+                world.talked_to(npc_uuid) && world.carries_quest_item(quest_id, item_name)
+            }
+            QuestObjective::StoryFlag { flag } => world.story_flag(flag),
+        }
+    }
+}
+
+/// One way a quest can end
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct QuestOutcome {
+    pub id: String,
+    pub description: String,
+    /// Branch is taken only if this holds; `None` always matches
+    #[serde(default)]
+    pub condition: Option<QuestTrigger>,
+    #[serde(default)]
+    pub set_flags: HashMap<String, bool>,
+    /// Positive adds dread, negative relieves it
+    #[serde(default)]
+    pub dread_change: f32,
+    /// Reputation change keyed by faction UUID
+    #[serde(default)]
+    pub reputation: HashMap<String, i32>,
+    /// Quest offered as soon as this outcome is applied
+    #[serde(default)]
+    pub unlocks: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestDefinition {
+    pub id: String,
+    pub title: String,
+    pub summary: String,
+    /// Quests with a start trigger begin on their own once it holds;
+    /// the rest must be offered
+    #[serde(default)]
+    pub start_trigger: Option<QuestTrigger>,
+    pub steps: Vec<QuestStep>,
+    /// Success branches, checked in order
+    pub outcomes: Vec<QuestOutcome>,
+    pub failure: QuestOutcome,
+    #[serde(default)]
+    pub time_limit_days: Option<u32>,
+}
+
+/// Every quest the game knows, keyed by quest id
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuestDatabase {
+    pub quests: HashMap<String, QuestDefinition>,
+}
+
+impl QuestDatabase {
+    pub fn insert(&mut self, quest: QuestDefinition) {
+        self.quests.insert(quest.id.clone(), quest);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&QuestDefinition> {
+        self.quests.get(id)
+    }
+}
+
+/// Flag set once the player has settled a quest's dilemma
+pub fn dilemma_resolved_flag(quest_id: &str) -> String {
+    format!("{}_resolved", quest_id)
+}
+
+/// Flag set alongside the resolved flag when the player chose mercy
+pub fn dilemma_mercy_flag(quest_id: &str) -> String {
+    format!("{}_mercy", quest_id)
+}
+
+/// Story flags set when the player settles a dilemma
+pub fn dilemma_flags(quest_id: &str, mercy: bool) -> HashMap<String, bool> {
+    HashMap::from([(dilemma_resolved_flag(quest_id), true), (dilemma_mercy_flag(quest_id), mercy)])
+}
+
+/// How settling a dilemma moves the player: mercy toward the light, sacrifice
+/// toward cruelty and the void
+pub fn dilemma_alignment(mercy: bool) -> Alignment {
+    if mercy {
+        Alignment {
+            mercy: 10.0,
+            light: 5.0,
+            loyalty: 0.0,
+        }
+    } else {
+        Alignment {
+            mercy: -10.0,
+            light: -5.0,
+            loyalty: 0.0,
+        }
+    }
+}
+
+/// What settling a dilemma does at once: companions warm to mercy, and a
+/// sacrifice weighs on the mind
+pub fn dilemma_effects(mercy: bool) -> Vec<ConsequenceEffect> {
+    if mercy {
+        vec![ConsequenceEffect::CompanionTrust {
+            companion: None,
+            amount: 5.0,
+        }]
+    } else {
+        vec![ConsequenceEffect::Dread { amount: 5.0 }]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveQuest {
+    pub quest_id: String,
+    pub step: usize,
+    pub started_day: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuestUpdate {
+    Started { quest_id: String },
+    StepCompleted { quest_id: String, step: usize, objective: QuestObjective },
+    Completed { quest_id: String, outcome: QuestOutcome },
+    Failed { quest_id: String, outcome: QuestOutcome },
+}
+
+/// The player's quests: in progress, and how finished ones ended
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuestLog {
+    pub active: Vec<ActiveQuest>,
+    /// Outcome id each completed quest ended with
+    pub completed: HashMap<String, String>,
+    pub failed: Vec<String>,
+}
+
+impl QuestLog {
+    pub fn is_active(&self, quest_id: &str) -> bool {
+        self.active.iter().any(|quest| quest.quest_id == quest_id)
+    }
+
+    pub fn is_finished(&self, quest_id: &str) -> bool {
+        self.completed.contains_key(quest_id) || self.failed.iter().any(|id| id == quest_id)
+    }
+
+    /// Take on a quest; refused if it is unknown, already taken or finished
+    pub fn start(&mut self, database: &QuestDatabase, quest_id: &str, day: u32) -> bool {
+        if database.get(quest_id).is_none() || self.is_active(quest_id) || self.is_finished(quest_id) {
+            return false;
+        }
+        self.active.push(ActiveQuest {
+            quest_id: quest_id.to_string(),
+            step: 0,
+            started_day: day,
+        });
+        true
+    }
+
+    /// Current step of an active quest
+    pub fn current_step<'a>(&self, database: &'a QuestDatabase, quest_id: &str) -> Option<&'a QuestStep> {
+        let active = self.active.iter().find(|quest| quest.quest_id == quest_id)?;
+        database.get(quest_id)?.steps.get(active.step)
+    }
+
+    /// The quest is waiting on the player to settle its dilemma
+    pub fn awaits_dilemma(&self, database: &QuestDatabase, quest_id: &str) -> bool {
+        self.current_step(database, quest_id).is_some_and(|step| {
+            matches!(&step.objective, QuestObjective::StoryFlag { flag } if *flag == dilemma_resolved_flag(quest_id))
+        })
+    }
+
+    /// Start triggered quests, then advance every active quest as far as
+    /// the world allows
+    pub fn update(&mut self, database: &QuestDatabase, world: &impl QuestWorld) -> Vec<QuestUpdate> {
+        let mut updates = Vec::new();
+
+        let mut triggered: Vec<&QuestDefinition> = database
+            .quests
+            .values()
+            .filter(|quest| quest.start_trigger.as_ref().is_some_and(|trigger| trigger.holds(world)))
+            .collect();
+        triggered.sort_by(|a, b| a.id.cmp(&b.id));
+        for quest in triggered {
+            if self.start(database, &quest.id, world.day()) {
+                updates.push(QuestUpdate::Started {
+                    quest_id: quest.id.clone(),
+                });
+            }
+        }
+
+        let mut finished = Vec::new();
+        for active in self.active.iter_mut() {
+            let Some(quest) = database.get(&active.quest_id) else {
+                continue;
+            };
+            if let Some(update) = Self::advance(active, quest, world, &mut updates) {
+                finished.push(update);
+            }
+        }
+
+        for update in finished {
+            let quest_id = match &update {
+                QuestUpdate::Completed { quest_id, outcome } => {
+                    self.completed.insert(quest_id.clone(), outcome.id.clone());
+                    quest_id
+                }
+                QuestUpdate::Failed { quest_id, .. } => {
+                    self.failed.push(quest_id.clone());
+                    quest_id
+                }
+                _ => continue,
+            };
+            self.active.retain(|quest| &quest.quest_id != quest_id);
+            updates.push(update);
+        }
+        updates
+    }
+
+    /// Complete as many steps as are met; returns the final update if the
+    /// quest ended
+    fn advance(
+        active: &mut ActiveQuest,
+        quest: &QuestDefinition,
+        world: &impl QuestWorld,
+        updates: &mut Vec<QuestUpdate>,
+    ) -> Option<QuestUpdate> {
+        let failure = || QuestUpdate::Failed {
+            quest_id: quest.id.clone(),
+            outcome: quest.failure.clone(),
+        };
+        let out_of_time = quest
+            .time_limit_days
+            .is_some_and(|limit| world.day().saturating_sub(active.started_day) > limit);
+        if out_of_time {
+            return Some(failure());
+        }
+
+        while let Some(step) = quest.steps.get(active.step) {
+            if step.fail_if.as_ref().is_some_and(|trigger| trigger.holds(world)) {
+                return Some(failure());
+            }
+            if !step.is_met(&quest.id, world) {
+                return None;
+            }
+            updates.push(QuestUpdate::StepCompleted {
+                quest_id: quest.id.clone(),
+                step: active.step,
+                objective: step.objective.clone(),
+            });
+            active.step += 1;
+        }
+
+        let outcome = quest
+            .outcomes
+            .iter()
+            .find(|outcome| outcome.condition.as_ref().is_none_or(|condition| condition.holds(world)));
+        Some(match outcome {
+            Some(outcome) => QuestUpdate::Completed {
+                quest_id: quest.id.clone(),
+                outcome: outcome.clone(),
+            },
+            // No branch fits the world as it stands: the quest was lost
+            None => failure(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(id: &str, condition: Option<QuestTrigger>) -> QuestOutcome {
+        QuestOutcome {
+            id: id.to_string(),
+            condition,
+            set_flags: HashMap::from([(format!("{}_done", id), true)]),
+            ..Default::default()
+        }
+    }
+
+    fn database() -> QuestDatabase {
+        let mut database = QuestDatabase::default();
+        database.insert(QuestDefinition {
+            id: "relic".to_string(),
+            title: "The Relic".to_string(),
+            summary: String::new(),
+            start_trigger: Some(QuestTrigger::PlayerAt(HexCoord::new(0, 0))),
+            steps: vec![
+                QuestStep {
+                    description: "Reach the shrine".to_string(),
+                    objective: QuestObjective::ReachHex { hex: HexCoord::new(3, 1) },
+                    fail_if: Some(QuestTrigger::DreadAtLeast(80.0)),
+                },
+                QuestStep {
+                    description: "Bring the relic to the priest".to_string(),
+                    objective: QuestObjective::DeliverItem {
+                        item_name: "Relic".to_string(),
+                        npc_uuid: "priest".to_string(),
+                    },
+                    fail_if: None,
+                },
+            ],
+            outcomes: vec![
+                outcome("merciful", Some(QuestTrigger::StoryFlag { flag: "spared".to_string(), value: true })),
+                outcome("ruthless", None),
+            ],
+            failure: outcome("lost", None),
+            time_limit_days: Some(5),
+        });
+        database
+    }
+
+    #[test]
+    fn test_quest_runs_steps_and_branches() {
+        let database = database();
+        let mut log = QuestLog::default();
+        let mut world = QuestWorldSnapshot {
+            hex: Some(HexCoord::new(0, 0)),
+            ..Default::default()
+        };
+
+        let updates = log.update(&database, &world);
+        assert_eq!(updates, vec![QuestUpdate::Started { quest_id: "relic".to_string() }]);
+        assert!(log.update(&database, &world).is_empty());
+
+        world.hex = Some(HexCoord::new(3, 1));
+        let updates = log.update(&database, &world);
+        assert!(matches!(&updates[0], QuestUpdate::StepCompleted { step: 0, .. }));
+        assert_eq!(log.current_step(&database, "relic").unwrap().description, "Bring the relic to the priest");
+
+        // Talking to the priest empty-handed is not a delivery
+        world.talked_to.push("priest".to_string());
+        assert!(log.update(&database, &world).is_empty());
+
+        world.items.push(("relic".to_string(), "Relic".to_string()));
+        world.flags.insert("spared".to_string(), true);
+        let updates = log.update(&database, &world);
+        assert!(matches!(&updates[1], QuestUpdate::Completed { outcome, .. } if outcome.id == "merciful"));
+        assert_eq!(log.completed.get("relic").map(String::as_str), Some("merciful"));
+        assert!(!log.is_active("relic"));
+
+        // Finished quests are never restarted by their trigger
+        world.hex = Some(HexCoord::new(0, 0));
+        assert!(log.update(&database, &world).is_empty());
+    }
+
+    #[test]
+    fn test_quest_fails_on_trigger_or_timeout() {
+        let database = database();
+        let mut log = QuestLog::default();
+        let mut world = QuestWorldSnapshot {
+            hex: Some(HexCoord::new(0, 0)),
+            ..Default::default()
+        };
+        log.update(&database, &world);
+
+        world.dread = 90.0;
+        let updates = log.update(&database, &world);
+        assert!(matches!(&updates[0], QuestUpdate::Failed { outcome, .. } if outcome.id == "lost"));
+        assert_eq!(log.failed, vec!["relic".to_string()]);
+
+        let mut log = QuestLog::default();
+        world.dread = 0.0;
+        log.start(&database, "relic", 1);
+        world.day = 6;
+        assert!(log.update(&database, &world).is_empty());
+        world.day = 7;
+        assert!(matches!(&log.update(&database, &world)[0], QuestUpdate::Failed { .. }));
+    }
+}
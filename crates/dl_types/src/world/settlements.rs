@@ -0,0 +1,303 @@
+//! Settlement types built from HBF settlement pages
+//!
+//! HBF describes a settlement as a set of establishment pages ("Blacksmith in
+//! Dokar", "\"The King's Torch Lodge\" from Dokar"). Each establishment maps to
+//! a `ServiceType`; a `Settlement` is the runtime hub those services belong
+//! to. Corruption rises over time and closes services one by one, until the
+//! worst of them turn hostile.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Corruption is tracked on a 0-100 scale
+pub const MAX_SETTLEMENT_CORRUPTION: f32 = 100.0;
+/// How far past its closing threshold a service turns hostile
+const HOSTILE_MARGIN: f32 = 25.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SettlementScale {
+    Village,
+    Town,
+    City,
+    Metropolis,
+}
+
+impl SettlementScale {
+    pub fn from_population(population: u32) -> Self {
+        match population {
+            0..=999 => SettlementScale::Village,
+            1000..=9999 => SettlementScale::Town,
+            10000..=49999 => SettlementScale::City,
+            _ => SettlementScale::Metropolis,
+        }
+    }
+
+    /// Larger settlements charge more and hold out longer
+    pub fn price_multiplier(&self) -> f32 {
+        match self {
+            SettlementScale::Village => 1.0,
+            SettlementScale::Town => 1.25,
+            SettlementScale::City => 1.5,
+            SettlementScale::Metropolis => 2.0,
+        }
+    }
+
+    pub fn base_resistance(&self) -> u32 {
+        match self {
+            SettlementScale::Village => 6,
+            SettlementScale::Town => 7,
+            SettlementScale::City => 8,
+            SettlementScale::Metropolis => 9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ServiceType {
+    Commerce,
+    Lodging,
+    Crafting,
+    Medical,
+    Religious,
+    Defense,
+    Government,
+    Learning,
+}
+
+impl ServiceType {
+    /// Service offered by an HBF establishment kind ("Blacksmith",
+    /// "Indoor Market", "Tavern", ...). Keywords and phrases match whole
+    /// words, so "Spinner" is not an inn and "Watchmaker" is not a watch.
+    pub fn for_establishment(kind: &str) -> Option<Self> {
+        let kind = kind.to_lowercase();
+        let words: Vec<&str> = kind
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let has = |phrases: &[&str]| {
+            phrases.iter().any(|phrase| {
+                let phrase: Vec<&str> = phrase.split_whitespace().collect();
+                words.windows(phrase.len()).any(|window| window == phrase.as_slice())
+            })
+        };
+
+        if has(&["tavern", "inn", "lodge", "bunkhouse"]) {
+            Some(ServiceType::Lodging)
+        } else if has(&["physician", "herbalist", "barber", "apothecary", "healer"]) {
+            Some(ServiceType::Medical)
+        } else if has(&["temple", "shrine", "cathedral", "chapel", "church", "monastery"]) {
+            Some(ServiceType::Religious)
+        } else if has(&["prison", "barracks", "guard", "garrison", "watch"]) {
+            Some(ServiceType::Defense)
+        } else if has(&["registry", "post office", "town hall", "court"]) {
+            Some(ServiceType::Government)
+        } else if has(&["library", "school", "scribe", "printing", "cartographer", "bookstore"]) {
+            Some(ServiceType::Learning)
+        } else if has(&[
+            "blacksmith", "craft", "worker", "tanner", "carpenter", "tinkerer", "cobbler", "glass blower",
+            "vehicle", "leatherworker", "tailor", "weaver", "shipwright",
+        ]) {
+            Some(ServiceType::Crafting)
+        } else if has(&[
+            "goods", "market", "trade post", "grocer", "spices", "bakery", "butchery", "liquor", "clothing",
+            "hatter", "jeweler", "shop", "supplies", "armor", "smokehouse", "winery", "brewery", "distillery",
+            "stables", "bank",
+        ]) {
+            Some(ServiceType::Commerce)
+        } else {
+            None
+        }
+    }
+
+    /// Corruption at which the service shuts its doors. Healers flee first;
+    /// temples hold out longest and are the worst when they fall.
+    pub fn closing_threshold(&self) -> f32 {
+        match self {
+            ServiceType::Medical => 35.0,
+            ServiceType::Learning => 40.0,
+            ServiceType::Commerce => 45.0,
+            ServiceType::Lodging => 50.0,
+            ServiceType::Crafting => 55.0,
+            ServiceType::Government => 55.0,
+            ServiceType::Defense => 60.0,
+            ServiceType::Religious => 65.0,
+        }
+    }
+
+    /// Base price in copper for one use of the service in a village
+    pub fn base_price(&self) -> u32 {
+        match self {
+            ServiceType::Lodging => 50,
+            ServiceType::Medical => 200,
+            ServiceType::Religious => 300,
+            ServiceType::Crafting => 20, // Per point of durability
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceStatus {
+    Open,
+    Closed,
+    Hostile,
+}
+
+/// One HBF establishment page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Establishment {
+    pub uuid: String,
+    pub name: String,
+    pub kind: String,
+    pub service: Option<ServiceType>,
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Settlement {
+    pub uuid: String,
+    pub name: String,
+    /// HBF hex page the settlement sits on, when known
+    pub hex_uuid: Option<String>,
+    pub scale: SettlementScale,
+    pub population: u32,
+    pub economic_activity: u32,
+    pub corruption_resistance: u32, // 0-10
+    pub service_types: Vec<ServiceType>,
+    pub npc_count: u32,
+    pub establishment_count: u32,
+    pub establishments: Vec<Establishment>,
+    pub corruption: f32, // 0.0 = untouched, MAX_SETTLEMENT_CORRUPTION = fallen
+}
+
+impl Settlement {
+    pub fn has_service(&self, service: ServiceType) -> bool {
+        self.service_types.contains(&service)
+    }
+
+    /// Stronger resistance pushes every threshold up by two points per rank
+    pub fn service_status(&self, service: ServiceType) -> Option<ServiceStatus> {
+        if !self.has_service(service) {
+            return None;
+        }
+        let closing = service.closing_threshold() + self.corruption_resistance as f32 * 2.0;
+        Some(if self.corruption >= closing + HOSTILE_MARGIN {
+            ServiceStatus::Hostile
+        } else if self.corruption >= closing {
+            ServiceStatus::Closed
+        } else {
+            ServiceStatus::Open
+        })
+    }
+
+    pub fn is_open(&self, service: ServiceType) -> bool {
+        self.service_status(service) == Some(ServiceStatus::Open)
+    }
+
+    /// Raise corruption, returning services whose status changed
+    pub fn add_corruption(&mut self, amount: f32) -> Vec<(ServiceType, ServiceStatus)> {
+        let before: Vec<_> = self
+            .service_types
+            .iter()
+            .map(|service| (*service, self.service_status(*service)))
+            .collect();
+        self.corruption = (self.corruption + amount).clamp(0.0, MAX_SETTLEMENT_CORRUPTION);
+
+        before
+            .into_iter()
+            .filter_map(|(service, old)| {
+                let new = self.service_status(service)?;
+                (old != Some(new)).then_some((service, new))
+            })
+            .collect()
+    }
+
+    /// Corruption band 1-5, matching the bands item templates are tagged with
+    pub fn corruption_band(&self) -> u8 {
+        1 + (self.corruption / MAX_SETTLEMENT_CORRUPTION * 4.0).round() as u8
+    }
+
+    /// Price in copper for one use of a service; desperate times cost more
+    pub fn service_price(&self, service: ServiceType) -> u32 {
+        let corruption_markup = 1.0 + self.corruption / MAX_SETTLEMENT_CORRUPTION;
+        (service.base_price() as f32 * self.scale.price_multiplier() * corruption_markup).round() as u32
+    }
+}
+
+/// All settlements, keyed by HBF settlement UUID
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettlementDatabase {
+    pub settlements: HashMap<String, Settlement>,
+}
+
+impl SettlementDatabase {
+    pub fn insert(&mut self, settlement: Settlement) {
+        self.settlements.insert(settlement.uuid.clone(), settlement);
+    }
+
+    pub fn get(&self, uuid: &str) -> Option<&Settlement> {
+        self.settlements.get(uuid)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&Settlement> {
+        self.settlements
+            .values()
+            .find(|settlement| settlement.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn village(services: Vec<ServiceType>) -> Settlement {
+        Settlement {
+            uuid: "8FcnTf8q".to_string(),
+            name: "Village of Dokar".to_string(),
+            hex_uuid: None,
+            scale: SettlementScale::Village,
+            population: 465,
+            economic_activity: 64,
+            corruption_resistance: 5,
+            service_types: services,
+            npc_count: 15,
+            establishment_count: 3,
+            establishments: Vec::new(),
+            corruption: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_establishment_services() {
+        assert_eq!(ServiceType::for_establishment("Blacksmith"), Some(ServiceType::Crafting));
+        assert_eq!(ServiceType::for_establishment("Indoor Market"), Some(ServiceType::Commerce));
+        assert_eq!(ServiceType::for_establishment("\"The Lost Torch Inn\""), Some(ServiceType::Lodging));
+        assert_eq!(ServiceType::for_establishment("Herbalist"), Some(ServiceType::Medical));
+        assert_eq!(ServiceType::for_establishment("Village District (district)"), None);
+        assert_eq!(ServiceType::for_establishment("Post Office"), Some(ServiceType::Government));
+        assert_eq!(ServiceType::for_establishment("Tin Worker"), Some(ServiceType::Crafting));
+
+        // Keywords inside longer words do not count
+        assert_eq!(ServiceType::for_establishment("Spinner"), None);
+        assert_eq!(ServiceType::for_establishment("Dinner Hall"), None);
+        assert_eq!(ServiceType::for_establishment("Watchmaker"), None);
+        assert_eq!(ServiceType::for_establishment("Guardian Shrine"), Some(ServiceType::Religious));
+        assert_eq!(ServiceType::for_establishment("Office Supplies"), Some(ServiceType::Commerce));
+    }
+
+    #[test]
+    fn test_corruption_closes_then_turns_services() {
+        let mut settlement = village(vec![ServiceType::Medical, ServiceType::Religious]);
+        assert!(settlement.is_open(ServiceType::Medical));
+        assert_eq!(settlement.service_status(ServiceType::Commerce), None);
+
+        let changed = settlement.add_corruption(50.0);
+        assert_eq!(changed, vec![(ServiceType::Medical, ServiceStatus::Closed)]);
+        assert!(settlement.is_open(ServiceType::Religious));
+
+        settlement.add_corruption(50.0);
+        assert_eq!(settlement.service_status(ServiceType::Medical), Some(ServiceStatus::Hostile));
+        assert_eq!(settlement.service_status(ServiceType::Religious), Some(ServiceStatus::Hostile));
+        assert_eq!(settlement.corruption, MAX_SETTLEMENT_CORRUPTION);
+    }
+}
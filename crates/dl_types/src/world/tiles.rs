@@ -133,6 +133,55 @@ impl BiomeType {
         }
     }
     
+    pub fn is_void(&self) -> bool {
+        matches!(
+            self,
+            BiomeType::Void
+                | BiomeType::VoidGrassland
+                | BiomeType::VoidForest
+                | BiomeType::VoidMountain
+                | BiomeType::VoidDesert
+                | BiomeType::VoidSwamp
+                | BiomeType::VoidWater
+                | BiomeType::VoidSnow
+                | BiomeType::VoidLava
+        )
+    }
+    
+    pub fn is_corrupted(&self) -> bool {
+        matches!(
+            self,
+            BiomeType::CorruptedGrassland
+                | BiomeType::CorruptedForest
+                | BiomeType::CorruptedMountain
+                | BiomeType::CorruptedDesert
+                | BiomeType::CorruptedSwamp
+                | BiomeType::CorruptedWater
+                | BiomeType::CorruptedSnow
+        )
+    }
+    
+    /// Terrain keyword used by `MountType::get_terrain_penalties`
+    pub fn terrain_category(&self) -> &'static str {
+        if self.is_void() {
+            return "void";
+        }
+        if self.is_corrupted() {
+            return "corrupted";
+        }
+        match self {
+            BiomeType::Grassland | BiomeType::ForestGrassland => "grassland",
+            BiomeType::Forest | BiomeType::MountainForest => "forest",
+            BiomeType::Mountain | BiomeType::DesertMountain | BiomeType::SnowMountain => "mountain",
+            BiomeType::Desert => "desert",
+            BiomeType::Swamp | BiomeType::SwampWater => "swamp",
+            BiomeType::Water => "water",
+            BiomeType::Snow => "snow",
+            BiomeType::Lava => "lava",
+            _ => "grassland",
+        }
+    }
+    
     pub fn get_damage_per_turn(&self) -> f32 {
         match self {
             BiomeType::Desert => 1.0,
@@ -0,0 +1,343 @@
+//! The game clock and the weather at the player's hex
+//!
+//! Time only moves when the player travels, rests or waits; the clock rolls
+//! days over and restores the day's movement. Weather comes from fronts that
+//! drift across the hex map, the strongest one over a hex deciding its sky.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+use crate::world::dread::DreadPhase;
+use crate::world::hex::HexCoord;
+
+pub const DAWN_HOUR: f32 = 6.0;
+pub const DUSK_HOUR: f32 = 20.0;
+/// Fronts travelling further than this from the player are dropped
+const FRONT_DESPAWN_DISTANCE: f32 = 40.0;
+
+/// The authoritative game clock. Time only moves when the player travels,
+/// rests or waits.
+#[derive(Resource, Debug)]
+pub struct DayNightCycle {
+    pub current_hour: f32, // 0.0 = midnight, 12.0 = noon
+    pub day: u32,
+    pub day_length_hours: f32,
+    pub movement_points_remaining: u32,
+    pub max_daily_movement: u32,
+}
+
+impl Default for DayNightCycle {
+    fn default() -> Self {
+        Self {
+            current_hour: 8.0, // Start at 8 AM
+            day: 1,
+            day_length_hours: 24.0,
+            movement_points_remaining: 8, // 8 hex walking, 12 hex running
+            max_daily_movement: 8,
+        }
+    }
+}
+
+impl DayNightCycle {
+    /// Move the clock forward, returning how many days rolled over
+    pub fn advance(&mut self, hours: f32) -> u32 {
+        self.current_hour += hours.max(0.0);
+        let mut days_passed = 0;
+        while self.current_hour >= self.day_length_hours {
+            self.current_hour -= self.day_length_hours;
+            self.day += 1;
+            days_passed += 1;
+        }
+        if days_passed > 0 {
+            // New day - reset movement points
+            self.movement_points_remaining = self.max_daily_movement;
+        }
+        days_passed
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.current_hour < DAWN_HOUR || self.current_hour >= DUSK_HOUR
+    }
+
+    /// 0.0 at noon, 1.0 at midnight
+    pub fn darkness(&self) -> f32 {
+        (1.0 + (self.current_hour / self.day_length_hours * 2.0 * PI).cos()) / 2.0
+    }
+
+    /// Sun height above the horizon in radians; negative at night
+    pub fn sun_elevation(&self) -> f32 {
+        let daylight_hours = DUSK_HOUR - DAWN_HOUR;
+        let progress = (self.current_hour - DAWN_HOUR) / daylight_hours;
+        (progress * PI).sin() * (PI / 2.0)
+    }
+
+    /// Encounters are twice as likely in the dead of night
+    pub fn encounter_multiplier(&self) -> f32 {
+        if self.is_night() {
+            1.0 + self.darkness()
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeatherType {
+    Clear,
+    Rain,
+    Storm,
+    Snow,
+    Fog,
+    VoidStorm, // Supernatural weather in corrupted areas
+}
+
+impl WeatherType {
+    pub fn visibility(&self) -> f32 {
+        match self {
+            WeatherType::Clear => 1.0,
+            WeatherType::Rain => 0.8,
+            WeatherType::Storm => 0.6,
+            WeatherType::Snow => 0.7,
+            WeatherType::Fog => 0.3,
+            WeatherType::VoidStorm => 0.2,
+        }
+    }
+
+    /// How much a fully intense front of this weather slows travel
+    pub fn movement_factor(&self) -> f32 {
+        match self {
+            WeatherType::Clear => 0.0,
+            WeatherType::Fog => 0.3,
+            WeatherType::Rain => 0.5,
+            WeatherType::Storm => 1.0,
+            WeatherType::Snow => 1.2,
+            WeatherType::VoidStorm => 1.5,
+        }
+    }
+
+    pub fn encounter_multiplier(&self) -> f32 {
+        match self {
+            WeatherType::Storm | WeatherType::VoidStorm => 1.5,
+            WeatherType::Fog => 1.2,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Weather at the player's hex, derived from the active fronts
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct WeatherSystem {
+    pub current_weather: WeatherType,
+    pub intensity: f32, // 0.0 = mild, 1.0 = severe
+    pub temperature: f32, // -1.0 = freezing, 0.0 = mild, 1.0 = scorching
+    pub visibility: f32, // 0.0 = no visibility, 1.0 = clear
+}
+
+impl Default for WeatherSystem {
+    fn default() -> Self {
+        Self {
+            current_weather: WeatherType::Clear,
+            intensity: 0.2,
+            temperature: 0.0,
+            visibility: 1.0,
+        }
+    }
+}
+
+impl WeatherSystem {
+    /// Weather intensity as seen by `get_movement_cost`
+    pub fn movement_penalty(&self) -> f32 {
+        self.intensity * self.current_weather.movement_factor()
+    }
+}
+
+/// A band of weather drifting across the hex map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherFront {
+    pub weather: WeatherType,
+    /// Fractional axial position so fronts can move less than a hex per hour
+    pub center: (f32, f32),
+    /// Hexes per game hour in axial (q, r)
+    pub velocity: (f32, f32),
+    pub radius: f32,
+    pub intensity: f32,
+    pub temperature: f32,
+    pub remaining_hours: f32,
+}
+
+impl WeatherFront {
+    pub fn distance_to(&self, hex: HexCoord) -> f32 {
+        let dq = hex.q as f32 - self.center.0;
+        let dr = hex.r as f32 - self.center.1;
+        (dq.abs() + (dq + dr).abs() + dr.abs()) / 2.0
+    }
+
+    /// Full strength at the centre, fading to nothing at the edge
+    pub fn intensity_at(&self, hex: HexCoord) -> f32 {
+        let distance = self.distance_to(hex);
+        if distance > self.radius {
+            return 0.0;
+        }
+        self.intensity * (1.0 - distance / (self.radius + 1.0))
+    }
+
+    pub fn advance(&mut self, hours: f32) {
+        self.center.0 += self.velocity.0 * hours;
+        self.center.1 += self.velocity.1 * hours;
+        self.remaining_hours -= hours;
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct WeatherFronts {
+    pub fronts: Vec<WeatherFront>,
+    pub hours_until_next_front: f32,
+}
+
+impl WeatherFronts {
+    /// The strongest front covering a hex decides its weather
+    pub fn weather_at(&self, hex: HexCoord) -> WeatherSystem {
+        self.fronts
+            .iter()
+            .map(|front| (front, front.intensity_at(hex)))
+            .filter(|(_, intensity)| *intensity > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(front, intensity)| WeatherSystem {
+                current_weather: front.weather,
+                intensity,
+                temperature: front.temperature,
+                visibility: 1.0 - (1.0 - front.weather.visibility()) * (intensity / front.intensity.max(f32::EPSILON)),
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn advance(&mut self, hours: f32, player_hex: HexCoord) {
+        for front in self.fronts.iter_mut() {
+            front.advance(hours);
+        }
+        self.fronts
+            .retain(|front| front.remaining_hours > 0.0 && front.distance_to(player_hex) < FRONT_DESPAWN_DISTANCE);
+        self.hours_until_next_front -= hours;
+    }
+}
+
+/// Chance multiplier for an encounter roll under the current dread, time
+/// of day and weather
+pub fn encounter_rate(phase: &DreadPhase, day_night: &DayNightCycle, weather: &WeatherSystem) -> f32 {
+    phase.get_encounter_spawn_rate() * day_night.encounter_multiplier() * weather.current_weather.encounter_multiplier()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn front(weather: WeatherType, center: (f32, f32), velocity: (f32, f32)) -> WeatherFront {
+        WeatherFront {
+            weather,
+            center,
+            velocity,
+            radius: 4.0,
+            intensity: 0.8,
+            temperature: 0.0,
+            remaining_hours: 24.0,
+        }
+    }
+
+    #[test]
+    fn test_clock_rolls_days_over() {
+        let mut clock = DayNightCycle {
+            current_hour: 22.0,
+            movement_points_remaining: 0,
+            ..Default::default()
+        };
+        assert!(clock.is_night());
+        assert_eq!(clock.advance(1.5), 0);
+        assert_eq!(clock.movement_points_remaining, 0);
+
+        assert_eq!(clock.advance(1.5), 1);
+        assert_eq!(clock.day, 2);
+        assert_eq!(clock.current_hour, 1.0);
+        assert_eq!(clock.movement_points_remaining, clock.max_daily_movement);
+
+        // A long wait can pass several days at once, and time never runs back
+        assert_eq!(clock.advance(49.0), 2);
+        assert_eq!((clock.day, clock.current_hour), (4, 2.0));
+        assert_eq!(clock.advance(-5.0), 0);
+        assert_eq!(clock.current_hour, 2.0);
+    }
+
+    #[test]
+    fn test_night_is_darkest_at_midnight() {
+        let at = |current_hour| DayNightCycle { current_hour, ..Default::default() };
+        assert!(!at(12.0).is_night());
+        assert!(at(DUSK_HOUR).is_night());
+        assert!(at(0.0).darkness() > 0.99);
+        assert!(at(12.0).darkness() < 0.01);
+        assert!(at(12.0).sun_elevation() > 0.0);
+        assert!(at(0.0).sun_elevation() < 0.0);
+        assert_eq!(at(12.0).encounter_multiplier(), 1.0);
+        assert!(at(0.0).encounter_multiplier() > 1.9);
+    }
+
+    #[test]
+    fn test_fronts_drift_over_the_player_and_expire() {
+        let player = HexCoord::new(0, 0);
+        let mut fronts = WeatherFronts {
+            fronts: vec![front(WeatherType::Storm, (-8.0, 0.0), (1.0, 0.0))],
+            hours_until_next_front: 10.0,
+        };
+        assert_eq!(fronts.weather_at(player), WeatherSystem::default());
+
+        fronts.advance(8.0, player);
+        assert_eq!(fronts.hours_until_next_front, 2.0);
+        let overhead = fronts.weather_at(player);
+        assert_eq!(overhead.current_weather, WeatherType::Storm);
+        assert_eq!(overhead.intensity, 0.8);
+        assert_eq!(overhead.visibility, WeatherType::Storm.visibility());
+
+        // Halfway out the front is weaker and easier to see through
+        fronts.advance(2.0, player);
+        let fading = fronts.weather_at(player);
+        assert!(fading.intensity < overhead.intensity);
+        assert!(fading.visibility > overhead.visibility);
+
+        fronts.advance(14.0, player);
+        assert!(fronts.fronts.is_empty());
+    }
+
+    #[test]
+    fn test_strongest_front_decides_and_distant_fronts_are_dropped() {
+        let player = HexCoord::new(0, 0);
+        let mut fronts = WeatherFronts {
+            fronts: vec![
+                front(WeatherType::Rain, (2.0, 0.0), (0.0, 0.0)),
+                front(WeatherType::Fog, (0.0, 0.0), (0.0, 0.0)),
+                front(WeatherType::Snow, (FRONT_DESPAWN_DISTANCE, 0.0), (0.0, 0.0)),
+            ],
+            hours_until_next_front: 0.0,
+        };
+        assert_eq!(fronts.weather_at(player).current_weather, WeatherType::Fog);
+
+        fronts.advance(1.0, player);
+        let remaining: Vec<WeatherType> = fronts.fronts.iter().map(|front| front.weather).collect();
+        assert_eq!(remaining, vec![WeatherType::Rain, WeatherType::Fog]);
+    }
+
+    #[test]
+    fn test_encounter_rate_follows_dread_night_and_storms() {
+        let noon = DayNightCycle { current_hour: 12.0, ..Default::default() };
+        let midnight = DayNightCycle { current_hour: 0.0, ..Default::default() };
+        let clear = WeatherSystem::default();
+        let storm = WeatherSystem {
+            current_weather: WeatherType::Storm,
+            ..Default::default()
+        };
+
+        let calm = encounter_rate(&DreadPhase::Peace, &noon, &clear);
+        assert_eq!(calm, DreadPhase::Peace.get_encounter_spawn_rate());
+        assert_eq!(encounter_rate(&DreadPhase::Peace, &noon, &storm), calm * 1.5);
+        assert!((encounter_rate(&DreadPhase::Peace, &midnight, &clear) - calm * 2.0).abs() < 1e-4);
+        assert!(encounter_rate(&DreadPhase::Void, &noon, &clear) > calm);
+    }
+}